@@ -0,0 +1,57 @@
+//! Node bindings of the msg builders, building `ValidatedExecute`
+//! payloads identical to the MCP server's without speaking MCP (`npm run
+//! build`, then `require("cosmwasm-mcp")`)
+
+use cosmwasm_mcp_template::cli::Cli;
+use cosmwasm_mcp_template::contract::{CwContract, Network};
+use cosmwasm_mcp_template::execute::ExecuteRequest;
+use cosmwasm_mcp_template::{CwMcp, CwMcpBuilder};
+use napi::bindgen_prelude::within_runtime_if_available;
+use napi_derive::napi;
+use serde_json::Value;
+
+fn invalid_arg(e: impl std::fmt::Display) -> napi::Error {
+    napi::Error::new(napi::Status::InvalidArg, e.to_string())
+}
+
+/// Msg builder of the contracts config (or of `contracts`, a JSON array
+/// of deployments), with the default `network` ('Mainnet' or 'Testnet')
+#[napi]
+pub struct MsgBuilder {
+    server: CwMcp,
+}
+
+#[napi]
+impl MsgBuilder {
+    #[napi(constructor)]
+    pub fn new(contracts: Option<String>, network: Option<String>) -> napi::Result<Self> {
+        // Node's arguments aren't the server's
+        let mut builder = CwMcpBuilder::new().options(Cli::default());
+        if let Some(contracts) = contracts {
+            let contracts: Vec<CwContract> =
+                serde_json::from_str(&contracts).map_err(invalid_arg)?;
+            builder = builder.contracts(contracts);
+        }
+        if let Some(network) = network {
+            let network: Network =
+                serde_json::from_value(Value::String(network)).map_err(invalid_arg)?;
+            builder = builder.network(network);
+        }
+        let server = within_runtime_if_available(|| builder.build());
+        Ok(Self { server })
+    }
+
+    /// Build an execute msg from the JSON stringified arguments of
+    /// `build_execute_msg`, resolving to the JSON stringified
+    /// `ValidatedExecute`; rejects if it can't be built
+    #[napi]
+    pub async fn build_execute_msg(&self, request: String) -> napi::Result<String> {
+        let request: ExecuteRequest = serde_json::from_str(&request).map_err(invalid_arg)?;
+        let built = self
+            .server
+            .build_execute(request)
+            .await
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+        Ok(serde_json::to_string(&built).unwrap_or_default())
+    }
+}
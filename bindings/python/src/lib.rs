@@ -0,0 +1,67 @@
+//! Python bindings of the msg builders, building `ValidatedExecute`
+//! payloads identical to the MCP server's without speaking MCP (`maturin
+//! develop`, then `import cosmwasm_mcp`)
+
+use cosmwasm_mcp_template::cli::Cli;
+use cosmwasm_mcp_template::contract::{CwContract, Network};
+use cosmwasm_mcp_template::execute::ExecuteRequest;
+use cosmwasm_mcp_template::{CwMcp, CwMcpBuilder};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// Msg builder of the contracts config (or of `contracts`, a JSON array
+/// of deployments), with the default `network` ('Mainnet' or 'Testnet')
+#[pyclass]
+struct MsgBuilder {
+    server: CwMcp,
+    runtime: Runtime,
+}
+
+#[pymethods]
+impl MsgBuilder {
+    #[new]
+    #[pyo3(signature = (contracts=None, network=None))]
+    fn new(contracts: Option<&str>, network: Option<&str>) -> PyResult<Self> {
+        // Python's arguments aren't the server's
+        let mut builder = CwMcpBuilder::new().options(Cli::default());
+        if let Some(contracts) = contracts {
+            let contracts: Vec<CwContract> =
+                serde_json::from_str(contracts).map_err(value_error)?;
+            builder = builder.contracts(contracts);
+        }
+        if let Some(network) = network {
+            let network: Network =
+                serde_json::from_value(Value::String(network.to_string())).map_err(value_error)?;
+            builder = builder.network(network);
+        }
+        let runtime = Runtime::new().map_err(value_error)?;
+        let server = {
+            let _runtime = runtime.enter();
+            builder.build()
+        };
+        Ok(Self { server, runtime })
+    }
+
+    /// Build an execute msg from the JSON stringified arguments of
+    /// `build_execute_msg`, returning the JSON stringified
+    /// `ValidatedExecute`; raises `ValueError` if it can't be built
+    fn build_execute_msg(&self, py: Python<'_>, request: &str) -> PyResult<String> {
+        let request: ExecuteRequest = serde_json::from_str(request).map_err(value_error)?;
+        let built = py
+            .allow_threads(|| self.runtime.block_on(self.server.build_execute(request)))
+            .map_err(value_error)?;
+        Ok(serde_json::to_string(&built).unwrap_or_default())
+    }
+}
+
+#[pymodule]
+fn cosmwasm_mcp(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<MsgBuilder>()?;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Generates the msg schemas and entry point descriptions of the contracts
+//! in `schema/` (the output of `cosmwasm schema`), served by the schema
+//! adapters of `src/codegen.rs`
+
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+// The entry points of the adapters, generated with the same code
+#[path = "src/entry_point.rs"]
+mod entry_point;
+
+use entry_point::entry_points;
+
+/// Directory of the contracts' schemas, relative to the crate (overridden
+/// by `CW_MCP_SCHEMA_DIR`)
+static SCHEMA_DIR: &str = "schema";
+
+/// Msg types of a schema, as (kind, key of the IDL file, file of the
+/// legacy per msg output)
+static KINDS: &[(&str, &str, &str)] = &[
+    ("query", "query", "query_msg.json"),
+    ("execute", "execute", "execute_msg.json"),
+    ("instantiate", "instantiate", "instantiate_msg.json"),
+    ("migrate", "migrate", "migrate_msg.json"),
+];
+
+/// Label of the legacy per msg output, which doesn't name its contract
+static LEGACY_LABEL: &str = "schema";
+
+struct Contract {
+    label: String,
+    version: String,
+    source: String,
+    schemas: Vec<(&'static str, Option<Value>)>,
+}
+
+fn read(path: &Path) -> Option<Value> {
+    let contents = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("cargo:warning=skipping {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn contracts(dir: &Path, relative: &str) -> Vec<Contract> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+    let mut contracts = vec![];
+    for path in &paths {
+        let Some(idl) = read(path) else {
+            continue;
+        };
+        let Some(label) = idl["contract_name"].as_str() else {
+            continue;
+        };
+        contracts.push(Contract {
+            label: label.to_string(),
+            version: idl["contract_version"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            source: format!(
+                "{}/{}",
+                relative,
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            schemas: KINDS
+                .iter()
+                .map(|(kind, key, _)| {
+                    (
+                        *kind,
+                        Some(idl[*key].clone()).filter(|schema| !schema.is_null()),
+                    )
+                })
+                .collect(),
+        });
+    }
+    let legacy: Vec<(&'static str, Option<Value>)> = KINDS
+        .iter()
+        .map(|(kind, _, file)| (*kind, read(&dir.join(file))))
+        .collect();
+    if legacy.iter().any(|(_, schema)| schema.is_some()) {
+        contracts.push(Contract {
+            label: LEGACY_LABEL.to_string(),
+            version: String::new(),
+            source: relative.to_string(),
+            schemas: legacy,
+        });
+    }
+    contracts
+}
+
+fn generate(contracts: &[Contract]) -> String {
+    let mut code = String::from("pub static GENERATED_SCHEMAS: &[GeneratedSchema] = &[\n");
+    for contract in contracts {
+        let _ = writeln!(code, "    GeneratedSchema {{");
+        let _ = writeln!(code, "        label: {:?},", contract.label);
+        let _ = writeln!(code, "        version: {:?},", contract.version);
+        let _ = writeln!(code, "        source: {:?},", contract.source);
+        for (kind, schema) in &contract.schemas {
+            match schema {
+                Some(schema) => {
+                    let _ = writeln!(code, "        {}: Some({:?}),", kind, schema.to_string());
+                }
+                None => {
+                    let _ = writeln!(code, "        {}: None,", kind);
+                }
+            }
+        }
+        let _ = writeln!(code, "        entry_points: &[");
+        for (kind, schema) in &contract.schemas {
+            let Some(schema) = schema else {
+                continue;
+            };
+            for (name, description) in entry_points(kind, schema) {
+                let _ = writeln!(
+                    code,
+                    "            GeneratedEntryPoint {{ kind: {:?}, name: {:?}, description: {:?} }},",
+                    kind, name, description
+                );
+            }
+        }
+        let _ = writeln!(code, "        ],");
+        let _ = writeln!(code, "    }},");
+    }
+    code.push_str("];\n");
+    code
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CW_MCP_SCHEMA_DIR");
+    let relative = env::var("CW_MCP_SCHEMA_DIR").unwrap_or(SCHEMA_DIR.to_string());
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let dir = Path::new(&manifest_dir).join(&relative);
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let code = generate(&contracts(&dir, &relative));
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("schemas.rs"), code).expect("failed to write schemas.rs");
+}
@@ -0,0 +1,9 @@
+#!/bin/sh
+# Build the msg building core for web dApps, as an ES module in pkg/.
+# The crate is only built as a cdylib here, so native builds (the server
+# and the bindings) don't link a dynamic library of it
+set -e
+
+cargo rustc --lib --release --target wasm32-unknown-unknown --crate-type cdylib
+wasm-bindgen --target web --out-dir pkg \
+    "${CARGO_TARGET_DIR:-target}/wasm32-unknown-unknown/release/cosmwasm_mcp_template.wasm"
@@ -0,0 +1,277 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cosmos::type_url;
+use crate::display::{DENOM_UNITS, to_display};
+use crate::events::CONTRACT_ADDRESS_ATTRIBUTE;
+use crate::price::PriceProvider;
+use crate::rpc::{LcdClient, url_encode};
+
+/// Txs fetched per tx_search page
+pub static EXPORT_PAGE_LIMIT: u64 = 50;
+/// Maximum number of pages fetched for an export
+pub static MAX_EXPORT_PAGES: u64 = 10;
+
+/// Fields of execute msgs naming the counterparty of a transfer
+static COUNTERPARTY_FIELDS: &[&str] = &["recipient", "contract", "spender", "owner"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// A contract execution of an exported transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActivityRecord {
+    pub tx_hash: String,
+    pub height: u64,
+    pub timestamp: String,
+    pub success: bool,
+    pub sender: String,
+    /// ExecuteMsg variant, e.g. `transfer`
+    pub action: String,
+    pub counterparty: Option<String>,
+    /// Native funds sent with the execute, otherwise the msg's `amount`
+    /// (in the contract's cw20 token)
+    pub amount: Option<String>,
+    pub denom: Option<String>,
+    /// Fee of the tx; txs with several executes report it on the first
+    pub fee_amount: Option<String>,
+    pub fee_denom: Option<String>,
+    pub amount_usd: Option<f64>,
+    pub fee_usd: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActivityExport {
+    pub format: ExportFormat,
+    pub records: usize,
+    /// False if the export stopped at `MAX_EXPORT_PAGES` before reaching
+    /// the start of the date range
+    pub complete: bool,
+    /// CSV (with a header row) or JSON array of the records
+    pub data: String,
+    /// Resource serving `data` as a file (`text/csv` or `application/json`)
+    pub resource: Option<String>,
+}
+
+/// Quote a CSV field if needed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(records: &[ActivityRecord]) -> String {
+    let mut csv = String::from(
+        "timestamp,tx_hash,height,success,sender,action,counterparty,amount,denom,fee_amount,fee_denom,amount_usd,fee_usd\n",
+    );
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+    let usd = |value: Option<f64>| {
+        value
+            .map(|value| format!("{:.2}", value))
+            .unwrap_or_default()
+    };
+    for record in records {
+        let fields = [
+            record.timestamp.clone(),
+            record.tx_hash.clone(),
+            record.height.to_string(),
+            record.success.to_string(),
+            record.sender.clone(),
+            record.action.clone(),
+            optional(&record.counterparty),
+            optional(&record.amount),
+            optional(&record.denom),
+            optional(&record.fee_amount),
+            optional(&record.fee_denom),
+            usd(record.amount_usd),
+            usd(record.fee_usd),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Contract executions of a tx response
+fn records(tx_response: &Value, contract_addr: &str, cw20_denom: &str) -> Vec<ActivityRecord> {
+    let height = match &tx_response["height"] {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_u64().unwrap_or_default(),
+    };
+    let fee = &tx_response["tx"]["auth_info"]["fee"]["amount"][0];
+    let mut records: Vec<ActivityRecord> = vec![];
+    let msgs = tx_response["tx"]["body"]["messages"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for msg in msgs {
+        if msg["@type"].as_str() != Some(type_url::MSG_EXECUTE_CONTRACT)
+            || msg["contract"].as_str() != Some(contract_addr)
+        {
+            continue;
+        }
+        let (action, fields) = match msg["msg"].as_object().and_then(|msg| msg.iter().next()) {
+            Some((action, fields)) => (action.clone(), fields.clone()),
+            None => (String::new(), Value::Null),
+        };
+        let funds = &msg["funds"][0];
+        let (amount, denom) = match (funds["amount"].as_str(), fields["amount"].as_str()) {
+            (Some(amount), _) => (
+                Some(amount.to_string()),
+                funds["denom"].as_str().map(str::to_string),
+            ),
+            (None, Some(amount)) => (Some(amount.to_string()), Some(cw20_denom.to_string())),
+            (None, None) => (None, None),
+        };
+        let first = records.is_empty();
+        records.push(ActivityRecord {
+            tx_hash: tx_response["txhash"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            height,
+            timestamp: tx_response["timestamp"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            success: tx_response["code"].as_u64().unwrap_or_default() == 0,
+            sender: msg["sender"].as_str().unwrap_or_default().to_string(),
+            action,
+            counterparty: COUNTERPARTY_FIELDS
+                .iter()
+                .find_map(|field| fields[*field].as_str())
+                .map(str::to_string),
+            amount,
+            denom,
+            fee_amount: fee["amount"].as_str().filter(|_| first).map(str::to_string),
+            fee_denom: fee["denom"].as_str().filter(|_| first).map(str::to_string),
+            amount_usd: None,
+            fee_usd: None,
+        });
+    }
+    records
+}
+
+#[derive(Clone, Debug)]
+pub struct ActivityExporter {
+    lcd: LcdClient,
+    prices: PriceProvider,
+}
+
+impl ActivityExporter {
+    pub fn new(lcd: LcdClient, prices: PriceProvider) -> Self {
+        Self { lcd, prices }
+    }
+
+    /// USD value of an amount of a native denom, at its price on the day
+    /// of a timestamp
+    async fn usd_value(
+        &self,
+        amount: Option<&str>,
+        denom: Option<&str>,
+        timestamp: &str,
+    ) -> Option<f64> {
+        let (amount, denom) = (amount?, denom?);
+        let (price_denom, exponent) = DENOM_UNITS
+            .iter()
+            .find(|(base, _, _)| *base == denom)
+            .map(|(base, _, exponent)| (*base, *exponent))?;
+        let price = self
+            .prices
+            .usd_price(price_denom, timestamp.get(..10)?)
+            .await
+            .ok()??;
+        let amount: f64 = to_display(amount, exponent)?.parse().ok()?;
+        Some(amount * price)
+    }
+
+    /// Export the executions of a contract (optionally only those sent by
+    /// an address) between two dates (`YYYY-MM-DD`, inclusive), newest
+    /// first. cw20 amounts are valued as `wrapped_denom`, which the
+    /// contract's token is pegged to
+    #[allow(clippy::too_many_arguments)]
+    pub async fn export(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        sender: Option<&str>,
+        from_date: Option<&str>,
+        to_date: Option<&str>,
+        wrapped_denom: &str,
+        format: ExportFormat,
+    ) -> anyhow::Result<ActivityExport> {
+        let mut query = format!("wasm.{}='{}'", CONTRACT_ADDRESS_ATTRIBUTE, contract_addr);
+        if let Some(sender) = sender {
+            query.push_str(&format!(" AND message.sender='{}'", sender));
+        }
+        let cw20_denom = format!("cw20:{}", contract_addr);
+        let mut exported: Vec<ActivityRecord> = vec![];
+        let mut complete = false;
+        for page in 1..=MAX_EXPORT_PAGES {
+            let path = format!(
+                "/cosmos/tx/v1beta1/txs?query={}&order_by=ORDER_BY_DESC&page={}&limit={}",
+                url_encode(&query),
+                page,
+                EXPORT_PAGE_LIMIT
+            );
+            let body = self.lcd.get(endpoint, &path).await?;
+            let responses = body["tx_responses"].as_array().cloned().unwrap_or_default();
+            let mut before_range = false;
+            for response in &responses {
+                let date = response["timestamp"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .get(..10)
+                    .unwrap_or_default();
+                if to_date.is_some_and(|to_date| date > to_date) {
+                    continue;
+                }
+                if from_date.is_some_and(|from_date| date < from_date) {
+                    before_range = true;
+                    break;
+                }
+                exported.extend(records(response, contract_addr, &cw20_denom));
+            }
+            if before_range || (responses.len() as u64) < EXPORT_PAGE_LIMIT {
+                complete = true;
+                break;
+            }
+        }
+        for record in exported.iter_mut() {
+            let amount_denom = match record.denom.as_deref() {
+                Some(denom) if denom == cw20_denom => Some(wrapped_denom),
+                denom => denom,
+            };
+            record.amount_usd = self
+                .usd_value(record.amount.as_deref(), amount_denom, &record.timestamp)
+                .await;
+            record.fee_usd = self
+                .usd_value(
+                    record.fee_amount.as_deref(),
+                    record.fee_denom.as_deref(),
+                    &record.timestamp,
+                )
+                .await;
+        }
+        let data = match format {
+            ExportFormat::Csv => to_csv(&exported),
+            ExportFormat::Json => serde_json::to_string(&exported)?,
+        };
+        Ok(ActivityExport {
+            format,
+            records: exported.len(),
+            complete,
+            data,
+            resource: None,
+        })
+    }
+}
@@ -0,0 +1,259 @@
+/// Replace (or extend) the below import with the contracts you want the
+/// MCP server to support
+use cw20_wrap::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+
+use rmcp::model::CallToolResult;
+use schemars::{JsonSchema, schema_for};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::marker::PhantomData;
+
+use crate::codegen::EntryPoint;
+use crate::entry_point;
+use crate::execute::MigrateMsg;
+use crate::idl::{schema_adapters, schema_only};
+use crate::resource::SchemaKind;
+use crate::schema;
+use crate::server::CwMcp;
+
+/// Msg types of a kind of contract, selected by the `contract_label` of
+/// the schema and build tools
+pub trait ContractAdapter: Send + Sync {
+    fn label(&self) -> &str;
+
+    /// What the msg types come from, e.g. the schema file of a generated
+    /// adapter
+    fn source(&self) -> String {
+        "rust types".to_string()
+    }
+
+    /// `contract_version` of a generated adapter's schema
+    fn version(&self) -> Option<String> {
+        None
+    }
+
+    /// Rendered JSON schema of one of the contract's msg types
+    fn schema(&self, kind: SchemaKind) -> String;
+
+    /// Parse a JSON stringified msg as one of the contract's msg types,
+    /// returning it (re-serialized) with the warnings of its parsing
+    fn parse(
+        &self,
+        kind: SchemaKind,
+        message: &str,
+        msg: &str,
+    ) -> Result<(Value, Vec<String>), CallToolResult>;
+
+    /// Key of a rendered schema (see `src/schema.rs`); the default
+    /// adapter's keys aren't prefixed with its label
+    fn schema_key(&self, kind: SchemaKind) -> String {
+        match self.label() == default_adapter().label() {
+            true => kind.schema_key(),
+            false => format!("{}:{}", self.label(), kind.schema_key()),
+        }
+    }
+
+    /// Variants of the msg types, with their doc comments
+    fn entry_points(&self) -> Vec<EntryPoint> {
+        SchemaKind::all()
+            .into_iter()
+            .flat_map(|kind| {
+                let root: Value = serde_json::from_str(&self.schema(kind)).unwrap_or_default();
+                entry_points(kind, &root)
+            })
+            .collect()
+    }
+}
+
+/// Entry points of a msg schema: the variants of a msg enum, or the msg
+/// of a struct (as `build.rs` generates them, with the same function)
+pub fn entry_points(kind: SchemaKind, root: &Value) -> Vec<EntryPoint> {
+    entry_point::entry_points(kind.name(), root)
+        .into_iter()
+        .map(|(name, description)| EntryPoint {
+            kind: kind.name().to_string(),
+            name,
+            description,
+        })
+        .collect()
+}
+
+/// Adapter of a contract's `QueryMsg`, `ExecuteMsg`, `InstantiateMsg` and
+/// `MigrateMsg` types
+pub struct TypedAdapter<Q, E, I, M> {
+    label: &'static str,
+    types: PhantomData<fn() -> (Q, E, I, M)>,
+}
+
+impl<Q, E, I, M> TypedAdapter<Q, E, I, M> {
+    pub const fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            types: PhantomData,
+        }
+    }
+}
+
+fn render<T: JsonSchema>(key: &str) -> String {
+    schema::rendered(key, || {
+        let schema = schema_for!(T);
+        serde_json::to_string(&schema).unwrap_or("".to_string())
+    })
+}
+
+fn parse<T: DeserializeOwned + Serialize + JsonSchema>(
+    message: &str,
+    msg: &str,
+    schema_key: &str,
+) -> Result<(Value, Vec<String>), CallToolResult> {
+    let (deserialized, warnings): (T, Vec<String>) = CwMcp::parse_msg(message, msg, schema_key)?;
+    Ok((
+        serde_json::to_value(&deserialized).unwrap_or_default(),
+        warnings,
+    ))
+}
+
+impl<Q, E, I, M> ContractAdapter for TypedAdapter<Q, E, I, M>
+where
+    Q: DeserializeOwned + Serialize + JsonSchema,
+    E: DeserializeOwned + Serialize + JsonSchema,
+    I: DeserializeOwned + Serialize + JsonSchema,
+    M: DeserializeOwned + Serialize + JsonSchema,
+{
+    fn label(&self) -> &str {
+        self.label
+    }
+
+    fn schema(&self, kind: SchemaKind) -> String {
+        let key = self.schema_key(kind);
+        // (Optionally) serve the schema of your contract's custom query
+        // responses too (see `list_query_responses`)
+        // SchemaKind::Response => render::<AllQueryResponse>(&key),
+        match kind {
+            SchemaKind::Query => render::<Q>(&key),
+            SchemaKind::Execute => render::<E>(&key),
+            SchemaKind::Instantiate => render::<I>(&key),
+            SchemaKind::Migrate => render::<M>(&key),
+        }
+    }
+
+    fn parse(
+        &self,
+        kind: SchemaKind,
+        message: &str,
+        msg: &str,
+    ) -> Result<(Value, Vec<String>), CallToolResult> {
+        let key = self.schema_key(kind);
+        match kind {
+            SchemaKind::Query => parse::<Q>(message, msg, &key),
+            SchemaKind::Execute => parse::<E>(message, msg, &key),
+            SchemaKind::Instantiate => parse::<I>(message, msg, &key),
+            SchemaKind::Migrate => parse::<M>(message, msg, &key),
+        }
+    }
+}
+
+/// Replace with (or add) the adapters of your contracts, e.g.
+/// `&TypedAdapter::<marketplace::msg::QueryMsg, marketplace::msg::ExecuteMsg,
+/// marketplace::msg::InstantiateMsg, MigrateMsg>::new("marketplace")`. The
+/// first adapter is the default, used when no `contract_label` is given.
+/// Contracts that can't be imported are served from their `cosmwasm schema`
+/// output instead, generated at build time (see `build.rs`) or loaded at
+/// startup (see `src/idl.rs`)
+pub static ADAPTERS: &[&dyn ContractAdapter] = &[&TypedAdapter::<
+    QueryMsg,
+    ExecuteMsg,
+    InstantiateMsg,
+    MigrateMsg,
+>::new("cw20-wrap")];
+
+/// Replace with the contract addresses served by an adapter other than the
+/// default, as (address, adapter label); contracts registered with the
+/// label of an adapter (see `register_contract`) are served by it too
+pub static CONTRACT_ADAPTERS: &[(&str, &str)] = &[];
+
+/// Adapter of a contract's msg types, as reported by
+/// `list_contract_adapters`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdapterInfo {
+    pub label: String,
+    pub source: String,
+    pub version: Option<String>,
+    pub default: bool,
+    pub entry_points: Vec<EntryPoint>,
+}
+
+impl AdapterInfo {
+    pub fn of(adapter: &dyn ContractAdapter) -> Self {
+        Self {
+            label: adapter.label().to_string(),
+            source: adapter.source(),
+            version: adapter.version(),
+            default: adapter.label() == default_adapter().label(),
+            entry_points: adapter.entry_points(),
+        }
+    }
+}
+
+/// The typed `ADAPTERS` (unless only schema adapters are served), then
+/// the schema adapters
+pub fn adapters() -> Vec<&'static dyn ContractAdapter> {
+    let schemas = schema_adapters();
+    let typed: &[&dyn ContractAdapter] = match schema_only() && !schemas.is_empty() {
+        true => &[],
+        false => ADAPTERS,
+    };
+    typed
+        .iter()
+        .copied()
+        .chain(
+            schemas
+                .iter()
+                .map(|adapter| adapter as &'static dyn ContractAdapter),
+        )
+        .collect()
+}
+
+/// The first of the adapters
+pub fn default_adapter() -> &'static dyn ContractAdapter {
+    adapters()
+        .first()
+        .copied()
+        .expect("ADAPTERS (or the schemas) has no adapters")
+}
+
+/// The adapter of a label (case insensitive), or the default adapter
+pub fn adapter(label: Option<&str>) -> anyhow::Result<&'static dyn ContractAdapter> {
+    let Some(label) = label.map(str::trim).filter(|label| !label.is_empty()) else {
+        return Ok(default_adapter());
+    };
+    let adapters = adapters();
+    adapters
+        .iter()
+        .find(|adapter| adapter.label().eq_ignore_ascii_case(label))
+        .copied()
+        .ok_or_else(|| {
+            let labels: Vec<&str> = adapters.iter().map(|adapter| adapter.label()).collect();
+            anyhow::anyhow!(
+                "unknown contract label '{}', expected one of: {}",
+                label,
+                labels.join(", ")
+            )
+        })
+}
+
+/// The adapter of a contract: the adapter of `CONTRACT_ADAPTERS`, or of
+/// its registry label, otherwise the default adapter
+pub fn adapter_of(
+    contract_addr: &str,
+    registry_label: Option<&str>,
+) -> &'static dyn ContractAdapter {
+    CONTRACT_ADAPTERS
+        .iter()
+        .find(|(address, _)| *address == contract_addr)
+        .map(|(_, label)| *label)
+        .or(registry_label)
+        .and_then(|label| adapter(Some(label)).ok())
+        .unwrap_or_else(default_adapter)
+}
@@ -0,0 +1,289 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bech32::{Bech32, Hrp};
+use ripemd::Ripemd160;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Replace with the bech32 prefixes addresses are commonly converted
+/// between, and the BIP44 coin type their wallets derive keys with. The
+/// same key only controls the converted address on chains of the same
+/// coin type (and address derivation)
+pub static KNOWN_PREFIXES: &[(&str, u32)] = &[
+    ("archway", 118),
+    ("cosmos", 118),
+    ("osmo", 118),
+    ("juno", 118),
+    ("stars", 118),
+    ("neutron", 118),
+    ("celestia", 118),
+    ("akash", 118),
+    ("terra", 330),
+    ("kava", 459),
+    ("secret", 529),
+    ("inj", 60),
+    ("evmos", 60),
+];
+
+static OWNERSHIP_WARNING: &str = "the converted address has the same bytes, it isn't proof of ownership on the other chain; only the key (or contract) controlling the address is";
+
+fn coin_type(prefix: &str) -> Option<u32> {
+    KNOWN_PREFIXES
+        .iter()
+        .find(|(known, _)| *known == prefix)
+        .map(|(_, coin_type)| *coin_type)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConvertedAddress {
+    pub address: String,
+    pub prefix: String,
+    pub converted_address: String,
+    pub converted_prefix: String,
+    /// What the conversion does (and doesn't) prove about the owner of
+    /// the converted address
+    pub warnings: Vec<String>,
+}
+
+/// Re-encode a bech32 address with another prefix; the address bytes are
+/// unchanged
+pub fn convert_prefix(address: &str, prefix: &str) -> anyhow::Result<ConvertedAddress> {
+    let address = address.trim();
+    let prefix = prefix.trim().trim_end_matches('1').to_lowercase();
+    let (hrp, data) = bech32::decode(address)
+        .map_err(|e| anyhow::anyhow!("invalid address '{}', {}", address, e))?;
+    let converted_hrp =
+        Hrp::parse(&prefix).map_err(|e| anyhow::anyhow!("invalid bech32 prefix: {}", e))?;
+    let converted_address = bech32::encode::<Bech32>(converted_hrp, &data)
+        .map_err(|e| anyhow::anyhow!("invalid address: {}", e))?;
+    let from = hrp.to_string();
+    let mut warnings = vec![OWNERSHIP_WARNING.to_string()];
+    if data.len() == 32 {
+        warnings.push(format!(
+            "{} is a contract (or module) address, contracts exist on a single chain and the converted address isn't the same contract",
+            address
+        ));
+    }
+    match (coin_type(&from), coin_type(&prefix)) {
+        (Some(from_type), Some(to_type)) if from_type != to_type => warnings.push(format!(
+            "'{}' wallets derive keys with coin type {} and '{}' wallets with coin type {}, the same mnemonic doesn't control both addresses",
+            from, from_type, prefix, to_type
+        )),
+        (Some(_), Some(_)) => {}
+        _ => warnings.push(format!(
+            "the coin type of '{}' or '{}' is unknown, the same mnemonic may not control both addresses",
+            from, prefix
+        )),
+    }
+    Ok(ConvertedAddress {
+        address: address.to_string(),
+        prefix: from,
+        converted_address,
+        converted_prefix: prefix,
+        warnings,
+    })
+}
+
+/// Bech32 account address of a compressed secp256k1 public key (the
+/// ripemd160 of its sha256)
+pub fn pubkey_address(public_key: &[u8], prefix: &str) -> anyhow::Result<String> {
+    let hrp = Hrp::parse(prefix).map_err(|e| anyhow::anyhow!("invalid bech32 prefix: {}", e))?;
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+    bech32::encode::<Bech32>(hrp, &hash).map_err(|e| anyhow::anyhow!("invalid address: {}", e))
+}
+
+/// Decode a hex string (e.g. a content hash)
+pub fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+    if encoded.len() % 2 != 0 {
+        return None;
+    }
+    (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(encoded.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Compressed secp256k1 public key of a hex or base64 encoded key (33
+/// bytes, or 65 uncompressed), or of its JSON (e.g. `{"@type":
+/// "/cosmos.crypto.secp256k1.PubKey", "key": "..."}` of an account).
+/// Returns whether the key had to be compressed
+pub fn decode_pubkey(encoded: &str) -> anyhow::Result<(Vec<u8>, bool)> {
+    let encoded = encoded.trim();
+    let key = match serde_json::from_str::<Value>(encoded) {
+        Ok(json) if json.is_object() => {
+            let kind = json["@type"]
+                .as_str()
+                .or(json["type"].as_str())
+                .unwrap_or_default();
+            if kind.contains("ethsecp256k1") {
+                anyhow::bail!(
+                    "{} keys derive addresses with keccak256, only secp256k1 keys are supported",
+                    kind
+                );
+            }
+            if !kind.is_empty() && !kind.contains("secp256k1") {
+                anyhow::bail!("{} keys aren't supported, only secp256k1 keys are", kind);
+            }
+            json["key"]
+                .as_str()
+                .or(json["value"].as_str())
+                .ok_or_else(|| anyhow::anyhow!("the public key JSON has no 'key'"))?
+                .to_string()
+        }
+        _ => encoded.trim_matches('"').to_string(),
+    };
+    let bytes = decode_hex(key.trim_start_matches("0x"))
+        .filter(|bytes| bytes.len() == 33 || bytes.len() == 65)
+        .or_else(|| STANDARD.decode(&key).ok())
+        .ok_or_else(|| anyhow::anyhow!("the public key isn't hex or base64 encoded"))?;
+    match (bytes.len(), bytes.first()) {
+        (33, Some(0x02 | 0x03)) => Ok((bytes, false)),
+        // Compressed keys are the x coordinate, prefixed with the parity of y
+        (65, Some(0x04)) => {
+            let mut compressed = vec![0x02 | (bytes[64] & 1)];
+            compressed.extend_from_slice(&bytes[1..33]);
+            Ok((compressed, true))
+        }
+        (len, _) => anyhow::bail!(
+            "invalid secp256k1 public key of {} bytes, expected 33 (compressed) or 65 (uncompressed)",
+            len
+        ),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DerivedAddress {
+    /// Base64 of the compressed public key, as in account JSON
+    pub public_key: String,
+    pub prefix: String,
+    pub address: String,
+    pub warnings: Vec<String>,
+}
+
+/// Account address of a secp256k1 public key, for a bech32 prefix
+pub fn derive_address(public_key: &str, prefix: &str) -> anyhow::Result<DerivedAddress> {
+    let prefix = prefix.trim().trim_end_matches('1').to_lowercase();
+    let (public_key, compressed) = decode_pubkey(public_key)?;
+    let address = pubkey_address(&public_key, &prefix)?;
+    let mut warnings = vec![];
+    if compressed {
+        warnings.push(
+            "the uncompressed public key was compressed, accounts are derived from compressed keys"
+                .to_string(),
+        );
+    }
+    if coin_type(&prefix) == Some(60) {
+        warnings.push(format!(
+            "'{}' accounts usually have ethsecp256k1 keys, whose addresses are derived with keccak256; this address only matches accounts with secp256k1 keys",
+            prefix
+        ));
+    }
+    Ok(DerivedAddress {
+        public_key: STANDARD.encode(&public_key),
+        prefix,
+        address,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Account of the cosmjs test faucet
+    const FAUCET_PUBKEY: &str = "A08EGB7ro1ORuFhjOnZcSgwYlpe0DSFjVNUIkNNQxwKQ";
+    const FAUCET_ADDRESS: &str = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+
+    /// Contract address of 32 bytes
+    const CONTRACT: &str = "cosmos14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9s4hmalr";
+
+    #[test]
+    fn pubkey_addresses() {
+        let faucet = STANDARD.decode(FAUCET_PUBKEY).unwrap();
+        assert_eq!(pubkey_address(&faucet, "cosmos").unwrap(), FAUCET_ADDRESS);
+        // pubkeyToAddress of the cosmjs amino tests
+        let key = STANDARD
+            .decode("AtQaCqFnshaZQp6rIkvAPyzThvCvXSDO+9AzbxVErqJP")
+            .unwrap();
+        assert_eq!(
+            pubkey_address(&key, "cosmos").unwrap(),
+            "cosmos1h806c7khnvmjlywdrkdgk2vrayy2mmvf9rxk2r"
+        );
+    }
+
+    #[test]
+    fn derives_addresses_of_encoded_keys() {
+        let hex = "034f04181eeba35391b858633a765c4a0c189697b40d216354d50890d350c70290";
+        let json = format!(
+            r#"{{"@type": "/cosmos.crypto.secp256k1.PubKey", "key": "{}"}}"#,
+            FAUCET_PUBKEY
+        );
+        for encoded in [FAUCET_PUBKEY, hex, json.as_str()] {
+            let derived = derive_address(encoded, "cosmos1").unwrap();
+            assert_eq!(derived.address, FAUCET_ADDRESS);
+            assert_eq!(derived.public_key, FAUCET_PUBKEY);
+            assert!(derived.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn compresses_uncompressed_keys() {
+        let uncompressed = concat!(
+            "044f04181eeba35391b858633a765c4a0c189697b40d216354d50890d350c70290",
+            "13b587a681e836cc187a8164b98a5848a2b89b3173315fdd0740d5032e259cd5"
+        );
+        let (key, compressed) = decode_pubkey(uncompressed).unwrap();
+        assert!(compressed);
+        assert_eq!(STANDARD.encode(key), FAUCET_PUBKEY);
+        let derived = derive_address(uncompressed, "cosmos").unwrap();
+        assert_eq!(derived.address, FAUCET_ADDRESS);
+        assert_eq!(derived.warnings.len(), 1);
+    }
+
+    #[test]
+    fn refuses_unsupported_keys() {
+        for kind in [
+            "/ethermint.crypto.v1.ethsecp256k1.PubKey",
+            "/cosmos.crypto.ed25519.PubKey",
+        ] {
+            let json = format!(r#"{{"@type": "{}", "key": "{}"}}"#, kind, FAUCET_PUBKEY);
+            assert!(decode_pubkey(&json).is_err());
+        }
+        // The x coordinate alone (32 bytes)
+        assert!(decode_pubkey("TwQYHuujU5G4WGM6dlxKDBiWl7QNIWNU1QiQ01DHApA=").is_err());
+    }
+
+    #[test]
+    fn converts_prefixes() {
+        let converted = convert_prefix(FAUCET_ADDRESS, "archway").unwrap();
+        assert_eq!(
+            converted.converted_address,
+            "archway1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmwam86d"
+        );
+        assert_eq!(converted.prefix, "cosmos");
+        // Only the ownership warning, both use coin type 118
+        assert_eq!(converted.warnings.len(), 1);
+        let converted = convert_prefix(CONTRACT, "osmo1").unwrap();
+        assert_eq!(
+            converted.converted_address,
+            "osmo14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sq2r9g9"
+        );
+        assert_eq!(converted.warnings.len(), 2);
+        assert_eq!(
+            convert_prefix(FAUCET_ADDRESS, "terra")
+                .unwrap()
+                .warnings
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn decodes_hex() {
+        assert_eq!(decode_hex("00ff7a"), Some(vec![0x00, 0xff, 0x7a]));
+        assert_eq!(decode_hex(""), Some(vec![]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
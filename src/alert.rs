@@ -0,0 +1,620 @@
+use cosmwasm_std::{Decimal, Uint128};
+use rmcp::{
+    Peer, RoleServer,
+    model::{LoggingLevel, LoggingMessageNotificationParam},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::breaker::CircuitBreaker;
+use crate::clock::now_rfc3339;
+use crate::contract::{CwContract, Network};
+use crate::events::{ContractEvent, EventScanner, ScanCursor};
+use crate::proxy::{HttpClient, HttpRequest, http_client};
+use crate::registry::ContractRegistry;
+use crate::rpc::LcdClient;
+use crate::supply::SupplyAuditor;
+use crate::watch::WATCH_INTERVAL_SECS;
+
+/// Number of triggered alerts kept in memory
+pub static MAX_ALERT_HISTORY: usize = 100;
+
+/// Logger name of alert notifications
+pub static ALERT_LOGGER: &str = "alerts";
+
+/// Whether the admin and cw-ownable owner of every registered contract
+/// are monitored (from when a client connects, or the contract is
+/// registered), alerting when they change
+pub static MONITOR_OWNERSHIP: bool = true;
+
+/// Replace with a URL ownership alerts are POSTed to (as JSON), in
+/// addition to MCP notifications
+pub static OWNERSHIP_WEBHOOK: Option<&str> = None;
+
+/// Replace with the amount (in the token's smallest unit) above which
+/// single transfers of the registered cw20 contracts raise an alert, e.g.
+/// `Some(1_000_000_000_000_000_000_000)`; `None` doesn't monitor them
+pub static WHALE_TRANSFER_THRESHOLD: Option<u128> = None;
+
+/// Replace with a URL whale alerts are POSTed to (as JSON)
+pub static WHALE_WEBHOOK: Option<&str> = None;
+
+/// `action` attributes of the cw20 events moving tokens between holders
+pub static TRANSFER_ACTIONS: &[&str] = &["transfer", "transfer_from", "send", "send_from"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Collateral ratio of the wrapped token below a ratio (e.g. `1`)
+    PegBelow { ratio: Decimal },
+    /// cw20 balance of an address below a threshold
+    BalanceBelow { address: String, threshold: Uint128 },
+    /// Native balance of an address below a threshold
+    NativeBalanceBelow {
+        address: String,
+        denom: String,
+        threshold: Uint128,
+    },
+    /// Numeric field of a query response (e.g. `total_supply` of
+    /// `{"token_info": {}}`) above a threshold
+    QueryAbove {
+        query: Value,
+        field: String,
+        threshold: Uint128,
+    },
+    /// Numeric field of a query response below a threshold
+    QueryBelow {
+        query: Value,
+        field: String,
+        threshold: Uint128,
+    },
+    /// Admin of the contract differs from the expected admin (`None` for
+    /// contracts without an admin)
+    AdminChanged { admin: Option<String> },
+    /// cw-ownable owner of the contract (`{"ownership": {}}`) differs from
+    /// the expected owner (`None` for renounced ownership)
+    OwnerChanged { owner: Option<String> },
+    /// A single cw20 transfer (or send) above a threshold, in the blocks
+    /// since the last evaluation
+    TransferAbove { threshold: Uint128 },
+}
+
+impl AlertCondition {
+    /// Anomalies tripping the circuit breaker unless a rule says otherwise
+    pub fn is_anomaly(&self) -> bool {
+        matches!(
+            self,
+            AlertCondition::PegBelow { .. }
+                | AlertCondition::AdminChanged { .. }
+                | AlertCondition::OwnerChanged { .. }
+        )
+    }
+
+    /// Conditions holding for new events (rather than a state), raising an
+    /// alert at every evaluation they hold
+    pub fn is_event(&self) -> bool {
+        matches!(self, AlertCondition::TransferAbove { .. })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AlertRule {
+    pub id: String,
+    pub network: Network,
+    pub contract: String,
+    pub condition: AlertCondition,
+    /// URL alerts are POSTed to (as JSON), in addition to MCP notifications
+    pub webhook: Option<String>,
+    /// Switch the server into read-only mode when the alert is raised
+    pub trips_breaker: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct Alert {
+    pub rule_id: String,
+    pub contract: String,
+    pub message: String,
+    /// Value that triggered the alert
+    pub value: String,
+    pub triggered_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AlertsStatus {
+    pub rules: Vec<AlertRule>,
+    /// Rules whose condition currently holds
+    pub firing: Vec<String>,
+    /// Most recent alerts first
+    pub triggered: Vec<Alert>,
+}
+
+/// Value of a (dotted) numeric field of a query response
+fn field_value(response: &Value, field: &str) -> Option<Uint128> {
+    let value = field
+        .split('.')
+        .try_fold(response, |value, key| value.get(key))?;
+    match value {
+        Value::String(value) => Uint128::from_str(value).ok(),
+        Value::Number(value) => value.as_u64().map(Uint128::from),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct AlertsState {
+    rules: BTreeMap<String, AlertRule>,
+    firing: BTreeMap<String, bool>,
+    /// Last height scanned by the rules of event conditions
+    scanned: BTreeMap<String, u64>,
+    triggered: VecDeque<Alert>,
+}
+
+/// Alert rules, evaluated at the watcher's interval. An alert is raised
+/// when a rule's condition starts holding (not again until it stops
+/// holding), and delivered as an MCP logging notification and to the
+/// rule's webhook
+#[derive(Clone)]
+pub struct Alerts {
+    lcd: LcdClient,
+    /// Deployments whose LCD endpoint rules are evaluated at
+    contracts: ContractRegistry,
+    supply: SupplyAuditor,
+    events: EventScanner,
+    http: HttpClient,
+    peer: Arc<RwLock<Option<Peer<RoleServer>>>>,
+    breaker: CircuitBreaker,
+    state: Arc<RwLock<AlertsState>>,
+    started: Arc<OnceCell<()>>,
+}
+
+impl std::fmt::Debug for Alerts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Alerts")
+            .field("rules", &self.status().rules.len())
+            .finish()
+    }
+}
+
+impl Alerts {
+    pub fn new(
+        lcd: LcdClient,
+        contracts: ContractRegistry,
+        peer: Arc<RwLock<Option<Peer<RoleServer>>>>,
+        breaker: CircuitBreaker,
+    ) -> Self {
+        Self {
+            supply: SupplyAuditor::new(lcd.clone()),
+            events: EventScanner::new(lcd.clone()),
+            lcd,
+            contracts,
+            http: http_client(None),
+            peer,
+            breaker,
+            state: Arc::new(RwLock::new(AlertsState::default())),
+            started: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Add (or replace) a rule, and start evaluating rules if it's the
+    /// first one
+    pub async fn add(&self, rule: AlertRule) {
+        if let Ok(mut state) = self.state.write() {
+            state.firing.remove(&rule.id);
+            state.scanned.remove(&rule.id);
+            state.rules.insert(rule.id.clone(), rule);
+        }
+        self.started
+            .get_or_init(|| async {
+                let alerts = self.clone();
+                tokio::spawn(async move { alerts.run().await });
+            })
+            .await;
+    }
+
+    /// Monitor the admin and cw-ownable owner of contracts, alerting when
+    /// they change from their current value. Contracts already monitored
+    /// keep their rules (and expected values); the owner is only
+    /// monitored if the contract implements cw-ownable
+    pub async fn monitor_ownership(&self, contracts: &[CwContract]) {
+        for contract in contracts {
+            let endpoint = contract.lcd_endpoint();
+            let rules = self.status().rules;
+            let monitored = |id: &str| rules.iter().any(|rule| rule.id == id);
+            let mut conditions = vec![];
+            let admin_id = format!("admin:{}", contract.contract_address);
+            if !monitored(&admin_id) {
+                match self
+                    .lcd
+                    .contract_info(endpoint, &contract.contract_address)
+                    .await
+                {
+                    Ok(info) => {
+                        let admin = info["admin"]
+                            .as_str()
+                            .filter(|admin| !admin.is_empty())
+                            .map(str::to_string);
+                        conditions.push((admin_id, AlertCondition::AdminChanged { admin }));
+                    }
+                    Err(e) => tracing::warn!(
+                        "admin of {} couldn't be monitored: {}",
+                        contract.contract_address,
+                        e
+                    ),
+                }
+            }
+            let owner_id = format!("owner:{}", contract.contract_address);
+            if !monitored(&owner_id) {
+                // Contracts without cw-ownable refuse the query
+                if let Ok(ownership) = self
+                    .lcd
+                    .smart_query(
+                        endpoint,
+                        &contract.contract_address,
+                        &json!({ "ownership": {} }),
+                    )
+                    .await
+                {
+                    let owner = ownership["owner"].as_str().map(str::to_string);
+                    conditions.push((owner_id, AlertCondition::OwnerChanged { owner }));
+                }
+            }
+            for (id, condition) in conditions {
+                self.add(AlertRule {
+                    id,
+                    network: contract.network.clone(),
+                    contract: contract.contract_address.clone(),
+                    trips_breaker: condition.is_anomaly(),
+                    condition,
+                    webhook: OWNERSHIP_WEBHOOK.map(str::to_string),
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Alert on single transfers of contracts above a threshold, from
+    /// the current block on
+    pub async fn monitor_transfers(&self, contracts: &[CwContract], threshold: Uint128) {
+        for contract in contracts {
+            let id = format!("whale:{}", contract.contract_address);
+            if self.status().rules.iter().any(|rule| rule.id == id) {
+                continue;
+            }
+            self.add(AlertRule {
+                id,
+                network: contract.network.clone(),
+                contract: contract.contract_address.clone(),
+                condition: AlertCondition::TransferAbove { threshold },
+                webhook: WHALE_WEBHOOK.map(str::to_string),
+                trips_breaker: false,
+            })
+            .await;
+        }
+    }
+
+    pub fn remove(&self, id: &str) -> Option<AlertRule> {
+        let mut state = self.state.write().ok()?;
+        state.firing.remove(id);
+        state.scanned.remove(id);
+        state.rules.remove(id)
+    }
+
+    pub fn status(&self) -> AlertsStatus {
+        match self.state.read() {
+            Ok(state) => AlertsStatus {
+                rules: state.rules.values().cloned().collect(),
+                firing: state
+                    .firing
+                    .iter()
+                    .filter(|(_, firing)| **firing)
+                    .map(|(id, _)| id.clone())
+                    .collect(),
+                triggered: state.triggered.iter().cloned().collect(),
+            },
+            Err(_) => AlertsStatus {
+                rules: vec![],
+                firing: vec![],
+                triggered: vec![],
+            },
+        }
+    }
+
+    async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(WATCH_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let rules = self.status().rules;
+            for rule in rules {
+                // Rules that can't be evaluated (e.g. an unavailable
+                // endpoint) keep their state until the next evaluation
+                let Ok(result) = self.evaluate(&rule).await else {
+                    continue;
+                };
+                let raised = match self.state.write() {
+                    Ok(mut state) => {
+                        let was_firing = state
+                            .firing
+                            .insert(rule.id.clone(), result.is_some())
+                            .unwrap_or_default();
+                        result.filter(|_| !was_firing || rule.condition.is_event())
+                    }
+                    Err(_) => None,
+                };
+                if let Some((value, message)) = raised {
+                    self.raise(&rule, value, message).await;
+                }
+            }
+        }
+    }
+
+    /// The value and a message if the rule's condition holds
+    pub async fn evaluate(&self, rule: &AlertRule) -> anyhow::Result<Option<(String, String)>> {
+        let endpoint = &self.contracts.lcd_endpoint(&rule.network);
+        let result = match &rule.condition {
+            AlertCondition::PegBelow { ratio } => {
+                let peg = self
+                    .supply
+                    .peg(endpoint, &rule.contract, rule.network.wrapped_denom())
+                    .await?;
+                let current = Decimal::from_str(peg.collateral_ratio.as_deref().unwrap_or("1"))?;
+                (current < *ratio).then(|| {
+                    (
+                        current.to_string(),
+                        format!(
+                            "collateral ratio of {} is {}, below {} (shortfall of {})",
+                            rule.contract, current, ratio, peg.shortfall
+                        ),
+                    )
+                })
+            }
+            AlertCondition::BalanceBelow { address, threshold } => {
+                let response = self
+                    .lcd
+                    .smart_query(
+                        endpoint,
+                        &rule.contract,
+                        &json!({ "balance": { "address": address } }),
+                    )
+                    .await?;
+                let balance = field_value(&response, "balance").unwrap_or_default();
+                (balance < *threshold).then(|| {
+                    (
+                        balance.to_string(),
+                        format!("balance of {} is {}, below {}", address, balance, threshold),
+                    )
+                })
+            }
+            AlertCondition::NativeBalanceBelow {
+                address,
+                denom,
+                threshold,
+            } => {
+                let balance = self.lcd.bank_balance(endpoint, address, denom).await?;
+                let balance = Uint128::from_str(&balance)?;
+                (balance < *threshold).then(|| {
+                    (
+                        balance.to_string(),
+                        format!(
+                            "{} balance of {} is {}, below {}",
+                            denom, address, balance, threshold
+                        ),
+                    )
+                })
+            }
+            AlertCondition::QueryAbove {
+                query,
+                field,
+                threshold,
+            }
+            | AlertCondition::QueryBelow {
+                query,
+                field,
+                threshold,
+            } => {
+                let response = self
+                    .lcd
+                    .smart_query(endpoint, &rule.contract, query)
+                    .await?;
+                let value = field_value(&response, field).ok_or_else(|| {
+                    anyhow::anyhow!("'{}' isn't a numeric field of the response", field)
+                })?;
+                let (holds, relation) = match &rule.condition {
+                    AlertCondition::QueryAbove { .. } => (value > *threshold, "above"),
+                    _ => (value < *threshold, "below"),
+                };
+                holds.then(|| {
+                    (
+                        value.to_string(),
+                        format!("'{}' is {}, {} {}", field, value, relation, threshold),
+                    )
+                })
+            }
+            AlertCondition::AdminChanged { admin } => {
+                let info = self.lcd.contract_info(endpoint, &rule.contract).await?;
+                let current = info["admin"].as_str().filter(|admin| !admin.is_empty());
+                (current != admin.as_deref()).then(|| {
+                    let current = current.unwrap_or("none");
+                    (
+                        current.to_string(),
+                        format!(
+                            "admin of {} changed to {} (expected {})",
+                            rule.contract,
+                            current,
+                            admin.as_deref().unwrap_or("none")
+                        ),
+                    )
+                })
+            }
+            AlertCondition::OwnerChanged { owner } => {
+                let ownership = self
+                    .lcd
+                    .smart_query(endpoint, &rule.contract, &json!({ "ownership": {} }))
+                    .await?;
+                let current = ownership["owner"].as_str();
+                (current != owner.as_deref()).then(|| {
+                    let current = current.unwrap_or("none");
+                    (
+                        current.to_string(),
+                        format!(
+                            "owner of {} changed to {} (expected {})",
+                            rule.contract,
+                            current,
+                            owner.as_deref().unwrap_or("none")
+                        ),
+                    )
+                })
+            }
+            AlertCondition::TransferAbove { threshold } => {
+                self.large_transfers(rule, endpoint, *threshold).await?
+            }
+        };
+        Ok(result)
+    }
+
+    /// Transfers of the blocks since the rule's last evaluation above the
+    /// threshold, the largest amount first; the first evaluation only
+    /// records the current height
+    async fn large_transfers(
+        &self,
+        rule: &AlertRule,
+        endpoint: &str,
+        threshold: Uint128,
+    ) -> anyhow::Result<Option<(String, String)>> {
+        let latest = self.events.latest_height(endpoint).await?;
+        let scanned = self
+            .state
+            .read()
+            .ok()
+            .and_then(|state| state.scanned.get(&rule.id).copied());
+        let record = |height: u64| {
+            if let Ok(mut state) = self.state.write() {
+                state.scanned.insert(rule.id.clone(), height);
+            }
+        };
+        let from_height = match scanned {
+            Some(scanned) if scanned < latest => scanned + 1,
+            Some(_) => return Ok(None),
+            None => {
+                record(latest);
+                return Ok(None);
+            }
+        };
+        let scan = self
+            .events
+            .scan(
+                endpoint,
+                ScanCursor {
+                    contract_addr: rule.contract.clone(),
+                    from_height,
+                    to_height: latest,
+                    page: 1,
+                },
+                Some("wasm"),
+            )
+            .await?;
+        record(latest);
+        if scan.next_cursor.is_some() {
+            tracing::warn!(
+                "alert '{}' scanned only the first {} of {} txs since height {}",
+                rule.id,
+                scan.txs_scanned,
+                scan.total_txs,
+                from_height
+            );
+        }
+        let attribute = |event: &ContractEvent, key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.clone())
+        };
+        let mut transfers: Vec<(Uint128, String)> = scan
+            .events
+            .iter()
+            .filter(|event| {
+                attribute(event, "action")
+                    .is_some_and(|action| TRANSFER_ACTIONS.contains(&action.as_str()))
+            })
+            .filter_map(|event| {
+                let amount = Uint128::from_str(&attribute(event, "amount")?).ok()?;
+                (amount > threshold).then(|| {
+                    (
+                        amount,
+                        format!(
+                            "{} from {} to {} (tx {})",
+                            amount,
+                            attribute(event, "from").unwrap_or_default(),
+                            attribute(event, "to").unwrap_or_default(),
+                            event.tx_hash
+                        ),
+                    )
+                })
+            })
+            .collect();
+        transfers.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(transfers.first().map(|(largest, _)| {
+            let transfers: Vec<&str> = transfers
+                .iter()
+                .map(|(_, transfer)| transfer.as_str())
+                .collect();
+            (
+                largest.to_string(),
+                format!(
+                    "{} transfer(s) of {} above {}: {}",
+                    transfers.len(),
+                    rule.contract,
+                    threshold,
+                    transfers.join("; ")
+                ),
+            )
+        }))
+    }
+
+    async fn raise(&self, rule: &AlertRule, value: String, message: String) {
+        let alert = Alert {
+            rule_id: rule.id.clone(),
+            contract: rule.contract.clone(),
+            message,
+            value,
+            triggered_at: now_rfc3339(),
+        };
+        if let Ok(mut state) = self.state.write() {
+            state.triggered.push_front(alert.clone());
+            state.triggered.truncate(MAX_ALERT_HISTORY);
+        }
+        if rule.trips_breaker {
+            self.breaker
+                .trip(format!("alert '{}': {}", rule.id, alert.message));
+        }
+        let peer = self.peer.read().ok().and_then(|peer| peer.clone());
+        if let Some(peer) = peer {
+            let _ = peer
+                .notify_logging_message(LoggingMessageNotificationParam {
+                    level: LoggingLevel::Alert,
+                    logger: Some(ALERT_LOGGER.to_string()),
+                    data: json!(alert),
+                })
+                .await;
+        }
+        if let Some(webhook) = &rule.webhook {
+            let request =
+                HttpRequest::post(webhook, serde_json::to_value(&alert).unwrap_or_default());
+            if let Err(e) = self
+                .http
+                .send(request)
+                .await
+                .and_then(|response| response.error_for_status(webhook))
+            {
+                tracing::warn!("alert webhook {} failed: {}", webhook, e);
+            }
+        }
+    }
+}
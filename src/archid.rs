@@ -0,0 +1,156 @@
+use anyhow::anyhow;
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::contract::Network;
+use crate::registry::ContractRegistry;
+use crate::rpc::LcdClient;
+
+/// ArchID top level domain
+pub static ARCHID_TLD: &str = ".arch";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryQueryMsg {
+    ResolveRecord { name: String },
+    ResolveAddress { address: String },
+    Config {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryExecuteMsg {
+    Register {
+        name: String,
+    },
+    RenewRegistration {
+        name: String,
+    },
+    UpdateResolver {
+        name: String,
+        new_resolver: String,
+    },
+    UpdateUserDomainData {
+        name: String,
+        metadata_update: Value,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchIdDomain {
+    pub name: String,
+    pub address: Option<String>,
+    /// Expiration (unix timestamp, seconds)
+    pub expiration: Option<u64>,
+}
+
+/// ArchID registry contract of a network
+pub fn registry(network: &Network) -> &'static str {
+    match network {
+        Network::Mainnet => crate::resolver::ARCHID_REGISTRY_MAINNET,
+        Network::Testnet => crate::resolver::ARCHID_REGISTRY_TESTNET,
+    }
+}
+
+/// Full domain (e.g. `alice.arch`), as expected by registry queries
+pub fn domain(name: &str) -> String {
+    format!("{}{}", bare_name(name), ARCHID_TLD)
+}
+
+/// Name without the top level domain (e.g. `alice`), as expected by
+/// registry execute messages
+pub fn bare_name(name: &str) -> &str {
+    let name = name.trim();
+    name.strip_suffix(ARCHID_TLD).unwrap_or(name)
+}
+
+#[derive(Clone, Debug)]
+pub struct ArchId {
+    lcd: LcdClient,
+    contracts: ContractRegistry,
+}
+
+impl ArchId {
+    /// Registry client querying the LCD endpoint of the deployments
+    pub fn new(lcd: LcdClient, contracts: ContractRegistry) -> Self {
+        Self { lcd, contracts }
+    }
+
+    async fn query(&self, network: &Network, msg: &RegistryQueryMsg) -> anyhow::Result<Value> {
+        self.lcd
+            .smart_query(
+                &self.contracts.lcd_endpoint(network),
+                registry(network),
+                &serde_json::to_value(msg)?,
+            )
+            .await
+    }
+
+    /// Domains registered to (e.g. resolving to) `address`
+    pub async fn domains_of(
+        &self,
+        address: &str,
+        network: &Network,
+    ) -> anyhow::Result<Vec<ArchIdDomain>> {
+        let response = self
+            .query(
+                network,
+                &RegistryQueryMsg::ResolveAddress {
+                    address: address.to_string(),
+                },
+            )
+            .await?;
+        let names: Vec<String> = response
+            .get("names")
+            .and_then(Value::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut domains = vec![];
+        for name in names {
+            domains.push(self.record(&name, network).await?);
+        }
+        Ok(domains)
+    }
+
+    /// Registration record of a domain
+    pub async fn record(&self, name: &str, network: &Network) -> anyhow::Result<ArchIdDomain> {
+        let name = domain(name);
+        let response = self
+            .query(
+                network,
+                &RegistryQueryMsg::ResolveRecord { name: name.clone() },
+            )
+            .await?;
+        Ok(ArchIdDomain {
+            name,
+            address: response
+                .get("address")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            expiration: response.get("expiration").and_then(Value::as_u64),
+        })
+    }
+
+    /// Funds required to register (or renew) a domain for `years`
+    pub async fn registration_cost(&self, network: &Network, years: u64) -> anyhow::Result<Coin> {
+        let config = self.query(network, &RegistryQueryMsg::Config {}).await?;
+        let base_cost = config
+            .get("base_cost")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("registry config is missing 'base_cost'"))?;
+        let amount = Uint128::from_str(base_cost)?.checked_mul(Uint128::from(years))?;
+        Ok(Coin {
+            denom: network.native_denom().to_string(),
+            amount,
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::is_past;
+use crate::cosmos::type_url;
+use crate::rpc::{LcdClient, url_encode};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum GrantRole {
+    Granter,
+    Grantee,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuthzGrant {
+    pub granter: String,
+    pub grantee: String,
+    /// Type of the authorization, e.g. `/cosmos.authz.v1beta1.GenericAuthorization`
+    pub authorization_type: String,
+    /// Message type the authorization allows the grantee to execute
+    pub msg_type_url: Option<String>,
+    /// Contracts covered by a `ContractExecutionAuthorization`
+    pub contracts: Vec<String>,
+    pub expiration: Option<String>,
+    pub expired: bool,
+    pub authorization: Value,
+}
+
+impl AuthzGrant {
+    /// Whether the grant allows executing `msg_type_url` (on `contract`,
+    /// for contract execution authorizations)
+    pub fn covers(&self, msg_type_url: &str, contract: Option<&str>) -> bool {
+        if self.msg_type_url.as_deref() != Some(msg_type_url) {
+            return false;
+        }
+        match contract {
+            Some(contract) if !self.contracts.is_empty() => {
+                self.contracts.iter().any(|c| c == contract)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Message type allowed by an authorization
+fn authorization_msg_type(authorization: &Value) -> Option<String> {
+    let ty = authorization["@type"].as_str().unwrap_or_default();
+    match ty {
+        "/cosmos.authz.v1beta1.GenericAuthorization" => {
+            authorization["msg"].as_str().map(str::to_string)
+        }
+        "/cosmos.bank.v1beta1.SendAuthorization" => {
+            Some("/cosmos.bank.v1beta1.MsgSend".to_string())
+        }
+        "/cosmwasm.wasm.v1.ContractExecutionAuthorization" => {
+            Some(type_url::MSG_EXECUTE_CONTRACT.to_string())
+        }
+        "/cosmwasm.wasm.v1.ContractMigrationAuthorization" => {
+            Some("/cosmwasm.wasm.v1.MsgMigrateContract".to_string())
+        }
+        "/cosmos.staking.v1beta1.StakeAuthorization" => {
+            match authorization["authorization_type"].as_str() {
+                Some("AUTHORIZATION_TYPE_DELEGATE") => {
+                    Some("/cosmos.staking.v1beta1.MsgDelegate".to_string())
+                }
+                Some("AUTHORIZATION_TYPE_UNDELEGATE") => {
+                    Some("/cosmos.staking.v1beta1.MsgUndelegate".to_string())
+                }
+                Some("AUTHORIZATION_TYPE_REDELEGATE") => {
+                    Some("/cosmos.staking.v1beta1.MsgBeginRedelegate".to_string())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_grant(grant: &Value, granter: Option<&str>, grantee: Option<&str>) -> AuthzGrant {
+    let authorization = grant["authorization"].clone();
+    let expiration = grant["expiration"].as_str().map(str::to_string);
+    let contracts = authorization["grants"]
+        .as_array()
+        .map(|grants| {
+            grants
+                .iter()
+                .filter_map(|grant| grant["contract"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    AuthzGrant {
+        granter: grant["granter"]
+            .as_str()
+            .or(granter)
+            .unwrap_or_default()
+            .to_string(),
+        grantee: grant["grantee"]
+            .as_str()
+            .or(grantee)
+            .unwrap_or_default()
+            .to_string(),
+        authorization_type: authorization["@type"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        msg_type_url: authorization_msg_type(&authorization),
+        contracts,
+        expired: expiration.as_deref().is_some_and(is_past),
+        expiration,
+        authorization,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Authz {
+    lcd: LcdClient,
+}
+
+impl Authz {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Grants given (or received) by an address
+    pub async fn grants_of(
+        &self,
+        endpoint: &str,
+        address: &str,
+        role: &GrantRole,
+    ) -> anyhow::Result<Vec<AuthzGrant>> {
+        let path = match role {
+            GrantRole::Granter => format!("/cosmos/authz/v1beta1/grants/granter/{}", address),
+            GrantRole::Grantee => format!("/cosmos/authz/v1beta1/grants/grantee/{}", address),
+        };
+        let body = self.lcd.get(endpoint, &path).await?;
+        Ok(body["grants"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|grant| parse_grant(grant, None, None))
+            .collect())
+    }
+
+    /// Grants from a granter to a grantee
+    pub async fn grants_between(
+        &self,
+        endpoint: &str,
+        granter: &str,
+        grantee: &str,
+    ) -> anyhow::Result<Vec<AuthzGrant>> {
+        let path = format!(
+            "/cosmos/authz/v1beta1/grants?granter={}&grantee={}",
+            url_encode(granter),
+            url_encode(grantee)
+        );
+        let body = self.lcd.get(endpoint, &path).await?;
+        Ok(body["grants"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|grant| parse_grant(grant, Some(granter), Some(grantee)))
+            .collect())
+    }
+
+    /// Warnings for an authz MsgExec of `msg_type_url` (on `contract`),
+    /// e.g. a missing or expired grant
+    pub async fn exec_warnings(
+        &self,
+        endpoint: &str,
+        granter: &str,
+        grantee: &str,
+        msg_type_url: &str,
+        contract: Option<&str>,
+    ) -> Vec<String> {
+        let grants = match self.grants_between(endpoint, granter, grantee).await {
+            Ok(grants) => grants,
+            Err(e) => return vec![format!("grants couldn't be checked: {}", e)],
+        };
+        let covering: Vec<&AuthzGrant> = grants
+            .iter()
+            .filter(|grant| grant.covers(msg_type_url, contract))
+            .collect();
+        if covering.is_empty() {
+            return vec![format!(
+                "{} hasn't granted {} permission to execute {}",
+                granter, grantee, msg_type_url
+            )];
+        }
+        if covering.iter().all(|grant| grant.expired) {
+            let expirations: Vec<&str> = covering
+                .iter()
+                .filter_map(|grant| grant.expiration.as_deref())
+                .collect();
+            return vec![format!(
+                "the grant from {} to {} for {} expired at {}",
+                granter,
+                grantee,
+                msg_type_url,
+                expirations.join(", ")
+            )];
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::anyhow;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+
+use crate::contract::Network;
+
+/// URI scheme of binary artifacts produced by tools (e.g. CSV exports and
+/// proto encoded txs)
+pub static BLOB_SCHEME: &str = "blob://";
+
+/// URI template of the wasm byte code of a code id
+pub static WASM_TEMPLATE: &str = "wasm://{network}/{code_id}";
+pub static WASM_SCHEME: &str = "wasm://";
+
+pub static MIME_CSV: &str = "text/csv";
+pub static MIME_JSON: &str = "application/json";
+pub static MIME_WASM: &str = "application/wasm";
+pub static MIME_PROTOBUF: &str = "application/x-protobuf";
+
+/// Blobs kept by the server; the oldest are dropped first
+pub static MAX_BLOBS: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blob {
+    pub uri: String,
+    pub name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Binary artifacts of tool calls, served as blob resources
+#[derive(Clone, Debug, Default)]
+pub struct BlobStore {
+    blobs: Arc<RwLock<VecDeque<Blob>>>,
+    count: Arc<RwLock<u64>>,
+}
+
+impl BlobStore {
+    /// Keep an artifact, returning its resource URI
+    pub fn put(&self, name: &str, mime_type: &str, bytes: Vec<u8>) -> Option<String> {
+        let mut count = self.count.write().ok()?;
+        *count += 1;
+        let uri = format!("{}{}/{}", BLOB_SCHEME, count, name);
+        let mut blobs = self.blobs.write().ok()?;
+        if blobs.len() >= MAX_BLOBS {
+            blobs.pop_front();
+        }
+        blobs.push_back(Blob {
+            uri: uri.clone(),
+            name: name.to_string(),
+            mime_type: mime_type.to_string(),
+            bytes,
+        });
+        Some(uri)
+    }
+
+    pub fn get(&self, uri: &str) -> anyhow::Result<Blob> {
+        self.blobs
+            .read()
+            .map_err(|_| anyhow!("blob store poisoned"))?
+            .iter()
+            .find(|blob| blob.uri == uri)
+            .cloned()
+            .ok_or_else(|| anyhow!("no blob '{}', it may have expired", uri))
+    }
+
+    /// Blobs kept, newest first
+    pub fn list(&self) -> Vec<Blob> {
+        self.blobs
+            .read()
+            .map(|blobs| blobs.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Network and code id of a `wasm://` resource URI
+pub fn parse_wasm_uri(uri: &str) -> anyhow::Result<(Network, u64)> {
+    let path = uri
+        .strip_prefix(WASM_SCHEME)
+        .ok_or_else(|| anyhow!("'{}' isn't a {} resource", uri, WASM_SCHEME))?;
+    let Some((network, code_id)) = path.split_once('/') else {
+        anyhow::bail!("'{}' doesn't match {}", uri, WASM_TEMPLATE);
+    };
+    let network = match network.to_lowercase().as_str() {
+        "mainnet" => Network::Mainnet,
+        "testnet" => Network::Testnet,
+        _ => anyhow::bail!(
+            "unknown network '{}', expected 'mainnet' or 'testnet'",
+            network
+        ),
+    };
+    let code_id = code_id
+        .parse()
+        .map_err(|_| anyhow!("'{}' isn't a code id", code_id))?;
+    Ok((network, code_id))
+}
@@ -0,0 +1,114 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use crate::clock::now_rfc3339;
+
+/// Tools building transactions, which are refused while the breaker is
+/// tripped. Queries, simulations and monitoring tools stay available
+pub static EXECUTE_TOOLS: &[&str] = &[
+    "build_execute_msg",
+    "build_instantiate_msg",
+    "build_migrate_msg",
+    "build_archid_register_msg",
+    "build_archid_renew_msg",
+    "build_archid_update_resolver_msg",
+    "build_archid_update_data_msg",
+    "embed_permit_signature",
+    "build_authz_revoke_msg",
+    "build_authz_exec_msg",
+    "build_group_proposal_msg",
+    "build_group_vote_msg",
+    "build_group_exec_msg",
+    "build_gov_vote_msg",
+    "build_gov_deposit_msg",
+    "wrap",
+    "unwrap",
+    "release_queued_execute",
+    "sign_and_broadcast",
+];
+
+/// Consecutive failed broadcasts tripping the breaker
+pub static MAX_FAILED_BROADCASTS: u32 = 3;
+
+/// Replace with a secret reference (e.g. `env:CW_MCP_OPERATOR_TOKEN`) of
+/// the token operators must provide to reset the breaker (and to use the
+/// operator endpoints of `src/operator.rs`). Without one, the breaker
+/// can't be reset over MCP (by the agent that tripped it), only by
+/// restarting the server
+pub static OPERATOR_TOKEN: Option<&str> = None;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct BreakerStatus {
+    /// True while execute tools are refused
+    pub read_only: bool,
+    pub reason: Option<String>,
+    pub tripped_at: Option<String>,
+    pub failed_broadcasts: u32,
+}
+
+/// Switches the server into read-only mode when an anomaly is detected
+/// (e.g. a peg break, an admin change or repeated failed broadcasts),
+/// until an operator resets it
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreaker {
+    status: Arc<RwLock<BreakerStatus>>,
+}
+
+impl CircuitBreaker {
+    pub fn status(&self) -> BreakerStatus {
+        self.status
+            .read()
+            .map(|status| status.clone())
+            .unwrap_or_default()
+    }
+
+    /// Trip the breaker; an already tripped breaker keeps its first reason
+    pub fn trip(&self, reason: impl Into<String>) {
+        if let Ok(mut status) = self.status.write() {
+            if !status.read_only {
+                status.read_only = true;
+                status.reason = Some(reason.into());
+                status.tripped_at = Some(now_rfc3339());
+            }
+        }
+    }
+
+    pub fn reset(&self) -> BreakerStatus {
+        if let Ok(mut status) = self.status.write() {
+            *status = BreakerStatus::default();
+        }
+        self.status()
+    }
+
+    /// Record the outcome of a broadcast, tripping the breaker after
+    /// `MAX_FAILED_BROADCASTS` consecutive failures
+    pub fn record_broadcast(&self, succeeded: bool) {
+        let failed = match self.status.write() {
+            Ok(mut status) => {
+                status.failed_broadcasts = if succeeded {
+                    0
+                } else {
+                    status.failed_broadcasts + 1
+                };
+                status.failed_broadcasts
+            }
+            Err(_) => return,
+        };
+        if failed >= MAX_FAILED_BROADCASTS {
+            self.trip(format!("{} consecutive broadcasts failed", failed));
+        }
+    }
+
+    /// Error refusing an execute tool while the breaker is tripped
+    pub fn refusal(&self, tool: &str) -> Option<String> {
+        let status = self.status();
+        (status.read_only && EXECUTE_TOOLS.contains(&tool)).then(|| {
+            format!(
+                "circuit breaker tripped at {} ({}), the server is read-only until an operator resets it",
+                status.tripped_at.unwrap_or_default(),
+                status.reason.unwrap_or_default()
+            )
+        })
+    }
+}
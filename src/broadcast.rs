@@ -0,0 +1,218 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{Coin, CosmosMsg, Uint128, WasmMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::address::pubkey_address;
+use crate::preflight::Preflight;
+use crate::rpc::LcdClient;
+use crate::secrets::Secret;
+use crate::tx;
+
+/// Replace with the HD path signer keys are derived from their mnemonic with
+pub static DERIVATION_PATH: &str = "m/44'/118'/0'/0/0";
+
+/// Replace with the gas price fees are paid with, in the fee denom's base
+/// unit (e.g. aarch)
+pub static GAS_PRICE: u128 = 140_000_000_000;
+
+/// `BROADCAST_MODE_SYNC` returns once the tx passed `CheckTx`, without
+/// waiting for it to be included in a block
+pub static BROADCAST_MODE: &str = "BROADCAST_MODE_SYNC";
+
+/// A built msg to sign and broadcast, with the deployment it's sent to
+#[derive(Clone, Debug)]
+pub struct BroadcastRequest {
+    pub endpoint: String,
+    pub chain_id: String,
+    pub bech32_prefix: String,
+    pub fee_denom: String,
+    /// JSON `CosmosMsg`, as built by `build_execute_msg`
+    pub cosmos_msg: String,
+    pub memo: String,
+    /// Zero for no timeout height
+    pub timeout_height: u64,
+    /// Simulated (with a safety margin) if not given
+    pub gas_limit: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BroadcastResult {
+    pub tx_hash: String,
+    /// Address of the signer
+    pub sender: String,
+    /// Name of the keystore key the tx was signed with
+    pub signer: String,
+    pub gas_limit: u64,
+    pub fee: Coin,
+    pub memo: String,
+    /// `CheckTx` result code, 0 if the tx was accepted
+    pub code: u32,
+    pub raw_log: String,
+}
+
+/// Contract, msg and funds of a built wasm execute `CosmosMsg`
+pub fn wasm_execute(cosmos_msg: &str) -> anyhow::Result<(String, Vec<u8>, Vec<Coin>)> {
+    match serde_json::from_str::<CosmosMsg>(cosmos_msg)? {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) => Ok((contract_addr, msg.to_vec(), funds)),
+        _ => anyhow::bail!("only wasm execute msgs can be signed and broadcast"),
+    }
+}
+
+/// Fee of a gas limit at `GAS_PRICE`
+pub fn fee(gas_limit: u64, denom: &str) -> Coin {
+    Coin {
+        denom: denom.to_string(),
+        amount: Uint128::from(u128::from(gas_limit) * GAS_PRICE),
+    }
+}
+
+#[cfg(feature = "signing")]
+mod crypto {
+    use super::*;
+    use crate::address::decode_hex;
+    use anyhow::anyhow;
+    use bip32::{DerivationPath, Language, Mnemonic, XPrv};
+    use k256::ecdsa::{Signature, SigningKey, signature::Signer};
+
+    /// Signer keys are hex encoded private keys, or mnemonics
+    fn signing_key(key: &Secret) -> anyhow::Result<SigningKey> {
+        let key = key.expose().trim();
+        if let Some(private_key) = decode_hex(key.trim_start_matches("0x")) {
+            return SigningKey::from_slice(&private_key)
+                .map_err(|_| anyhow!("invalid secp256k1 private key"));
+        }
+        let mnemonic = Mnemonic::new(key, Language::English)
+            .map_err(|_| anyhow!("the signer key must be a private key or a (BIP39) mnemonic"))?;
+        let path: DerivationPath = DERIVATION_PATH
+            .parse()
+            .map_err(|e| anyhow!("invalid derivation path: {}", e))?;
+        let xprv = XPrv::derive_from_path(mnemonic.to_seed(""), &path)
+            .map_err(|e| anyhow!("key derivation failed: {}", e))?;
+        Ok(xprv.private_key().clone())
+    }
+
+    /// Compressed secp256k1 public key
+    pub fn public_key(key: &Secret) -> anyhow::Result<Vec<u8>> {
+        Ok(signing_key(key)?
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec())
+    }
+
+    /// Signature (r || s, low s) of the sha256 of a message
+    pub fn sign(key: &Secret, message: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let signature: Signature = signing_key(key)?.sign(message);
+        let signature = signature.normalize_s().unwrap_or(signature);
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+#[cfg(not(feature = "signing"))]
+mod crypto {
+    use super::*;
+
+    static SIGNING_DISABLED: &str =
+        "local signing is disabled, build the server with the 'signing' feature";
+
+    pub fn public_key(_key: &Secret) -> anyhow::Result<Vec<u8>> {
+        Err(anyhow!(SIGNING_DISABLED))
+    }
+
+    pub fn sign(_key: &Secret, _message: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Err(anyhow!(SIGNING_DISABLED))
+    }
+}
+
+/// Signs built msgs with the unlocked signer key, and broadcasts them
+#[derive(Clone, Debug)]
+pub struct Broadcaster {
+    lcd: LcdClient,
+    preflight: Preflight,
+}
+
+impl Broadcaster {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self {
+            preflight: Preflight::new(lcd.clone()),
+            lcd,
+        }
+    }
+
+    /// Sign a built msg (direct mode) with the key named `signer`, and
+    /// broadcast it
+    pub async fn sign_and_broadcast(
+        &self,
+        signer: &str,
+        key: &Secret,
+        request: BroadcastRequest,
+    ) -> anyhow::Result<BroadcastResult> {
+        let (contract, msg, funds) = wasm_execute(&request.cosmos_msg)?;
+        let public_key = crypto::public_key(key)?;
+        let sender = pubkey_address(&public_key, &request.bech32_prefix)?;
+        let gas_limit = match request.gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => {
+                let report = self
+                    .preflight
+                    .execute(
+                        &request.endpoint,
+                        &sender,
+                        &contract,
+                        &serde_json::from_slice(&msg)?,
+                        &funds,
+                    )
+                    .await?;
+                match (report.suggested_gas_limit, report.error) {
+                    (Some(gas_limit), None) => gas_limit,
+                    (_, Some(error)) => anyhow::bail!("{}", error.raw),
+                    (None, None) => anyhow::bail!("the simulation didn't report the gas used"),
+                }
+            }
+        };
+        let (account_number, sequence) = self.preflight.account(&request.endpoint, &sender).await?;
+        let fee = fee(gas_limit, &request.fee_denom);
+        let any = tx::msg_execute_contract(&sender, &contract, &msg, &funds);
+        let body = tx::tx_body(vec![any], &request.memo, request.timeout_height);
+        let auth_info = tx::auth_info(
+            Some(&public_key),
+            sequence,
+            std::slice::from_ref(&fee),
+            gas_limit,
+        );
+        let signature = crypto::sign(
+            key,
+            &tx::sign_doc(&body, &auth_info, &request.chain_id, account_number),
+        )?;
+        let tx_bytes = tx::tx_raw(&body, &auth_info, &[signature]);
+        let broadcast = self
+            .lcd
+            .post(
+                &request.endpoint,
+                "/cosmos/tx/v1beta1/txs",
+                &json!({ "tx_bytes": STANDARD.encode(&tx_bytes), "mode": BROADCAST_MODE }),
+            )
+            .await?;
+        let response = &broadcast["tx_response"];
+        let tx_hash = response["txhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("the broadcast didn't return a tx hash"))?;
+        Ok(BroadcastResult {
+            tx_hash: tx_hash.to_string(),
+            sender,
+            signer: signer.to_string(),
+            gas_limit,
+            fee,
+            memo: request.memo,
+            code: response["code"].as_u64().unwrap_or_default() as u32,
+            raw_log: response["raw_log"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+}
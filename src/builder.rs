@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use crate::cli::{self, Cli};
+use crate::contract::{CwContract, Network, configured_contracts, configured_or_default_contracts};
+#[cfg(feature = "rpc")]
+use crate::proxy::{HttpClient, HttpClients};
+use crate::recipe::{RECIPES_PATH, Recipes};
+use crate::registry::{CONTRACT_REGISTRY_PATH, ContractRegistry};
+use crate::rpc::LcdClient;
+use crate::server::CwMcp;
+use crate::transport::{ServerTransport, serve_with};
+
+/// Configures the server instances of an application embedding the MCP
+/// server; every option left unset falls back to the binary's default
+/// (its config files, and `CW_MCP_*` environment variables)
+#[derive(Clone, Debug, Default)]
+pub struct CwMcpBuilder {
+    options: Option<Cli>,
+    contracts: Option<Vec<CwContract>>,
+    registry: Option<ContractRegistry>,
+    registry_path: Option<PathBuf>,
+    lcd: Option<LcdClient>,
+    recipes: Option<Recipes>,
+    network: Option<Network>,
+}
+
+impl CwMcpBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Startup options of the server (bind address, contracts config,
+    /// default network), instead of the defaults; the embedding
+    /// application's own arguments are never parsed as the server's
+    pub fn options(mut self, options: Cli) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// The configured deployments, instead of the contracts config
+    pub fn contracts(mut self, contracts: Vec<CwContract>) -> Self {
+        self.contracts = Some(contracts);
+        self
+    }
+
+    /// A registry shared by every server instance (contracts registered in
+    /// one session are seen by the others); overrides `contracts` and
+    /// `registry_path`
+    pub fn registry(mut self, registry: ContractRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Path the contracts registered at runtime are persisted to, instead
+    /// of `CONTRACT_REGISTRY_PATH`
+    pub fn registry_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.registry_path = Some(path.into());
+        self
+    }
+
+    /// A chain client shared by every server instance, with its endpoint
+    /// scores, offline cache and usage
+    pub fn lcd(mut self, lcd: LcdClient) -> Self {
+        self.lcd = Some(lcd);
+        self
+    }
+
+    /// An http client (e.g. with the application's proxy, timeouts or
+    /// middleware) sending every request of the chain client
+    #[cfg(feature = "rpc")]
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        let client = HttpClient::from(client);
+        self.lcd = Some(LcdClient::with_http(HttpClients::from(client)));
+        self
+    }
+
+    /// Recipes of `run_recipe`, instead of the ones of `RECIPES_PATH`
+    pub fn recipes(mut self, recipes: Recipes) -> Self {
+        self.recipes = Some(recipes);
+        self
+    }
+
+    /// Default network of the sessions
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// A server instance, e.g. to serve over a transport of the embedding
+    /// application (any rmcp transport)
+    pub fn build(&self) -> CwMcp {
+        let options = self.startup_options();
+        let contracts = match &self.registry {
+            Some(registry) => registry.clone(),
+            None => {
+                let configured = self
+                    .contracts
+                    .clone()
+                    .unwrap_or_else(configured_or_default_contracts);
+                let path = self
+                    .registry_path
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(CONTRACT_REGISTRY_PATH));
+                ContractRegistry::load(configured.clone(), &path).unwrap_or_else(|e| {
+                    tracing::warn!("contract registry couldn't be loaded: {}", e);
+                    ContractRegistry::new(configured, &path)
+                })
+            }
+        };
+        let recipes = self.recipes.clone().unwrap_or_else(|| {
+            Recipes::load(RECIPES_PATH).unwrap_or_else(|e| {
+                tracing::warn!("recipes couldn't be loaded: {}", e);
+                Recipes::default()
+            })
+        });
+        CwMcp::from_parts(
+            contracts,
+            self.lcd.clone().unwrap_or_else(LcdClient::new),
+            recipes,
+            self.network.clone().or(options.network.clone()),
+        )
+    }
+
+    /// Fail if the builder has no deployments of its own, and the contracts
+    /// config can't be loaded
+    pub(crate) fn check_contracts(&self) -> anyhow::Result<()> {
+        if self.registry.is_none() && self.contracts.is_none() {
+            configured_contracts()?;
+        }
+        Ok(())
+    }
+
+    /// The builder's options, unless the server's options were already set
+    /// (or parsed)
+    pub(crate) fn startup_options(&self) -> &'static Cli {
+        cli::init(self.options.clone().unwrap_or_default())
+    }
+
+    /// Serve the MCP server over a transport, each session (of the sse and
+    /// streamable http transports) getting an instance of the builder's
+    pub async fn serve(self, transport: ServerTransport) -> anyhow::Result<()> {
+        serve_with(self, transport).await
+    }
+}
@@ -0,0 +1,199 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::clock::now_rfc3339;
+
+/// Replace with the path cached chain data is persisted to
+pub static CHAIN_CACHE_PATH: &str = "chain_cache.json";
+
+/// Replace with `true` to start the server in offline mode, serving live
+/// tools exclusively from cached chain data (e.g. in air-gapped
+/// environments)
+pub static OFFLINE: bool = false;
+
+/// Responses cached between saves of the cache
+pub static SAVE_EVERY: u64 = 20;
+
+/// Most responses kept in the cache; the oldest are dropped first
+pub static MAX_CACHED_RESPONSES: usize = 5_000;
+
+tokio::task_local! {
+    /// Oldest cached response served during a tool call
+    static STALE: RefCell<Option<String>>;
+}
+
+/// Note that a cached response was served for the current tool call
+pub fn mark_stale(cached_at: &str) {
+    let _ = STALE.try_with(|stale| {
+        let mut stale = stale.borrow_mut();
+        if stale.as_deref().is_none_or(|oldest| cached_at < oldest) {
+            *stale = Some(cached_at.to_string());
+        }
+    });
+}
+
+/// Run a tool call, returning the time of the oldest cached response it
+/// was served (if any)
+pub async fn track_stale<F: Future>(call: F) -> (F::Output, Option<String>) {
+    STALE
+        .scope(RefCell::new(None), async {
+            let output = call.await;
+            let stale = STALE.with(|stale| stale.borrow().clone());
+            (output, stale)
+        })
+        .await
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CachedResponse {
+    pub body: Value,
+    pub cached_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CacheStatus {
+    pub offline: bool,
+    pub cached_responses: usize,
+    /// Time of the oldest cached response
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+}
+
+/// Responses of LCD queries, persisted to a JSON file, and served (marked
+/// stale) in offline mode or while all endpoints of a network are
+/// unavailable
+#[derive(Clone, Debug)]
+pub struct ChainCache {
+    path: PathBuf,
+    responses: Arc<RwLock<HashMap<String, CachedResponse>>>,
+    offline: Arc<AtomicBool>,
+    inserted: Arc<AtomicU64>,
+}
+
+impl Default for ChainCache {
+    fn default() -> Self {
+        Self::new(CHAIN_CACHE_PATH)
+    }
+}
+
+impl ChainCache {
+    /// Cache persisted to a file, starting empty if it can't be read
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let responses = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            responses: Arc::new(RwLock::new(responses)),
+            offline: Arc::new(AtomicBool::new(OFFLINE)),
+            inserted: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Key of a query to an endpoint, at a height
+    pub fn key(endpoint: &str, path: &str, height: Option<u64>) -> String {
+        let endpoint = endpoint.trim_end_matches('/');
+        match height {
+            Some(height) => format!("{}{}@{}", endpoint, path, height),
+            None => format!("{}{}", endpoint, path),
+        }
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    /// Cached response of a query, marking the tool call as served stale
+    /// data
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let cached = self.responses.read().ok()?.get(key).cloned()?;
+        mark_stale(&cached.cached_at);
+        Some(cached.body)
+    }
+
+    /// Cached responses whose key contains a fragment (e.g. a query
+    /// path), without marking the tool call stale
+    pub fn matching(&self, fragment: &str) -> Vec<(String, CachedResponse)> {
+        self.responses
+            .read()
+            .map(|responses| {
+                responses
+                    .iter()
+                    .filter(|(key, _)| key.contains(fragment))
+                    .map(|(key, cached)| (key.clone(), cached.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn insert(&self, key: String, body: &Value) {
+        let Ok(mut responses) = self.responses.write() else {
+            return;
+        };
+        if responses.len() >= MAX_CACHED_RESPONSES && !responses.contains_key(&key) {
+            let oldest = responses
+                .iter()
+                .min_by(|a, b| a.1.cached_at.cmp(&b.1.cached_at))
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                responses.remove(&oldest);
+            }
+        }
+        responses.insert(
+            key,
+            CachedResponse {
+                body: body.clone(),
+                cached_at: now_rfc3339(),
+            },
+        );
+        if self.inserted.fetch_add(1, Ordering::Relaxed) % SAVE_EVERY == SAVE_EVERY - 1 {
+            if let Err(e) = self.write(&responses) {
+                tracing::warn!("saving chain cache failed: {}", e);
+            }
+        }
+    }
+
+    /// Persist the cache, e.g. to snapshot it before going offline
+    pub fn save(&self) -> anyhow::Result<()> {
+        let responses = self
+            .responses
+            .read()
+            .map_err(|_| anyhow::anyhow!("chain cache poisoned"))?;
+        self.write(&responses)
+    }
+
+    fn write(&self, responses: &HashMap<String, CachedResponse>) -> anyhow::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string(responses)?)?;
+        Ok(())
+    }
+
+    pub fn status(&self) -> CacheStatus {
+        let responses = self.responses.read();
+        let times: Vec<&str> = match &responses {
+            Ok(responses) => responses
+                .values()
+                .map(|cached| cached.cached_at.as_str())
+                .collect(),
+            Err(_) => vec![],
+        };
+        CacheStatus {
+            offline: self.offline(),
+            cached_responses: times.len(),
+            oldest: times.iter().min().map(|time| time.to_string()),
+            newest: times.iter().max().map(|time| time.to_string()),
+        }
+    }
+}
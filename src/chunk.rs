@@ -0,0 +1,110 @@
+use rmcp::model::{CallToolResult, Content, RawContent};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+
+/// Replace with the size (in bytes) above which tool results are returned
+/// in chunks
+pub static MAX_RESULT_BYTES: usize = 64 * 1024;
+
+/// Chunked results kept for `get_result_chunk`; the oldest are dropped
+/// first
+pub static MAX_CHUNKED_RESULTS: usize = 20;
+
+#[derive(Clone, Debug)]
+struct ChunkedResult {
+    id: String,
+    chunks: Vec<String>,
+}
+
+/// Split text into chunks of at most `size` bytes, on char boundaries
+pub fn split(text: &str, size: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, remaining) = rest.split_at(end);
+        chunks.push(chunk.to_string());
+        rest = remaining;
+    }
+    chunks
+}
+
+/// Notice describing a chunk of a result, appended to it
+fn notice(id: &str, index: usize, chunks: usize) -> Content {
+    let notice = json!({
+        "chunked": true,
+        "result_id": id,
+        "chunk": index,
+        "chunks": chunks,
+        "notice": "the result is split into chunks, concatenate the text of all chunks (get them with 'result__get_chunk') before parsing it",
+    });
+    Content::text(notice.to_string())
+}
+
+/// Oversized tool results, held so their chunks can be retrieved
+#[derive(Clone, Debug, Default)]
+pub struct ResultChunks {
+    results: Arc<RwLock<VecDeque<ChunkedResult>>>,
+    count: Arc<RwLock<u64>>,
+}
+
+impl ResultChunks {
+    /// Return a result exceeding `MAX_RESULT_BYTES` as its first chunk,
+    /// holding the others
+    pub fn chunk_result(&self, mut result: CallToolResult) -> CallToolResult {
+        let texts: Vec<&str> = result
+            .content
+            .iter()
+            .filter_map(|content| match &content.raw {
+                RawContent::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        if texts.iter().map(|text| text.len()).sum::<usize>() <= MAX_RESULT_BYTES {
+            return result;
+        }
+        let chunks = split(&texts.join("\n"), MAX_RESULT_BYTES);
+        let Ok(mut count) = self.count.write() else {
+            return result;
+        };
+        *count += 1;
+        let id = format!("result-{}", count);
+        let first = Content::text(chunks[0].clone());
+        result.content = vec![first, notice(&id, 0, chunks.len())];
+        if let Ok(mut results) = self.results.write() {
+            if results.len() >= MAX_CHUNKED_RESULTS {
+                results.pop_front();
+            }
+            results.push_back(ChunkedResult { id, chunks });
+        }
+        result
+    }
+
+    /// A chunk of a held result, with its notice
+    pub fn chunk(&self, id: &str, index: usize) -> anyhow::Result<Vec<Content>> {
+        let results = self
+            .results
+            .read()
+            .map_err(|_| anyhow::anyhow!("result chunks poisoned"))?;
+        let result = results
+            .iter()
+            .find(|result| result.id == id)
+            .ok_or_else(|| anyhow::anyhow!("no chunked result with id '{}'", id))?;
+        let chunk = result.chunks.get(index).ok_or_else(|| {
+            anyhow::anyhow!(
+                "'{}' has {} chunks (0 to {})",
+                id,
+                result.chunks.len(),
+                result.chunks.len() - 1
+            )
+        })?;
+        Ok(vec![
+            Content::text(chunk.clone()),
+            notice(id, index, result.chunks.len()),
+        ])
+    }
+}
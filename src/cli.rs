@@ -0,0 +1,72 @@
+use clap::{CommandFactory, Parser, error::ErrorKind};
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::contract::Network;
+use crate::transport::ServerTransport;
+
+/// Startup options of the server; every option falls back to its
+/// `CW_MCP_*` environment variable, then to the static it overrides (e.g.
+/// `TRANSPORT_MODE` in `src/main.rs`), so the same binary runs in
+/// different environments
+#[derive(Parser, Clone, Debug, Default, PartialEq)]
+#[command(version, about)]
+pub struct Cli {
+    /// Transport to serve: 'stdio', 'sse' or 'http' (streamable http)
+    #[arg(long, env = "CW_MCP_TRANSPORT")]
+    pub transport: Option<ServerTransport>,
+    /// Address the sse and streamable http servers listen on (e.g.
+    /// 0.0.0.0:8000)
+    #[arg(long, env = "CW_MCP_BIND_ADDRESS")]
+    pub bind: Option<SocketAddr>,
+    /// Path of the contracts config (`CONTRACTS_CONFIG_PATH`)
+    #[arg(long, env = "CW_MCP_CONFIG")]
+    pub config: Option<String>,
+    /// Default network of the sessions, 'mainnet' or 'testnet'
+    #[arg(long, env = "CW_MCP_NETWORK")]
+    pub network: Option<Network>,
+    /// `cosmwasm schema` file, directory or URL of a contract to serve
+    /// (see `SCHEMA_SOURCES`); repeatable, or comma separated
+    #[arg(long = "schema", env = "CW_MCP_SCHEMA", value_delimiter = ',')]
+    pub schemas: Vec<String>,
+    /// Only serve the loaded schemas, building msgs as validated JSON
+    #[arg(long, env = "CW_MCP_SCHEMA_ONLY")]
+    pub schema_only: bool,
+}
+
+static CLI: OnceLock<Cli> = OnceLock::new();
+
+/// Value of an environment variable of earlier releases (e.g.
+/// `MCP_TRANSPORT`), still honored when its `CW_MCP_*` variable isn't set
+fn legacy_var<T: FromStr>(name: &str) -> Option<T>
+where
+    T::Err: Display,
+{
+    let value = std::env::var(name).ok().filter(|value| !value.is_empty())?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(e) => Cli::command()
+            .error(ErrorKind::InvalidValue, format!("invalid {}: {}", name, e))
+            .exit(),
+    }
+}
+
+/// Options of an application embedding the server (see `CwMcpBuilder`),
+/// whose own arguments aren't the server's; the options set (or parsed)
+/// first are kept
+pub fn init(options: Cli) -> &'static Cli {
+    CLI.get_or_init(|| options)
+}
+
+/// Options the server was started with, parsed on first use (invalid
+/// arguments exit with their usage)
+pub fn cli() -> &'static Cli {
+    CLI.get_or_init(|| {
+        let mut cli = Cli::parse();
+        cli.transport = cli.transport.or_else(|| legacy_var("MCP_TRANSPORT"));
+        cli.bind = cli.bind.or_else(|| legacy_var("MCP_BIND_ADDRESS"));
+        cli
+    })
+}
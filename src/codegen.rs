@@ -0,0 +1,68 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::idl::ContractSchema;
+
+/// Msg schemas of a contract, generated by `build.rs` from its
+/// `cosmwasm schema` output
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedSchema {
+    /// `contract_name` of the schema
+    pub label: &'static str,
+    pub version: &'static str,
+    /// Schema file (or directory) it was generated from
+    pub source: &'static str,
+    pub query: Option<&'static str>,
+    pub execute: Option<&'static str>,
+    pub instantiate: Option<&'static str>,
+    pub migrate: Option<&'static str>,
+    pub entry_points: &'static [GeneratedEntryPoint],
+}
+
+/// A variant of a msg type, with its doc comment
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedEntryPoint {
+    pub kind: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/schemas.rs"));
+
+/// An entry point of an adapter's msg types
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EntryPoint {
+    /// `query`, `execute`, `instantiate` or `migrate`
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+}
+
+impl From<&GeneratedEntryPoint> for EntryPoint {
+    fn from(entry_point: &GeneratedEntryPoint) -> Self {
+        Self {
+            kind: entry_point.kind.to_string(),
+            name: entry_point.name.to_string(),
+            description: entry_point.description.to_string(),
+        }
+    }
+}
+
+impl From<&GeneratedSchema> for ContractSchema {
+    fn from(generated: &GeneratedSchema) -> Self {
+        Self {
+            label: generated.label.to_string(),
+            version: generated.version.to_string(),
+            source: generated.source.to_string(),
+            query: generated.query.map(str::to_string),
+            execute: generated.execute.map(str::to_string),
+            instantiate: generated.instantiate.map(str::to_string),
+            migrate: generated.migrate.map(str::to_string),
+            entry_points: generated
+                .entry_points
+                .iter()
+                .map(EntryPoint::from)
+                .collect(),
+        }
+    }
+}
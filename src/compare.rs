@@ -0,0 +1,168 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::preflight::PreflightReport;
+
+/// Differences reported by a comparison, the rest are only counted
+pub static MAX_DIFFERENCES: usize = 50;
+
+/// Stands in for the address of each deployment, which always differ
+pub static CONTRACT_PLACEHOLDER: &str = "<contract>";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum ComparisonKind {
+    Query,
+    Simulation,
+}
+
+/// A value that differs between the deployments, a missing value is
+/// `None` (e.g. a key only the mainnet response has)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValueDifference {
+    /// Path of the value (e.g. `balances[0].amount`), empty for the root
+    pub path: String,
+    pub mainnet: Option<Value>,
+    pub testnet: Option<Value>,
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", path, key),
+    }
+}
+
+fn diff_at(path: String, mainnet: &Value, testnet: &Value, differences: &mut Vec<ValueDifference>) {
+    match (mainnet, testnet) {
+        (Value::Object(mainnet), Value::Object(testnet)) => {
+            for (key, value) in mainnet {
+                let path = child_path(&path, key);
+                match testnet.get(key) {
+                    Some(testnet) => diff_at(path, value, testnet, differences),
+                    None => differences.push(ValueDifference {
+                        path,
+                        mainnet: Some(value.clone()),
+                        testnet: None,
+                    }),
+                }
+            }
+            for (key, value) in testnet {
+                if !mainnet.contains_key(key) {
+                    differences.push(ValueDifference {
+                        path: child_path(&path, key),
+                        mainnet: None,
+                        testnet: Some(value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(mainnet), Value::Array(testnet)) => {
+            for i in 0..mainnet.len().max(testnet.len()) {
+                let path = format!("{}[{}]", path, i);
+                match (mainnet.get(i), testnet.get(i)) {
+                    (Some(mainnet), Some(testnet)) => diff_at(path, mainnet, testnet, differences),
+                    (mainnet, testnet) => differences.push(ValueDifference {
+                        path,
+                        mainnet: mainnet.cloned(),
+                        testnet: testnet.cloned(),
+                    }),
+                }
+            }
+        }
+        (mainnet, testnet) if mainnet != testnet => differences.push(ValueDifference {
+            path,
+            mainnet: Some(mainnet.clone()),
+            testnet: Some(testnet.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// The value with the contract's address (e.g. in the `_contract_address`
+/// attribute of events) replaced by `CONTRACT_PLACEHOLDER`
+pub fn mask_contract(value: &Value, contract_addr: &str) -> Value {
+    match value {
+        Value::String(s) if s == contract_addr => Value::String(CONTRACT_PLACEHOLDER.to_string()),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| mask_contract(value, contract_addr))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), mask_contract(value, contract_addr)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Values that differ between two JSON documents, by path
+pub fn diff(mainnet: &Value, testnet: &Value) -> Vec<ValueDifference> {
+    let mut differences = vec![];
+    diff_at(String::new(), mainnet, testnet, &mut differences);
+    differences
+}
+
+/// Outcome of a simulation that's compared between the deployments; gas
+/// is reported separately, since it differs with the chains' state
+pub fn simulation_outcome(report: &PreflightReport) -> Value {
+    serde_json::json!({
+        "will_succeed": report.will_succeed,
+        "contract_error": report.contract_error,
+        "events": report.events,
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DeploymentComparison {
+    pub kind: ComparisonKind,
+    /// JSON stringified msg sent to both deployments
+    pub msg: String,
+    pub mainnet_contract: String,
+    pub testnet_contract: String,
+    /// Query response, or simulation outcome, of each deployment (with its
+    /// address masked)
+    pub mainnet: Value,
+    pub testnet: Value,
+    pub mainnet_gas_used: Option<u64>,
+    pub testnet_gas_used: Option<u64>,
+    /// Whether both deployments returned the same result
+    pub matches: bool,
+    pub difference_count: usize,
+    /// The first `MAX_DIFFERENCES` differences
+    pub differences: Vec<ValueDifference>,
+    pub warnings: Vec<String>,
+}
+
+impl DeploymentComparison {
+    pub fn new(
+        kind: ComparisonKind,
+        msg: String,
+        (mainnet_contract, mainnet): (String, Value),
+        (testnet_contract, testnet): (String, Value),
+        warnings: Vec<String>,
+    ) -> Self {
+        let mainnet = mask_contract(&mainnet, &mainnet_contract);
+        let testnet = mask_contract(&testnet, &testnet_contract);
+        let mut differences = diff(&mainnet, &testnet);
+        let difference_count = differences.len();
+        differences.truncate(MAX_DIFFERENCES);
+        Self {
+            kind,
+            msg,
+            mainnet_contract,
+            testnet_contract,
+            mainnet,
+            testnet,
+            mainnet_gas_used: None,
+            testnet_gas_used: None,
+            matches: difference_count == 0,
+            difference_count,
+            differences,
+            warnings,
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::breaker::BreakerStatus;
+use crate::contract::Network;
+use crate::keystore::SignerStatus;
+use crate::transport::*;
+
+/// Cargo features the server was built with
+pub fn enabled_features() -> Vec<String> {
+    let features: [(&str, bool); 7] = [
+        ("rpc", cfg!(feature = "rpc")),
+        ("http", cfg!(feature = "http")),
+        ("sandbox", cfg!(feature = "sandbox")),
+        ("prices", cfg!(feature = "prices")),
+        ("signing", cfg!(feature = "signing")),
+        ("provenance", cfg!(feature = "provenance")),
+        ("graphql", cfg!(feature = "graphql")),
+    ];
+    features
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| feature.to_string())
+        .collect()
+}
+
+/// Tools (methods) that can't run without a cargo feature, left out of the
+/// tool list when the server is built without it. Simulations (and the
+/// preflights and deployment comparisons running them) can't be served
+/// from the offline cache, unlike the other chain data of `rpc`
+pub static FEATURE_TOOLS: &[(&str, &[&str])] = &[
+    (
+        "rpc",
+        &[
+            "simulate_execute",
+            "preflight_execute",
+            "compare_deployments",
+        ],
+    ),
+    (
+        "sandbox",
+        &[
+            "profile_gas",
+            "generate_test_fixture",
+            "load_test",
+            "replay_tx",
+        ],
+    ),
+    (
+        "signing",
+        &[
+            "import_signer_key",
+            "unlock_signer",
+            "lock_signer",
+            "sign_and_broadcast",
+        ],
+    ),
+];
+
+/// Whether the server was built with the features a tool needs
+pub fn tool_available(method: &str) -> bool {
+    let features = enabled_features();
+    FEATURE_TOOLS
+        .iter()
+        .filter(|(_, tools)| tools.contains(&method))
+        .all(|(feature, _)| features.iter().any(|enabled| enabled == feature))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransportConfig {
+    /// Transport the server was started with (unknown when embedded)
+    pub active: Option<ServerTransport>,
+    /// Address the sse and streamable http servers listen on
+    pub bind_address: Option<String>,
+    pub paths: Vec<String>,
+}
+
+impl TransportConfig {
+    pub fn current() -> Self {
+        let active = active_transport();
+        let bind_address = bind_address()
+            .ok()
+            .map(|bind| bind.to_string())
+            .filter(|_| {
+                matches!(
+                    active,
+                    Some(ServerTransport::Sse | ServerTransport::StreamableHttp)
+                )
+            });
+        let paths = match active {
+            Some(ServerTransport::Sse) => vec![
+                setting("MCP_SSE_PATH", SSE_PATH),
+                setting("MCP_SSE_POST_PATH", SSE_POST_PATH),
+            ],
+            Some(ServerTransport::StreamableHttp) => {
+                vec![setting("MCP_HTTP_PATH", STREAMABLE_HTTP_PATH)]
+            }
+            _ => vec![],
+        };
+        Self {
+            active,
+            bind_address,
+            paths,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkConfig {
+    pub network: Network,
+    pub chain_id: String,
+    pub contract_address: String,
+    pub lcd_endpoints: Vec<String>,
+    pub rpc_endpoints: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendCap {
+    pub denom: String,
+    pub cap: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PolicySummary {
+    /// Only registered contracts are accepted as tool targets
+    pub strict_contracts: bool,
+    pub breaker: BreakerStatus,
+    pub daily_spend_caps: Vec<SpendCap>,
+    /// Time queued transactions are held before they can be released
+    pub execution_delay_secs: u64,
+    pub offline: bool,
+    pub memo_watermark: Option<String>,
+    /// Whether outbound connections go through a proxy
+    pub proxied: bool,
+    pub redacted_fields: usize,
+    pub redacted_patterns: usize,
+    pub max_result_bytes: usize,
+}
+
+/// Whether the server can sign and broadcast, without any key material
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignerAvailability {
+    pub signing_enabled: bool,
+    #[serde(flatten)]
+    pub status: SignerStatus,
+    pub can_broadcast: bool,
+    /// Why transactions can't be broadcast, if they can't
+    pub reason: Option<String>,
+    pub provenance_enabled: bool,
+    pub provenance_key_configured: bool,
+}
+
+impl SignerAvailability {
+    pub fn new(status: SignerStatus, breaker: &BreakerStatus, provenance_key: bool) -> Self {
+        let signing_enabled = cfg!(feature = "signing");
+        let reason = if !signing_enabled {
+            Some("the server isn't built with the 'signing' feature".to_string())
+        } else if breaker.read_only {
+            Some(format!(
+                "the circuit breaker is tripped ({})",
+                breaker.reason.clone().unwrap_or_default()
+            ))
+        } else if status.keys.is_empty() {
+            Some("the keystore has no keys, import one with 'signer__import_key'".to_string())
+        } else if status.unlocked.is_none() {
+            Some("no signer is unlocked, call 'signer__unlock' first".to_string())
+        } else {
+            None
+        };
+        Self {
+            signing_enabled,
+            status,
+            can_broadcast: reason.is_none(),
+            reason,
+            provenance_enabled: cfg!(feature = "provenance"),
+            provenance_key_configured: provenance_key,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ServerConfig {
+    pub server_version: String,
+    pub features: Vec<String>,
+    pub transport: TransportConfig,
+    pub networks: Vec<NetworkConfig>,
+    pub policy: PolicySummary,
+    pub signer: SignerAvailability,
+    /// Tools of the loaded recipes
+    pub recipes: Vec<String>,
+}
@@ -1,5 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::cli::cli;
+use crate::env::{apply_overrides, network_var};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum Network {
@@ -7,13 +12,321 @@ pub enum Network {
     Testnet,
 }
 
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            _ => anyhow::bail!("unknown network '{}', expected 'mainnet' or 'testnet'", s),
+        }
+    }
+}
+
+impl Network {
+    /// REST (LCD) endpoint used for live queries to the network (the first
+    /// of `CW_MCP_LCD_URL_*`, if it's set)
+    pub fn lcd_endpoint(&self) -> &'static str {
+        if let Some(endpoint) = network_var("LCD_URL", self)
+            .and_then(|endpoints| endpoints.split(',').map(str::trim).find(|e| !e.is_empty()))
+        {
+            return endpoint;
+        }
+        match self {
+            Network::Mainnet => LCD_MAINNET,
+            Network::Testnet => LCD_TESTNET,
+        }
+    }
+
+    /// LCD endpoints of the network, the primary endpoint first; requests
+    /// go to the healthiest of them (see `src/endpoint.rs`). The endpoints
+    /// of `CW_MCP_LCD_URL_*` replace them
+    pub fn lcd_endpoints(&self) -> Vec<&'static str> {
+        if let Some(endpoints) = network_var("LCD_URL", self) {
+            return endpoints
+                .split(',')
+                .map(str::trim)
+                .filter(|endpoint| !endpoint.is_empty())
+                .collect();
+        }
+        let fallbacks = match self {
+            Network::Mainnet => LCD_FALLBACKS_MAINNET,
+            Network::Testnet => LCD_FALLBACKS_TESTNET,
+        };
+        std::iter::once(self.lcd_endpoint())
+            .chain(fallbacks.iter().copied())
+            .collect()
+    }
+
+    /// Tendermint RPC endpoint of the network (overridden by
+    /// `CW_MCP_RPC_URL_*`)
+    pub fn rpc_endpoint(&self) -> &'static str {
+        if let Some(endpoints) = network_var("RPC_URL", self) {
+            return endpoints.split(',').next().unwrap_or(endpoints).trim();
+        }
+        match self {
+            Network::Mainnet => RPC_MAINNET,
+            Network::Testnet => RPC_TESTNET,
+        }
+    }
+
+    /// Chain id of the network (overridden by `CW_MCP_CHAIN_ID_*`)
+    pub fn chain_id(&self) -> &'static str {
+        if let Some(chain_id) = network_var("CHAIN_ID", self) {
+            return chain_id;
+        }
+        match self {
+            Network::Mainnet => CHAIN_ID_MAINNET,
+            Network::Testnet => CHAIN_ID_TESTNET,
+        }
+    }
+
+    /// Block explorer of the network
+    pub fn explorer(&self) -> &'static str {
+        match self {
+            Network::Mainnet => EXPLORER_MAINNET,
+            Network::Testnet => EXPLORER_TESTNET,
+        }
+    }
+
+    /// Native staking and fee denom of the network
+    pub fn native_denom(&self) -> &'static str {
+        match self {
+            Network::Mainnet => NATIVE_DENOM_MAINNET,
+            Network::Testnet => NATIVE_DENOM_TESTNET,
+        }
+    }
+
+    /// Native denom wrapped by the contract on the network
+    pub fn wrapped_denom(&self) -> &'static str {
+        match self {
+            Network::Mainnet => WRAPPED_DENOM_MAINNET,
+            Network::Testnet => WRAPPED_DENOM_TESTNET,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CwContract {
     pub network: Network,
     pub chain_id: String,
     pub contract_address: String,
+    pub bech32_prefix: String,
+    pub fee_denom: String,
+    pub lcd_endpoints: Vec<String>,
+    pub rpc_endpoints: Vec<String>,
+    pub explorer: Option<String>,
+    /// Code id of the deployed contract (looked up on chain if unknown)
+    pub code_id: Option<u64>,
+    /// Block height the contract was instantiated at (looked up on chain
+    /// if unknown)
+    pub deployed_at: Option<u64>,
+}
+
+impl CwContract {
+    /// Deployment of the contract on a network, using the network's
+    /// default endpoints
+    pub fn new(network: Network, chain_id: &str, contract_address: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_string(),
+            contract_address: contract_address.to_string(),
+            bech32_prefix: BECH32_PREFIX.to_string(),
+            fee_denom: network.native_denom().to_string(),
+            lcd_endpoints: network
+                .lcd_endpoints()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            rpc_endpoints: vec![network.rpc_endpoint().to_string()],
+            explorer: Some(network.explorer().to_string()),
+            code_id: None,
+            deployed_at: None,
+            network,
+        }
+    }
+
+    /// Primary LCD endpoint of the deployment
+    pub fn lcd_endpoint(&self) -> &str {
+        self.lcd_endpoints
+            .first()
+            .map(String::as_str)
+            .unwrap_or(self.network.lcd_endpoint())
+    }
+
+    /// Explorer page of the contract
+    pub fn explorer_url(&self) -> Option<String> {
+        self.explorer.as_ref().map(|explorer| {
+            format!(
+                "{}/contracts/{}",
+                explorer.trim_end_matches('/'),
+                self.contract_address
+            )
+        })
+    }
+}
+
+/// A deployment of `CONTRACTS_CONFIG_PATH`; omitted values default to
+/// the network's (see the statics below)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractConfig {
+    pub network: Network,
+    pub contract_address: String,
+    pub chain_id: Option<String>,
+    pub bech32_prefix: Option<String>,
+    pub fee_denom: Option<String>,
+    pub lcd_endpoints: Option<Vec<String>>,
+    pub rpc_endpoints: Option<Vec<String>>,
+    pub explorer: Option<String>,
+    pub code_id: Option<u64>,
+    pub deployed_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ContractsConfig {
+    pub contracts: Vec<ContractConfig>,
 }
 
+impl From<ContractConfig> for CwContract {
+    fn from(config: ContractConfig) -> Self {
+        let chain_id = config
+            .chain_id
+            .unwrap_or_else(|| config.network.chain_id().to_string());
+        let defaults = CwContract::new(config.network, &chain_id, &config.contract_address);
+        CwContract {
+            bech32_prefix: config.bech32_prefix.unwrap_or(defaults.bech32_prefix),
+            fee_denom: config.fee_denom.unwrap_or(defaults.fee_denom),
+            lcd_endpoints: config.lcd_endpoints.unwrap_or(defaults.lcd_endpoints),
+            rpc_endpoints: config.rpc_endpoints.unwrap_or(defaults.rpc_endpoints),
+            explorer: config.explorer.or(defaults.explorer),
+            code_id: config.code_id,
+            deployed_at: config.deployed_at,
+            ..defaults
+        }
+    }
+}
+
+/// The mainnet and testnet deployments below
+pub fn default_contracts() -> Vec<CwContract> {
+    vec![
+        CwContract {
+            code_id: CODE_ID_MAINNET,
+            deployed_at: DEPLOYED_AT_MAINNET,
+            ..CwContract::new(Network::Mainnet, CHAIN_ID_MAINNET, CONTRACT_MAINNET)
+        },
+        CwContract {
+            code_id: CODE_ID_TESTNET,
+            deployed_at: DEPLOYED_AT_TESTNET,
+            ..CwContract::new(Network::Testnet, CHAIN_ID_TESTNET, CONTRACT_TESTNET)
+        },
+    ]
+}
+
+/// Deployments of the contract: those of `CONTRACTS_CONFIG_PATH` if the
+/// file exists, otherwise the default deployments; the `CW_MCP_*`
+/// variables override them either way (see `src/env.rs`)
+pub fn load_contracts(path: &str) -> anyhow::Result<Vec<CwContract>> {
+    if !std::path::Path::new(path).exists() {
+        return Ok(apply_overrides(default_contracts()));
+    }
+    let contents = std::fs::read_to_string(path)?;
+    let config: ContractsConfig = toml::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("invalid contracts config {}: {}", path, e))?;
+    if config.contracts.is_empty() {
+        anyhow::bail!("contracts config {} has no contracts", path);
+    }
+    Ok(apply_overrides(
+        config.contracts.into_iter().map(CwContract::from).collect(),
+    ))
+}
+
+/// Deployments of the `--config` file (otherwise `CONTRACTS_CONFIG_PATH`,
+/// if it exists); a `--config` file that doesn't exist is an error, as is
+/// a config that can't be parsed
+pub fn configured_contracts() -> anyhow::Result<Vec<CwContract>> {
+    match cli().config.as_deref() {
+        Some(path) if !std::path::Path::new(path).exists() => {
+            anyhow::bail!("contracts config {} doesn't exist", path)
+        }
+        Some(path) => load_contracts(path),
+        None => load_contracts(CONTRACTS_CONFIG_PATH),
+    }
+}
+
+/// The configured deployments, or the default deployments if the config
+/// can't be loaded (servers refuse to start with such a config, see
+/// `serve_with`)
+pub fn configured_or_default_contracts() -> Vec<CwContract> {
+    configured_contracts().unwrap_or_else(|e| {
+        tracing::error!("contracts config couldn't be loaded: {}", e);
+        apply_overrides(default_contracts())
+    })
+}
+
+/// A deployment of the contract, with its on-chain contract info
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Deployment {
+    #[serde(flatten)]
+    pub contract: CwContract,
+    pub explorer_url: Option<String>,
+    pub label: Option<String>,
+    pub creator: Option<String>,
+    pub admin: Option<String>,
+    /// Whether the deployment is configured, rather than registered at
+    /// runtime (with `register_contract`)
+    pub configured: bool,
+    /// Label given when the contract was registered
+    pub registry_label: Option<String>,
+    pub registered_at: Option<String>,
+    /// Label of the adapter of the contract's msg types (see
+    /// `src/adapter.rs`)
+    pub adapter: Option<String>,
+}
+
+impl Deployment {
+    /// Complete a deployment with its `ContractInfo` (if it could be
+    /// queried); configured code ids and heights take precedence
+    pub fn new(mut contract: CwContract, info: Option<&Value>) -> Self {
+        let field = |key: &str| {
+            info.and_then(|info| info[key].as_str())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        if contract.code_id.is_none() {
+            contract.code_id = field("code_id").and_then(|id| id.parse().ok());
+        }
+        if contract.deployed_at.is_none() {
+            contract.deployed_at = info
+                .and_then(|info| info["created"]["block_height"].as_str())
+                .and_then(|height| height.parse().ok());
+        }
+        Self {
+            explorer_url: contract.explorer_url(),
+            label: field("label"),
+            creator: field("creator"),
+            admin: field("admin"),
+            configured: true,
+            registry_label: None,
+            registered_at: None,
+            adapter: None,
+            contract,
+        }
+    }
+
+    /// The deployment of a contract registered at runtime
+    pub fn registered(mut self, label: Option<String>, registered_at: String) -> Self {
+        self.configured = false;
+        self.registry_label = label;
+        self.registered_at = Some(registered_at);
+        self
+    }
+}
+
+/// Replace with the path of a TOML file listing your deployments (see
+/// `ContractConfig`), to configure them without recompiling the server.
+/// When the file doesn't exist, the deployments below are used
+pub static CONTRACTS_CONFIG_PATH: &str = "contracts.toml";
+
 /// Replace with your deployed contract addresses.
 /// This helps the query msg and tx msg builders wrap
 /// your query and tx messages to the contract into
@@ -23,3 +336,58 @@ pub static CONTRACT_MAINNET: &str =
     "archway1gaf9nw7n8v5lpjz9caxjpps006kxfcrzcuc8y5qp4clslhven2ns2g0ule";
 pub static CONTRACT_TESTNET: &str =
     "archway1r8kepegwhldwqanuurc769l2g0qxlsm2sm6t5rhqjzcerxsgshls267f7a";
+
+/// Replace with the code ids and instantiation heights of your deployed
+/// contracts, if known; unknown values are looked up on chain
+pub static CODE_ID_MAINNET: Option<u64> = None;
+pub static CODE_ID_TESTNET: Option<u64> = None;
+pub static DEPLOYED_AT_MAINNET: Option<u64> = None;
+pub static DEPLOYED_AT_TESTNET: Option<u64> = None;
+
+/// Replace with `true` to only accept the contract addresses above (and
+/// their registered deployments) as tool targets, so agents can't be
+/// talked into querying or building transactions for other contracts
+pub static STRICT_CONTRACTS: bool = false;
+
+/// Replace with the chain ids of the chains your contract is deployed to
+pub static CHAIN_ID_MAINNET: &str = "archway-1";
+pub static CHAIN_ID_TESTNET: &str = "constantine-3";
+
+/// Bech32 prefix of the chains your contract is deployed to
+pub static BECH32_PREFIX: &str = "archway";
+
+/// Replace with the REST (LCD) endpoints of the chains your contract
+/// is deployed to. These are used by tools that need to read live
+/// chain data (e.g. resolving names to addresses)
+pub static LCD_MAINNET: &str = "https://api.mainnet.archway.io";
+pub static LCD_TESTNET: &str = "https://api.constantine.archway.io";
+
+/// Replace with additional LCD endpoints of the chains, used when they're
+/// healthier than the primary endpoints above (e.g. while those are down)
+pub static LCD_FALLBACKS_MAINNET: &[&str] = &[];
+pub static LCD_FALLBACKS_TESTNET: &[&str] = &[];
+
+/// Replace with a secret reference if your LCD endpoints require a bearer
+/// token, e.g. `Some("env:LCD_AUTH_TOKEN")`, `Some("file:/run/secrets/lcd")`
+/// or `Some("vault:secret/data/mcp#lcd_token")` (see `src/secrets.rs`).
+/// Never put the token itself here
+pub static LCD_AUTH_TOKEN: Option<&str> = None;
+
+/// Replace with the Tendermint RPC endpoints of the chains your contract
+/// is deployed to
+pub static RPC_MAINNET: &str = "https://rpc.mainnet.archway.io";
+pub static RPC_TESTNET: &str = "https://rpc.constantine.archway.io";
+
+/// Replace with the block explorers of the chains your contract is
+/// deployed to
+pub static EXPLORER_MAINNET: &str = "https://www.mintscan.io/archway";
+pub static EXPLORER_TESTNET: &str = "https://testnet.mintscan.io/archway-testnet";
+
+/// Native denoms of the chains your contract is deployed to
+pub static NATIVE_DENOM_MAINNET: &str = "aarch";
+pub static NATIVE_DENOM_TESTNET: &str = "aconst";
+
+/// Replace with the native denoms wrapped by your contract (deposited
+/// with `deposit` and returned by `withdraw`)
+pub static WRAPPED_DENOM_MAINNET: &str = "aarch";
+pub static WRAPPED_DENOM_TESTNET: &str = "aconst";
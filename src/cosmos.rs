@@ -0,0 +1,173 @@
+use cosmwasm_std::Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Type URLs of the Cosmos SDK messages built by this server
+pub mod type_url {
+    pub const MSG_EXECUTE_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+    pub const MSG_EXEC: &str = "/cosmos.authz.v1beta1.MsgExec";
+    pub const MSG_REVOKE: &str = "/cosmos.authz.v1beta1.MsgRevoke";
+    pub const MSG_GROUP_SUBMIT_PROPOSAL: &str = "/cosmos.group.v1.MsgSubmitProposal";
+    pub const MSG_GROUP_VOTE: &str = "/cosmos.group.v1.MsgVote";
+    pub const MSG_GROUP_EXEC: &str = "/cosmos.group.v1.MsgExec";
+    pub const MSG_GOV_VOTE: &str = "/cosmos.gov.v1.MsgVote";
+    pub const MSG_GOV_DEPOSIT: &str = "/cosmos.gov.v1.MsgDeposit";
+    pub const PUB_KEY_SECP256K1: &str = "/cosmos.crypto.secp256k1.PubKey";
+}
+
+/// Vote options shared by x/group and x/gov
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum VoteOption {
+    Yes,
+    Abstain,
+    No,
+    NoWithVeto,
+}
+
+impl VoteOption {
+    /// Proto JSON enum value
+    pub fn as_proto(&self) -> &'static str {
+        match self {
+            VoteOption::Yes => "VOTE_OPTION_YES",
+            VoteOption::Abstain => "VOTE_OPTION_ABSTAIN",
+            VoteOption::No => "VOTE_OPTION_NO",
+            VoteOption::NoWithVeto => "VOTE_OPTION_NO_WITH_VETO",
+        }
+    }
+}
+
+/// A Cosmos SDK message without a CosmWasm `CosmosMsg` representation,
+/// serialized as proto JSON (`{"@type": .., ..}`), as accepted by wallets
+/// and `tx sign`
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatedSdkMsg {
+    pub type_url: String,
+    pub sdk_msg: String,
+    pub address_labels: Vec<crate::label::AddressLabel>,
+    /// Problems found while building the message (e.g. an expired grant),
+    /// which would make the transaction fail
+    pub warnings: Vec<String>,
+    /// Memo the transaction must be broadcast with (the server's
+    /// watermark), if one is configured
+    pub memo: Option<String>,
+    pub metadata: crate::provenance::BuildMetadata,
+}
+
+/// Add the `@type` of a proto JSON message
+pub fn sdk_msg(type_url: &str, fields: Value) -> Value {
+    let mut msg = json!({ "@type": type_url });
+    if let (Some(msg), Value::Object(fields)) = (msg.as_object_mut(), fields) {
+        msg.extend(fields);
+    }
+    msg
+}
+
+pub fn msg_execute_contract(sender: &str, contract: &str, msg: &Value, funds: &[Coin]) -> Value {
+    sdk_msg(
+        type_url::MSG_EXECUTE_CONTRACT,
+        json!({
+            "sender": sender,
+            "contract": contract,
+            "msg": msg,
+            "funds": funds,
+        }),
+    )
+}
+
+pub fn msg_exec(grantee: &str, msgs: Vec<Value>) -> Value {
+    sdk_msg(
+        type_url::MSG_EXEC,
+        json!({ "grantee": grantee, "msgs": msgs }),
+    )
+}
+
+pub fn msg_revoke(granter: &str, grantee: &str, msg_type_url: &str) -> Value {
+    sdk_msg(
+        type_url::MSG_REVOKE,
+        json!({
+            "granter": granter,
+            "grantee": grantee,
+            "msg_type_url": msg_type_url,
+        }),
+    )
+}
+
+/// Proto JSON `Exec` value; `EXEC_TRY` executes the proposal as soon as
+/// it's accepted (by the proposal, or the vote that makes it pass)
+pub fn exec_mode(try_exec: bool) -> &'static str {
+    if try_exec {
+        "EXEC_TRY"
+    } else {
+        "EXEC_UNSPECIFIED"
+    }
+}
+
+pub fn msg_group_submit_proposal(
+    group_policy_address: &str,
+    proposers: &[String],
+    msgs: Vec<Value>,
+    title: &str,
+    summary: &str,
+    try_exec: bool,
+) -> Value {
+    sdk_msg(
+        type_url::MSG_GROUP_SUBMIT_PROPOSAL,
+        json!({
+            "group_policy_address": group_policy_address,
+            "proposers": proposers,
+            "metadata": "",
+            "messages": msgs,
+            "exec": exec_mode(try_exec),
+            "title": title,
+            "summary": summary,
+        }),
+    )
+}
+
+pub fn msg_group_vote(proposal_id: u64, voter: &str, option: &VoteOption, try_exec: bool) -> Value {
+    sdk_msg(
+        type_url::MSG_GROUP_VOTE,
+        json!({
+            "proposal_id": proposal_id.to_string(),
+            "voter": voter,
+            "option": option.as_proto(),
+            "metadata": "",
+            "exec": exec_mode(try_exec),
+        }),
+    )
+}
+
+pub fn msg_group_exec(proposal_id: u64, executor: &str) -> Value {
+    sdk_msg(
+        type_url::MSG_GROUP_EXEC,
+        json!({
+            "proposal_id": proposal_id.to_string(),
+            "executor": executor,
+        }),
+    )
+}
+
+pub fn msg_gov_vote(proposal_id: u64, voter: &str, option: &VoteOption) -> Value {
+    sdk_msg(
+        type_url::MSG_GOV_VOTE,
+        json!({
+            "proposal_id": proposal_id.to_string(),
+            "voter": voter,
+            "option": option.as_proto(),
+            "metadata": "",
+        }),
+    )
+}
+
+pub fn msg_gov_deposit(proposal_id: u64, depositor: &str, amount: &[Coin]) -> Value {
+    sdk_msg(
+        type_url::MSG_GOV_DEPOSIT,
+        json!({
+            "proposal_id": proposal_id.to_string(),
+            "depositor": depositor,
+            "amount": amount,
+        }),
+    )
+}
@@ -0,0 +1,109 @@
+use cosmwasm_std::{Coin, Uint128};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::contract::Network;
+use crate::repair::edit_distance;
+use crate::rpc::LcdClient;
+
+/// Replace with other denoms commonly sent to the contract (e.g. IBC
+/// denoms), suggested in place of mistyped denoms
+pub static KNOWN_DENOMS: &[&str] = &[];
+
+/// Edit distance within which a denom is suggested for a mistyped one
+pub static DENOM_TYPO_DISTANCE: usize = 2;
+
+/// Verifies the denoms of attached funds exist on chain (have a supply),
+/// suggesting the known denom a missing one is a typo of
+#[derive(Clone, Debug)]
+pub struct DenomVerifier {
+    lcd: LcdClient,
+    /// Denoms found on chain, shared by clones of the verifier
+    verified: Arc<RwLock<BTreeSet<String>>>,
+}
+
+impl DenomVerifier {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self {
+            lcd,
+            verified: Arc::new(RwLock::new(BTreeSet::new())),
+        }
+    }
+
+    fn is_verified(&self, denom: &str) -> bool {
+        self.verified
+            .read()
+            .map(|verified| verified.contains(denom))
+            .unwrap_or(false)
+    }
+
+    /// Denoms a missing denom may be a typo of, closest first
+    fn suggestions(network: &Network, denom: &str) -> Vec<String> {
+        let mut known: Vec<&str> = vec![network.native_denom(), network.wrapped_denom()];
+        known.extend(KNOWN_DENOMS);
+        known.dedup();
+        let mut suggestions: Vec<(usize, &str)> = known
+            .into_iter()
+            .map(|known| (edit_distance(&denom.to_lowercase(), known), known))
+            .filter(|(distance, _)| *distance <= DENOM_TYPO_DISTANCE)
+            .collect();
+        suggestions.sort();
+        suggestions
+            .into_iter()
+            .map(|(_, known)| known.to_string())
+            .collect()
+    }
+
+    /// Check the denoms of funds against the chain's bank supply. Denoms
+    /// without a supply are refused (with suggestions); denoms that can't
+    /// be checked (e.g. the endpoint is unavailable) are returned as
+    /// warnings instead
+    pub async fn verify(
+        &self,
+        endpoint: &str,
+        network: &Network,
+        funds: &[Coin],
+    ) -> anyhow::Result<Vec<String>> {
+        let mut warnings = vec![];
+        for coin in funds {
+            if self.is_verified(&coin.denom) {
+                continue;
+            }
+            let supply = match self.lcd.bank_supply(endpoint, &coin.denom).await {
+                Ok(supply) => Uint128::from_str(&supply).unwrap_or_default(),
+                Err(e) => {
+                    warnings.push(format!(
+                        "the {} denom couldn't be verified on chain: {}",
+                        coin.denom, e
+                    ));
+                    continue;
+                }
+            };
+            if supply.is_zero() {
+                let suggestions = Self::suggestions(network, &coin.denom);
+                match suggestions.is_empty() {
+                    true => anyhow::bail!(
+                        "unknown denom '{}', it has no supply on {:?}",
+                        coin.denom,
+                        network
+                    ),
+                    false => anyhow::bail!(
+                        "unknown denom '{}', it has no supply on {:?} (did you mean {}?)",
+                        coin.denom,
+                        network,
+                        suggestions
+                            .iter()
+                            .map(|suggestion| format!("'{}'", suggestion))
+                            .collect::<Vec<String>>()
+                            .join(" or ")
+                    ),
+                }
+            }
+            if let Ok(mut verified) = self.verified.write() {
+                verified.insert(coin.denom.clone());
+            }
+        }
+        Ok(warnings)
+    }
+}
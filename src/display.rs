@@ -0,0 +1,158 @@
+use rmcp::model::{CallToolResult, RawContent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::{Arc, RwLock};
+
+/// Replace with the denoms amounts are rendered in: base denom, display
+/// denom and the exponent between them (1 ARCH = 10^18 aarch)
+pub static DENOM_UNITS: &[(&str, &str, u32)] = &[("aarch", "ARCH", 18), ("aconst", "CONST", 18)];
+
+/// Keys of the payloads (or their parts) agents copy into what gets signed:
+/// built tx bodies, queued and pending executes, fees and funds. Their
+/// coins keep their base amount in every format, with a `display` field
+/// added
+pub(crate) static PAYLOAD_KEYS: &[&str] = &[
+    "tx_body",
+    "execute",
+    "pending",
+    "compensations",
+    "fee",
+    "suggested_fee",
+    "funds",
+];
+
+/// How amounts (`{"denom": .., "amount": ..}` coins) are rendered in tool
+/// output
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum AmountFormat {
+    /// As returned by the chain, e.g. `1500000000000000000 aarch`
+    #[default]
+    Base,
+    /// In display units, e.g. `1.5 ARCH` (coins of payloads get a `display`
+    /// field instead)
+    Display,
+    /// In base units, with a `display` field added, e.g. `1.5 ARCH`
+    Both,
+}
+
+/// Render a base amount in display units, e.g. `1500000` with exponent 6
+/// as `1.5`
+pub fn to_display(amount: &str, exponent: u32) -> Option<String> {
+    if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let exponent = exponent as usize;
+    let padded = format!("{:0>width$}", amount, width = exponent + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - exponent);
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        Some(whole.to_string())
+    } else {
+        Some(format!("{}.{}", whole, fraction))
+    }
+}
+
+/// Output layer rendering the amounts of every tool result in the
+/// selected format. Serialized messages (e.g. a built `cosmos_msg`) are
+/// strings, and are never changed, and the coins of `PAYLOAD_KEYS` keep
+/// their base amounts, so what gets signed is unaffected
+#[derive(Clone, Debug, Default)]
+pub struct AmountFormatter {
+    format: Arc<RwLock<AmountFormat>>,
+}
+
+impl AmountFormatter {
+    pub fn format(&self) -> AmountFormat {
+        self.format.read().map(|format| *format).unwrap_or_default()
+    }
+
+    pub fn set_format(&self, format: AmountFormat) {
+        if let Ok(mut current) = self.format.write() {
+            *current = format;
+        }
+    }
+
+    /// Apply the selected format to the coins of a JSON value
+    pub fn format_value(&self, value: &mut Value) {
+        let format = self.format();
+        if format != AmountFormat::Base {
+            Self::format_at(value, format);
+        }
+    }
+
+    fn format_at(value: &mut Value, format: AmountFormat) {
+        match value {
+            Value::Object(map) => {
+                let unit = match (map.get("denom"), map.get("amount")) {
+                    (Some(Value::String(denom)), Some(Value::String(amount))) => DENOM_UNITS
+                        .iter()
+                        .find(|(base, _, _)| base == denom)
+                        .and_then(|(_, display, exponent)| {
+                            to_display(amount, *exponent).map(|amount| (amount, *display))
+                        }),
+                    _ => None,
+                };
+                match (unit, format) {
+                    (Some((amount, denom)), AmountFormat::Display) => {
+                        map.insert("amount".to_string(), json!(amount));
+                        map.insert("denom".to_string(), json!(denom));
+                    }
+                    (Some((amount, denom)), _) => {
+                        map.insert(
+                            "display".to_string(),
+                            json!(format!("{} {}", amount, denom)),
+                        );
+                    }
+                    (None, _) => {
+                        for (key, value) in map.iter_mut() {
+                            let format = match format {
+                                AmountFormat::Display if PAYLOAD_KEYS.contains(&key.as_str()) => {
+                                    AmountFormat::Both
+                                }
+                                format => format,
+                            };
+                            Self::format_at(value, format);
+                        }
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for value in values.iter_mut() {
+                    Self::format_at(value, format);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Format JSON tool output; output that isn't JSON is left unchanged
+    pub fn format_text(&self, text: &str) -> String {
+        match serde_json::from_str::<Value>(text) {
+            Ok(mut value) if value.is_object() || value.is_array() => {
+                let original = value.clone();
+                self.format_value(&mut value);
+                if value == original {
+                    text.to_string()
+                } else {
+                    value.to_string()
+                }
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    pub fn format_result(&self, mut result: CallToolResult) -> CallToolResult {
+        if self.format() == AmountFormat::Base {
+            return result;
+        }
+        for content in result.content.iter_mut() {
+            if let RawContent::Text(text) = &mut content.raw {
+                text.text = self.format_text(&text.text);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,286 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{Coin, CosmosMsg, WasmMsg, to_json_binary};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cosmos::msg_execute_contract;
+use crate::tx;
+
+/// Serialization of built execute msgs, for the downstream signing stack
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MsgFormat {
+    /// CosmWasm `CosmosMsg` JSON
+    #[default]
+    Json,
+    /// Base64 encoded `google.protobuf.Any` of a `MsgExecuteContract`
+    /// (requires the sender)
+    Proto,
+    /// Base64 encoded CBOR of the `CosmosMsg`
+    Cbor,
+    /// Base64 encoded unsigned `cosmos.tx.v1beta1.TxRaw` of the msg, memo
+    /// and timeout height, with the sender's signer info and fee (requires
+    /// the sender)
+    #[serde(rename = "tx_raw")]
+    TxRaw,
+}
+
+/// An execute msg to be encoded
+pub struct ExecuteContract<'a> {
+    pub sender: Option<&'a str>,
+    pub contract: &'a str,
+    pub msg: &'a Value,
+    pub funds: &'a [Coin],
+    /// Serialized `CosmosMsg` of the msg
+    pub cosmos_msg: &'a str,
+    pub memo: &'a str,
+    /// Zero for no timeout height
+    pub timeout_height: u64,
+    /// Signer and fee of the `tx_raw` format
+    pub auth: Option<&'a TxAuth>,
+}
+
+/// Signer info and fee of an unsigned tx
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxAuth {
+    /// Compressed secp256k1 public key of the sender, if known
+    pub public_key: Option<Vec<u8>>,
+    pub sequence: u64,
+    pub fee: Vec<Coin>,
+    pub gas_limit: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EncodedMsg {
+    pub format: MsgFormat,
+    pub mime_type: String,
+    pub data: String,
+}
+
+/// JSON stringified `CosmosMsg` of a `WasmMsg::Execute` of a msg
+pub fn execute_cosmos_msg(contract: &str, msg: &Value, funds: Vec<Coin>) -> anyhow::Result<String> {
+    let cosmos_msg: CosmosMsg = WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: to_json_binary(msg)?,
+        funds,
+    }
+    .into();
+    Ok(serde_json::to_string(&cosmos_msg)?)
+}
+
+/// Unsigned `cosmos.tx.v1beta1.TxBody` of an execute msg, with its memo
+/// and timeout height
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TxBody {
+    /// Proto JSON of the body (`messages`, `memo` and `timeout_height`),
+    /// as in the `body` of an unsigned tx for `tx sign`
+    pub json: Value,
+    /// Base64 encoded protobuf of the body, the `body_bytes` of the
+    /// `SignDoc` and `TxRaw`
+    pub body_bytes: String,
+}
+
+/// Protobuf `TxBody` of an execute msg, its memo and timeout height
+fn body_bytes(execute: &ExecuteContract) -> anyhow::Result<Vec<u8>> {
+    let sender = execute
+        .sender
+        .ok_or_else(|| anyhow::anyhow!("the tx body requires the sender"))?;
+    let any = tx::msg_execute_contract(
+        sender,
+        execute.contract,
+        &serde_json::to_vec(execute.msg)?,
+        execute.funds,
+    );
+    Ok(tx::tx_body(vec![any], execute.memo, execute.timeout_height))
+}
+
+impl TxBody {
+    /// Body of an execute msg, its memo and timeout height
+    pub fn new(execute: &ExecuteContract) -> anyhow::Result<Self> {
+        let body_bytes = body_bytes(execute)?;
+        let sender = execute.sender.unwrap_or_default();
+        let json = serde_json::json!({
+            "messages": [msg_execute_contract(sender, execute.contract, execute.msg, execute.funds)],
+            "memo": execute.memo,
+            "timeout_height": execute.timeout_height.to_string(),
+            "extension_options": [],
+            "non_critical_extension_options": [],
+        });
+        Ok(Self {
+            json,
+            body_bytes: STANDARD.encode(body_bytes),
+        })
+    }
+}
+
+/// Encoding of execute msgs into one format
+pub trait MsgEncoder {
+    fn format(&self) -> MsgFormat;
+    fn mime_type(&self) -> &'static str;
+    fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<String>;
+}
+
+pub struct JsonMsgEncoder;
+
+impl MsgEncoder for JsonMsgEncoder {
+    fn format(&self) -> MsgFormat {
+        MsgFormat::Json
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<String> {
+        Ok(execute.cosmos_msg.to_string())
+    }
+}
+
+pub struct ProtoMsgEncoder;
+
+impl MsgEncoder for ProtoMsgEncoder {
+    fn format(&self) -> MsgFormat {
+        MsgFormat::Proto
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/x-protobuf"
+    }
+
+    fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<String> {
+        let sender = execute
+            .sender
+            .ok_or_else(|| anyhow::anyhow!("the proto format requires the sender"))?;
+        let any = tx::msg_execute_contract(
+            sender,
+            execute.contract,
+            &serde_json::to_vec(execute.msg)?,
+            execute.funds,
+        );
+        Ok(STANDARD.encode(any.finish()))
+    }
+}
+
+pub struct CborMsgEncoder;
+
+impl MsgEncoder for CborMsgEncoder {
+    fn format(&self) -> MsgFormat {
+        MsgFormat::Cbor
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/cbor"
+    }
+
+    fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<String> {
+        let cosmos_msg: Value = serde_json::from_str(execute.cosmos_msg)?;
+        let mut buf = vec![];
+        cbor(&mut buf, &cosmos_msg);
+        Ok(STANDARD.encode(buf))
+    }
+}
+
+pub struct TxRawMsgEncoder;
+
+impl MsgEncoder for TxRawMsgEncoder {
+    fn format(&self) -> MsgFormat {
+        MsgFormat::TxRaw
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/x-protobuf"
+    }
+
+    /// A `TxRaw` without signatures, to be signed over its body and auth
+    /// info bytes (`SIGN_MODE_DIRECT`)
+    fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<String> {
+        let auth = execute.auth.ok_or_else(|| {
+            anyhow::anyhow!("the tx_raw format requires the sender's signer info and fee")
+        })?;
+        let body = body_bytes(execute)?;
+        let auth_info = tx::auth_info(
+            auth.public_key.as_deref(),
+            auth.sequence,
+            &auth.fee,
+            auth.gas_limit,
+        );
+        Ok(STANDARD.encode(tx::tx_raw(&body, &auth_info, &[])))
+    }
+}
+
+impl MsgFormat {
+    pub fn encoder(&self) -> &'static dyn MsgEncoder {
+        match self {
+            MsgFormat::Json => &JsonMsgEncoder,
+            MsgFormat::Proto => &ProtoMsgEncoder,
+            MsgFormat::Cbor => &CborMsgEncoder,
+            MsgFormat::TxRaw => &TxRawMsgEncoder,
+        }
+    }
+
+    pub fn encode(&self, execute: &ExecuteContract) -> anyhow::Result<EncodedMsg> {
+        let encoder = self.encoder();
+        Ok(EncodedMsg {
+            format: encoder.format(),
+            mime_type: encoder.mime_type().to_string(),
+            data: encoder.encode(execute)?,
+        })
+    }
+}
+
+/// CBOR head of a major type and argument
+fn cbor_head(buf: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => buf.push(major | value as u8),
+        24..=0xff => buf.extend([major | 24, value as u8]),
+        0x100..=0xffff => {
+            buf.push(major | 25);
+            buf.extend((value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(major | 26);
+            buf.extend((value as u32).to_be_bytes());
+        }
+        _ => {
+            buf.push(major | 27);
+            buf.extend(value.to_be_bytes());
+        }
+    }
+}
+
+/// Encode a JSON value as CBOR (RFC 8949); map keys keep their order
+pub fn cbor(buf: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => buf.push(0xf6),
+        Value::Bool(false) => buf.push(0xf4),
+        Value::Bool(true) => buf.push(0xf5),
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(unsigned), _) => cbor_head(buf, 0, unsigned),
+            (None, Some(negative)) => cbor_head(buf, 1, (-1 - negative) as u64),
+            (None, None) => {
+                buf.push(0xfb);
+                buf.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        },
+        Value::String(text) => {
+            cbor_head(buf, 3, text.len() as u64);
+            buf.extend(text.as_bytes());
+        }
+        Value::Array(values) => {
+            cbor_head(buf, 4, values.len() as u64);
+            for value in values {
+                cbor(buf, value);
+            }
+        }
+        Value::Object(fields) => {
+            cbor_head(buf, 5, fields.len() as u64);
+            for (key, value) in fields {
+                cbor_head(buf, 3, key.len() as u64);
+                buf.extend(key.as_bytes());
+                cbor(buf, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::clock::now_rfc3339;
+
+/// Replace with the path endpoint scores are persisted to
+pub static ENDPOINT_STATS_PATH: &str = "endpoint_stats.json";
+
+/// Weight of the latest request in the moving averages of an endpoint
+pub static SCORE_SMOOTHING: f64 = 0.1;
+
+/// Latency endpoints without requests yet are scored with (as if they
+/// were reliable but slow), so they're tried after healthy endpoints
+pub static UNSCORED_LATENCY_MS: f64 = 1_000.0;
+
+/// Requests recorded between saves of the scores
+pub static SAVE_EVERY: u64 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EndpointStat {
+    pub endpoint: String,
+    pub requests: u64,
+    pub failures: u64,
+    /// Moving average of the success rate, from 0 to 1
+    pub success_rate: f64,
+    /// Moving average of the latency of successful requests
+    pub latency_ms: f64,
+    pub last_error: Option<String>,
+    pub last_used: Option<String>,
+}
+
+impl EndpointStat {
+    fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            requests: 0,
+            failures: 0,
+            success_rate: 1.0,
+            latency_ms: 0.0,
+            last_error: None,
+            last_used: None,
+        }
+    }
+
+    /// Higher is healthier: the success rate, discounted by latency
+    pub fn score(&self) -> f64 {
+        self.success_rate * 1_000.0 / (self.latency_ms + 100.0)
+    }
+}
+
+/// Success rate and latency of the endpoints used by the server,
+/// persisted to a JSON file so the healthiest endpoints are preferred
+/// across restarts
+#[derive(Clone, Debug)]
+pub struct EndpointScores {
+    path: PathBuf,
+    stats: Arc<RwLock<BTreeMap<String, EndpointStat>>>,
+}
+
+impl Default for EndpointScores {
+    fn default() -> Self {
+        Self::new(ENDPOINT_STATS_PATH)
+    }
+}
+
+impl EndpointScores {
+    /// Scores persisted to a file, starting over if it can't be read
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let stats = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            stats: Arc::new(RwLock::new(stats)),
+        }
+    }
+
+    /// Record the outcome of a request to an endpoint
+    pub fn record(&self, endpoint: &str, latency: Duration, error: Option<String>) {
+        let Ok(mut stats) = self.stats.write() else {
+            return;
+        };
+        let stat = stats
+            .entry(endpoint.to_string())
+            .or_insert_with(|| EndpointStat::new(endpoint));
+        let succeeded = if error.is_none() { 1.0 } else { 0.0 };
+        stat.success_rate += SCORE_SMOOTHING * (succeeded - stat.success_rate);
+        if error.is_none() {
+            let latency_ms = latency.as_secs_f64() * 1_000.0;
+            stat.latency_ms = if stat.requests == stat.failures {
+                latency_ms
+            } else {
+                stat.latency_ms + SCORE_SMOOTHING * (latency_ms - stat.latency_ms)
+            };
+        } else {
+            stat.failures += 1;
+            stat.last_error = error;
+        }
+        stat.requests += 1;
+        stat.last_used = Some(now_rfc3339());
+        let requests: u64 = stats.values().map(|stat| stat.requests).sum();
+        if requests % SAVE_EVERY == 0 {
+            if let Err(e) = serde_json::to_string_pretty(&*stats)
+                .map_err(anyhow::Error::from)
+                .and_then(|contents| Ok(std::fs::write(&self.path, contents)?))
+            {
+                tracing::warn!("saving endpoint stats failed: {}", e);
+            }
+        }
+    }
+
+    /// Endpoints ordered from healthiest to least healthy (ties keep
+    /// their order)
+    pub fn rank(&self, endpoints: &[&str]) -> Vec<String> {
+        let unscored = 1_000.0 / (UNSCORED_LATENCY_MS + 100.0);
+        let score = |endpoint: &str| {
+            self.stats
+                .read()
+                .ok()
+                .and_then(|stats| stats.get(endpoint).map(EndpointStat::score))
+                .unwrap_or(unscored)
+        };
+        let mut ranked: Vec<(String, f64)> = endpoints
+            .iter()
+            .map(|endpoint| (endpoint.to_string(), score(endpoint)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.into_iter().map(|(endpoint, _)| endpoint).collect()
+    }
+
+    /// Stats of the endpoints, healthiest first
+    pub fn stats(&self) -> Vec<EndpointStat> {
+        let mut stats: Vec<EndpointStat> = self
+            .stats
+            .read()
+            .map(|stats| stats.values().cloned().collect())
+            .unwrap_or_default();
+        stats.sort_by(|a, b| b.score().total_cmp(&a.score()));
+        stats
+    }
+}
@@ -0,0 +1,55 @@
+//! Entry points of msg schemas (the output of `cosmwasm schema`), shared
+//! by the adapters and `build.rs`, which includes this file as a module:
+//! it only depends on `serde_json`
+
+use serde_json::Value;
+
+/// Resolve a `#/definitions/..` reference of a schema
+pub fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    match schema["$ref"].as_str() {
+        Some(reference) => reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| root["definitions"].get(name))
+            .unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// Doc comment of a schema, on a single line
+pub fn description(schema: &Value) -> String {
+    schema["description"]
+        .as_str()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Entry points of a msg schema of a kind (e.g. `query`), as (name,
+/// description): the variants of a msg enum, or the msg of a struct
+pub fn entry_points(kind: &str, root: &Value) -> Vec<(String, String)> {
+    let Some(alternatives) = root["oneOf"].as_array().or(root["anyOf"].as_array()) else {
+        return match root.is_object() {
+            true => vec![(kind.to_string(), description(root))],
+            false => vec![],
+        };
+    };
+    let mut entry_points = vec![];
+    for alternative in alternatives {
+        let alternative = resolve(alternative, root);
+        for unit in alternative["enum"].as_array().into_iter().flatten() {
+            if let Some(name) = unit.as_str() {
+                entry_points.push((name.to_string(), description(alternative)));
+            }
+        }
+        for name in alternative["properties"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, _)| name)
+        {
+            entry_points.push((name.clone(), description(alternative)));
+        }
+    }
+    entry_points
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::contract::{CwContract, Network};
+
+/// Prefix of the environment variables overriding the server's settings,
+/// e.g. `CW_MCP_CONTRACT_MAINNET`, so containers can be deployed without
+/// baking addresses into the image
+pub static ENV_PREFIX: &str = "CW_MCP_";
+
+/// The `CW_MCP_*` variables, read once at startup
+static VARS: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
+    std::env::vars()
+        .filter(|(key, value)| key.starts_with(ENV_PREFIX) && !value.trim().is_empty())
+        .map(|(key, value)| (key, value.trim().to_string()))
+        .collect()
+});
+
+/// Value of `CW_MCP_<name>`, if it's set (and not empty)
+pub fn var(name: &str) -> Option<&'static str> {
+    VARS.get(&format!("{}{}", ENV_PREFIX, name))
+        .map(String::as_str)
+}
+
+/// Value of a network's `CW_MCP_<name>_MAINNET` or `CW_MCP_<name>_TESTNET`
+pub fn network_var(name: &str, network: &Network) -> Option<&'static str> {
+    let network = match network {
+        Network::Mainnet => "MAINNET",
+        Network::Testnet => "TESTNET",
+    };
+    var(&format!("{}_{}", name, network))
+}
+
+/// Endpoints of a comma separated list variable
+fn endpoints(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Override the configured deployments (of the contracts config, or the
+/// defaults) with the `CW_MCP_CONTRACT_*`, `CW_MCP_CHAIN_ID_*`,
+/// `CW_MCP_LCD_URL_*` and `CW_MCP_RPC_URL_*` variables of each network.
+/// The first deployment of a network is overridden; a network without one
+/// gets a deployment of `CW_MCP_CONTRACT_*`
+pub fn apply_overrides(mut contracts: Vec<CwContract>) -> Vec<CwContract> {
+    for network in [Network::Mainnet, Network::Testnet] {
+        let contract_address = network_var("CONTRACT", &network);
+        let position = contracts
+            .iter()
+            .position(|contract| contract.network == network);
+        let contract = match (position, contract_address) {
+            (Some(i), _) => &mut contracts[i],
+            (None, Some(contract_address)) => {
+                contracts.push(CwContract::new(
+                    network.clone(),
+                    network.chain_id(),
+                    contract_address,
+                ));
+                contracts.last_mut().expect("pushed deployment")
+            }
+            (None, None) => continue,
+        };
+        if let Some(contract_address) = contract_address {
+            contract.contract_address = contract_address.to_string();
+            // The configured code id and height are of another contract
+            contract.code_id = None;
+            contract.deployed_at = None;
+        }
+        if let Some(chain_id) = network_var("CHAIN_ID", &network) {
+            contract.chain_id = chain_id.to_string();
+        }
+        if let Some(lcd_endpoints) = network_var("LCD_URL", &network) {
+            contract.lcd_endpoints = endpoints(lcd_endpoints);
+        }
+        if let Some(rpc_endpoints) = network_var("RPC_URL", &network) {
+            contract.rpc_endpoints = endpoints(rpc_endpoints);
+        }
+    }
+    contracts
+}
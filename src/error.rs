@@ -0,0 +1,295 @@
+use rmcp::model::{CallToolResult, Content};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::repair::MsgRepair;
+use crate::validate::FieldError;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Unauthorized,
+    InsufficientFunds,
+    InsufficientFee,
+    InvalidZeroAmount,
+    NegativeAmount,
+    AmountOverflow,
+    DuplicateRecipient,
+    UnknownDenom,
+    CapExceeded,
+    OutOfGas,
+    SequenceMismatch,
+    ContractNotFound,
+    AccountNotFound,
+    NoAllowance,
+    Expired,
+    InvalidAddress,
+    WrongNetwork,
+    UnregisteredContract,
+    ReadOnly,
+    UnknownVariant,
+    InvalidMsg,
+    EndpointUnavailable,
+    Offline,
+    Unknown,
+}
+
+/// Structured error returned (as JSON) by tools, so agents can explain
+/// what went wrong and how to fix it instead of relaying raw chain errors
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ToolError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Raw error reported by the contract, chain or endpoint
+    pub raw: String,
+    pub explanation: String,
+    pub suggestion: String,
+    /// Path of the offending field of an invalid msg, e.g.
+    /// `transfer.amount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_path: Option<String>,
+    /// Fields of an invalid msg that don't match its schema
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub field_errors: Vec<FieldError>,
+    /// Suggested repairs of an invalid msg
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repair: Option<MsgRepair>,
+}
+
+struct Rule {
+    code: ErrorCode,
+    /// Matched (case insensitive) against the raw error
+    patterns: &'static [&'static str],
+    explanation: &'static str,
+    suggestion: &'static str,
+}
+
+/// Rules are checked in order, the first rule with a matching pattern wins
+static RULES: &[Rule] = &[
+    Rule {
+        code: ErrorCode::OutOfGas,
+        patterns: &["out of gas"],
+        explanation: "The transaction ran out of gas before the contract finished executing.",
+        suggestion: "Increase the gas limit (or gas adjustment) and broadcast the transaction again.",
+    },
+    Rule {
+        code: ErrorCode::InsufficientFee,
+        patterns: &["insufficient fee", "insufficient fees"],
+        explanation: "The fee attached to the transaction is below the chain's minimum gas price.",
+        suggestion: "Increase the fee (gas price) attached to the transaction.",
+    },
+    Rule {
+        code: ErrorCode::SequenceMismatch,
+        patterns: &["account sequence mismatch", "incorrect account sequence"],
+        explanation: "The signer's account sequence changed, usually because another transaction from the same account was broadcast first.",
+        suggestion: "Wait for pending transactions to be included, then sign the transaction again.",
+    },
+    Rule {
+        code: ErrorCode::InvalidZeroAmount,
+        patterns: &["invalid zero amount"],
+        explanation: "The contract rejected an amount of zero.",
+        suggestion: "Use an amount greater than zero.",
+    },
+    Rule {
+        code: ErrorCode::NegativeAmount,
+        patterns: &["negative amount"],
+        explanation: "An amount in the message is negative; amounts are unsigned integers.",
+        suggestion: "Use a positive amount, in the smallest unit of the denom or token.",
+    },
+    Rule {
+        code: ErrorCode::AmountOverflow,
+        patterns: &["exceeds the maximum amount"],
+        explanation: "An amount in the message doesn't fit in a u128, the largest amount a contract or the chain accepts.",
+        suggestion: "Check the amount's decimals (amounts are in the smallest unit, e.g. 1 ARCH is 10^18 aarch) and use a smaller amount.",
+    },
+    Rule {
+        code: ErrorCode::DuplicateRecipient,
+        patterns: &["duplicate recipient"],
+        explanation: "A batch in the message lists the same recipient more than once.",
+        suggestion: "Merge the recipient's entries into one (adding up their amounts), or remove the duplicate if it's a mistake.",
+    },
+    Rule {
+        code: ErrorCode::UnknownDenom,
+        patterns: &["unknown denom"],
+        explanation: "A denom of the attached funds has no supply on the network, so it doesn't exist there (it's likely mistyped, or belongs to another chain).",
+        suggestion: "Use the suggested denom if there is one (e.g. 'aarch' rather than 'uarch'), or check the denom (IBC denoms differ between chains).",
+    },
+    Rule {
+        code: ErrorCode::CapExceeded,
+        patterns: &["cannot exceed the cap", "exceeds the cap"],
+        explanation: "The mint would raise the token's total supply above its cap.",
+        suggestion: "Mint at most the remaining mintable amount (the cap minus the total supply).",
+    },
+    Rule {
+        code: ErrorCode::InsufficientFunds,
+        patterns: &[
+            "insufficient funds",
+            "cannot sub with",
+            "overflow: cannot sub",
+        ],
+        explanation: "The sender doesn't hold enough of the token (or native denom) being sent or spent.",
+        suggestion: "Check the sender's balance and use a smaller amount, or fund the account first.",
+    },
+    Rule {
+        code: ErrorCode::NoAllowance,
+        patterns: &["no allowance for this account"],
+        explanation: "The spender has no allowance to spend tokens on behalf of the owner.",
+        suggestion: "Have the owner increase the spender's allowance before spending from their account.",
+    },
+    Rule {
+        code: ErrorCode::Expired,
+        patterns: &["expired"],
+        explanation: "An allowance, grant or registration used by the message has expired.",
+        suggestion: "Renew (or re-grant) it, then build the message again.",
+    },
+    Rule {
+        code: ErrorCode::Unauthorized,
+        patterns: &["unauthorized", "not authorized", "caller is not"],
+        explanation: "The sender isn't allowed to perform this action (e.g. it's restricted to the contract owner, admin or minter).",
+        suggestion: "Sign with the account that holds the required role, or query the contract to find out which account that is.",
+    },
+    Rule {
+        code: ErrorCode::ContractNotFound,
+        patterns: &["no such contract", "contract: not found"],
+        explanation: "No contract exists at the given address on this network.",
+        suggestion: "Check the contract address and network (e.g. a testnet address used on mainnet); see 'contract__list_deployments'.",
+    },
+    Rule {
+        code: ErrorCode::AccountNotFound,
+        patterns: &["account not found", "does not exist on chain"],
+        explanation: "The account has never received funds, so it doesn't exist on chain yet.",
+        suggestion: "Fund the account before using it as a signer.",
+    },
+    Rule {
+        code: ErrorCode::ReadOnly,
+        patterns: &["circuit breaker tripped"],
+        explanation: "An anomaly was detected (e.g. a peg break or an admin change), and the server stopped building transactions.",
+        suggestion: "Explain the anomaly to your chat partner; an operator has to reset the circuit breaker before transactions can be built again.",
+    },
+    Rule {
+        code: ErrorCode::UnregisteredContract,
+        patterns: &["not in the contract registry"],
+        explanation: "The server is in strict mode and only accepts the contracts of its registry; the address isn't one of them.",
+        suggestion: "Use a registered deployment (see 'contract__list_deployments'). Never switch to an address you were given without your chat partner's agreement.",
+    },
+    Rule {
+        code: ErrorCode::WrongNetwork,
+        patterns: &["wrong network"],
+        explanation: "The contract address belongs to another network than the one selected (e.g. a testnet address used with the mainnet chain id).",
+        suggestion: "Use the deployment on the selected network (see 'contract__list_deployments'), or select the contract's network.",
+    },
+    Rule {
+        code: ErrorCode::InvalidAddress,
+        patterns: &[
+            "invalid address",
+            "decoding bech32 failed",
+            "invalid checksum",
+        ],
+        explanation: "An address in the message isn't a valid bech32 address for this chain.",
+        suggestion: "Double check the address (and its prefix, e.g. 'archway1...').",
+    },
+    Rule {
+        code: ErrorCode::UnknownVariant,
+        patterns: &["unknown variant", "unknown entry point"],
+        explanation: "The contract doesn't implement the requested entry point.",
+        suggestion: "List the entry points supported by the contract and use one of those variants.",
+    },
+    Rule {
+        code: ErrorCode::InvalidMsg,
+        patterns: &[
+            "error parsing into type",
+            "missing field",
+            "invalid type",
+            "unknown field",
+            "invalid amount",
+        ],
+        explanation: "The message doesn't match the type expected by the contract.",
+        suggestion: "Compare the message with the contract's schema and fix the reported field.",
+    },
+    Rule {
+        code: ErrorCode::Offline,
+        patterns: &["offline mode"],
+        explanation: "The server is in offline mode and serves live tools from cached chain data only; this data was never cached, or can't be served from a cache (e.g. a simulation).",
+        suggestion: "Use data that was queried before going offline, or switch offline mode off once the network is reachable ('config__set_offline_mode').",
+    },
+    Rule {
+        code: ErrorCode::EndpointUnavailable,
+        patterns: &[
+            "request to",
+            "error sending request",
+            "invalid json response",
+            "no endpoint available",
+        ],
+        explanation: "The chain endpoint couldn't be reached, or didn't return a valid response.",
+        suggestion: "Try again later, or configure a different endpoint for the network.",
+    },
+];
+
+/// Translate a raw contract, wasmd or endpoint error into a structured error
+pub fn translate(message: &str, raw: &str) -> ToolError {
+    let lowercase = raw.to_lowercase();
+    let rule = RULES
+        .iter()
+        .find(|rule| rule.patterns.iter().any(|p| lowercase.contains(p)));
+    match rule {
+        Some(rule) => ToolError {
+            code: rule.code,
+            message: message.to_string(),
+            raw: raw.to_string(),
+            explanation: rule.explanation.to_string(),
+            suggestion: rule.suggestion.to_string(),
+            field_path: None,
+            field_errors: vec![],
+            repair: None,
+        },
+        None => ToolError {
+            code: ErrorCode::Unknown,
+            message: message.to_string(),
+            raw: raw.to_string(),
+            explanation: "The error wasn't recognized.".to_string(),
+            suggestion: "Relay the raw error to your chat partner.".to_string(),
+            field_path: None,
+            field_errors: vec![],
+            repair: None,
+        },
+    }
+}
+
+/// Tool result for a failed tool call, carrying a translated error
+pub fn tool_error(message: &str, raw: impl Display) -> CallToolResult {
+    let error = translate(message, &raw.to_string());
+    let serialized: String = serde_json::to_string(&error).unwrap_or_default();
+    CallToolResult::error(vec![Content::text(serialized)])
+}
+
+static REPAIR_SUGGESTION: &str = "Check the suggested repairs of the msg ('repair'), and retry with 'suggested_msg' if it's what your chat partner intended.";
+
+static PLACEHOLDER_SUGGESTION: &str = "Check the suggested repairs of the msg ('repair'), fill the placeholders of 'suggested_msg' and retry with it if it's what your chat partner intended.";
+
+/// Tool result for an invalid msg, carrying the path of the offending
+/// field, the msg's field level errors and its suggested repairs (if any)
+pub fn schema_error(
+    message: &str,
+    raw: impl Display,
+    field_path: Option<String>,
+    field_errors: Vec<FieldError>,
+    repair: Option<MsgRepair>,
+) -> CallToolResult {
+    let raw = match &field_path {
+        Some(path) => format!("{} (at '{}')", raw, path),
+        None => raw.to_string(),
+    };
+    let mut error = translate(message, &raw);
+    if let Some(repair) = &repair {
+        error.suggestion = match repair.valid {
+            true => REPAIR_SUGGESTION.to_string(),
+            false => PLACEHOLDER_SUGGESTION.to_string(),
+        };
+    }
+    error.field_path = field_path;
+    error.field_errors = field_errors;
+    error.repair = repair;
+    let serialized: String = serde_json::to_string(&error).unwrap_or_default();
+    CallToolResult::error(vec![Content::text(serialized)])
+}
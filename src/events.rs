@@ -0,0 +1,200 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rpc::{LcdClient, url_encode};
+
+/// Attribute wasmd adds to every event emitted by a contract
+pub static CONTRACT_ADDRESS_ATTRIBUTE: &str = "_contract_address";
+
+/// Txs fetched per tx_search page
+pub static SCAN_PAGE_LIMIT: u64 = 50;
+
+/// Maximum number of pages fetched in a single scan, longer ranges are
+/// continued with the returned cursor
+pub static MAX_SCAN_PAGES: u64 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EventAttribute {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractEvent {
+    pub height: u64,
+    pub tx_hash: String,
+    /// Event type, e.g. `wasm` or a custom `wasm-{type}` event
+    pub event_type: String,
+    pub attributes: Vec<EventAttribute>,
+}
+
+/// Position of a scan, so long ranges can be resumed where they stopped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScanCursor {
+    pub contract_addr: String,
+    pub from_height: u64,
+    pub to_height: u64,
+    pub page: u64,
+}
+
+impl ScanCursor {
+    /// Opaque string form of the cursor returned to agents
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(cursor: &str) -> anyhow::Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|e| anyhow!("invalid cursor: {}", e))?;
+        serde_json::from_slice(&bytes).map_err(|e| anyhow!("invalid cursor: {}", e))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EventScan {
+    pub events: Vec<ContractEvent>,
+    /// Number of txs scanned (so far) in the range
+    pub txs_scanned: u64,
+    pub total_txs: u64,
+    /// Cursor to continue the scan with, if the range wasn't exhausted
+    pub next_cursor: Option<String>,
+}
+
+fn attribute<'a>(event: &'a Value, key: &str) -> Option<&'a str> {
+    event["attributes"]
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute["key"].as_str() == Some(key))
+        .and_then(|attribute| attribute["value"].as_str())
+}
+
+/// Extract the events emitted by a contract from a tx response, optionally
+/// only those of one event type
+pub fn contract_events(
+    tx_response: &Value,
+    contract_addr: &str,
+    event_type: Option<&str>,
+) -> Vec<ContractEvent> {
+    let height = match &tx_response["height"] {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_u64().unwrap_or_default(),
+    };
+    let tx_hash = tx_response["txhash"].as_str().unwrap_or_default();
+    tx_response["events"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|event| {
+            let ty = event["type"].as_str().unwrap_or_default();
+            let is_wasm = ty == "wasm" || ty.starts_with("wasm-");
+            is_wasm
+                && event_type.is_none_or(|event_type| event_type == ty)
+                && attribute(event, CONTRACT_ADDRESS_ATTRIBUTE) == Some(contract_addr)
+        })
+        .map(|event| ContractEvent {
+            height,
+            tx_hash: tx_hash.to_string(),
+            event_type: event["type"].as_str().unwrap_or_default().to_string(),
+            attributes: event["attributes"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|attribute| EventAttribute {
+                    key: attribute["key"].as_str().unwrap_or_default().to_string(),
+                    value: attribute["value"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct EventScanner {
+    lcd: LcdClient,
+}
+
+impl EventScanner {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Height of the latest block of a network
+    pub async fn latest_height(&self, endpoint: &str) -> anyhow::Result<u64> {
+        let body = self
+            .lcd
+            .get(endpoint, "/cosmos/base/tendermint/v1beta1/blocks/latest")
+            .await?;
+        body["block"]["header"]["height"]
+            .as_str()
+            .and_then(|height| height.parse().ok())
+            .ok_or_else(|| anyhow!("latest block has no height"))
+    }
+
+    /// Scan up to `MAX_SCAN_PAGES` pages of txs (oldest first) sent to a
+    /// contract within a height range, collecting its events
+    pub async fn scan(
+        &self,
+        endpoint: &str,
+        cursor: ScanCursor,
+        event_type: Option<&str>,
+    ) -> anyhow::Result<EventScan> {
+        if cursor.from_height > cursor.to_height {
+            anyhow::bail!(
+                "invalid height range {}..{}",
+                cursor.from_height,
+                cursor.to_height
+            );
+        }
+        let query = format!(
+            "wasm.{}='{}' AND tx.height>={} AND tx.height<={}",
+            CONTRACT_ADDRESS_ATTRIBUTE, cursor.contract_addr, cursor.from_height, cursor.to_height
+        );
+        let mut events = vec![];
+        let mut page = cursor.page.max(1);
+        let mut total_txs = 0;
+        let mut txs_scanned = (page - 1) * SCAN_PAGE_LIMIT;
+        let mut exhausted = false;
+        for _ in 0..MAX_SCAN_PAGES {
+            let path = format!(
+                "/cosmos/tx/v1beta1/txs?query={}&order_by=ORDER_BY_ASC&page={}&limit={}",
+                url_encode(&query),
+                page,
+                SCAN_PAGE_LIMIT
+            );
+            let body = self.lcd.get(endpoint, &path).await?;
+            total_txs = match &body["total"] {
+                Value::String(s) => s.parse().unwrap_or_default(),
+                value => value.as_u64().unwrap_or_default(),
+            };
+            let responses = body["tx_responses"].as_array().cloned().unwrap_or_default();
+            txs_scanned += responses.len() as u64;
+            for response in &responses {
+                events.extend(contract_events(response, &cursor.contract_addr, event_type));
+            }
+            page += 1;
+            exhausted = responses.is_empty() || txs_scanned >= total_txs;
+            if exhausted {
+                break;
+            }
+        }
+        let next_cursor = (!exhausted).then(|| {
+            ScanCursor {
+                page,
+                ..cursor.clone()
+            }
+            .encode()
+        });
+        Ok(EventScan {
+            events,
+            txs_scanned,
+            total_txs,
+            next_cursor,
+        })
+    }
+}
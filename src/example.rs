@@ -0,0 +1,186 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::repair::{Variant, resolve, variants};
+
+/// Replace with example values of the custom types of your contract's
+/// msgs, by schema definition name
+pub static EXAMPLE_VALUES: &[(&str, &str)] = &[
+    ("Uint64", "1"),
+    ("Uint128", "1000000"),
+    ("Uint256", "1000000"),
+    ("Decimal", "0.5"),
+    ("Decimal256", "0.5"),
+    // base64 of `{}`
+    ("Binary", "e30="),
+    ("Timestamp", "1700000000000000000"),
+];
+
+/// Depth past which nested (e.g. recursive) types aren't expanded
+static MAX_EXAMPLE_DEPTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum MsgType {
+    Execute,
+    Query,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExampleMsg {
+    pub msg_type: MsgType,
+    pub entry_point: String,
+    pub description: Option<String>,
+    pub example: Value,
+    /// The example, JSON stringified as the build tools take it
+    pub example_msg: String,
+    pub required_fields: Vec<String>,
+    /// Fields that may be added to the example
+    pub optional_fields: Vec<String>,
+    /// Whether the example deserializes as the msg type (placeholders
+    /// aside, which must still be replaced)
+    pub valid: bool,
+}
+
+/// Example value of a field, from its schema: `<field>` placeholders for
+/// strings, `EXAMPLE_VALUES` for known types, and the first alternative
+/// of enums
+pub fn example_value(schema: &Value, root: &Value, field: &str, depth: usize) -> Value {
+    if let Some(name) = schema["$ref"]
+        .as_str()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+    {
+        if let Some((_, example)) = EXAMPLE_VALUES
+            .iter()
+            .find(|(type_name, _)| *type_name == name)
+        {
+            return Value::String(example.to_string());
+        }
+    }
+    let schema = resolve(schema, root);
+    if depth > MAX_EXAMPLE_DEPTH {
+        return Value::Null;
+    }
+    if let Some(first) = schema["enum"].as_array().and_then(|values| values.first()) {
+        return first.clone();
+    }
+    for keyword in ["oneOf", "anyOf", "allOf"] {
+        if let Some(alternatives) = schema[keyword].as_array() {
+            // The first alternative that isn't null (e.g. the `T` of an
+            // `Option<T>`)
+            let alternative = alternatives
+                .iter()
+                .find(|alternative| alternative["type"] != "null")
+                .or(alternatives.first());
+            if let Some(alternative) = alternative {
+                return example_value(alternative, root, field, depth + 1);
+            }
+        }
+    }
+    let primary = match &schema["type"] {
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| *kind != "null")
+            .unwrap_or("null")
+            .to_string(),
+        Value::String(kind) => kind.clone(),
+        _ if schema["properties"].is_object() => "object".to_string(),
+        _ => String::new(),
+    };
+    match primary.as_str() {
+        "string" => Value::String(format!("<{}>", field)),
+        "integer" => Value::from(schema["minimum"].as_u64().unwrap_or(1).max(1)),
+        "number" => Value::from(1),
+        "boolean" => Value::Bool(false),
+        "array" => Value::Array(vec![example_value(
+            &schema["items"],
+            root,
+            field,
+            depth + 1,
+        )]),
+        "object" => {
+            let mut object = Map::new();
+            let required: Vec<&str> = schema["required"]
+                .as_array()
+                .map(|required| required.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            if let Some(properties) = schema["properties"].as_object() {
+                for (name, property) in properties {
+                    if required.contains(&name.as_str()) {
+                        object.insert(name.clone(), example_value(property, root, name, depth + 1));
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        _ => Value::Null,
+    }
+}
+
+/// Example msg of a variant, with its required fields
+pub fn example_msg(variant: &Variant, root: &Value) -> Value {
+    if variant.unit {
+        return Value::String(variant.name.clone());
+    }
+    let mut msg = Map::new();
+    msg.insert(
+        variant.name.clone(),
+        example_value(&variant.body, root, &variant.name, 0),
+    );
+    Value::Object(msg)
+}
+
+/// Description (doc comment) of a variant, from the alternative of the
+/// schema defining it
+pub fn variant_description(variant: &Variant, root: &Value) -> Option<String> {
+    let alternatives = root["oneOf"].as_array().or(root["anyOf"].as_array())?;
+    alternatives
+        .iter()
+        .map(|alternative| resolve(alternative, root))
+        .find(|alternative| {
+            alternative["properties"].get(&variant.name).is_some()
+                || alternative["enum"]
+                    .as_array()
+                    .is_some_and(|units| units.iter().any(|unit| unit == &variant.name))
+        })
+        .and_then(|alternative| alternative["description"].as_str())
+        .map(str::to_string)
+}
+
+/// Example msg of the entry point of a msg schema, or the names of the
+/// schema's entry points if it has none of that name. The returned
+/// example isn't yet checked against the msg type (`valid` is false)
+pub fn generate(
+    msg_type: MsgType,
+    schema: &Value,
+    entry_point: &str,
+) -> Result<ExampleMsg, Vec<String>> {
+    let variants = variants(schema);
+    let variant = variants
+        .iter()
+        .find(|variant| variant.name == entry_point)
+        .ok_or_else(|| {
+            variants
+                .iter()
+                .map(|variant| variant.name.clone())
+                .collect::<Vec<String>>()
+        })?;
+    let example = example_msg(variant, schema);
+    let optional_fields = variant
+        .fields
+        .iter()
+        .filter(|field| !variant.required.contains(field))
+        .cloned()
+        .collect();
+    Ok(ExampleMsg {
+        msg_type,
+        entry_point: variant.name.clone(),
+        description: variant_description(variant, schema),
+        example_msg: serde_json::to_string(&example).unwrap_or_default(),
+        example,
+        required_fields: variant.required.clone(),
+        optional_fields,
+        valid: false,
+    })
+}
@@ -1,8 +1,113 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::encoding::{EncodedMsg, MsgFormat, TxBody};
+use crate::label::AddressLabel;
+use crate::mint::MintCheck;
+use crate::pause::PauseCheck;
+use crate::provenance::BuildMetadata;
+use crate::resolver::NameResolution;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ValidatedExecute {
     pub execute_msg: String,
     pub cosmos_msg: String,
+    pub address_labels: Vec<AddressLabel>,
+    pub name_resolutions: Vec<NameResolution>,
+    /// Minter and cap of the token, for cw20 mints
+    pub mint_check: Option<MintCheck>,
+    /// Pause state of the contract, if it was checked (`check_paused`)
+    pub pause_check: Option<PauseCheck>,
+    /// Problems found while building the message, which may make the
+    /// transaction fail
+    pub warnings: Vec<String>,
+    /// Memo the transaction must be broadcast with (the server's
+    /// watermark), if one is configured
+    pub memo: Option<String>,
+    /// Block height past which the transaction can't be included, if one
+    /// is set
+    pub timeout_height: Option<u64>,
+    /// The msg in the requested format, if it isn't the JSON `cosmos_msg`
+    pub encoded_msg: Option<EncodedMsg>,
+    /// Unsigned tx body of the msg, memo and timeout height (built when
+    /// the sender is given)
+    pub tx_body: Option<TxBody>,
+    pub metadata: BuildMetadata,
+}
+
+impl ValidatedExecute {
+    /// Canonical serialization of everything that ends up signed (the
+    /// msg, memo, timeout height, encoded msg and tx body), the payload of
+    /// the provenance content hash
+    pub fn signed_payload(&self) -> String {
+        json!({
+            "cosmos_msg": self.cosmos_msg,
+            "memo": self.memo,
+            "timeout_height": self.timeout_height,
+            "encoded_msg": self.encoded_msg,
+            "tx_body": self.tx_body,
+        })
+        .to_string()
+    }
+}
+
+/// Arguments of `build_execute_msg`, for applications building execute
+/// msgs without an MCP client (e.g. the Python and Node bindings)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecuteRequest {
+    pub contract_addr: Option<String>,
+    /// JSON stringified ExecuteMsg variant
+    pub execute_msg: String,
+    pub payment: Option<String>,
+    pub payment_denom: Option<String>,
+    /// JSON stringified array of coins
+    pub funds: Option<String>,
+    pub format: Option<MsgFormat>,
+    pub sender: Option<String>,
+    pub check_paused: Option<bool>,
+    pub contract_label: Option<String>,
+    pub memo: Option<String>,
+    pub timeout_height: Option<u64>,
+    pub gas_limit: Option<u64>,
+    pub sequence: Option<u64>,
+    pub public_key: Option<String>,
+}
+
+/// A built `WasmMsg::Instantiate`, deploying a new instance of a code id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatedInstantiate {
+    pub code_id: u64,
+    pub label: String,
+    pub admin: Option<String>,
+    pub instantiate_msg: String,
+    pub cosmos_msg: String,
+    pub address_labels: Vec<AddressLabel>,
+    pub name_resolutions: Vec<NameResolution>,
+    pub warnings: Vec<String>,
+    pub memo: Option<String>,
+    pub metadata: BuildMetadata,
+}
+
+/// Replace with the `MigrateMsg` of your contract (e.g.
+/// `cw20_wrap::msg::MigrateMsg`), so migrate msgs are validated against
+/// its schema; by default any JSON msg is accepted
+pub type MigrateMsg = serde_json::Value;
+
+/// A built `WasmMsg::Migrate`, migrating a contract to a new code id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatedMigrate {
+    pub contract: String,
+    /// Code id the contract runs, if it could be queried
+    pub current_code_id: Option<u64>,
+    pub new_code_id: u64,
+    /// Admin of the contract, the only address that can sign the migration
+    pub admin: Option<String>,
+    pub migrate_msg: String,
+    pub cosmos_msg: String,
+    pub address_labels: Vec<AddressLabel>,
+    pub warnings: Vec<String>,
+    pub memo: Option<String>,
+    pub metadata: BuildMetadata,
 }
@@ -0,0 +1,183 @@
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::broadcast::GAS_PRICE;
+use crate::events::CONTRACT_ADDRESS_ATTRIBUTE;
+use crate::mempool::MempoolStatus;
+use crate::rpc::{LcdClient, url_encode};
+
+/// Recent txs sampled for a fee suggestion
+pub static FEE_SAMPLE_TXS: u64 = 50;
+
+/// Samples of the contract's txs below which the txs of every contract
+/// are sampled instead
+pub static MIN_CONTRACT_SAMPLES: usize = 5;
+
+/// Percentile of the sampled gas prices suggested, so the fee is higher
+/// than most recent fees and the tx confirms quickly
+pub static FAST_PERCENTILE: usize = 75;
+
+static EXECUTE_ACTION: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+/// Fee paid by a recent tx, per unit of its gas limit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSample {
+    pub tx_hash: String,
+    pub height: u64,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    pub fee: Coin,
+    /// Fee per unit of gas wanted, in the fee denom's base unit
+    pub gas_price: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSuggestion {
+    pub fee_denom: String,
+    /// Contract whose txs were sampled, or none if the executions of every
+    /// contract were
+    pub sampled_contract: Option<String>,
+    pub samples: usize,
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+    pub min_gas_price: Option<String>,
+    pub median_gas_price: Option<String>,
+    /// `FAST_PERCENTILE` of the sampled gas prices
+    pub fast_gas_price: Option<String>,
+    /// Static gas price of the server (`GAS_PRICE`), the suggestion's floor
+    pub static_gas_price: String,
+    /// Mempool of the network, if it could be read; the suggested gas
+    /// price is raised while it's busy or congested
+    pub mempool: Option<MempoolStatus>,
+    pub suggested_gas_price: String,
+    pub gas_limit: Option<u64>,
+    /// Fee of `gas_limit` at the suggested gas price
+    pub suggested_fee: Option<Coin>,
+    /// Most recent of the sampled fees
+    pub recent_fees: Vec<FeeSample>,
+}
+
+/// Fee of a tx in a denom, if it paid one
+fn sample(response: &Value, fee_denom: &str) -> Option<FeeSample> {
+    let gas_wanted: u64 = response["gas_wanted"].as_str()?.parse().ok()?;
+    let fee = response["tx"]["auth_info"]["fee"]["amount"]
+        .as_array()?
+        .iter()
+        .find(|coin| coin["denom"] == fee_denom)?;
+    let amount: u128 = fee["amount"].as_str()?.parse().ok()?;
+    if gas_wanted == 0 || amount == 0 {
+        return None;
+    }
+    Some(FeeSample {
+        tx_hash: response["txhash"].as_str().unwrap_or_default().to_string(),
+        height: response["height"].as_str()?.parse().ok()?,
+        gas_wanted,
+        gas_used: response["gas_used"]
+            .as_str()
+            .and_then(|gas| gas.parse().ok())
+            .unwrap_or_default(),
+        fee: Coin {
+            denom: fee_denom.to_string(),
+            amount: Uint128::from(amount),
+        },
+        gas_price: (amount / u128::from(gas_wanted)).to_string(),
+    })
+}
+
+/// Samples the fees of recent contract executions
+#[derive(Clone, Debug)]
+pub struct FeeHistory {
+    lcd: LcdClient,
+}
+
+impl FeeHistory {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    async fn samples(
+        &self,
+        endpoint: &str,
+        contract_addr: Option<&str>,
+        fee_denom: &str,
+    ) -> anyhow::Result<Vec<FeeSample>> {
+        let mut query = format!("message.action='{}'", EXECUTE_ACTION);
+        if let Some(contract_addr) = contract_addr {
+            query.push_str(&format!(
+                " AND wasm.{}='{}'",
+                CONTRACT_ADDRESS_ATTRIBUTE, contract_addr
+            ));
+        }
+        let path = format!(
+            "/cosmos/tx/v1beta1/txs?query={}&order_by=ORDER_BY_DESC&page=1&limit={}",
+            url_encode(&query),
+            FEE_SAMPLE_TXS
+        );
+        let body = self.lcd.get(endpoint, &path).await?;
+        Ok(body["tx_responses"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|response| response["code"].as_u64().unwrap_or_default() == 0)
+            .filter_map(|response| sample(response, fee_denom))
+            .collect())
+    }
+
+    /// Suggest a gas price (and the fee of a gas limit) from the fees of
+    /// the contract's recent executions, falling back to the executions
+    /// of every contract when the contract has too few. The suggestion is
+    /// never below `GAS_PRICE`, and is raised by the mempool's congestion
+    pub async fn suggest(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        fee_denom: &str,
+        gas_limit: Option<u64>,
+        mempool: Option<MempoolStatus>,
+    ) -> anyhow::Result<FeeSuggestion> {
+        let mut sampled_contract = Some(contract_addr.to_string());
+        let mut samples = self
+            .samples(endpoint, Some(contract_addr), fee_denom)
+            .await?;
+        if samples.len() < MIN_CONTRACT_SAMPLES {
+            sampled_contract = None;
+            samples = self.samples(endpoint, None, fee_denom).await?;
+        }
+        let mut prices: Vec<u128> = samples
+            .iter()
+            .filter_map(|sample| sample.gas_price.parse().ok())
+            .collect();
+        prices.sort_unstable();
+        let percentile = |percent: usize| {
+            let i = (prices.len() * percent / 100).min(prices.len().saturating_sub(1));
+            prices.get(i).copied()
+        };
+        let fast = percentile(FAST_PERCENTILE);
+        let fee_percent = mempool
+            .as_ref()
+            .map(|mempool| mempool.fee_percent)
+            .unwrap_or(100);
+        let suggested = fast.unwrap_or(GAS_PRICE).max(GAS_PRICE) * fee_percent / 100;
+        Ok(FeeSuggestion {
+            fee_denom: fee_denom.to_string(),
+            sampled_contract,
+            samples: samples.len(),
+            from_height: samples.iter().map(|sample| sample.height).min(),
+            to_height: samples.iter().map(|sample| sample.height).max(),
+            min_gas_price: prices.first().map(u128::to_string),
+            median_gas_price: percentile(50).map(|price| price.to_string()),
+            fast_gas_price: fast.map(|price| price.to_string()),
+            static_gas_price: GAS_PRICE.to_string(),
+            mempool,
+            suggested_gas_price: suggested.to_string(),
+            gas_limit,
+            suggested_fee: gas_limit.map(|gas_limit| Coin {
+                denom: fee_denom.to_string(),
+                amount: Uint128::from(u128::from(gas_limit) * suggested),
+            }),
+            recent_fees: samples.into_iter().take(10).collect(),
+        })
+    }
+}
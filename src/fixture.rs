@@ -0,0 +1,212 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::contract::NATIVE_DENOM_MAINNET;
+use crate::label::is_address;
+use crate::sandbox::{SANDBOX_BALANCE, SANDBOX_OWNER, SANDBOX_PREFIX, Sandbox, SandboxCase};
+
+/// Replace with the crate of the contract, as imported by its tests
+pub static FIXTURE_CONTRACT_CRATE: &str = "cw20_wrap";
+
+/// Dev-dependencies the generated tests need
+pub static FIXTURE_DEV_DEPENDENCIES: &[&str] = &["cw-multi-test = \"2.5\"", "serde_json = \"1\""];
+
+/// Outcome of a case in the sandbox, asserted by the generated test
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CaseOutcome {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TestFixture {
+    pub test_name: String,
+    /// Rust source of the test
+    pub code: String,
+    pub dev_dependencies: Vec<String>,
+    pub outcomes: Vec<CaseOutcome>,
+}
+
+/// Snake case Rust identifier of a name, e.g. `Transfer to Bob` ->
+/// `transfer_to_bob`
+pub fn identifier(name: &str) -> String {
+    let mut identifier = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            identifier.push(c.to_ascii_lowercase());
+        } else if !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+    }
+    let identifier = identifier.trim_matches('_').to_string();
+    match identifier.chars().next() {
+        None => "case".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("case_{}", identifier),
+        Some(_) => identifier,
+    }
+}
+
+/// Variables of the generated test, which accounts can't be bound to
+static RESERVED_VARIABLES: &[&str] = &["api", "app", "code_id", "contract", "err", "msg", "res"];
+
+/// Variable an account's address is bound to
+fn account_var(account: &str) -> String {
+    let variable = identifier(account);
+    if RESERVED_VARIABLES.contains(&variable.as_str()) {
+        format!("{}_addr", variable)
+    } else {
+        variable
+    }
+}
+
+/// Raw string literal of a JSON msg
+fn raw_string(json: &str) -> String {
+    let mut hashes = "#".to_string();
+    while json.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}\"{}\"{}", hashes, json, hashes)
+}
+
+/// Expression of a sender: sandbox names become `addr_make` addresses
+/// bound to a variable, bech32 addresses are used unchecked
+fn sender_expr(sender: &str) -> String {
+    if is_address(sender) {
+        format!("Addr::unchecked(\"{}\")", sender)
+    } else {
+        format!("{}.clone()", account_var(sender))
+    }
+}
+
+/// Run the cases in a fresh sandbox, in order, recording their outcomes
+pub fn record_outcomes(
+    instantiate_msg: &Value,
+    cases: &[SandboxCase],
+) -> anyhow::Result<Vec<CaseOutcome>> {
+    let senders: Vec<&str> = cases.iter().map(SandboxCase::sender).collect();
+    let mut sandbox = Sandbox::new(instantiate_msg, &senders)?;
+    Ok(cases
+        .iter()
+        .map(|case| {
+            let profile = sandbox.execute(case);
+            CaseOutcome {
+                name: profile.name,
+                success: profile.success,
+                error: profile.error,
+            }
+        })
+        .collect())
+}
+
+/// A cw-multi-test test instantiating the contract and running the cases,
+/// asserting the recorded outcomes
+pub fn generate(
+    test_name: &str,
+    instantiate_msg: &Value,
+    cases: &[SandboxCase],
+    outcomes: Vec<CaseOutcome>,
+) -> anyhow::Result<TestFixture> {
+    if let Some(invalid) = outcomes.iter().find_map(|outcome| {
+        outcome
+            .error
+            .as_ref()
+            .filter(|error| error.starts_with("invalid ExecuteMsg"))
+            .map(|error| format!("case '{}' has an {}", outcome.name, error))
+    }) {
+        anyhow::bail!(invalid);
+    }
+    let test_name = identifier(test_name);
+    let mut accounts: Vec<String> = vec![SANDBOX_OWNER.to_string()];
+    for case in cases {
+        let sender = case.sender();
+        if !is_address(sender) && !accounts.iter().any(|account| account == sender) {
+            accounts.push(sender.to_string());
+        }
+    }
+    let mut code = format!(
+        "use cosmwasm_std::testing::MockApi;\n\
+         use cosmwasm_std::{{Addr, Coin, coins}};\n\
+         use cw_multi_test::{{AppBuilder, ContractWrapper, Executor}};\n\
+         use {krate}::contract::{{execute, instantiate, query}};\n\
+         use {krate}::msg::{{ExecuteMsg, InstantiateMsg}};\n\
+         \n\
+         #[test]\n\
+         fn {test_name}() {{\n\
+         \x20   let api = MockApi::default().with_prefix(\"{prefix}\");\n",
+        krate = FIXTURE_CONTRACT_CRATE,
+        prefix = SANDBOX_PREFIX,
+    );
+    for account in &accounts {
+        code.push_str(&format!(
+            "    let {} = api.addr_make(\"{}\");\n",
+            account_var(account),
+            account
+        ));
+    }
+    let funded: Vec<String> = accounts
+        .iter()
+        .map(|account| format!("&{}", account_var(account)))
+        .collect();
+    code.push_str(&format!(
+        "    let mut app = AppBuilder::new().with_api(api).build(|router, _api, storage| {{\n\
+         \x20       for account in [{funded}] {{\n\
+         \x20           router\n\
+         \x20               .bank\n\
+         \x20               .init_balance(storage, account, coins({balance}, \"{denom}\"))\n\
+         \x20               .unwrap();\n\
+         \x20       }}\n\
+         \x20   }});\n\
+         \x20   let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));\n\
+         \x20   let msg: InstantiateMsg = serde_json::from_str({msg}).unwrap();\n\
+         \x20   let contract = app\n\
+         \x20       .instantiate_contract(code_id, {owner}.clone(), &msg, &[], \"fixture\", Some({owner}.to_string()))\n\
+         \x20       .unwrap();\n",
+        funded = funded.join(", "),
+        balance = SANDBOX_BALANCE,
+        denom = NATIVE_DENOM_MAINNET,
+        msg = raw_string(&instantiate_msg.to_string()),
+        owner = account_var(SANDBOX_OWNER),
+    ));
+    for (case, outcome) in cases.iter().zip(&outcomes) {
+        let funds: Vec<String> = case
+            .funds
+            .iter()
+            .map(|coin| format!("Coin::new({}u128, \"{}\")", coin.amount, coin.denom))
+            .collect();
+        code.push_str(&format!(
+            "\n\
+             \x20   // {name}\n\
+             \x20   let msg: ExecuteMsg = serde_json::from_str({msg}).unwrap();\n\
+             \x20   let res = app.execute_contract({sender}, contract.clone(), &msg, &[{funds}]);\n",
+            name = outcome.name.replace('\n', " "),
+            msg = raw_string(&case.execute_msg.to_string()),
+            sender = sender_expr(case.sender()),
+            funds = funds.join(", "),
+        ));
+        match (&outcome.success, &outcome.error) {
+            (true, _) => code.push_str("    assert!(res.is_ok(), \"{:?}\", res.err());\n"),
+            (false, Some(error)) => {
+                // The root cause is the contract error (e.g. `Unauthorized`)
+                let root_cause = error.rsplit(": ").next().unwrap_or(error);
+                code.push_str(&format!(
+                    "    let err = res.unwrap_err();\n\
+                     \x20   assert!(err.root_cause().to_string().contains({}), \"{{:?}}\", err);\n",
+                    raw_string(root_cause)
+                ));
+            }
+            (false, None) => code.push_str("    assert!(res.is_err());\n"),
+        }
+    }
+    code.push_str("}\n");
+    Ok(TestFixture {
+        test_name,
+        code,
+        dev_dependencies: FIXTURE_DEV_DEPENDENCIES
+            .iter()
+            .map(|dependency| dependency.to_string())
+            .collect(),
+        outcomes,
+    })
+}
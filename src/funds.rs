@@ -0,0 +1,85 @@
+use cosmwasm_std::{Coin, Uint128};
+use serde::Deserialize;
+
+use crate::semantic::parse_amount;
+
+/// Coin of the `funds` of a transaction, with its amount unchecked
+#[derive(Deserialize)]
+struct FundsCoin {
+    denom: String,
+    amount: serde_json::Value,
+}
+
+/// Funds as the chain accepts them: sorted by denom, with the coins of
+/// a denom sent more than once merged into a single coin of their total
+pub fn normalized_funds(funds: Vec<Coin>) -> anyhow::Result<Vec<Coin>> {
+    let mut merged: Vec<Coin> = vec![];
+    for coin in funds {
+        let denom = coin.denom.trim();
+        if denom.is_empty() {
+            anyhow::bail!("funds must have a denom");
+        }
+        match merged.iter_mut().find(|merged| merged.denom == denom) {
+            Some(merged) => {
+                merged.amount = merged.amount.checked_add(coin.amount).map_err(|_| {
+                    anyhow::anyhow!(
+                        "the total amount of {} in the funds exceeds the maximum amount ({})",
+                        denom,
+                        Uint128::MAX
+                    )
+                })?;
+            }
+            None => merged.push(Coin {
+                denom: denom.to_string(),
+                amount: coin.amount,
+            }),
+        }
+    }
+    merged.sort_by(|a, b| a.denom.cmp(&b.denom));
+    if let Some(coin) = merged.iter().find(|coin| coin.amount.is_zero()) {
+        anyhow::bail!("invalid zero amount of {} in the funds", coin.denom);
+    }
+    Ok(merged)
+}
+
+/// Native funds of a transaction: the `payment` of `payment_denom`, and
+/// the coins of `funds` (a JSON array, e.g. `[{"denom": "aarch", "amount":
+/// "1000"}]`), normalized as the chain requires (see `normalized_funds`).
+/// A `payment` without its `payment_denom` (or the reverse) is an error,
+/// rather than funds silently left out
+pub fn native_funds(
+    payment: Option<String>,
+    payment_denom: Option<String>,
+    funds: Option<String>,
+) -> anyhow::Result<Vec<Coin>> {
+    let coins: Vec<FundsCoin> = match funds {
+        Some(funds) => serde_json::from_str(&funds).map_err(|e| {
+            anyhow::anyhow!(
+                "invalid funds, expected a JSON array of {{\"denom\", \"amount\"}} coins: {}",
+                e
+            )
+        })?,
+        None => vec![],
+    };
+    let mut funds = vec![];
+    for (i, coin) in coins.into_iter().enumerate() {
+        let amount = match &coin.amount {
+            serde_json::Value::String(amount) => amount.clone(),
+            amount => amount.to_string(),
+        };
+        funds.push(Coin {
+            denom: coin.denom,
+            amount: parse_amount(&format!("funds[{}].amount", i), &amount)?,
+        });
+    }
+    match (payment, payment_denom) {
+        (Some(payment), Some(denom)) => funds.push(Coin {
+            denom,
+            amount: parse_amount("payment", &payment)?,
+        }),
+        (Some(_), None) => anyhow::bail!("a 'payment' needs its 'payment_denom'"),
+        (None, Some(_)) => anyhow::bail!("a 'payment_denom' needs its 'payment' amount"),
+        (None, None) => {}
+    }
+    normalized_funds(funds)
+}
@@ -0,0 +1,196 @@
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::rpc::LcdClient;
+
+/// Maximum number of proposals returned by a listing
+pub static MAX_GOV_PROPOSALS: u64 = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum ProposalStatus {
+    DepositPeriod,
+    VotingPeriod,
+    Passed,
+    Rejected,
+    Failed,
+}
+
+impl ProposalStatus {
+    pub fn as_proto(&self) -> &'static str {
+        match self {
+            ProposalStatus::DepositPeriod => "PROPOSAL_STATUS_DEPOSIT_PERIOD",
+            ProposalStatus::VotingPeriod => "PROPOSAL_STATUS_VOTING_PERIOD",
+            ProposalStatus::Passed => "PROPOSAL_STATUS_PASSED",
+            ProposalStatus::Rejected => "PROPOSAL_STATUS_REJECTED",
+            ProposalStatus::Failed => "PROPOSAL_STATUS_FAILED",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovProposal {
+    pub id: u64,
+    pub title: String,
+    pub summary: String,
+    pub status: String,
+    /// Type URLs of the messages executed if the proposal passes
+    pub message_types: Vec<String>,
+    /// True if a message of the proposal references a deployment of the
+    /// contract (e.g. migrating it, or changing its rewards)
+    pub affects_contract: bool,
+    pub total_deposit: Vec<Coin>,
+    pub deposit_end_time: Option<String>,
+    pub voting_end_time: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TallyResult {
+    pub proposal_id: u64,
+    pub yes: Uint128,
+    pub abstain: Uint128,
+    pub no: Uint128,
+    pub no_with_veto: Uint128,
+    /// Share of each option in the votes cast so far (percent)
+    pub yes_percent: f64,
+    pub abstain_percent: f64,
+    pub no_percent: f64,
+    pub no_with_veto_percent: f64,
+}
+
+fn parse_u64(value: &Value) -> u64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        _ => value.as_u64().unwrap_or_default(),
+    }
+}
+
+fn parse_uint(value: &Value) -> Uint128 {
+    Uint128::from_str(value.as_str().unwrap_or_default()).unwrap_or_default()
+}
+
+fn parse_proposal(proposal: &Value, contracts: &[String]) -> GovProposal {
+    let messages = proposal["messages"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let serialized = proposal["messages"].to_string();
+    GovProposal {
+        id: parse_u64(&proposal["id"]),
+        title: proposal["title"].as_str().unwrap_or_default().to_string(),
+        summary: proposal["summary"].as_str().unwrap_or_default().to_string(),
+        status: proposal["status"].as_str().unwrap_or_default().to_string(),
+        message_types: messages
+            .iter()
+            .filter_map(|msg| msg["@type"].as_str().map(str::to_string))
+            .collect(),
+        affects_contract: contracts
+            .iter()
+            .any(|contract| serialized.contains(contract.as_str())),
+        total_deposit: serde_json::from_value(proposal["total_deposit"].clone())
+            .unwrap_or_default(),
+        deposit_end_time: proposal["deposit_end_time"].as_str().map(str::to_string),
+        voting_end_time: proposal["voting_end_time"].as_str().map(str::to_string),
+    }
+}
+
+fn percent(part: Uint128, total: Uint128) -> f64 {
+    if total.is_zero() {
+        return 0.0;
+    }
+    part.u128() as f64 * 100.0 / total.u128() as f64
+}
+
+/// Client for the x/gov (v1) module
+#[derive(Clone, Debug)]
+pub struct Gov {
+    lcd: LcdClient,
+}
+
+impl Gov {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Most recent proposals with a status, newest first
+    pub async fn proposals(
+        &self,
+        endpoint: &str,
+        status: &ProposalStatus,
+        contracts: &[String],
+    ) -> anyhow::Result<Vec<GovProposal>> {
+        let path = format!(
+            "/cosmos/gov/v1/proposals?proposal_status={}&pagination.reverse=true&pagination.limit={}",
+            status.as_proto(),
+            MAX_GOV_PROPOSALS
+        );
+        let body = self.lcd.get(endpoint, &path).await?;
+        Ok(body["proposals"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|proposal| parse_proposal(proposal, contracts))
+            .collect())
+    }
+
+    pub async fn proposal(
+        &self,
+        endpoint: &str,
+        proposal_id: u64,
+        contracts: &[String],
+    ) -> anyhow::Result<GovProposal> {
+        let path = format!("/cosmos/gov/v1/proposals/{}", proposal_id);
+        let body = self.lcd.get(endpoint, &path).await?;
+        Ok(parse_proposal(&body["proposal"], contracts))
+    }
+
+    /// Current tally of a proposal (the final tally, once voting ended)
+    pub async fn tally(&self, endpoint: &str, proposal_id: u64) -> anyhow::Result<TallyResult> {
+        let path = format!("/cosmos/gov/v1/proposals/{}/tally", proposal_id);
+        let body = self.lcd.get(endpoint, &path).await?;
+        let tally = &body["tally"];
+        let yes = parse_uint(&tally["yes_count"]);
+        let abstain = parse_uint(&tally["abstain_count"]);
+        let no = parse_uint(&tally["no_count"]);
+        let no_with_veto = parse_uint(&tally["no_with_veto_count"]);
+        let total = yes + abstain + no + no_with_veto;
+        Ok(TallyResult {
+            proposal_id,
+            yes_percent: percent(yes, total),
+            abstain_percent: percent(abstain, total),
+            no_percent: percent(no, total),
+            no_with_veto_percent: percent(no_with_veto, total),
+            yes,
+            abstain,
+            no,
+            no_with_veto,
+        })
+    }
+
+    /// Warnings for voting on (or depositing to) a proposal whose status
+    /// doesn't allow it
+    pub async fn status_warnings(
+        &self,
+        endpoint: &str,
+        proposal_id: u64,
+        allowed: &[ProposalStatus],
+    ) -> Vec<String> {
+        match self.proposal(endpoint, proposal_id, &[]).await {
+            Ok(proposal)
+                if !allowed
+                    .iter()
+                    .any(|status| status.as_proto() == proposal.status) =>
+            {
+                vec![format!(
+                    "proposal {} is {}, which doesn't accept this message",
+                    proposal_id, proposal.status
+                )]
+            }
+            Ok(_) => vec![],
+            Err(e) => vec![format!("proposal couldn't be checked: {}", e)],
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::LazyLock;
+
+use crate::rpc::{ContractVersion, LcdClient};
+
+/// Queries commonly exposing a contract's configuration (and the
+/// addresses of the contracts it depends on); unsupported ones are skipped
+pub static CONFIG_QUERIES: &[&str] = &[
+    "config",
+    "get_config",
+    "state",
+    "minter",
+    "ownership",
+    "pair",
+    "token_info",
+];
+
+pub static MAX_GRAPH_DEPTH: u8 = 3;
+pub static MAX_GRAPH_NODES: usize = 50;
+
+static ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[a-z]{2,20}1[ac-hj-np-z02-9]{38,58}$").expect("valid address pattern")
+});
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GraphNode {
+    pub address: String,
+    /// False for accounts (e.g. an owner or minter wallet)
+    pub is_contract: bool,
+    pub code_id: Option<u64>,
+    pub label: Option<String>,
+    pub cw2: Option<ContractVersion>,
+    /// Config queries answered by the contract
+    pub queries: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Query and field the address was found at, e.g. `config.router`
+    pub relation: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DependencyGraph {
+    pub root: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// True if the node limit was reached before the graph was complete
+    pub truncated: bool,
+}
+
+/// Addresses in a JSON value, with the path of the field holding them
+fn find_addresses(value: &Value, path: &str, found: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(s) if ADDRESS.is_match(s) => found.push((path.to_string(), s.clone())),
+        Value::Object(map) => {
+            for (key, value) in map {
+                find_addresses(value, &format!("{}.{}", path, key), found);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                find_addresses(value, &format!("{}[]", path), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GraphExplorer {
+    lcd: LcdClient,
+}
+
+impl GraphExplorer {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Inspect a contract, returning its node and (if `follow`) the
+    /// addresses referenced by its config
+    async fn inspect(
+        &self,
+        endpoint: &str,
+        address: &str,
+        follow: bool,
+    ) -> (GraphNode, Vec<(String, String)>) {
+        let mut node = GraphNode {
+            address: address.to_string(),
+            is_contract: false,
+            code_id: None,
+            label: None,
+            cw2: None,
+            queries: vec![],
+        };
+        let Ok(info) = self.lcd.contract_info(endpoint, address).await else {
+            return (node, vec![]);
+        };
+        node.is_contract = true;
+        node.code_id = info["code_id"].as_str().and_then(|id| id.parse().ok());
+        node.label = info["label"].as_str().map(str::to_string);
+        node.cw2 = self.lcd.cw2_version(endpoint, address).await.ok().flatten();
+        let mut references = vec![];
+        if !follow {
+            return (node, references);
+        }
+        for admin_field in ["admin", "creator"] {
+            if let Some(admin) = info[admin_field].as_str().filter(|a| ADDRESS.is_match(a)) {
+                references.push((format!("contract_info.{}", admin_field), admin.to_string()));
+            }
+        }
+        for query in CONFIG_QUERIES {
+            let msg = json!({ *query: {} });
+            if let Ok(response) = self.lcd.smart_query(endpoint, address, &msg).await {
+                node.queries.push(query.to_string());
+                find_addresses(&response, query, &mut references);
+            }
+        }
+        (node, references)
+    }
+
+    /// Breadth first discovery of the contracts (and accounts) referenced
+    /// by a contract, up to `depth` hops away
+    pub async fn discover(
+        &self,
+        endpoint: &str,
+        root: &str,
+        depth: u8,
+    ) -> anyhow::Result<DependencyGraph> {
+        let depth = depth.clamp(1, MAX_GRAPH_DEPTH);
+        let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+        let mut edges: BTreeSet<(String, String, String)> = BTreeSet::new();
+        let mut queue: VecDeque<(String, u8)> = VecDeque::from([(root.to_string(), 0)]);
+        let mut truncated = false;
+        while let Some((address, hops)) = queue.pop_front() {
+            if nodes.contains_key(&address) {
+                continue;
+            }
+            if nodes.len() >= MAX_GRAPH_NODES {
+                truncated = true;
+                break;
+            }
+            let (node, references) = self.inspect(endpoint, &address, hops < depth).await;
+            if hops == 0 && !node.is_contract {
+                anyhow::bail!("{} isn't a contract", address);
+            }
+            for (relation, to) in references {
+                if to == address {
+                    continue;
+                }
+                queue.push_back((to.clone(), hops + 1));
+                edges.insert((address.clone(), to, relation));
+            }
+            nodes.insert(address, node);
+        }
+        // Edges to addresses left unexplored (once the node limit is
+        // reached) are dropped
+        let edges: Vec<GraphEdge> = edges
+            .into_iter()
+            .filter(|(_, to, _)| nodes.contains_key(to))
+            .map(|(from, to, relation)| GraphEdge { from, to, relation })
+            .collect();
+        Ok(DependencyGraph {
+            root: root.to_string(),
+            nodes: nodes.into_values().collect(),
+            edges,
+            truncated,
+        })
+    }
+}
@@ -0,0 +1,333 @@
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Json, Object, Schema, SimpleObject,
+};
+use async_graphql_axum::GraphQLRequest;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use axum::routing::get;
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{LazyLock, RwLock};
+
+use crate::alert::{Alert, AlertRule, Alerts};
+use crate::cache::{CachedResponse, ChainCache};
+use crate::clock::now_rfc3339;
+use crate::contract::{CwContract, configured_or_default_contracts};
+use crate::redact::Redactor;
+use crate::registry::{CONTRACT_REGISTRY_PATH, ContractRegistry};
+use crate::secrets::{LazySecret, Secrets, token_matches};
+use crate::spend::{DenomSpend, SpendLedger};
+use crate::watch::Watcher;
+
+/// Replace with the path the GraphQL endpoint is served at, next to the
+/// MCP endpoint (or on the bind address, with the stdio transport)
+pub static GRAPHQL_PATH: &str = "/graphql";
+
+/// Replace with a secret reference of the bearer token dashboards must
+/// send (e.g. `Some("env:MCP_GRAPHQL_TOKEN")`, see `src/secrets.rs`); the
+/// endpoint isn't served without one
+pub static GRAPHQL_TOKEN: Option<&str> = None;
+
+/// Sessions whose alerts, watches and cached responses are reported; the
+/// oldest are dropped first
+pub static MAX_DASHBOARD_SESSIONS: usize = 32;
+
+/// Query paths of cached responses holding balances
+static BALANCE_QUERIES: &[&str] = &["/cosmos/bank/v1beta1/balances/", "/smart/"];
+
+/// Live state of an MCP session
+#[derive(Clone, Debug)]
+struct SessionState {
+    started_at: String,
+    cache: ChainCache,
+    alerts: Alerts,
+    watcher: Watcher,
+}
+
+static SESSIONS: LazyLock<RwLock<VecDeque<SessionState>>> =
+    LazyLock::new(|| RwLock::new(VecDeque::new()));
+
+/// Report the state of a new session to dashboards
+pub fn track_session(cache: &ChainCache, alerts: &Alerts, watcher: &Watcher) {
+    if let Ok(mut sessions) = SESSIONS.write() {
+        if sessions.len() >= MAX_DASHBOARD_SESSIONS {
+            sessions.pop_front();
+        }
+        sessions.push_back(SessionState {
+            started_at: now_rfc3339(),
+            cache: cache.clone(),
+            alerts: alerts.clone(),
+            watcher: watcher.clone(),
+        });
+    }
+}
+
+fn sessions() -> Vec<SessionState> {
+    SESSIONS
+        .read()
+        .map(|sessions| sessions.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[derive(SimpleObject)]
+struct DeploymentNode {
+    network: String,
+    chain_id: String,
+    contract_address: String,
+    bech32_prefix: String,
+    fee_denom: String,
+    explorer_url: Option<String>,
+    code_id: Option<u64>,
+    deployed_at: Option<u64>,
+    /// False for contracts registered at runtime
+    configured: bool,
+    registry_label: Option<String>,
+    registered_at: Option<String>,
+}
+
+impl DeploymentNode {
+    fn new(contract: CwContract, registry: &ContractRegistry) -> Self {
+        let registered = registry
+            .registered()
+            .into_iter()
+            .find(|entry| entry.contract_address == contract.contract_address);
+        Self {
+            network: format!("{:?}", contract.network),
+            explorer_url: contract.explorer_url(),
+            configured: registry.is_configured(&contract.contract_address),
+            registry_label: registered.as_ref().and_then(|entry| entry.label.clone()),
+            registered_at: registered.map(|entry| entry.registered_at),
+            chain_id: contract.chain_id,
+            contract_address: contract.contract_address,
+            bech32_prefix: contract.bech32_prefix,
+            fee_denom: contract.fee_denom,
+            code_id: contract.code_id,
+            deployed_at: contract.deployed_at,
+        }
+    }
+}
+
+/// A balance served by a query cached in the chain cache
+#[derive(SimpleObject)]
+struct CachedBalance {
+    /// Holder of the balance
+    address: String,
+    /// cw20 contract of the balance, none for bank balances
+    token: Option<String>,
+    /// The bank balances (coins), or the cw20 balance
+    balance: Json<Value>,
+    cached_at: String,
+    /// Cache key (endpoint and query path) of the response
+    key: String,
+}
+
+/// Balance of a cached response, if it's a bank balance query or a cw20
+/// `balance` query
+fn cached_balance(key: String, cached: CachedResponse) -> Option<CachedBalance> {
+    if let Some((_, rest)) = key.split_once(BALANCE_QUERIES[0]) {
+        let address = rest.split(['/', '?']).next()?.to_string();
+        let balance = match cached.body.get("balances") {
+            Some(balances) => balances.clone(),
+            None => cached.body.get("balance")?.clone(),
+        };
+        return Some(CachedBalance {
+            address,
+            token: None,
+            balance: Json(balance),
+            cached_at: cached.cached_at,
+            key,
+        });
+    }
+    let (_, rest) = key.split_once("/cosmwasm/wasm/v1/contract/")?;
+    let (contract, encoded) = rest.split_once("/smart/")?;
+    let encoded = encoded.split('@').next()?;
+    let msg: Value = serde_json::from_slice(&URL_SAFE.decode(encoded).ok()?).ok()?;
+    let address = msg["balance"]["address"].as_str()?.to_string();
+    Some(CachedBalance {
+        address,
+        token: Some(contract.to_string()),
+        balance: Json(cached.body["data"]["balance"].clone()),
+        cached_at: cached.cached_at,
+        key,
+    })
+}
+
+#[derive(SimpleObject)]
+struct SessionNode {
+    started_at: String,
+    /// URIs of the session's watched `state://` resources
+    watched: Vec<String>,
+    alert_rules: Json<Vec<AlertRule>>,
+    /// Rules whose condition currently holds
+    firing: Vec<String>,
+}
+
+/// State shared by the queries of the endpoint
+struct Dashboard {
+    configured: Vec<CwContract>,
+    /// Chain cache as persisted at startup, for balances cached by previous
+    /// runs of the server
+    persisted: ChainCache,
+    spend: SpendLedger,
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Deployments of the registry, configured and registered at runtime
+    async fn deployments(&self, ctx: &Context<'_>) -> Vec<DeploymentNode> {
+        let dashboard = ctx.data_unchecked::<Dashboard>();
+        // Contracts are registered by the sessions' registries, which
+        // persist them
+        let registry = ContractRegistry::load(dashboard.configured.clone(), CONTRACT_REGISTRY_PATH)
+            .unwrap_or_else(|_| {
+                ContractRegistry::new(dashboard.configured.clone(), CONTRACT_REGISTRY_PATH)
+            });
+        registry
+            .all()
+            .into_iter()
+            .map(|contract| DeploymentNode::new(contract, &registry))
+            .collect()
+    }
+
+    /// Bank and cw20 balances of the chain cache, newest first; optionally
+    /// of an address
+    async fn cached_balances(
+        &self,
+        ctx: &Context<'_>,
+        address: Option<String>,
+    ) -> Vec<CachedBalance> {
+        let dashboard = ctx.data_unchecked::<Dashboard>();
+        let mut responses: BTreeMap<String, CachedResponse> = BTreeMap::new();
+        let caches = std::iter::once(dashboard.persisted.clone())
+            .chain(sessions().into_iter().map(|session| session.cache));
+        for cache in caches {
+            let cached = BALANCE_QUERIES
+                .iter()
+                .flat_map(|fragment| cache.matching(fragment));
+            for (key, cached) in cached {
+                let newer = responses
+                    .get(&key)
+                    .is_none_or(|current| current.cached_at < cached.cached_at);
+                if newer {
+                    responses.insert(key, cached);
+                }
+            }
+        }
+        let mut balances: Vec<CachedBalance> = responses
+            .into_iter()
+            .filter_map(|(key, cached)| cached_balance(key, cached))
+            .filter(|balance| {
+                address
+                    .as_ref()
+                    .is_none_or(|address| &balance.address == address)
+            })
+            .collect();
+        balances.sort_by(|a, b| b.cached_at.cmp(&a.cached_at));
+        balances
+    }
+
+    /// Alerts raised by the sessions, most recent first
+    async fn alert_history(&self, limit: Option<usize>) -> Vec<Alert> {
+        let mut alerts: Vec<Alert> = sessions()
+            .into_iter()
+            .flat_map(|session| session.alerts.status().triggered)
+            .collect();
+        alerts.sort_by(|a, b| b.triggered_at.cmp(&a.triggered_at));
+        alerts.truncate(limit.unwrap_or(usize::MAX));
+        alerts
+    }
+
+    /// Amounts broadcast within the last 24h, per denom, and their caps
+    async fn spending(&self, ctx: &Context<'_>) -> async_graphql::Result<Json<Vec<DenomSpend>>> {
+        let dashboard = ctx.data_unchecked::<Dashboard>();
+        Ok(Json(dashboard.spend.status()?))
+    }
+
+    /// Watches and alert rules of the sessions
+    async fn sessions(&self) -> Vec<SessionNode> {
+        sessions()
+            .into_iter()
+            .map(|session| {
+                let status = session.alerts.status();
+                SessionNode {
+                    started_at: session.started_at,
+                    watched: session.watcher.watched(),
+                    alert_rules: Json(status.rules),
+                    firing: status.firing,
+                }
+            })
+            .collect()
+    }
+}
+
+type DashboardSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+fn schema() -> DashboardSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(Dashboard {
+            configured: configured_or_default_contracts(),
+            persisted: ChainCache::default(),
+            spend: SpendLedger::default(),
+        })
+        .finish()
+}
+
+/// The GraphQL endpoint, answering dashboards sending the bearer token
+#[derive(Clone)]
+struct Endpoint {
+    schema: DashboardSchema,
+    token: LazySecret,
+    redactor: Redactor,
+}
+
+impl Endpoint {
+    async fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Ok(token) = self.token.get().await else {
+            tracing::warn!("graphql token couldn't be resolved");
+            return false;
+        };
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, token.expose()))
+    }
+}
+
+/// Answer a query, its response passing through the redaction rules like
+/// the output of every tool
+async fn query(
+    State(endpoint): State<Endpoint>,
+    headers: HeaderMap,
+    request: GraphQLRequest,
+) -> (StatusCode, axum::Json<Value>) {
+    if !endpoint.authorized(&headers).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            axum::Json(json!({ "error": "missing or invalid bearer token" })),
+        );
+    }
+    let response = endpoint.schema.execute(request.into_inner()).await;
+    let mut response = serde_json::to_value(&response).unwrap_or_default();
+    endpoint.redactor.redact_value(&mut response);
+    (StatusCode::OK, axum::Json(response))
+}
+
+/// Router serving the (read-only) GraphQL endpoint at `GRAPHQL_PATH`, if a
+/// `GRAPHQL_TOKEN` is configured
+pub fn router() -> Option<axum::Router> {
+    let token = GRAPHQL_TOKEN?;
+    let endpoint = Endpoint {
+        schema: schema(),
+        token: LazySecret::new(token, Secrets::default()),
+        redactor: Redactor::default(),
+    };
+    Some(
+        axum::Router::new()
+            .route(GRAPHQL_PATH, get(query).post(query))
+            .with_state(endpoint),
+    )
+}
@@ -0,0 +1,162 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::is_past;
+use crate::rpc::LcdClient;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GroupProposal {
+    pub id: u64,
+    pub group_policy_address: String,
+    /// e.g. `PROPOSAL_STATUS_SUBMITTED`, `PROPOSAL_STATUS_ACCEPTED`
+    pub status: String,
+    /// e.g. `PROPOSAL_EXECUTOR_RESULT_NOT_RUN`
+    pub executor_result: String,
+    pub voting_period_end: Option<String>,
+}
+
+fn parse_u64(value: &Value) -> u64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        _ => value.as_u64().unwrap_or_default(),
+    }
+}
+
+/// Client for the x/group module, used to warn about group transactions
+/// that would fail (e.g. proposers that aren't members)
+#[derive(Clone, Debug)]
+pub struct Groups {
+    lcd: LcdClient,
+}
+
+impl Groups {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Members of the group administered by a group policy
+    pub async fn policy_members(
+        &self,
+        endpoint: &str,
+        group_policy_address: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let path = format!(
+            "/cosmos/group/v1/group_policy_info/{}",
+            group_policy_address
+        );
+        let info = self.lcd.get(endpoint, &path).await?;
+        let group_id = parse_u64(&info["info"]["group_id"]);
+        let path = format!("/cosmos/group/v1/group_members/{}", group_id);
+        let members = self.lcd.get(endpoint, &path).await?;
+        Ok(members["members"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|member| member["member"]["address"].as_str().map(str::to_string))
+            .collect())
+    }
+
+    pub async fn proposal(
+        &self,
+        endpoint: &str,
+        proposal_id: u64,
+    ) -> anyhow::Result<GroupProposal> {
+        let path = format!("/cosmos/group/v1/proposal/{}", proposal_id);
+        let body = self.lcd.get(endpoint, &path).await?;
+        let proposal = &body["proposal"];
+        Ok(GroupProposal {
+            id: parse_u64(&proposal["id"]),
+            group_policy_address: proposal["group_policy_address"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            status: proposal["status"].as_str().unwrap_or_default().to_string(),
+            executor_result: proposal["executor_result"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            voting_period_end: proposal["voting_period_end"].as_str().map(str::to_string),
+        })
+    }
+
+    /// Warnings for submitting a proposal to a group policy
+    pub async fn proposal_warnings(
+        &self,
+        endpoint: &str,
+        group_policy_address: &str,
+        proposers: &[String],
+    ) -> Vec<String> {
+        match self.policy_members(endpoint, group_policy_address).await {
+            Ok(members) => proposers
+                .iter()
+                .filter(|proposer| !members.contains(proposer))
+                .map(|proposer| {
+                    format!(
+                        "{} isn't a member of the group of {}, and can't submit proposals",
+                        proposer, group_policy_address
+                    )
+                })
+                .collect(),
+            Err(e) => vec![format!("group policy couldn't be checked: {}", e)],
+        }
+    }
+
+    /// Warnings for voting on a proposal
+    pub async fn vote_warnings(
+        &self,
+        endpoint: &str,
+        proposal_id: u64,
+        voter: &str,
+    ) -> Vec<String> {
+        let proposal = match self.proposal(endpoint, proposal_id).await {
+            Ok(proposal) => proposal,
+            Err(e) => return vec![format!("proposal couldn't be checked: {}", e)],
+        };
+        let mut warnings = vec![];
+        if proposal.status != "PROPOSAL_STATUS_SUBMITTED" {
+            warnings.push(format!(
+                "proposal {} is {}, votes are only accepted while it's submitted",
+                proposal_id, proposal.status
+            ));
+        } else if proposal.voting_period_end.as_deref().is_some_and(is_past) {
+            warnings.push(format!(
+                "the voting period of proposal {} has ended",
+                proposal_id
+            ));
+        }
+        match self
+            .policy_members(endpoint, &proposal.group_policy_address)
+            .await
+        {
+            Ok(members) if !members.iter().any(|member| member == voter) => {
+                warnings.push(format!(
+                    "{} isn't a member of the group, and can't vote",
+                    voter
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => warnings.push(format!("group members couldn't be checked: {}", e)),
+        }
+        warnings
+    }
+
+    /// Warnings for executing a proposal
+    pub async fn exec_warnings(&self, endpoint: &str, proposal_id: u64) -> Vec<String> {
+        match self.proposal(endpoint, proposal_id).await {
+            Ok(proposal) if proposal.status != "PROPOSAL_STATUS_ACCEPTED" => vec![format!(
+                "proposal {} is {}, only accepted proposals can be executed",
+                proposal_id, proposal.status
+            )],
+            Ok(proposal) if proposal.executor_result == "PROPOSAL_EXECUTOR_RESULT_SUCCESS" => {
+                vec![format!(
+                    "proposal {} has already been executed",
+                    proposal_id
+                )]
+            }
+            Ok(_) => vec![],
+            Err(e) => vec![format!("proposal couldn't be checked: {}", e)],
+        }
+    }
+}
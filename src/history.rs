@@ -0,0 +1,213 @@
+use anyhow::anyhow;
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::rpc::LcdClient;
+use crate::supply::HOLDERS_PAGE_LIMIT;
+
+/// Replace with the default number of blocks between samples
+pub static DEFAULT_SAMPLE_INTERVAL: u64 = 10_000;
+
+/// Samples of a series when its start height is omitted
+pub static DEFAULT_SAMPLES: u64 = 24;
+
+/// Maximum number of samples of a series
+pub static MAX_SAMPLES: u64 = 100;
+
+/// Maximum number of `all_accounts` pages counted per sample, larger
+/// holder counts are lower bounds
+pub static MAX_HOLDER_PAGES: usize = 50;
+
+/// Total supply (and holder count) of a token at a block height
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplySample {
+    pub height: u64,
+    pub total_supply: Uint128,
+    /// Accounts with a stored balance (cw20 enumerable `all_accounts`),
+    /// which may include emptied accounts; `None` if they weren't counted
+    pub holders: Option<usize>,
+    /// False if the count stopped at `MAX_HOLDER_PAGES`
+    pub holders_complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplySeries {
+    pub contract: String,
+    pub from_height: u64,
+    pub to_height: u64,
+    pub interval: u64,
+    /// Samples, oldest first, for charting
+    pub samples: Vec<SupplySample>,
+    /// Samples served from the cache of previous series
+    pub cached_samples: usize,
+    /// Heights that couldn't be sampled (e.g. pruned by the node, which
+    /// requires an archive endpoint)
+    pub errors: Vec<String>,
+}
+
+/// Heights of a series, `to_height` included
+pub fn sample_heights(from_height: u64, to_height: u64, interval: u64) -> anyhow::Result<Vec<u64>> {
+    if interval == 0 {
+        anyhow::bail!("the sample interval must be at least 1 block");
+    }
+    if from_height > to_height {
+        anyhow::bail!("invalid height range {}..{}", from_height, to_height);
+    }
+    let samples = (to_height - from_height) / interval + 1;
+    if samples > MAX_SAMPLES {
+        anyhow::bail!(
+            "{} samples exceed the maximum of {}, raise the interval",
+            samples,
+            MAX_SAMPLES
+        );
+    }
+    let mut heights: Vec<u64> = (0..samples).map(|i| from_height + i * interval).collect();
+    if heights.last() != Some(&to_height) {
+        heights.push(to_height);
+    }
+    Ok(heights)
+}
+
+/// Samples the supply of tokens at past heights; samples are kept, so
+/// series overlapping earlier ones only query the new heights
+#[derive(Clone, Debug)]
+pub struct SupplyHistory {
+    lcd: LcdClient,
+    samples: Arc<RwLock<BTreeMap<(String, u64), SupplySample>>>,
+}
+
+impl SupplyHistory {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self {
+            lcd,
+            samples: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Cached sample of a height, if it holds what's asked for
+    fn cached(&self, contract_addr: &str, height: u64, holders: bool) -> Option<SupplySample> {
+        self.samples
+            .read()
+            .ok()?
+            .get(&(contract_addr.to_string(), height))
+            .filter(|sample| !holders || sample.holders.is_some())
+            .cloned()
+    }
+
+    async fn holders_at(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        height: u64,
+    ) -> anyhow::Result<(usize, bool)> {
+        let mut holders = 0;
+        let mut start_after: Option<String> = None;
+        for _ in 0..MAX_HOLDER_PAGES {
+            let page = self
+                .lcd
+                .smart_query_at(
+                    endpoint,
+                    contract_addr,
+                    &json!({ "all_accounts": {
+                        "start_after": start_after,
+                        "limit": HOLDERS_PAGE_LIMIT,
+                    }}),
+                    Some(height),
+                )
+                .await
+                .map_err(|e| anyhow!("holders of {} can't be enumerated ({})", contract_addr, e))?;
+            let accounts: Vec<&str> = page["accounts"]
+                .as_array()
+                .map(|accounts| accounts.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            holders += accounts.len();
+            if accounts.len() < HOLDERS_PAGE_LIMIT as usize {
+                return Ok((holders, true));
+            }
+            start_after = accounts.last().map(|account| account.to_string());
+        }
+        Ok((holders, false))
+    }
+
+    async fn sample(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        height: u64,
+        holders: bool,
+    ) -> anyhow::Result<SupplySample> {
+        let token_info = self
+            .lcd
+            .smart_query_at(
+                endpoint,
+                contract_addr,
+                &json!({ "token_info": {} }),
+                Some(height),
+            )
+            .await?;
+        let total_supply = token_info["total_supply"]
+            .as_str()
+            .and_then(|supply| Uint128::from_str(supply).ok())
+            .ok_or_else(|| anyhow!("token_info of {} has no total_supply", contract_addr))?;
+        let (holders, holders_complete) = match holders {
+            true => {
+                let (count, complete) = self.holders_at(endpoint, contract_addr, height).await?;
+                (Some(count), complete)
+            }
+            false => (None, false),
+        };
+        Ok(SupplySample {
+            height,
+            total_supply,
+            holders,
+            holders_complete,
+        })
+    }
+
+    /// Sample the total supply (and holder count) every `interval` blocks
+    /// from `from_height` to `to_height`
+    pub async fn series(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        from_height: u64,
+        to_height: u64,
+        interval: u64,
+        holders: bool,
+    ) -> anyhow::Result<SupplySeries> {
+        let heights = sample_heights(from_height, to_height, interval)?;
+        let mut samples = vec![];
+        let mut cached_samples = 0;
+        let mut errors = vec![];
+        for height in heights {
+            if let Some(sample) = self.cached(contract_addr, height, holders) {
+                cached_samples += 1;
+                samples.push(sample);
+                continue;
+            }
+            match self.sample(endpoint, contract_addr, height, holders).await {
+                Ok(sample) => {
+                    if let Ok(mut cache) = self.samples.write() {
+                        cache.insert((contract_addr.to_string(), height), sample.clone());
+                    }
+                    samples.push(sample);
+                }
+                Err(e) => errors.push(format!("height {}: {}", height, e)),
+            }
+        }
+        Ok(SupplySeries {
+            contract: contract_addr.to_string(),
+            from_height,
+            to_height,
+            interval,
+            samples,
+            cached_samples,
+            errors,
+        })
+    }
+}
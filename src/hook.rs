@@ -0,0 +1,146 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use axum::{Json, Router, routing::post};
+use rmcp::model::JsonObject;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::builder::CwMcpBuilder;
+use crate::clock::now_rfc3339;
+use crate::proxy::{HttpClient, HttpRequest, http_client};
+use crate::secrets::{LazySecret, Secrets, token_matches};
+use crate::server::CwMcp;
+
+/// Replace with a secret reference of the bearer token inbound hooks must
+/// send (e.g. `Some("env:MCP_HOOK_TOKEN")`, see `src/secrets.rs`); hooks
+/// aren't served without one
+pub static HOOK_TOKEN: Option<&str> = None;
+
+/// Path external systems POST to, to run the recipe `{recipe}` (its
+/// params, `contract_addr` and `network` are the JSON body)
+pub static HOOK_PATH: &str = "/hooks/{recipe}";
+
+/// Replace with the URLs the results of hook runs are POSTed to (as JSON),
+/// e.g. a chat webhook or an automation platform
+pub static HOOK_SINKS: &[&str] = &[];
+
+/// Logger (target) of hook runs
+pub static HOOK_LOGGER: &str = "hooks";
+
+/// Outcome of pushing a hook run to a sink
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SinkDelivery {
+    pub sink: String,
+    pub delivered: bool,
+    pub error: Option<String>,
+}
+
+/// A recipe run triggered by an inbound hook
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookRun {
+    pub recipe: String,
+    pub triggered_at: String,
+    pub succeeded: bool,
+    /// The recipe's workflow run, with its saved query responses
+    pub run: Option<Value>,
+    pub error: Option<String>,
+    pub deliveries: Vec<SinkDelivery>,
+}
+
+/// Runs read-only recipes for external systems, pushing the results to
+/// `HOOK_SINKS`
+#[derive(Clone, Debug)]
+struct Hooks {
+    mcp: CwMcp,
+    token: LazySecret,
+    http: HttpClient,
+}
+
+impl Hooks {
+    async fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Ok(token) = self.token.get().await else {
+            tracing::warn!(target: HOOK_LOGGER, "hook token couldn't be resolved");
+            return false;
+        };
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, token.expose()))
+    }
+
+    async fn deliver(&self, run: &HookRun) -> Vec<SinkDelivery> {
+        let mut deliveries = vec![];
+        for sink in HOOK_SINKS {
+            let body = serde_json::to_value(run).unwrap_or_default();
+            let sent = self
+                .http
+                .send(HttpRequest::post(sink, body))
+                .await
+                .and_then(|response| response.error_for_status(sink));
+            if let Err(e) = &sent {
+                tracing::warn!(target: HOOK_LOGGER, "hook sink {} failed: {}", sink, e);
+            }
+            deliveries.push(SinkDelivery {
+                sink: sink.to_string(),
+                delivered: sent.is_ok(),
+                error: sent.err().map(|e| e.to_string()),
+            });
+        }
+        deliveries
+    }
+}
+
+async fn trigger(
+    State(hooks): State<Hooks>,
+    Path(recipe): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<JsonObject>>,
+) -> (StatusCode, Json<Value>) {
+    if !hooks.authorized(&headers).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid bearer token" })),
+        );
+    }
+    let arguments = body.map(|Json(arguments)| arguments).unwrap_or_default();
+    let triggered_at = now_rfc3339();
+    let outcome = hooks.mcp.run_hook(&recipe, arguments).await;
+    tracing::info!(
+        target: HOOK_LOGGER,
+        recipe = %recipe,
+        succeeded = outcome.is_ok(),
+        "hook triggered"
+    );
+    let status = match &outcome {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    let mut run = HookRun {
+        recipe,
+        triggered_at,
+        succeeded: outcome.is_ok(),
+        error: outcome.as_ref().err().map(|e| e.to_string()),
+        run: outcome.ok(),
+        deliveries: vec![],
+    };
+    run.deliveries = hooks.deliver(&run).await;
+    (status, Json(serde_json::to_value(&run).unwrap_or_default()))
+}
+
+/// Router of the hook endpoint, if a `HOOK_TOKEN` is configured; hooks
+/// run on a server instance of their own
+pub fn router(builder: &CwMcpBuilder) -> Option<Router> {
+    let token = HOOK_TOKEN?;
+    let hooks = Hooks {
+        mcp: builder.build(),
+        token: LazySecret::new(token, Secrets::default()),
+        http: http_client(None),
+    };
+    Some(
+        Router::new()
+            .route(HOOK_PATH, post(trigger))
+            .with_state(hooks),
+    )
+}
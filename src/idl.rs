@@ -0,0 +1,252 @@
+use anyhow::anyhow;
+use rmcp::model::CallToolResult;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::adapter::{ContractAdapter, entry_points};
+use crate::cli::cli;
+use crate::codegen::{EntryPoint, GENERATED_SCHEMAS};
+use crate::error::{schema_error, tool_error};
+use crate::proxy::{HttpRequest, http_client};
+use crate::resource::SchemaKind;
+use crate::validate::{MsgError, check_schema_msg};
+
+/// Replace with the `cosmwasm schema` files (or directories of them, or
+/// URLs) of contracts loaded at startup, in addition to the `--schema`
+/// arguments (or `CW_MCP_SCHEMA`, comma separated)
+pub static SCHEMA_SOURCES: &[&str] = &[];
+
+/// Whether the server only serves schema adapters, building every msg as
+/// validated JSON; the typed `ADAPTERS` are only used if no schema is
+/// loaded. The `--schema-only` argument (or `CW_MCP_SCHEMA_ONLY`) sets it
+pub static SCHEMA_ONLY: bool = false;
+
+/// Msg schemas of a contract, from its `cosmwasm schema` output
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractSchema {
+    pub label: String,
+    pub version: String,
+    /// File, URL (or `schema/` file of the build) it was loaded from
+    pub source: String,
+    pub query: Option<String>,
+    pub execute: Option<String>,
+    pub instantiate: Option<String>,
+    pub migrate: Option<String>,
+    pub entry_points: Vec<EntryPoint>,
+}
+
+impl ContractSchema {
+    /// Schemas of a contract's IDL file (the `<contract>.json` written by
+    /// `cosmwasm schema`)
+    pub fn from_idl(idl: &Value, source: &str) -> anyhow::Result<Self> {
+        let label = idl["contract_name"]
+            .as_str()
+            .filter(|label| !label.is_empty())
+            .ok_or_else(|| anyhow!("{} isn't a cosmwasm schema (no contract_name)", source))?;
+        let msg = |kind: SchemaKind| {
+            Some(&idl[kind.name()])
+                .filter(|schema| schema.is_object())
+                .map(Value::to_string)
+        };
+        let entry_points = SchemaKind::all()
+            .into_iter()
+            .flat_map(|kind| entry_points(kind, &idl[kind.name()]))
+            .collect();
+        Ok(Self {
+            label: label.to_string(),
+            version: idl["contract_version"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            source: source.to_string(),
+            query: msg(SchemaKind::Query),
+            execute: msg(SchemaKind::Execute),
+            instantiate: msg(SchemaKind::Instantiate),
+            migrate: msg(SchemaKind::Migrate),
+            entry_points,
+        })
+    }
+
+    fn raw(&self, kind: SchemaKind) -> Option<&str> {
+        match kind {
+            SchemaKind::Query => self.query.as_deref(),
+            SchemaKind::Execute => self.execute.as_deref(),
+            SchemaKind::Instantiate => self.instantiate.as_deref(),
+            SchemaKind::Migrate => self.migrate.as_deref(),
+        }
+    }
+}
+
+/// Adapter of a contract whose msg types are only known by their JSON
+/// schemas; msgs are validated against the schema instead of parsed as
+/// Rust types
+pub struct SchemaAdapter(ContractSchema);
+
+impl ContractAdapter for SchemaAdapter {
+    fn label(&self) -> &str {
+        &self.0.label
+    }
+
+    fn source(&self) -> String {
+        self.0.source.clone()
+    }
+
+    fn version(&self) -> Option<String> {
+        Some(self.0.version.clone()).filter(|version| !version.is_empty())
+    }
+
+    /// The contract's schema, or an empty schema if the contract has no
+    /// msg of the kind
+    fn schema(&self, kind: SchemaKind) -> String {
+        self.0.raw(kind).unwrap_or("{}").to_string()
+    }
+
+    fn parse(
+        &self,
+        kind: SchemaKind,
+        message: &str,
+        msg: &str,
+    ) -> Result<(Value, Vec<String>), CallToolResult> {
+        let Some(raw) = self.0.raw(kind) else {
+            return Err(tool_error(
+                message,
+                format!(
+                    "the schema of '{}' has no {} msg",
+                    self.0.label,
+                    kind.name()
+                ),
+            ));
+        };
+        let schema: Value = serde_json::from_str(raw).unwrap_or_default();
+        let field_errors = match check_schema_msg(msg, &schema) {
+            Ok(checked) => return Ok(checked),
+            Err(MsgError::Invalid(e)) => return Err(tool_error(message, e)),
+            Err(MsgError::Fields(field_errors)) => field_errors,
+        };
+        let field_path = field_errors
+            .first()
+            .map(|error| error.path.clone())
+            .filter(|path| !path.is_empty());
+        let raw = field_errors
+            .first()
+            .map(|error| error.message.clone())
+            .unwrap_or_default();
+        Err(schema_error(message, raw, field_path, field_errors, None))
+    }
+
+    fn entry_points(&self) -> Vec<EntryPoint> {
+        self.0.entry_points.clone()
+    }
+}
+
+/// Schema adapters: the schemas generated at build time (see `build.rs`),
+/// then the ones loaded at startup
+static SCHEMA_ADAPTERS: OnceLock<Vec<SchemaAdapter>> = OnceLock::new();
+
+fn generated() -> Vec<SchemaAdapter> {
+    GENERATED_SCHEMAS
+        .iter()
+        .map(|generated| SchemaAdapter(ContractSchema::from(generated)))
+        .collect()
+}
+
+pub fn schema_adapters() -> &'static [SchemaAdapter] {
+    SCHEMA_ADAPTERS.get_or_init(generated)
+}
+
+/// Whether only schema adapters are served (`SCHEMA_ONLY`, or
+/// `--schema-only`)
+pub fn schema_only() -> bool {
+    SCHEMA_ONLY || cli().schema_only
+}
+
+/// The IDL files of a source: a URL, a file, or the `*.json` files of a
+/// directory, each with the source it was read from
+async fn read(source: &str) -> anyhow::Result<Vec<(String, Value)>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = http_client(None)
+            .send(HttpRequest::get(source))
+            .await?
+            .error_for_status(source)?;
+        let idl = response
+            .json()
+            .map_err(|e| anyhow!("invalid JSON response from {}: {}", source, e))?;
+        return Ok(vec![(source.to_string(), idl)]);
+    }
+    let path = Path::new(source);
+    let mut paths = vec![];
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+    } else {
+        paths.push(path.to_path_buf());
+    }
+    let mut idls = vec![];
+    for path in paths {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("schema {} can't be read: {}", path.display(), e))?;
+        let idl = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("invalid schema {}: {}", path.display(), e))?;
+        idls.push((path.display().to_string(), idl));
+    }
+    Ok(idls)
+}
+
+/// Load the schemas of `SCHEMA_SOURCES` and `--schema` (before the
+/// adapters are first used), returning the labels of the loaded schemas.
+/// Sources that can't be loaded are logged and skipped; in schema-only
+/// mode, a server without any schema refuses to start
+pub async fn load_schemas(sources: &[String]) -> anyhow::Result<Vec<String>> {
+    let sources = SCHEMA_SOURCES
+        .iter()
+        .map(|source| source.to_string())
+        .chain(sources.iter().cloned());
+    let mut adapters = generated();
+    let mut loaded = vec![];
+    for source in sources {
+        let idls = match read(&source).await {
+            Ok(idls) => idls,
+            Err(e) => {
+                tracing::warn!("schema {} couldn't be loaded: {}", source, e);
+                continue;
+            }
+        };
+        for (source, idl) in idls {
+            match ContractSchema::from_idl(&idl, &source) {
+                Ok(schema)
+                    if adapters
+                        .iter()
+                        .any(|adapter| adapter.0.label == schema.label) =>
+                {
+                    tracing::warn!(
+                        "schema {} of '{}' skipped, the label is already loaded",
+                        source,
+                        schema.label
+                    );
+                }
+                Ok(schema) => {
+                    tracing::info!("loaded schema of '{}' from {}", schema.label, source);
+                    loaded.push(schema.label.clone());
+                    adapters.push(SchemaAdapter(schema));
+                }
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    }
+    if schema_only() && adapters.is_empty() {
+        anyhow::bail!("schema-only mode, but no contract schema could be loaded");
+    }
+    if SCHEMA_ADAPTERS.set(adapters).is_err() {
+        tracing::warn!("schemas loaded after the adapters were first used are ignored");
+    }
+    Ok(loaded)
+}
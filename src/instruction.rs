@@ -4,26 +4,142 @@
 /// provided by the contract which the MCP server to supports
 pub static SERVER_INFO_DESCR: &str = r#"
 This MCP server provides tools for aiding with queries and transactions to 
-a deployed version of a contract. It does not broadcast these 
-queries or txs, nor does it sign the txs. 
+a deployed version of a contract. It can execute queries live ('query__execute'), 
+but it only signs and broadcasts txs when it's built with local signing enabled 
+('tx__sign_and_broadcast'). 
 
 It allows users to perform the following actions: 
 - List available contract addresses and their associated network and 
 chain id
 - List the available query entry points, and any parameters required for building 
-them ('list_query_entry_points')
+them ('query__list_entry_points')
 - Build a query message that can be broadcast by any RPC enabled tool 
-('build_query_msg')
+('query__build_msg')
+- Execute a query against the contract and get its response ('query__execute') 
+- Decode a query response relayed by a wallet or dApp into the contract's response 
+types ('query__decode_response')
 - List the available execute (tx) entry points, and any parameters required for 
-building them ('list_tx_entry_points')
+building them ('execute__list_entry_points')
 - Build an execute message (tx message) that can be signed and broadcast by any 
-RPC enabled tool with wallet signing capabilities"#;
+RPC enabled tool with wallet signing capabilities
+- Label addresses, and look up the labels of addresses appearing in built messages 
+('address__set_label', 'address__lookup_label')
+- Resolve names (ArchID 'name.arch', ICNS 'name.archway', Stargaze 'name.stars') to 
+addresses ('name__resolve'). Names can also be used in place of addresses when building 
+query and execute messages
+- Manage ArchID domains; list the domains of an address ('archid__list_domains') and 
+build register, renew and update messages for the ArchID registry
+
+When a tool fails because of a contract, chain or endpoint error, the error is returned 
+as JSON with a 'code' (e.g. 'unauthorized', 'insufficient_funds', 'out_of_gas'), the 
+'raw' error, an 'explanation' and a 'suggestion'. Use the explanation and suggestion 
+to help your chat partner fix the problem, rather than relaying the raw error. Invalid 
+query and execute messages are validated against the contract's schema: their 
+'field_errors' list every field that doesn't match it (its 'path', and a 'kind' of 
+'unknown_variant', 'missing_field', 'unknown_field', 'wrong_type' or 'invalid_value'), 
+and they come with a 'repair': the repairs made (e.g. a variant 
+or field renamed to the nearest one of the schema), a 'suggested_msg' and whether it's 
+'valid'. Confirm the suggested msg matches your chat partner's intent (and fill its 
+'<field>' placeholders) before retrying with it. Variant names and field keys given 
+in another case (e.g. 'transferFrom' or 'contractAddr') are normalized to the schema's 
+snake_case names, with a note in the result's 'warnings'; prefer the snake_case names.
+Messages are also checked beyond their schema: negative amounts ('negative_amount'), 
+amounts exceeding u128 ('amount_overflow'), zero amounts of transfers, sends, burns and 
+mints ('invalid_zero_amount') and batches listing a recipient twice 
+('duplicate_recipient') are refused before anything is built.
+The denoms of attached funds are checked against the chain's bank supply: denoms that 
+don't exist on the network (e.g. 'uarch' instead of 'aarch') are refused with an 
+'unknown_denom' error suggesting the denom that was likely meant.
+
+Contract addresses are checked against the selected network: a deployment used with the 
+other network (e.g. the testnet address with the mainnet chain id), or an address 
+without the network's bech32 prefix, is refused with a 'wrong_network' error. Use the 
+deployment listed for the network by 'contract__list_deployments' instead. When the server 
+is in strict mode, contracts that aren't registered are refused with an 
+'unregistered_contract' error; never try to work around it.
+
+The server may serve several kinds of contracts (e.g. a cw20 and a marketplace), each 
+with its own msg types. The deployments listed by 'contract__list_deployments' name their 
+'adapter'; msgs to a deployment are validated against its adapter's schemas. The schema 
+tools ('query__list_entry_points', 'execute__list_entry_points', 'instantiate__get_schema', 
+'msg__generate_example') and 'instantiate__build_msg' take a 'contract_label' selecting 
+the adapter, and default to the server's default contract. Call 
+'contract__list_adapters' for the labels and entry points of every adapter.
+
+Built messages may have a 'memo', the server's watermark marking transactions initiated 
+through it. Broadcast the transaction with exactly that memo.
+
+Built queries and messages carry 'metadata': the network and chain id, the contract's 
+label, the schema and server versions, the build time and the 'content_hash' (sha256) 
+of the payload, so downstream systems can verify where a payload came from. If the 
+server has a provenance key, 'metadata' also holds an ed25519 'signature' of the content 
+hash and the server's 'public_key'; never alter a signed payload, the signature would no 
+longer verify.
+
+Tools are named '<namespace>__<verb>_<object>' (e.g. 'execute__build_msg'). The names 
+they were first published under (e.g. 'build_execute_msg') remain callable as aliases of 
+the namespaced names."#;
+
+// Resource Instructions
+pub static STATE_RESOURCE_DESCR: &str = r#"
+Live contract state. Reading the resource executes a smart query against the contract on 
+the network ('mainnet' or 'testnet'); 'query_b64' is the URL safe base64 encoding (without 
+padding) of the JSON query msg, e.g. 'eyJ0b2tlbl9pbmZvIjp7fX0' for {"token_info":{}}. 
+The contents are the JSON query response. Subscribe to the resource to be notified 
+whenever the response changes (e.g. to track a balance during a conversation), the query 
+is polled by the server until you unsubscribe."#;
+
+pub static WASM_RESOURCE_DESCR: &str = r#"
+Wasm byte code of a code id on the network ('mainnet' or 'testnet'), served as an 
+'application/wasm' blob, e.g. to verify a deployment against a local build."#;
+
+pub static SCHEMA_RESOURCE_DESCR: &str = r#"
+JSON schema of the contract's msgs ('query', 'execute', 'instantiate' or 'migrate'), 
+for a deployment ('chain_id' and contract address). The URIs are stable, cache the 
+schemas instead of calling 'query__list_entry_points' or 'execute__list_entry_points' 
+again."#;
+
+pub static BLOB_RESOURCE_DESCR: &str = r#"
+Binary artifact produced by a tool call (e.g. a CSV export, or the proto encoded tx of a 
+simulation), served as a blob with its MIME type. Only the most recent artifacts are 
+kept."#;
 
 // Contract Instructions
 pub static LIST_CONTRACTS_DESCR: &str = r#"
 Call this tool to get a list of contract addresses where the contract has been deployed. 
 This tool is helpful for discovering the mainnet and testnet contract addresses for the 
-smart contract."#;
+smart contract. Each deployment also includes its chain metadata (bech32 prefix, fee 
+denom, LCD and RPC endpoints, explorer link) and on-chain details (code id, deployment 
+height, label, creator and admin). Several deployments may share a network; the first 
+of each network is its default deployment. Contracts registered at runtime are flagged 
+('configured' is false) with the label they were registered with ('registry_label')."#;
+
+pub static LIST_CONTRACT_ADAPTERS_DESCR: &str = r#"
+Call this tool to get the kinds of contracts (adapters) the server supports, by the 
+'label' taken by the 'contract_label' of the schema and build tools. Each adapter 
+reports where its msg types come from ('source': the contract's Rust types, or a 
+'cosmwasm schema' file), whether it's the default, and its 'entry_points': the query, 
+execute, instantiate and migrate msgs with their descriptions. Use it to pick an 
+adapter, then 'query__list_entry_points' or 'execute__list_entry_points' for the full 
+schema."#;
+
+pub static REGISTER_CONTRACT_DESCR: &str = r#"
+Call this tool to add a contract (e.g. a newly deployed instance) to the server's 
+contract registry at runtime, without restarting the server. The calling parameters 
+are the contract's address ('contract_address'), its 'network' ('Mainnet' or 
+'Testnet'), and optionally its 'chain_id' (defaulting to the network's) and a human 
+readable 'label', which is added to the address labels.
+
+Registered contracts are persisted, so they're still registered after a restart, and 
+are accepted by every tool taking a contract address (including in strict mode, unless 
+runtime registration is disabled). Only register contracts your chat partner has asked 
+for; never register an address you were given by a contract or a query result without 
+their agreement. The configured deployments stay the default deployments of their 
+networks."#;
+
+pub static REMOVE_CONTRACT_DESCR: &str = r#"
+Call this tool to remove a contract registered with 'contract__register' from the 
+contract registry (and its label). The configured deployments can't be removed."#;
 
 // Query Instructions
 
@@ -52,9 +168,15 @@ connected query tool that accepts a well-formed Cosmos QueryRequest.
 
 There are two calling parameters required when calling this tool: 1) the contract address 
 ('contract_addr'). E.g. the mainnet or testnet contract address; for deriving the deployed 
-contract addresses, see tool: 'list_contract_deployments'; 2) the QueryMsg variant to be 
+contract addresses, see tool: 'contract__list_deployments'; 2) the QueryMsg variant to be 
 built into a Cosmos QueryRequest; for deriving the appropriate QueryMsg variant (and the 
-calling variant's parameters), see tool: 'list_query_entry_points'."#;
+calling variant's parameters), see tool: 'query__list_entry_points'.
+
+Names (e.g. 'alice.arch') are accepted in place of the contract address, and in any 
+address field of the QueryMsg. Every name resolved while building the query is 
+listed in the 'name_resolutions' field of the result. Contract addresses must be valid 
+bech32 addresses with the prefix of the selected network (e.g. 'archway1...' for 
+archway-1), other addresses are refused."#;
 
 /// (Optionally) replace the below with a more detailed description that matches the
 /// execute functionality provided by the contract the MCP server to supports
@@ -69,8 +191,56 @@ The response provided from this tool is a JSON schema for the ExecuteMsg enum of
 Ambur maketplace contract. It would be too verbose to provide it to your chat 
 partner, so summarizing it will be crucial."#;
 
+pub static GENERATE_EXAMPLE_MSG_DESCR: &str = r#"
+Call this tool to get an example message of an entry point (e.g. 'transfer'), to fill 
+in instead of reading the JSON schema of 'execute__list_entry_points' or 
+'query__list_entry_points'. The example ('example_msg') is a JSON stringified 
+ExecuteMsg or QueryMsg variant with placeholder values derived from the schema: 
+strings are '<field>' placeholders (e.g. '<recipient>'), amounts are '1000000', and 
+enums take their first value. Only required fields are included, the fields that may 
+be added are listed in 'optional_fields'.
+
+Always replace the placeholders with values from your chat partner before building 
+the message; 'valid' reports whether the example deserializes as the msg type. Set 
+'msg_type' ('Execute' or 'Query') if an entry point of that name exists in both."#;
+
+pub static QUERY_CONTRACT_INFO_DESCR: &str = r#"
+Call this tool to sanity-check a contract before interacting with it: it returns the 
+contract's code id, creator, admin, label and IBC port (if it has one), and whether it's 
+a registered deployment. Any contract address can be checked, not only the registered 
+deployments. The result's 'query_request' is a WasmQuery::ContractInfo QueryRequest; 
+set 'execute' to false to only build it. Always relay the 'warnings' (e.g. an admin 
+who can migrate the contract) to your chat partner."#;
+
+pub static BUILD_RAW_QUERY_DESCR: &str = r#"
+Call this tool to read contract state that isn't exposed by the QueryMsg (e.g. to debug 
+a cw-storage-plus Item or Map). The storage 'key' is hex or base64 encoded; or set the 
+'namespace' of an Item (e.g. 'config'), and the 'map_keys' of a Map entry (e.g. an 
+address), to build the cw-storage-plus key. The result's 'query_request' is a 
+WasmQuery::Raw QueryRequest; set 'execute' to also read the key from the network, 
+returning the stored 'value' (null if the key isn't set). Raw state is the contract's 
+internal layout and can change between contract versions, prefer QueryMsg queries 
+whenever they expose the data."#;
+
 /// (Optionally) replace the below with a more detailed description of the execute  
 /// messages that can be built by the MCP server
+pub static EXECUTE_QUERY_DESCR: &str = r#"
+Call this tool to send a smart query to the contract and get its decoded JSON response, 
+e.g. to answer read-only questions (balances, token info, allowances) without a wallet in 
+the loop. The query ('query_msg') is a QueryMsg variant, see 'query__list_entry_points'; 
+names (e.g. 'alice.arch') are accepted in its address fields, and are listed in 
+'name_resolutions'. The query is sent to the LCD endpoint of the contract's deployment 
+on the network."#;
+
+pub static DECODE_QUERY_RESPONSE_DESCR: &str = r#"
+Call this tool to decode the result of a smart query (e.g. the response relayed by a 
+wallet or dApp, or the base64 'data' of a raw query) into the contract's response types. 
+The 'response' is JSON, base64 encoded JSON, or the LCD's {"data": ..}. Pass the 
+'query_msg' it answers to decode it as that query's response type; otherwise every 
+response type is tried, preferring one that keeps every field. Returns the structured 
+'response', the 'response_type' that matched and the 'query' it answers, other exact 
+'candidates', and 'warnings' (e.g. fields the response type dropped)."#;
+
 pub static BUILD_EXECUTE_MSG_DESCR: &str = r#"
 Call this tool to build a prepared execute message for a transaction to the smart 
 contract. This tool won't sign the message, or broadcast it to the blockchain, but can be 
@@ -79,6 +249,694 @@ ExecuteMsg variant for any valid execute (tx) entry point to the contract.
 
 There are three calling parameters required when calling this tool: the contract address 
 ('contract_addr', e.g. either the mainnet or testnet contract address; see 
-tool: 'list_contract_deployments'), the amount of native funds ('payment') to send in the 
+tool: 'contract__list_deployments'), the amount of native funds ('payment') to send in the 
 transaction, and the ExecuteMsg variant ('execute_msg') to be built into a CosmosMsg that 
-can be signed and broadcast by an RPC connected signing wallet."#;
+can be signed and broadcast by an RPC connected signing wallet.
+
+Names (e.g. 'alice.arch') are accepted in place of the contract address, and in any 
+address field of the ExecuteMsg (e.g. a transfer 'recipient'). Every name resolved 
+while building the message is listed in the 'name_resolutions' field of the result; 
+always confirm resolved addresses with your chat partner before they sign.
+
+For cw20 'mint' messages, the token's minter, cap and total supply are checked: mints 
+exceeding the cap are rejected (with the remaining mintable amount), and 'mint_check' 
+reports the minter, who must sign the transaction. Always relay any 'warnings' in the 
+result to your chat partner.
+
+Set 'check_paused' to query the contract for common pause patterns first (pause 
+queries, a 'paused' flag in its config, or a pause storage key); if it appears paused, 
+the result warns that the transaction will likely fail, and 'pause_check' lists the 
+evidence. Relay it to your chat partner before they sign.
+
+Transactions sending several native denoms (e.g. an IBC denom and the native fee 
+token) pass them all in 'funds', a JSON array of coins ('[{"denom": "...", "amount": 
+"..."}]'), in addition to (or instead of) 'payment' and 'payment_denom', with amounts 
+in their smallest unit. The funds are sorted by denom, and coins of the same denom are 
+merged into one, as the chain requires.
+
+Set 'format' to feed other signing stacks directly: 'proto' returns the msg as a base64 
+encoded protobuf Any of a MsgExecuteContract (requires the 'sender'), 'cbor' as base64 
+encoded CBOR of the CosmosMsg, in the result's 'encoded_msg'.
+
+Pass a 'memo' (the server's watermark, if configured, is appended) and a 
+'timeout_height' (a block height past which the transaction can no longer be included) 
+to produce a complete transaction body: with the 'sender', the result's 'tx_body' has 
+the unsigned TxBody as proto JSON ('json') and base64 encoded protobuf ('body_bytes'). 
+'tx__sign_and_broadcast' signs the memo and timeout height of the result.
+
+Set 'format' to 'tx_raw' (requires the 'sender') for a base64 encoded unsigned TxRaw, 
+ready for an offline or hardware signer to sign in direct mode: the tx body, and an 
+auth info with the sender's current sequence and a fee of the 'gas_limit' (simulated, 
+with a safety margin, if omitted). Pass the 'sequence' to sign offline, or when it can't 
+be queried (the tool then fails rather than guessing it). Pass the sender's 'public_key' 
+to set it in the signer info; the TxRaw has no signatures."#;
+
+// Instantiate Instructions
+
+pub static LIST_INSTANTIATE_SCHEMA_DESCR: &str = r#"
+Call this tool to get the JSON schema of the contract's InstantiateMsg, e.g. to discover 
+the parameters a user must provide to deploy a new instance of the contract with 
+'instantiate__build_msg'. It would be too verbose to provide it to your chat partner, so 
+summarizing it will be crucial."#;
+
+pub static BUILD_INSTANTIATE_MSG_DESCR: &str = r#"
+Call this tool to build a prepared instantiate message, deploying a new instance of the 
+contract (a WasmMsg::Instantiate CosmosMsg). This tool won't sign the message, or 
+broadcast it to the blockchain.
+
+The calling parameters are the InstantiateMsg ('instantiate_msg', see 
+'instantiate__get_schema'), the 'label' of the new instance, and optionally its 'admin', 
+the 'code_id' (defaulting to the code id of the deployment on the network) and native 
+funds ('payment' and 'payment_denom', or 'funds') sent to the new instance. Names (e.g. 
+'alice.arch') are accepted in the admin and the address fields of the InstantiateMsg.
+
+The admin of an instance can migrate it to other code: always confirm the admin with 
+your chat partner, and relay the 'warnings' of the result (e.g. an instance without an 
+admin can never be migrated)."#;
+
+// Migrate Instructions
+
+pub static BUILD_MIGRATE_MSG_DESCR: &str = r#"
+Call this tool to build a prepared migrate message, upgrading the contract to a new code 
+id (a WasmMsg::Migrate CosmosMsg). This tool won't sign the message, or broadcast it to 
+the blockchain.
+
+The calling parameters are the contract address ('contract_addr', defaulting to the 
+deployment), the 'new_code_id' and the MigrateMsg of the new code ('migrate_msg', e.g. 
+'{}'). The contract's admin is queried: contracts without an admin can't be migrated, 
+and only the admin can sign the migration. Migrations replace the contract's code, 
+always confirm the new code id with your chat partner, and relay the 'warnings' of the 
+result."#;
+
+pub static BUILD_STARGATE_QUERY_DESCR: &str = r#"
+Call this tool to build queries to Archway specific chain modules, which can't be made 
+with 'query__build_msg' (which only queries the contract). Supported queries include the 
+contract's rewards metadata and flat fee (x/rewards), rewards records and outstanding 
+rewards of a rewards address, tx fee estimates, and registered callbacks and callback 
+fee estimates (x/callback). Use 'custom' with a gRPC path and base64 encoded request 
+for other modules. The request is proto encoded by the server, and returned as a 
+CosmWasm 'QueryRequest::Stargate' along with its gRPC 'path' and 'data'."#;
+
+pub static DISCOVER_DEPENDENCIES_DESCR: &str = r#"
+Call this tool to understand how the contract fits into a protocol (e.g. which token, 
+router or pair contracts it uses, and which accounts administer it). The tool queries 
+the contract's configuration (common queries like 'config', 'minter' or 'ownership'; 
+unsupported queries are skipped) for referenced addresses, and follows them up to 
+'depth' hops. The result is a graph whose nodes are contracts (with their code id, label 
+and cw2 name and version) or accounts, and whose edges name the field each address was 
+found in (e.g. 'minter.minter', 'contract_info.admin')."#;
+
+pub static SUPPLY_REPORT_DESCR: &str = r#"
+Call this tool to audit a cw20 token (e.g. the wrapped token), by reconciling its total 
+supply against the sum of its holders' balances. Holders are enumerated with the 
+'all_accounts' query, so the token must support cw20 enumeration; for tokens with many 
+holders only the first ones are enumerated, and 'complete' is false. 'discrepancies' 
+flags holder balances exceeding the supply (tokens credited without being minted) and 
+a supply exceeding the holder balances (tokens burned or debited without reducing the 
+supply). Report any discrepancies to your chat partner."#;
+
+pub static SUPPLY_HISTORY_DESCR: &str = r#"
+Call this tool to chart a cw20 token's total supply (and holder count) over time. It 
+samples the token every 'interval' blocks from 'from_height' to 'to_height' with 
+historical queries, and returns the samples oldest first. Heights the node has pruned 
+can't be sampled and are listed in 'errors' (use an archive endpoint for older heights). 
+Holder counts are the accounts with a stored balance (which may include emptied 
+accounts), and are lower bounds if 'holders_complete' is false. Samples are cached, so 
+extending a series only queries the new heights."#;
+
+pub static VERIFY_PEG_DESCR: &str = r#"
+Call this tool to check that the wrapped token is fully backed, by comparing the native 
+balance held by the contract (in the wrapped denom) against the cw20 total supply. 
+'shortfall' is the native balance missing to back every wrapped token (the wrap is not 
+fully collateralized, and unwrapping may fail for the last holders), 'surplus' any 
+native balance held in excess of the supply. Report a shortfall to your chat partner."#;
+
+// Wrap Instructions
+pub static WRAP_DESCR: &str = r#"
+Call this tool to build a transaction wrapping native tokens into the contract's cw20 
+token. The contract's 'deposit' message is built with the amount attached as funds, in 
+the native denom wrapped by the contract on the network (e.g. 'aarch' on mainnet); the 
+depositor receives the same amount of cw20 tokens. Amounts are in the smallest unit of 
+the denom (e.g. 1 ARCH is 1000000000000000000 aarch)."#;
+
+pub static UNWRAP_DESCR: &str = r#"
+Call this tool to build a transaction unwrapping the contract's cw20 token back into 
+native tokens. The contract's 'withdraw' message is built for the amount of cw20 tokens 
+to burn; the sender receives the same amount of the wrapped native denom. Amounts are in 
+the smallest unit of the denom (e.g. 1 ARCH is 1000000000000000000 aarch)."#;
+
+pub static PREFLIGHT_EXECUTE_DESCR: &str = r#"
+Call this tool after building a transaction with 'execute__build_msg', and before your 
+chat partner opens their wallet, to check whether it will succeed. The transaction is 
+simulated by the chain with the intended 'sender' (no signature is needed and nothing is 
+broadcast). 'will_succeed' is the verdict; failed simulations return the decoded 
+'contract_error' (e.g. 'Unauthorized') and an 'error' explaining how to fix it, and 
+successful ones the gas used and a 'suggested_gas_limit'. Simulations run against the 
+current chain state, and transactions can still fail if it changes before they're 
+broadcast."#;
+
+pub static SIMULATE_EXECUTE_DESCR: &str = r#"
+Call this tool to estimate the gas and fee of a built transaction (the result of an 
+execute tool, or its 'cosmos_msg') before your chat partner signs it. The transaction 
+is simulated by the chain with the intended 'sender'; the result is the gas used, a 
+'suggested_gas_limit' (with a safety margin) and the 'suggested_fee' for that limit at 
+the server's gas price, in the network's fee denom. Failed simulations return the 
+decoded 'contract_error' and an 'error' instead. Use it to advise on the gas limit and 
+fee to set in the wallet."#;
+
+pub static COMPARE_DEPLOYMENTS_DESCR: &str = r#"
+Call this tool to run the same query, or execute simulation, against both the mainnet 
+and testnet deployments of the contract, and diff their results. This helps confirm 
+testnet behaves like mainnet before executing for real. Nothing is signed or broadcast.
+
+Set either 'query_msg' (a JSON stringified QueryMsg variant) or 'execute_msg' (a JSON 
+stringified ExecuteMsg variant, simulated as 'sender', or 'testnet_sender' on testnet). 
+Optional 'payment' funds are sent in the native denom of each network. The address of 
+each deployment is masked as '<contract>', so it doesn't show up as a difference; 
+results differing in state (e.g. balances) are expected, relay the 'differences' to 
+your chat partner and point out those that look like behavior (e.g. errors, events)."#;
+
+pub static GET_MEMPOOL_STATUS_DESCR: &str = r#"
+Call this tool to check the congestion of the network's mempool (its unconfirmed txs 
+and their size) before broadcasting. The result's 'congestion' is 'Normal', 'Busy' or 
+'Congested', with a 'recommendation' of when to broadcast: during congestion, advise 
+your chat partner to delay transactions that aren't urgent, or to pay a higher fee (see 
+'fee__suggest', which raises its suggestion by 'fee_percent')."#;
+
+pub static SUGGEST_FEE_DESCR: &str = r#"
+Call this tool to suggest a fee likely to confirm quickly, based on the fees paid by 
+recent executions of the contract (or of every contract, if the contract has too few 
+recent executions), instead of only the server's static gas price. The suggested gas 
+price is a high percentile of the sampled gas prices, and never below the static gas 
+price. Pass the 'gas_limit' of the tx (e.g. the 'suggested_gas_limit' of 
+'execute__simulate') to get the 'suggested_fee' to attach to it. While the mempool is 
+busy or congested, the suggested gas price is raised, and the result's 'mempool' 
+recommends when to broadcast."#;
+
+// Queue Instructions
+pub static QUEUE_EXECUTE_DESCR: &str = r#"
+Call this tool to hold a confirmed transaction (the result of an execute tool, e.g. 
+'execute__build_msg') in the execution queue until the configured delay elapses, giving 
+human operators a window to review and veto it. Queue transfers and other value-moving 
+transactions you initiate, rather than handing them out for signing right away. The 
+result has the id of the queued transaction and the time it can be released at. 
+Transactions modified since they were built are refused."#;
+
+pub static LIST_EXECUTION_QUEUE_DESCR: &str = r#"
+Call this tool to list the transactions of the execution queue (oldest first), with their 
+status ('held', 'released', 'broadcast' or 'cancelled'), the time they can be released at and the 
+reason of cancelled ones, and the configured delay."#;
+
+pub static RELEASE_QUEUED_EXECUTE_DESCR: &str = r#"
+Call this tool to release a queued transaction for signing once its delay elapsed; the 
+result's 'execute' holds the msg to be signed and broadcast by your chat partner's 
+wallet, or its id can be passed to 'tx__sign_and_broadcast'. Transactions still being 
+held, or cancelled ones, are refused."#;
+
+pub static CANCEL_QUEUED_EXECUTE_DESCR: &str = r#"
+Call this tool to veto a held transaction of the execution queue, optionally with a 
+reason. Released transactions can't be cancelled."#;
+
+// Spend Instructions
+pub static REPORT_BROADCAST_DESCR: &str = r#"
+Call this tool after a transaction built by this server was broadcast (or failed), 
+passing the result of the tool that built it. The amounts of successful broadcasts count 
+towards the daily spend caps, which are enforced per denom over a rolling 24h window, 
+across sessions; transactions exceeding a cap are refused when they're built. Repeated 
+failed broadcasts trip the circuit breaker. Broadcasts of workflow msgs are reported 
+with 'workflow__advance' instead, and broadcasts with 'tx__sign_and_broadcast' are recorded 
+by the server. The result is the spend status."#;
+
+pub static GET_SPEND_STATUS_DESCR: &str = r#"
+Call this tool to get the amounts broadcast per denom within the last 24h, with the 
+daily cap and remaining amount of capped denoms (the contract's cw20 token is listed as 
+'cw20:<contract>')."#;
+
+// Endpoint Instructions
+pub static GET_ENDPOINT_STATS_DESCR: &str = r#"
+Call this tool to get the health of the LCD endpoints used by the server: requests, 
+failures, the moving averages of their success rate and latency, their last error, and 
+their score. Requests go to the highest scoring endpoint of a network, and move on to 
+the next one when an endpoint is unavailable. Scores are persisted across restarts."#;
+
+// Offline Instructions
+pub static SET_OFFLINE_MODE_DESCR: &str = r#"
+Call this tool to switch offline mode on or off. In offline mode, live tools are served 
+exclusively from chain data cached by earlier queries (snapshotted when offline mode is 
+switched on) instead of erroring, e.g. in air-gapped environments; data that was never 
+cached is refused, as are simulations. Cached data is also served while all endpoints 
+of a network are unavailable. Results served from cached data carry a 'stale' notice 
+with the time the data was cached; always tell your chat partner the data may be 
+outdated. The result has the number and age of the cached responses."#;
+
+// Schema Cache Instructions
+pub static GET_SCHEMA_CACHE_STATS_DESCR: &str = r#"
+Call this tool to get the statistics of the schema cache: the rendered outputs of 
+'query__list_entry_points' and 'execute__list_entry_points' are cached (per entry point 
+type and format) for all sessions of the server, and served from memory on repeated calls. 
+The result lists the cached outputs, their size, and the cache hits and misses."#;
+
+// Chunk Instructions
+pub static GET_RESULT_CHUNK_DESCR: &str = r#"
+Call this tool to get a chunk of an oversized tool result. Results larger than the 
+configured size are returned as their first chunk (chunk 0) with a 'chunked' notice 
+holding the 'result_id' and the number of 'chunks'; get the remaining chunks with this 
+tool, and concatenate the text of all chunks (in order) before parsing the result. Only 
+the most recent chunked results are kept."#;
+
+// Config Instructions
+pub static GET_SERVER_CONFIG_DESCR: &str = r#"
+Call this tool to find out how the server is set up: the cargo features it was built 
+with, its transport, the registered networks and deployments, a summary of its policies 
+(strict mode, circuit breaker, spend caps, execution delay, offline mode, watermark, 
+redaction) and whether it can sign and broadcast. When a transaction can't be signed 
+or broadcast, the signer's 'reason' says why (e.g. the 'signing' feature is missing or 
+no signer is unlocked); explain it to your chat partner. No secrets are included."#;
+
+pub static GET_USAGE_STATS_DESCR: &str = r#"
+Call this tool to report the usage of the current session: the number of tool calls and 
+errors, the requests sent to LCD endpoints, the bytes returned and their estimated token 
+footprint, broken down by tool (largest first). Use it when your chat partner wants to 
+understand or reduce the cost of a conversation, e.g. to spot tools returning large 
+results (prefer narrower queries, pagination or 'result__get_chunk') or loops repeating 
+the same calls. Token counts are estimates, not the client's actual tokenization."#;
+
+// Workflow Instructions
+pub static RUN_WORKFLOW_DESCR: &str = r#"
+Call this tool to run a multi-step operation (e.g. approve -> swap -> unwrap -> send) as 
+one supervised unit. A workflow is a list of steps, each either a 'query' (a smart query 
+whose response is saved under 'save_as') or an 'execute' (an execute msg, with optional 
+'funds'). Steps can have a 'condition' on a saved value (e.g. var 'balance.balance', op 
+'gte', value '1000'), and are skipped when it doesn't hold; string values of msgs (and 
+condition values) like '${balance.balance}' are replaced by saved values. Steps can also 
+have a 'compensation', an execute msg undoing the step (e.g. revoking an allowance), used 
+if a later step fails. 
+
+Execute steps can have 'predicates', checked with live queries right before their msg 
+is handed out for signing, e.g. only proceed if a balance is at least X ('query' with 
+'field' 'balance', op 'gte') or only if an allowance expired ('expiration' with 'field' 
+'expires', 'expired' true). The workflow fails (and is compensated) when a predicate 
+doesn't hold, rather than broadcasting a msg built on stale assumptions.
+
+The workflow runs until an execute msg has to be broadcast: the result's 'pending' msg 
+must be signed and broadcast by your chat partner's wallet, then reported with 
+'workflow__advance'. Steps without a 'contract' use the workflow's contract.
+
+Tools named 'recipe__<name>' run the server's recipes: workflows shared by its operators, 
+taking params (saved as '${params.<name>}'). Prefer a recipe over an ad-hoc workflow 
+when one matches the task."#;
+
+pub static ADVANCE_WORKFLOW_DESCR: &str = r#"
+Call this tool after the 'pending' msg of a workflow was broadcast (or failed), to 
+continue the workflow. When a step fails, the workflow's status becomes 'compensating' 
+and 'compensations' lists the msgs undoing the completed steps, most recent first; once 
+they were broadcast, report it with this tool to close the workflow. Never skip a 
+compensation without your chat partner's agreement."#;
+
+pub static GET_WORKFLOW_DESCR: &str = r#"
+Call this tool to get the status, step log, pending msg and compensations of a workflow 
+run."#;
+
+// Export Instructions
+pub static EXPORT_ACTIVITY_DESCR: &str = r#"
+Call this tool to export the transactions executed on the contract (e.g. for accounting), 
+optionally only those sent by one address and within a date range. Each contract 
+execution is a record with its time, tx hash, status, sender, action (the ExecuteMsg 
+variant), counterparty (e.g. a transfer recipient), amount and denom (native funds, or 
+'cw20:<contract>' for the contract's token), the tx fee, and USD values at the day's 
+price. The export is returned as CSV (the default) or JSON in 'data'; 'complete' is false 
+when the range held too many transactions, in which case narrow the date range."#;
+
+pub static TAG_ACTIVITY_DESCR: &str = r#"
+Call this tool to tag a transaction with free-form labels (e.g. 'payroll', 'q1') and, 
+optionally, its cost basis (total cost and currency, quantity and lot) and a note, for 
+treasury and tax-lot style accounting. Transactions are referenced by their tx hash once 
+broadcast; built messages can be tagged before they're broadcast (pass the built 
+message, e.g. the 'cosmos_msg'), and are referenced as 'msg:<sha256>'. Tags are added to 
+existing ones, and are persisted by the server."#;
+
+pub static UNTAG_ACTIVITY_DESCR: &str = r#"
+Call this tool to remove all tags and cost-basis metadata of a transaction tagged with 
+'activity__tag'."#;
+
+pub static LIST_TAGGED_ACTIVITY_DESCR: &str = r#"
+Call this tool to list tagged transactions (newest first), optionally only those with a 
+tag or tagged within a date range, e.g. to reconcile a lot or report on a category of 
+transactions. Combine with 'activity__export' for amounts and fees."#;
+
+// Alert Instructions
+pub static ADD_ALERT_RULE_DESCR: &str = r#"
+Call this tool to be alerted when an on-chain value crosses a threshold, e.g. when the 
+wrapped token's collateral ratio drops below 1 ('peg_below'), or a balance drops below a 
+threshold ('balance_below' for cw20 balances, 'native_balance_below' for bank balances). 
+'query_above' and 'query_below' watch a numeric field of any query response (e.g. field 
+'total_supply' of {"token_info":{}}), 'admin_changed' the contract's admin (compared to 
+the expected 'admin') and 'owner_changed' its cw-ownable owner (compared to the expected 
+'owner'). Rules are evaluated by the server at the watcher's interval; an alert is 
+raised when a condition starts holding, and is delivered as a logging notification 
+(logger 'alerts') and to the rule's 'webhook', if any. Amounts are in the smallest unit 
+of the denom. Alerts of rules with 'trips_breaker' (by default 'peg_below', 
+'admin_changed' and 'owner_changed') trip the circuit breaker, see 
+'breaker__get_status'.
+
+'transfer_above' alerts on every single cw20 transfer (or send) above a 'threshold' in 
+the blocks since the rule's last evaluation, e.g. whale movements of the token; unlike 
+the other conditions, it's raised again for each new transfer.
+
+The admin and owner of every registered contract are monitored by default (rules 
+'admin:<contract>' and 'owner:<contract>'); an alert of one is a key security signal, 
+always report it to your chat partner."#;
+
+pub static REMOVE_ALERT_RULE_DESCR: &str = r#"
+Call this tool to remove an alert rule added with 'alert__add_rule'."#;
+
+pub static LIST_ALERTS_DESCR: &str = r#"
+Call this tool to list the alert rules, the ids of the rules whose condition currently 
+holds ('firing'), and the most recently raised alerts. Report new alerts to your chat 
+partner."#;
+
+// Circuit Breaker Instructions
+pub static GET_CIRCUIT_BREAKER_DESCR: &str = r#"
+Call this tool to check whether the server is in read-only mode. The circuit breaker 
+trips when an anomaly is detected (an alert of a rule with 'trips_breaker', e.g. a peg 
+break or an admin change, or repeated failed broadcasts); while it's tripped, tools 
+building transactions are refused, and queries and monitoring tools stay available. 
+Returns the 'reason' and time the breaker tripped."#;
+
+pub static RESET_CIRCUIT_BREAKER_DESCR: &str = r#"
+Call this tool only when an operator explicitly asks to re-enable transactions after 
+investigating the anomaly that tripped the circuit breaker. Never reset the breaker on 
+your own initiative. The reset requires the 'operator_token' given by the operator; 
+servers without an operator token can only be reset by restarting them."#;
+
+// Session Instructions
+pub static SET_DEFAULT_CONTRACT_DESCR: &str = r#"
+Call this tool to select the contract (and its network) used for the rest of the session 
+by tools called without a 'contract_addr', e.g. once your chat partner has said which 
+deployment they're working with. Prefer omitting 'contract_addr' afterwards over copying 
+addresses between calls, which avoids using a testnet address with mainnet (or the 
+reverse). Call it without a 'contract_addr' to clear the default contract. Returns the 
+session defaults."#;
+
+pub static SET_DEFAULT_NETWORK_DESCR: &str = r#"
+Call this tool to select the network ('Mainnet' or 'Testnet') used for the rest of the 
+session by tools called without a 'network' or 'contract_addr'; calls without a 
+'contract_addr' then use the contract's deployment on that network. A default contract 
+set with 'config__set_default_contract' on the other network is cleared. Returns the 
+session defaults."#;
+
+// Display Instructions
+pub static SET_AMOUNT_FORMAT_DESCR: &str = r#"
+Call this tool to choose how amounts (coins with a 'denom' and an 'amount') are rendered 
+in the results of every tool. 'Base' returns them as the chain does (e.g. 
+'1500000000000000000' 'aarch'), 'Display' in display units (e.g. '1.5' 'ARCH'), and 
+'Both' adds a 'display' field (e.g. '1.5 ARCH') to amounts in base units. Defaults to 
+'Base'. Serialized messages that will be signed (e.g. 'cosmos_msg') are never changed, 
+and always use base units; when passing amounts to tools, always use base units."#;
+
+// Prompt Instructions
+pub static QUERY_BALANCE_PROMPT: &str = r#"
+Query the token balance of {address} on {contract_addr} ({network}).
+
+Resolve {address} with 'name__resolve' if it's a name, then call 'query__execute' with the 
+contract's balance query (see 'query__list_entry_points', e.g. {"balance": {"address": 
+"..."}}). Query {"token_info": {}} for the token's symbol and decimals, and report the 
+balance in display units (and base units), with any label of the address."#;
+
+pub static EXPLAIN_TRANSFER_PROMPT: &str = r#"
+Build a transfer of {amount} (base units) of the token on {contract_addr} ({network}) to 
+{recipient}, and explain it to me before I sign it.
+
+Resolve {recipient} with 'name__resolve' if it's a name, and confirm the resolved address 
+with me. Build the transfer with 'execute__build_msg' (e.g. {"transfer": {"recipient": 
+"...", "amount": "..."}}), then explain in plain words what the built 'cosmos_msg' does: 
+the contract, the recipient (and its label), the amount in display units (query 
+{"token_info": {}} for the decimals), and any 'warnings'. If I give you my address, 
+simulate it with 'execute__simulate' and report whether it will succeed, and its fee. 
+Never sign or broadcast it unless I ask."#;
+
+pub static AUDIT_CONTRACT_PROMPT: &str = r#"
+Summarize the state of {contract_addr} ({network}) for a token holder.
+
+Call 'contract__query_info' for its code id, creator, admin and label, read its cw2 
+version with 'query__build_raw' (namespace 'contract_info'), and query its cw-ownable 
+owner (e.g. {"ownership": {}}) if it has one. Check the peg of the wrapped token with 
+'peg__verify', and list any firing alerts with 'alert__list'. Point out anything a 
+holder should worry about, e.g. a contract with an admin can have its code replaced by 
+a migration, while one without an admin can never be migrated."#;
+
+// Label Instructions
+pub static SET_ADDRESS_LABEL_DESCR: &str = r#"
+Call this tool to attach a human readable label to an address (e.g. 'treasury', 'alice'). 
+Labeled addresses are annotated in the 'address_labels' field of every built query and 
+execute message, which makes it much easier to explain to your chat partner who, or what, 
+a message interacts with."#;
+
+pub static REMOVE_ADDRESS_LABEL_DESCR: &str = r#"
+Call this tool to remove a label previously added with 'address__set_label'. Well-known 
+labels (e.g. the contract deployments and chain module accounts) can't be removed."#;
+
+pub static LIST_ADDRESS_LABELS_DESCR: &str = r#"
+Call this tool to get a list of all labeled addresses, including user supplied labels and 
+well-known addresses (the contract deployments and chain module accounts such as the fee 
+collector)."#;
+
+pub static LOOKUP_ADDRESS_LABEL_DESCR: &str = r#"
+Call this tool to look up the label of an address, or to do a reverse lookup of the 
+addresses matching a label. If the search parameter is a bech32 address its label is 
+returned, otherwise every address whose label contains the search text is returned."#;
+
+pub static CONVERT_ADDRESS_PREFIX_DESCR: &str = r#"
+Call this tool to re-encode an address with another bech32 prefix (e.g. 'cosmos1...' 
+to 'archway1...'), for cross-chain identity checks such as matching a user's accounts 
+on several chains. The converted address has the same bytes as the original address.
+
+A converted address isn't proof of anything: what matters is who holds the key 
+controlling it. Wallets of chains with another coin type (e.g. Injective, Evmos or 
+Terra) derive other keys from the same mnemonic, and contract addresses only exist on 
+their own chain. Always relay the 'warnings' of the result to your chat partner, and 
+never send funds to a converted address without their confirmation."#;
+
+pub static DERIVE_PUBKEY_ADDRESS_DESCR: &str = r#"
+Call this tool to derive the account address of a secp256k1 public key (base64 or hex 
+encoded, e.g. of a signer config, a multisig member or the 'pub_key' of an on-chain 
+account), e.g. to reconcile a configured key with the account it signs for. The 
+'prefix' defaults to the contract's bech32 prefix. Uncompressed keys are compressed 
+first; ethsecp256k1 keys (e.g. of Injective or Evmos accounts) aren't supported."#;
+
+// Name Resolution Instructions
+pub static RESOLVE_NAME_DESCR: &str = r#"
+Call this tool to resolve a name to an address. Supported name services are ArchID 
+(e.g. 'alice.arch'), ICNS (e.g. 'alice.archway', 'alice.osmo', 'alice.cosmos') and 
+Stargaze Names (e.g. 'alice.stars'). ArchID names are resolved on mainnet unless the 
+'network' parameter is set to 'Testnet'."#;
+
+// ArchID Instructions
+pub static LIST_ARCHID_DOMAINS_DESCR: &str = r#"
+Call this tool to list the ArchID domains (e.g. 'alice.arch') registered to an address, 
+including the address each domain resolves to and its expiration (unix timestamp, 
+seconds)."#;
+
+pub static BUILD_ARCHID_REGISTER_MSG_DESCR: &str = r#"
+Call this tool to build a prepared execute message registering an ArchID domain. The 
+registration cost is queried from the ArchID registry and attached as funds, based on 
+the number of 'years' the domain is registered for. Like 'execute__build_msg', the 
+message isn't signed or broadcast by this tool."#;
+
+pub static BUILD_ARCHID_RENEW_MSG_DESCR: &str = r#"
+Call this tool to build a prepared execute message renewing an ArchID domain. The 
+renewal cost is queried from the ArchID registry and attached as funds, based on the 
+number of 'years' the domain is renewed for."#;
+
+pub static BUILD_ARCHID_UPDATE_RESOLVER_MSG_DESCR: &str = r#"
+Call this tool to build a prepared execute message changing the address an ArchID 
+domain resolves to. Only the owner of the domain can sign this message."#;
+
+pub static BUILD_ARCHID_UPDATE_DATA_MSG_DESCR: &str = r#"
+Call this tool to build a prepared execute message updating the metadata of an ArchID 
+domain (e.g. its description, image, linked accounts and websites). Only the owner of 
+the domain can sign this message."#;
+
+// Probe Instructions
+pub static PROBE_CONTRACT_DESCR: &str = r#"
+EXPERIMENTAL. Call this tool to bootstrap a minimal query schema for a contract that 
+doesn't publish one (e.g. any contract other than the deployments listed by 
+'contract__list_deployments'). The tool sends a deliberately invalid query to the 
+contract and parses the resulting error, which lists the query variants the contract 
+expects. It then discovers the required fields of each variant by filling in the 
+fields the contract reports as missing.
+
+The resulting schema is a best-effort guess; optional fields are never discovered and 
+field types are inferred from error messages. Variants marked 'complete: false' could 
+not be fully probed. Let your chat partner know the schema was inferred before relying 
+on it."#;
+
+// Sandbox Instructions
+pub static PROFILE_GAS_DESCR: &str = r#"
+Call this tool to compare the gas consumption of execute entry points before the contract 
+is deployed (or without spending any funds). Each case is executed, in order, against a 
+single instance of the contract running in an in-process sandbox chain (cw-multi-test), 
+so later cases can depend on state created by earlier cases (e.g. a 'transfer' after 
+a 'mint'). Senders given as names (e.g. 'alice') are turned into sandbox addresses, and 
+every sender is funded with native tokens. Without 'cases', an example msg (with its 
+required fields) of every execute entry point is profiled, sent by the owner; example 
+values can make a case fail (e.g. a transfer of more than the owner's balance), so give 
+representative cases for exact comparisons.
+
+The sandbox meters storage access using the Cosmos SDK's KV store gas costs, which is 
+usually the dominant cost of contract execution. Wasm compute isn't metered, so treat 
+the 'storage_gas' of each case as a relative measure (see 'relative_gas') rather than 
+the exact gas a transaction will use on chain. Results are sorted from most to least 
+expensive."#;
+
+pub static GENERATE_TEST_FIXTURE_DESCR: &str = r#"
+Call this tool to turn a scenario explored with the sandbox into a regression test for 
+the contract's repo. The cases are run, in order, in a fresh sandbox (like 'gas__profile') 
+and the result is the Rust source of a cw-multi-test test instantiating the contract, 
+executing every case and asserting its recorded outcome: success, or failure with the 
+contract error. Give the test a descriptive 'test_name'. Share the 'code' with your chat 
+partner as is, along with the 'dev_dependencies' it needs; cases that failed are asserted 
+to fail, so point them out in case the failure is a bug rather than expected behavior."#;
+
+pub static LOAD_TEST_DESCR: &str = r#"
+Call this tool to stress test the contract logic in the sandbox. The tool runs the 
+requested number of executes against a single sandbox instance of the contract, picking 
+a case (and sender) at random for every execution, with up to 'concurrency' executions 
+in flight at once. Use the '$random_amount' and '$random_sender' placeholders in the 
+cases to randomize amounts and counterparties.
+
+The report includes throughput, success and failure counts per case, the distribution 
+of errors (grouped by error, with a translated error code) and request latency. Errors 
+are expected when randomized inputs are invalid (e.g. transferring more than the sender 
+holds); summarize which errors occurred and whether they point to a contract bug."#;
+
+pub static REPLAY_TX_DESCR: &str = r#"
+Call this tool to debug a past transaction (e.g. one that failed, or had an unexpected 
+result). The tool fetches the tx by hash, downloads the contract's state as of the block 
+before the tx (and its bank balances), restores it into a sandbox instance of the 
+contract and re-executes the tx's MsgExecuteContract messages, returning the result (and error) of each execution.
+
+Use 'diverged' to tell whether the replay reproduced the original outcome. Replays are 
+approximate: the sandbox contract has a different address, block time isn't replayed, 
+and txs included earlier in the same block aren't applied. Loading past state requires 
+an archive endpoint for older heights."#;
+
+// Event Instructions
+pub static SCAN_EVENTS_DESCR: &str = r#"
+Call this tool to find what happened to the contract over a range of blocks (e.g. every 
+transfer, mint or admin action). The tool searches the txs sent to the contract within 
+the height range, oldest first, and returns the contract's events ('wasm' and custom 
+'wasm-{type}' events) with their attributes, height and tx hash.
+
+A single call scans a limited number of txs. If 'next_cursor' is returned, call the tool 
+again with that cursor to continue scanning the remaining range. An archive endpoint may 
+be required for old heights."#;
+
+// Permit Instructions
+pub static BUILD_PERMIT_DESCR: &str = r#"
+Call this tool (only for contracts that support signed permits, e.g. SNIP-24 style 
+'query_permit's) to build the sign doc of a permit. A permit lets a contract verify that 
+a query (or execute) was authorized by the signer, without a transaction. The returned 
+'sign_doc' must be signed by your chat partner's wallet (e.g. Keplr 'signAmino' for 
+Snip24 permits, or 'signArbitrary' for Adr036 permits); this server never signs permits. 
+Once signed, embed the signature with 'permit__embed_signature'."#;
+
+pub static EMBED_PERMIT_SIGNATURE_DESCR: &str = r#"
+Call this tool to embed a signed permit into a query msg (or, with 'execute', an execute 
+msg) for a contract that supports permits. The permit 'params' must be exactly the 
+params returned by 'permit__build', otherwise the signature won't verify. The result is a 
+prepared query (like 'query__build_msg') or execute message (like 'execute__build_msg') 
+whose msg is wrapped as '{"with_permit": {"permit": ..., "query": ...}}'.
+
+Permit wrapped msgs aren't validated against the contract's QueryMsg or ExecuteMsg 
+schema, since permit support is contract specific."#;
+
+// Signer Instructions
+pub static IMPORT_SIGNER_KEY_DESCR: &str = r#"
+Call this tool to add a key (mnemonic or private key) to the server's encrypted keystore, 
+for servers built with local signing enabled. The key is loaded from a secret reference 
+(e.g. 'env:SIGNER_MNEMONIC', 'file:/run/secrets/signer'); never ask your chat partner to 
+paste a mnemonic or private key into the chat. The key is encrypted on disk with the 
+passphrase, and stays locked until 'signer__unlock' is called."#;
+
+pub static UNLOCK_SIGNER_DESCR: &str = r#"
+Call this tool to unlock a keystore key for signing, using the passphrase provided by 
+your chat partner. The key stays decrypted in memory only until 'timeout_secs' have 
+passed (or 'signer__lock' is called), after which it must be unlocked again. Only one 
+key is unlocked at a time; unlocking another key locks the previous one."#;
+
+pub static LOCK_SIGNER_DESCR: &str = r#"
+Call this tool to lock the unlocked signer as soon as signing is done, dropping the 
+decrypted key from memory."#;
+
+pub static SIGN_AND_BROADCAST_DESCR: &str = r#"
+Call this tool to sign a transaction built by this server (the result of an execute tool, 
+e.g. 'execute__build_msg') with the unlocked signer, and broadcast it, for servers built 
+with local signing enabled. When an execution delay is configured, only released 
+transactions of the execution queue are broadcast: pass its 'queue_id' (each is broadcast 
+once). Transactions modified since they were built, or lacking the server's provenance 
+signature when a provenance key is configured, are refused. Only wasm execute msgs are 
+supported; the signer's address is the sender. Without a 'gas_limit', the transaction is simulated first and the gas used 
+(with a safety margin) is the limit; the fee is paid in the network's fee denom. Spend 
+caps are checked again before signing, and the broadcast is recorded (no need to call 
+'tx__report_broadcast'). The result is the tx hash, sender, gas limit, fee and memo. Always 
+confirm the transaction with your chat partner before calling this tool."#;
+
+// Authz Instructions
+pub static LIST_AUTHZ_GRANTS_DESCR: &str = r#"
+Call this tool to list the authz grants an address has given (or, as 'Grantee', received). 
+Each grant includes the message type it allows the grantee to execute, the contracts it 
+is limited to (for contract execution authorizations), its expiration and whether it has 
+expired."#;
+
+pub static BUILD_AUTHZ_REVOKE_MSG_DESCR: &str = r#"
+Call this tool to build an authz MsgRevoke, revoking the grant that lets the grantee 
+execute a message type on behalf of the granter. The message must be signed by the 
+granter. It's returned as proto JSON ('sdk_msg'), with warnings if there's no such 
+grant to revoke."#;
+
+pub static BUILD_AUTHZ_EXEC_MSG_DESCR: &str = r#"
+Call this tool to build an authz MsgExec, letting the grantee execute the contract on 
+behalf of the granter (e.g. a bot acting for a user). The execute msg is validated like 
+'execute__build_msg', wrapped as a MsgExecuteContract sent by the granter, and wrapped 
+in a MsgExec signed by the grantee. The grants between them are checked; 'warnings' 
+reports a missing or expired grant, in which case the transaction would fail. Tell your 
+chat partner about any warnings before they sign."#;
+
+// Group Instructions
+pub static BUILD_GROUP_PROPOSAL_MSG_DESCR: &str = r#"
+Call this tool when the contract is administered (or used) by a Cosmos SDK x/group 
+group policy instead of a single account or cw3 multisig. The execute msg is validated 
+like 'execute__build_msg', wrapped as a MsgExecuteContract sent by the group policy, and 
+submitted in a x/group MsgSubmitProposal signed by the proposer. Once enough members 
+vote yes ('group__build_vote_msg'), the proposal can be executed ('group__build_exec_msg'). 
+'warnings' reports a proposer who isn't a member of the group."#;
+
+pub static BUILD_GROUP_VOTE_MSG_DESCR: &str = r#"
+Call this tool to build a x/group MsgVote on a group proposal. 'warnings' reports a 
+proposal that isn't open for voting, or a voter who isn't a member of the group."#;
+
+pub static BUILD_GROUP_EXEC_MSG_DESCR: &str = r#"
+Call this tool to build a x/group MsgExec, executing the messages of an accepted group 
+proposal. 'warnings' reports a proposal that hasn't been accepted, or was already 
+executed."#;
+
+// Governance Instructions
+pub static LIST_GOV_PROPOSALS_DESCR: &str = r#"
+Call this tool to list governance proposals (by default those being voted on). Changes 
+to contract related parameters on Archway (e.g. rewards, callbacks, contract migrations 
+of privileged contracts) often go through governance, so check for proposals that 
+'affects_contract' when your chat partner asks about upcoming changes. Each proposal 
+includes the types of the messages it executes if it passes."#;
+
+pub static GET_GOV_TALLY_DESCR: &str = r#"
+Call this tool to fetch the current tally of a governance proposal (the final tally, 
+once voting has ended), with the share of the votes cast for each option."#;
+
+pub static BUILD_GOV_VOTE_MSG_DESCR: &str = r#"
+Call this tool to build a governance MsgVote. 'warnings' reports a proposal that isn't 
+in its voting period."#;
+
+pub static BUILD_GOV_DEPOSIT_MSG_DESCR: &str = r#"
+Call this tool to build a governance MsgDeposit, adding native tokens to the deposit of 
+a proposal. 'warnings' reports a proposal that no longer accepts deposits."#;
@@ -0,0 +1,316 @@
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::secrets::Secret;
+
+/// Replace with the path of the encrypted keystore used for local signing
+pub static KEYSTORE_PATH: &str = "keystore.json";
+
+/// Time an unlocked signer stays unlocked, unless another timeout is given
+pub static SIGNER_AUTO_LOCK_SECS: u64 = 300;
+pub static MAX_SIGNER_AUTO_LOCK_SECS: u64 = 3_600;
+
+/// scrypt cost parameters used for new keys (N = 2^15, r = 8, p = 1)
+pub static SCRYPT_LOG_N: u8 = 15;
+pub static SCRYPT_R: u32 = 8;
+pub static SCRYPT_P: u32 = 1;
+
+/// A key encrypted with a passphrase (scrypt + XChaCha20-Poly1305)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EncryptedKey {
+    pub kdf: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    /// Base64 encoded values
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct KeystoreFile {
+    pub keys: BTreeMap<String, EncryptedKey>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignerStatus {
+    pub unlocked: Option<String>,
+    pub locks_in_secs: Option<u64>,
+    pub keys: Vec<String>,
+}
+
+/// Encrypted on-disk keystore; key material is only decrypted by
+/// `SignerSession::unlock`
+#[derive(Clone, Debug)]
+pub struct Keystore {
+    path: PathBuf,
+}
+
+impl Default for Keystore {
+    fn default() -> Self {
+        Self::new(KEYSTORE_PATH)
+    }
+}
+
+impl Keystore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn load(&self) -> anyhow::Result<KeystoreFile> {
+        if !self.path.exists() {
+            return Ok(KeystoreFile::default());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("invalid keystore {}: {}", self.path.display(), e))
+    }
+
+    fn save(&self, file: &KeystoreFile) -> anyhow::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(file)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    pub fn names(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self.load()?.keys.into_keys().collect())
+    }
+
+    /// Encrypt and store a key under a name, replacing any key with the
+    /// same name
+    pub fn import(&self, name: &str, key: &Secret, passphrase: &str) -> anyhow::Result<()> {
+        let mut file = self.load()?;
+        file.keys
+            .insert(name.to_string(), crypto::encrypt(key, passphrase)?);
+        self.save(&file)
+    }
+
+    pub fn decrypt(&self, name: &str, passphrase: &str) -> anyhow::Result<Secret> {
+        let file = self.load()?;
+        let key = file
+            .keys
+            .get(name)
+            .ok_or_else(|| anyhow!("no key named '{}' in the keystore", name))?;
+        crypto::decrypt(key, passphrase)
+    }
+}
+
+#[cfg(feature = "signing")]
+mod crypto {
+    use anyhow::anyhow;
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+    use rand::RngCore;
+    use zeroize::Zeroize;
+
+    use super::*;
+
+    fn derive_key(
+        passphrase: &str,
+        salt: &[u8],
+        log_n: u8,
+        r: u32,
+        p: u32,
+    ) -> anyhow::Result<[u8; 32]> {
+        let params = scrypt::Params::new(log_n, r, p, 32)
+            .map_err(|e| anyhow!("invalid scrypt params: {}", e))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+            .map_err(|e| anyhow!("key derivation failed: {}", e))?;
+        Ok(key)
+    }
+
+    pub fn encrypt(secret: &Secret, passphrase: &str) -> anyhow::Result<EncryptedKey> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        rand::rng().fill_bytes(&mut salt);
+        rand::rng().fill_bytes(&mut nonce);
+        let mut key = derive_key(passphrase, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+        let cipher = XChaCha20Poly1305::new(&key.into());
+        key.zeroize();
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), secret.expose().as_bytes())
+            .map_err(|_| anyhow!("encryption failed"))?;
+        Ok(EncryptedKey {
+            kdf: "scrypt".to_string(),
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(encrypted: &EncryptedKey, passphrase: &str) -> anyhow::Result<Secret> {
+        if encrypted.kdf != "scrypt" {
+            anyhow::bail!("unsupported keystore kdf '{}'", encrypted.kdf);
+        }
+        let salt = STANDARD.decode(&encrypted.salt)?;
+        let nonce = STANDARD.decode(&encrypted.nonce)?;
+        let ciphertext = STANDARD.decode(&encrypted.ciphertext)?;
+        if nonce.len() != 24 {
+            anyhow::bail!("invalid keystore nonce");
+        }
+        let mut key = derive_key(passphrase, &salt, encrypted.log_n, encrypted.r, encrypted.p)?;
+        let cipher = XChaCha20Poly1305::new(&key.into());
+        key.zeroize();
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("wrong passphrase (or corrupted key)"))?;
+        let secret = String::from_utf8(plaintext).map_err(|_| anyhow!("corrupted key"))?;
+        Ok(Secret::new(secret))
+    }
+}
+
+#[cfg(not(feature = "signing"))]
+mod crypto {
+    use super::*;
+
+    static SIGNING_DISABLED: &str =
+        "local signing is disabled, build the server with the 'signing' feature";
+
+    pub fn encrypt(_secret: &Secret, _passphrase: &str) -> anyhow::Result<EncryptedKey> {
+        Err(anyhow!(SIGNING_DISABLED))
+    }
+
+    pub fn decrypt(_encrypted: &EncryptedKey, _passphrase: &str) -> anyhow::Result<Secret> {
+        Err(anyhow!(SIGNING_DISABLED))
+    }
+}
+
+struct UnlockedKey {
+    name: String,
+    key: Secret,
+    locks_at: Instant,
+    /// Distinguishes unlocks, so an auto-lock only locks its own unlock
+    generation: u64,
+}
+
+#[derive(Default)]
+struct SessionState {
+    unlocked: Option<UnlockedKey>,
+    generation: u64,
+}
+
+/// Holds at most one decrypted key, until it's locked or times out
+#[derive(Clone, Default)]
+pub struct SignerSession {
+    state: Arc<RwLock<SessionState>>,
+}
+
+impl std::fmt::Debug for SignerSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignerSession")
+            .field("unlocked", &self.unlocked_name())
+            .finish()
+    }
+}
+
+impl SignerSession {
+    /// Decrypt a key and keep it in memory for `timeout`, replacing any
+    /// unlocked key
+    pub fn unlock(
+        &self,
+        keystore: &Keystore,
+        name: &str,
+        passphrase: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let key = keystore.decrypt(name, passphrase)?;
+        let generation = {
+            let mut state = self.state.write().map_err(|_| anyhow!("signer poisoned"))?;
+            state.generation += 1;
+            let generation = state.generation;
+            state.unlocked = Some(UnlockedKey {
+                name: name.to_string(),
+                key,
+                locks_at: Instant::now() + timeout,
+                generation,
+            });
+            generation
+        };
+        let session = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if let Ok(mut state) = session.state.write() {
+                if state
+                    .unlocked
+                    .as_ref()
+                    .is_some_and(|unlocked| unlocked.generation == generation)
+                {
+                    state.unlocked = None;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Drop the unlocked key (its memory is zeroed)
+    pub fn lock(&self) {
+        if let Ok(mut state) = self.state.write() {
+            state.unlocked = None;
+        }
+    }
+
+    fn unlocked_name(&self) -> Option<String> {
+        self.state.read().ok().and_then(|state| {
+            state
+                .unlocked
+                .as_ref()
+                .filter(|unlocked| unlocked.locks_at > Instant::now())
+                .map(|unlocked| unlocked.name.clone())
+        })
+    }
+
+    /// The unlocked key, if it hasn't timed out
+    pub fn key(&self) -> anyhow::Result<(String, Secret)> {
+        let state = self.state.read().map_err(|_| anyhow!("signer poisoned"))?;
+        state
+            .unlocked
+            .as_ref()
+            .filter(|unlocked| unlocked.locks_at > Instant::now())
+            .map(|unlocked| (unlocked.name.clone(), unlocked.key.clone()))
+            .ok_or_else(|| anyhow!("no signer is unlocked, call 'signer__unlock' first"))
+    }
+
+    pub fn status(&self, keystore: &Keystore) -> SignerStatus {
+        let (unlocked, locks_in_secs) = match self.state.read() {
+            Ok(state) => match state
+                .unlocked
+                .as_ref()
+                .filter(|unlocked| unlocked.locks_at > Instant::now())
+            {
+                Some(unlocked) => (
+                    Some(unlocked.name.clone()),
+                    Some(
+                        unlocked
+                            .locks_at
+                            .saturating_duration_since(Instant::now())
+                            .as_secs(),
+                    ),
+                ),
+                None => (None, None),
+            },
+            Err(_) => (None, None),
+        };
+        SignerStatus {
+            unlocked,
+            locks_in_secs,
+            keys: keystore.names().unwrap_or_default(),
+        }
+    }
+}
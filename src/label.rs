@@ -0,0 +1,165 @@
+use bech32::{Bech32, Hrp};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use crate::contract::*;
+
+/// Matches anything shaped like a bech32 account or contract address
+/// (e.g. `archway1...`), so built messages and query results can be
+/// scanned for addresses worth annotating
+static ADDRESS_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[a-z]{2,20}1[ac-hj-np-z02-9]{38,58}\b").expect("valid address pattern")
+});
+
+/// Cosmos SDK module accounts that commonly show up as counterparties
+/// (fees, staking, governance deposits). Their addresses are derived
+/// from the module name, so they never need to be hardcoded
+pub static WELL_KNOWN_MODULES: [&str; 6] = [
+    "fee_collector",
+    "distribution",
+    "bonded_tokens_pool",
+    "not_bonded_tokens_pool",
+    "gov",
+    "mint",
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum LabelSource {
+    User,
+    WellKnown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddressLabel {
+    pub address: String,
+    pub label: String,
+    pub source: LabelSource,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LabelStore {
+    labels: BTreeMap<String, AddressLabel>,
+}
+
+impl LabelStore {
+    /// Seed the store with the contract deployments and well-known
+    /// module accounts of every network the server supports
+    pub fn new(contracts: &[CwContract]) -> Self {
+        let mut store = Self::default();
+        for contract in contracts {
+            store.insert_well_known(
+                contract.contract_address.clone(),
+                format!("contract ({})", contract.chain_id),
+            );
+            let prefix = contract
+                .contract_address
+                .split('1')
+                .next()
+                .unwrap_or_default();
+            for module in WELL_KNOWN_MODULES {
+                if let Some(address) = module_address(prefix, module) {
+                    store.insert_well_known(address, format!("{} module", module));
+                }
+            }
+        }
+        store
+    }
+
+    fn insert_well_known(&mut self, address: String, label: String) {
+        self.labels.insert(
+            address.clone(),
+            AddressLabel {
+                address,
+                label,
+                source: LabelSource::WellKnown,
+            },
+        );
+    }
+
+    /// Add (or replace) a user supplied label
+    pub fn insert(&mut self, address: String, label: String) -> AddressLabel {
+        let entry = AddressLabel {
+            address: address.clone(),
+            label,
+            source: LabelSource::User,
+        };
+        self.labels.insert(address, entry.clone());
+        entry
+    }
+
+    /// Remove a user supplied label; well-known labels can't be removed
+    pub fn remove(&mut self, address: &str) -> Option<AddressLabel> {
+        match self.labels.get(address) {
+            Some(entry) if entry.source == LabelSource::User => self.labels.remove(address),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, address: &str) -> Option<&AddressLabel> {
+        self.labels.get(address)
+    }
+
+    pub fn list(&self) -> Vec<AddressLabel> {
+        self.labels.values().cloned().collect()
+    }
+
+    /// Reverse lookup; find every address whose label contains `label`
+    /// (case insensitive)
+    pub fn find_by_label(&self, label: &str) -> Vec<AddressLabel> {
+        let needle = label.to_lowercase();
+        self.labels
+            .values()
+            .filter(|entry| entry.label.to_lowercase().contains(&needle))
+            .cloned()
+            .collect()
+    }
+
+    /// Find every labeled address appearing in `text` (e.g. a serialized
+    /// query result or built message)
+    pub fn annotate(&self, text: &str) -> Vec<AddressLabel> {
+        let mut found: BTreeMap<&str, AddressLabel> = BTreeMap::new();
+        for address in ADDRESS_PATTERN.find_iter(text) {
+            if let Some(entry) = self.labels.get(address.as_str()) {
+                found.insert(address.as_str(), entry.clone());
+            }
+        }
+        found.into_values().collect()
+    }
+}
+
+/// Returns true if `address` looks like a bech32 address
+pub fn is_address(address: &str) -> bool {
+    ADDRESS_PATTERN
+        .find(address)
+        .is_some_and(|m| m.as_str() == address)
+}
+
+/// Decode a bech32 address, returning its prefix; the checksum and the
+/// length of its data (20 bytes for accounts, 32 for contracts) are
+/// checked
+pub fn decode_address(address: &str) -> anyhow::Result<String> {
+    let (hrp, data) = bech32::decode(address)
+        .map_err(|e| anyhow::anyhow!("invalid address '{}', {}", address, e))?;
+    if address.chars().any(|c| c.is_ascii_uppercase()) {
+        anyhow::bail!("invalid address '{}', addresses are lowercase", address);
+    }
+    if data.len() != 20 && data.len() != 32 {
+        anyhow::bail!(
+            "invalid address '{}', decodes to {} bytes instead of 20 (account) or 32 (contract)",
+            address,
+            data.len()
+        );
+    }
+    Ok(hrp.to_string())
+}
+
+/// Derive a module account address (e.g. `fee_collector`) for a bech32 prefix
+pub fn module_address(prefix: &str, module: &str) -> Option<String> {
+    let hrp = Hrp::parse(prefix).ok()?;
+    let hash = Sha256::digest(module.as_bytes());
+    bech32::encode::<Bech32>(hrp, &hash[..20]).ok()
+}
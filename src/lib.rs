@@ -0,0 +1,172 @@
+//! The MCP server of a CosmWasm contract, as a library: applications
+//! embed it with `CwMcpBuilder` (custom contract registries, injected
+//! clients), and serve it over a transport with `serve`. Its msg
+//! building core also compiles to `wasm32-unknown-unknown`, for web dApps
+
+// Msg building core, compiled for every target (including wasm32, with
+// the JavaScript bindings of `src/wasm.rs`)
+pub mod address;
+pub mod cosmos;
+pub mod encoding;
+pub mod entry_point;
+pub mod example;
+pub mod funds;
+pub mod proto;
+pub mod repair;
+pub mod semantic;
+pub mod stargate;
+pub mod tx;
+pub mod validate;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+// The MCP server (native targets only)
+#[cfg(not(target_arch = "wasm32"))]
+pub mod activity;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod adapter;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod alert;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod archid;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod authz;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod blob;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod breaker;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod broadcast;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod builder;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chunk;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clock;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod codegen;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod compare;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod contract;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod denom;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod display;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod endpoint;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod env;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod execute;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fees;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fixture;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gov;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod graph;
+#[cfg(all(not(target_arch = "wasm32"), feature = "graphql"))]
+pub mod graphql;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod group;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod history;
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+pub mod hook;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod idl;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod instruction;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod keystore;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod label;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod loadtest;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mempool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mint;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod naming;
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+pub mod operator;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pause;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod permit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod preflight;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod price;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod probe;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod prompt;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod provenance;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod proxy;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod query;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod queue;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recipe;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod redact;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod registry;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod replay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod resolver;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod resource;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rpc;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod sandbox;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod schema;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod secrets;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod spend;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod supply;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tags;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transport;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod usage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod workflow;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use builder::CwMcpBuilder;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::CwMcp;
+#[cfg(not(target_arch = "wasm32"))]
+pub use transport::{ServerTransport, serve, serve_with};
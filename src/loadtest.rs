@@ -0,0 +1,194 @@
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::error::{ErrorCode, translate};
+use crate::sandbox::*;
+
+/// String values replaced with a random amount in every execution
+pub static RANDOM_AMOUNT: &str = "$random_amount";
+/// String values replaced with a random sender in every execution
+pub static RANDOM_SENDER: &str = "$random_sender";
+
+pub static MAX_RANDOM_AMOUNT: u128 = 1_000_000;
+pub static MAX_LOAD_TEST_EXECUTIONS: usize = 10_000;
+pub static MAX_LOAD_TEST_CONCURRENCY: usize = 256;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CaseSummary {
+    pub executions: u64,
+    pub failures: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ErrorBucket {
+    pub code: ErrorCode,
+    pub error: String,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct LatencySummary {
+    pub p50_micros: u128,
+    pub p95_micros: u128,
+    pub max_micros: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoadTestReport {
+    pub executions: usize,
+    pub concurrency: usize,
+    pub duration_millis: u128,
+    pub throughput_per_sec: f64,
+    pub successes: u64,
+    pub failures: u64,
+    pub per_case: BTreeMap<String, CaseSummary>,
+    /// Failures grouped by (translated) error, most frequent first
+    pub errors: Vec<ErrorBucket>,
+    /// Time from a request being sent to the sandbox, until its result is
+    /// received (includes time queued behind other requests)
+    pub latency: LatencySummary,
+}
+
+/// Replace random placeholders in a message; senders are replaced with
+/// their sandbox address
+fn randomize(value: &Value, senders: &[String], rng: &mut impl Rng) -> Value {
+    match value {
+        Value::String(s) if s == RANDOM_AMOUNT => {
+            Value::String(rng.random_range(1..=MAX_RANDOM_AMOUNT).to_string())
+        }
+        Value::String(s) if s == RANDOM_SENDER => match senders.choose(rng) {
+            Some(sender) => Value::String(sandbox_address(sender)),
+            None => value.clone(),
+        },
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), randomize(value, senders, rng)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| randomize(value, senders, rng))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn percentile(sorted: &[u128], percent: usize) -> u128 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[(sorted.len() - 1) * percent / 100]
+}
+
+/// Run `executions` randomly picked (and randomized) cases against a single
+/// sandbox instance, keeping up to `concurrency` requests in flight
+pub async fn run(
+    instantiate_msg: Value,
+    cases: Vec<SandboxCase>,
+    executions: usize,
+    concurrency: usize,
+    senders: Vec<String>,
+) -> anyhow::Result<LoadTestReport> {
+    if cases.is_empty() {
+        anyhow::bail!("at least one case is required");
+    }
+    let executions = executions.clamp(1, MAX_LOAD_TEST_EXECUTIONS);
+    let concurrency = concurrency.clamp(1, MAX_LOAD_TEST_CONCURRENCY);
+    let planned: Vec<SandboxCase> = {
+        let mut rng = rand::rng();
+        let mut planned = Vec::with_capacity(executions);
+        for _ in 0..executions {
+            let Some(case) = cases.choose(&mut rng) else {
+                break;
+            };
+            let mut case = case.clone();
+            case.name = Some(case.name());
+            case.execute_msg = randomize(&case.execute_msg, &senders, &mut rng);
+            if case.sender.is_none() {
+                case.sender = senders.choose(&mut rng).cloned();
+            }
+            planned.push(case);
+        }
+        planned
+    };
+    let mut funded = senders.clone();
+    funded.extend(cases.iter().filter_map(|case| case.sender.clone()));
+    let sandbox = SandboxHandle::spawn(instantiate_msg, funded).await?;
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let started = Instant::now();
+    let mut tasks = JoinSet::new();
+    for case in planned {
+        let permit = semaphore.clone().acquire_owned().await?;
+        let sandbox = sandbox.clone();
+        tasks.spawn(async move {
+            let sent = Instant::now();
+            let profile = sandbox.execute(case).await;
+            drop(permit);
+            (profile, sent.elapsed().as_micros())
+        });
+    }
+
+    let mut successes = 0;
+    let mut failures = 0;
+    let mut latencies: Vec<u128> = vec![];
+    let mut per_case: BTreeMap<String, CaseSummary> = BTreeMap::new();
+    let mut errors: BTreeMap<String, ErrorBucket> = BTreeMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (profile, latency) = joined?;
+        let profile = profile?;
+        latencies.push(latency);
+        let summary = per_case.entry(profile.name.clone()).or_default();
+        summary.executions += 1;
+        if profile.success {
+            successes += 1;
+            continue;
+        }
+        failures += 1;
+        summary.failures += 1;
+        let error = profile
+            .error
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        errors
+            .entry(error.clone())
+            .or_insert_with(|| ErrorBucket {
+                code: translate("", &error).code,
+                error,
+                count: 0,
+            })
+            .count += 1;
+    }
+    let duration = started.elapsed();
+    latencies.sort_unstable();
+    let mut errors: Vec<ErrorBucket> = errors.into_values().collect();
+    errors.sort_by(|a, b| b.count.cmp(&a.count));
+    Ok(LoadTestReport {
+        executions,
+        concurrency,
+        duration_millis: duration.as_millis(),
+        throughput_per_sec: executions as f64 / duration.as_secs_f64().max(f64::EPSILON),
+        successes,
+        failures,
+        per_case,
+        errors,
+        latency: LatencySummary {
+            p50_micros: percentile(&latencies, 50),
+            p95_micros: percentile(&latencies, 95),
+            max_micros: latencies.last().copied().unwrap_or_default(),
+        },
+    })
+}
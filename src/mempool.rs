@@ -0,0 +1,104 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::rpc::LcdClient;
+
+/// Replace with the unconfirmed txs from which the mempool is busy, and
+/// congested (Archway blocks hold a few hundred txs)
+pub static BUSY_TXS: u64 = 100;
+pub static CONGESTED_TXS: u64 = 500;
+
+/// Gas price of fee suggestions while the mempool is busy or congested,
+/// in percent of the suggestion from recent fees
+pub static BUSY_FEE_PERCENT: u128 = 120;
+pub static CONGESTED_FEE_PERCENT: u128 = 150;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum Congestion {
+    #[default]
+    Normal,
+    Busy,
+    Congested,
+}
+
+impl Congestion {
+    pub fn of(unconfirmed_txs: u64) -> Self {
+        if unconfirmed_txs >= CONGESTED_TXS {
+            Congestion::Congested
+        } else if unconfirmed_txs >= BUSY_TXS {
+            Congestion::Busy
+        } else {
+            Congestion::Normal
+        }
+    }
+
+    /// Gas price adjustment, in percent
+    pub fn fee_percent(&self) -> u128 {
+        match self {
+            Congestion::Normal => 100,
+            Congestion::Busy => BUSY_FEE_PERCENT,
+            Congestion::Congested => CONGESTED_FEE_PERCENT,
+        }
+    }
+
+    /// When to broadcast, given the congestion
+    pub fn recommendation(&self) -> &'static str {
+        match self {
+            Congestion::Normal => {
+                "the mempool is clear, transactions should confirm in the next blocks"
+            }
+            Congestion::Busy => {
+                "the mempool is busy, pay the suggested fee (or more) so the transaction isn't delayed"
+            }
+            Congestion::Congested => {
+                "the mempool is congested, delay transactions that aren't urgent, or pay the raised suggested fee and expect delays"
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MempoolStatus {
+    /// RPC endpoint the mempool was read from
+    pub endpoint: String,
+    pub unconfirmed_txs: u64,
+    pub unconfirmed_bytes: u64,
+    pub congestion: Congestion,
+    pub fee_percent: u128,
+    pub recommendation: String,
+}
+
+/// Reads the unconfirmed txs of a node's mempool
+#[derive(Clone, Debug)]
+pub struct MempoolMonitor {
+    lcd: LcdClient,
+}
+
+impl MempoolMonitor {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Mempool of the node of a (CometBFT) RPC endpoint
+    pub async fn status(&self, rpc_endpoint: &str) -> anyhow::Result<MempoolStatus> {
+        let body = self.lcd.get(rpc_endpoint, "/num_unconfirmed_txs").await?;
+        let result = &body["result"];
+        let count = |key: &str| {
+            result[key]
+                .as_str()
+                .and_then(|count| count.parse::<u64>().ok())
+        };
+        let unconfirmed_txs = count("total")
+            .or(count("n_txs"))
+            .ok_or_else(|| anyhow::anyhow!("invalid num_unconfirmed_txs response"))?;
+        let congestion = Congestion::of(unconfirmed_txs);
+        Ok(MempoolStatus {
+            endpoint: rpc_endpoint.to_string(),
+            unconfirmed_txs,
+            unconfirmed_bytes: count("total_bytes").unwrap_or_default(),
+            congestion,
+            fee_percent: congestion.fee_percent(),
+            recommendation: congestion.recommendation().to_string(),
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::anyhow;
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+use crate::rpc::LcdClient;
+
+/// ExecuteMsg variant of cw20 mints
+pub static MINT_VARIANT: &str = "mint";
+
+/// Minter, cap and supply of a cw20 token, checked against a proposed mint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintCheck {
+    /// Only this address can sign the mint
+    pub minter: String,
+    /// Maximum total supply, if the token is capped
+    pub cap: Option<Uint128>,
+    pub total_supply: Uint128,
+    pub amount: Uint128,
+    /// Amount that can still be minted, if the token is capped
+    pub remaining_mintable: Option<Uint128>,
+    pub within_cap: bool,
+}
+
+/// Amount of a cw20 mint msg (`{"mint": {"recipient": .., "amount": ..}}`)
+pub fn mint_amount(msg: &Value) -> Option<Uint128> {
+    msg.get(MINT_VARIANT)?
+        .get("amount")?
+        .as_str()
+        .and_then(|amount| Uint128::from_str(amount).ok())
+}
+
+fn parse_uint(value: &Value) -> Option<Uint128> {
+    value
+        .as_str()
+        .and_then(|value| Uint128::from_str(value).ok())
+}
+
+#[derive(Clone, Debug)]
+pub struct MintChecker {
+    lcd: LcdClient,
+}
+
+impl MintChecker {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Query the token's minter, cap and total supply, and check a mint of
+    /// `amount` against them
+    pub async fn check(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        amount: Uint128,
+    ) -> anyhow::Result<MintCheck> {
+        let minter = self
+            .lcd
+            .smart_query(endpoint, contract_addr, &json!({ "minter": {} }))
+            .await?;
+        if minter.is_null() {
+            anyhow::bail!("{} has no minter, minting is disabled", contract_addr);
+        }
+        let token_info = self
+            .lcd
+            .smart_query(endpoint, contract_addr, &json!({ "token_info": {} }))
+            .await?;
+        let total_supply = parse_uint(&token_info["total_supply"])
+            .ok_or_else(|| anyhow!("token_info of {} has no total_supply", contract_addr))?;
+        let cap = parse_uint(&minter["cap"]);
+        let remaining_mintable = cap.map(|cap| cap.saturating_sub(total_supply));
+        Ok(MintCheck {
+            minter: minter["minter"].as_str().unwrap_or_default().to_string(),
+            within_cap: remaining_mintable.is_none_or(|remaining| amount <= remaining),
+            cap,
+            total_supply,
+            amount,
+            remaining_mintable,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use rmcp::model::Tool;
+
+use crate::recipe::{LEGACY_RECIPE_TOOL_PREFIX, RECIPE_TOOL_PREFIX};
+
+/// Published names of the tools (`<namespace>__<verb>_<object>`, the object
+/// omitted when it's the namespace's; MCP clients only accept tool names of
+/// `[a-zA-Z0-9_-]`), with the names they were first published under. The
+/// first published names are the tools' methods, and stay callable as
+/// aliases for clients with pinned tool names; a tool added to the server
+/// must be added here, in its namespace
+pub static TOOL_NAMES: &[(&str, &str)] = &[
+    // Contracts
+    ("contract__list_deployments", "list_contract_deployments"),
+    ("contract__register", "register_contract"),
+    ("contract__remove", "remove_contract"),
+    ("contract__list_adapters", "list_contract_adapters"),
+    ("contract__query_info", "query_contract_info"),
+    ("contract__probe", "probe_contract"),
+    ("contract__discover_dependencies", "discover_dependencies"),
+    ("contract__compare_deployments", "compare_deployments"),
+    // Queries
+    ("query__list_entry_points", "list_query_entry_points"),
+    ("query__list_responses", "list_query_responses"),
+    ("query__build_msg", "build_query_msg"),
+    ("query__build_raw", "build_raw_query"),
+    ("query__execute", "execute_query"),
+    ("query__decode_response", "decode_query_response"),
+    ("stargate__build_query", "build_stargate_query"),
+    // Msgs
+    ("msg__generate_example", "generate_example_msg"),
+    ("execute__list_entry_points", "list_tx_entry_points"),
+    ("execute__build_msg", "build_execute_msg"),
+    ("execute__preflight", "preflight_execute"),
+    ("execute__simulate", "simulate_execute"),
+    ("instantiate__get_schema", "list_instantiate_schema"),
+    ("instantiate__build_msg", "build_instantiate_msg"),
+    ("migrate__build_msg", "build_migrate_msg"),
+    // Addresses and names
+    ("address__set_label", "set_address_label"),
+    ("address__remove_label", "remove_address_label"),
+    ("address__list_labels", "list_address_labels"),
+    ("address__lookup_label", "lookup_address_label"),
+    ("address__convert_prefix", "convert_address_prefix"),
+    ("address__derive_from_pubkey", "derive_pubkey_address"),
+    ("name__resolve", "resolve_name"),
+    ("archid__list_domains", "list_archid_domains"),
+    ("archid__build_register_msg", "build_archid_register_msg"),
+    ("archid__build_renew_msg", "build_archid_renew_msg"),
+    (
+        "archid__build_update_resolver_msg",
+        "build_archid_update_resolver_msg",
+    ),
+    (
+        "archid__build_update_data_msg",
+        "build_archid_update_data_msg",
+    ),
+    // Testing
+    ("gas__profile", "profile_gas"),
+    ("test__generate_fixture", "generate_test_fixture"),
+    ("test__load", "load_test"),
+    ("tx__replay", "replay_tx"),
+    ("events__scan", "scan_events"),
+    // Permits and signers
+    ("permit__build", "build_permit"),
+    ("permit__embed_signature", "embed_permit_signature"),
+    ("signer__import_key", "import_signer_key"),
+    ("signer__unlock", "unlock_signer"),
+    ("signer__lock", "lock_signer"),
+    // Modules
+    ("authz__list_grants", "list_authz_grants"),
+    ("authz__build_revoke_msg", "build_authz_revoke_msg"),
+    ("authz__build_exec_msg", "build_authz_exec_msg"),
+    ("group__build_proposal_msg", "build_group_proposal_msg"),
+    ("group__build_vote_msg", "build_group_vote_msg"),
+    ("group__build_exec_msg", "build_group_exec_msg"),
+    ("gov__list_proposals", "list_gov_proposals"),
+    ("gov__get_tally", "get_gov_tally"),
+    ("gov__build_vote_msg", "build_gov_vote_msg"),
+    ("gov__build_deposit_msg", "build_gov_deposit_msg"),
+    // Wrapped token
+    ("supply__get_report", "supply_report"),
+    ("supply__get_history", "supply_history"),
+    ("peg__build_wrap_msg", "wrap"),
+    ("peg__build_unwrap_msg", "unwrap"),
+    ("peg__verify", "verify_peg"),
+    // Fees and mempool
+    ("mempool__get_status", "get_mempool_status"),
+    ("fee__suggest", "suggest_fee"),
+    // Session settings
+    ("config__set_amount_format", "set_amount_format"),
+    ("config__set_default_contract", "set_default_contract"),
+    ("config__set_default_network", "set_default_network"),
+    ("config__set_offline_mode", "set_offline_mode"),
+    // Alerts and circuit breaker
+    ("alert__add_rule", "add_alert_rule"),
+    ("alert__remove_rule", "remove_alert_rule"),
+    ("alert__list", "list_alerts"),
+    ("breaker__get_status", "get_circuit_breaker"),
+    ("breaker__reset", "reset_circuit_breaker"),
+    // Activity
+    ("activity__export", "export_activity"),
+    ("activity__tag", "tag_activity"),
+    ("activity__untag", "untag_activity"),
+    ("activity__list_tagged", "list_tagged_activity"),
+    // Workflows and the execution queue
+    ("workflow__run", "run_workflow"),
+    ("workflow__advance", "advance_workflow"),
+    ("workflow__get", "get_workflow"),
+    ("queue__add_execute", "queue_execute"),
+    ("queue__list", "list_execution_queue"),
+    ("queue__release_execute", "release_queued_execute"),
+    ("queue__cancel_execute", "cancel_queued_execute"),
+    // Broadcasting
+    ("tx__sign_and_broadcast", "sign_and_broadcast"),
+    ("tx__report_broadcast", "report_broadcast"),
+    // Server
+    ("server__get_config", "get_server_config"),
+    ("server__get_usage_stats", "get_usage_stats"),
+    ("server__get_spend_status", "get_spend_status"),
+    ("server__get_endpoint_stats", "get_endpoint_stats"),
+    ("server__get_schema_cache_stats", "get_schema_cache_stats"),
+    ("result__get_chunk", "get_result_chunk"),
+];
+
+/// Replace with `true` to list the tools under their first published
+/// names, for clients checking pinned tool names against the list (the
+/// published names stay callable)
+pub static LIST_LEGACY_NAMES: bool = false;
+
+/// Published name of a tool method
+pub fn published_name(method: &str) -> Option<&'static str> {
+    TOOL_NAMES
+        .iter()
+        .find(|(_, legacy)| *legacy == method)
+        .map(|(name, _)| *name)
+}
+
+/// Name a called tool is dispatched by: the method of a tool's published
+/// name (aliases are the methods themselves), or the current tool name of
+/// a recipe's legacy one (which the current prefix extends). Every
+/// per-tool rule (e.g. the circuit breaker and usage stats) is keyed by it
+pub fn dispatch_name(name: &str) -> String {
+    if let Some((_, method)) = TOOL_NAMES.iter().find(|(published, _)| *published == name) {
+        return method.to_string();
+    }
+    if name.starts_with(RECIPE_TOOL_PREFIX) {
+        return name.to_string();
+    }
+    match name.strip_prefix(LEGACY_RECIPE_TOOL_PREFIX) {
+        Some(recipe) => format!("{}{}", RECIPE_TOOL_PREFIX, recipe),
+        None => name.to_string(),
+    }
+}
+
+/// A tool method, listed under its published name
+pub fn published(mut tool: Tool) -> Tool {
+    if LIST_LEGACY_NAMES {
+        return tool;
+    }
+    if let Some(name) = published_name(&tool.name) {
+        tool.name = name.into();
+    }
+    tool
+}
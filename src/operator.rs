@@ -0,0 +1,104 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::breaker::OPERATOR_TOKEN;
+use crate::queue::ExecutionQueue;
+use crate::secrets::{LazySecret, Secrets, token_matches};
+
+/// Path operators list the execution queue at
+pub static OPERATOR_QUEUE_PATH: &str = "/operator/queue";
+
+/// Path operators POST to, to veto (cancel) the held transaction `{id}`
+pub static OPERATOR_CANCEL_PATH: &str = "/operator/queue/{id}/cancel";
+
+/// Logger (target) of operator actions
+pub static OPERATOR_LOGGER: &str = "operator";
+
+/// The execution queue, for operators outside the agent's MCP sessions
+#[derive(Clone, Debug)]
+struct Operator {
+    queue: ExecutionQueue,
+    token: LazySecret,
+}
+
+#[derive(Deserialize, Default)]
+struct CancelRequest {
+    reason: Option<String>,
+}
+
+impl Operator {
+    async fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Ok(token) = self.token.get().await else {
+            tracing::warn!(target: OPERATOR_LOGGER, "operator token couldn't be resolved");
+            return false;
+        };
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, token.expose()))
+    }
+}
+
+fn unauthorized() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({ "error": "missing or invalid bearer token" })),
+    )
+}
+
+async fn list(State(operator): State<Operator>, headers: HeaderMap) -> (StatusCode, Json<Value>) {
+    if !operator.authorized(&headers).await {
+        return unauthorized();
+    }
+    let queue = json!({
+        "delay_secs": operator.queue.delay_secs(),
+        "queue": operator.queue.list(),
+    });
+    (StatusCode::OK, Json(queue))
+}
+
+async fn cancel(
+    State(operator): State<Operator>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<CancelRequest>>,
+) -> (StatusCode, Json<Value>) {
+    if !operator.authorized(&headers).await {
+        return unauthorized();
+    }
+    let reason = body.and_then(|Json(request)| request.reason);
+    match operator.queue.cancel(&id, reason) {
+        Ok(entry) => {
+            tracing::info!(target: OPERATOR_LOGGER, id = %id, "queued transaction vetoed");
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(&entry).unwrap_or_default()),
+            )
+        }
+        Err(e) => (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+/// Router of the operator endpoints, if an `OPERATOR_TOKEN` is configured:
+/// the (process-wide) execution queue, and vetoes of its held transactions
+pub fn router() -> Option<Router> {
+    let token = OPERATOR_TOKEN?;
+    let operator = Operator {
+        queue: ExecutionQueue::default(),
+        token: LazySecret::new(token, Secrets::default()),
+    };
+    Some(
+        Router::new()
+            .route(OPERATOR_QUEUE_PATH, get(list))
+            .route(OPERATOR_CANCEL_PATH, post(cancel))
+            .with_state(operator),
+    )
+}
@@ -0,0 +1,113 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::rpc::LcdClient;
+
+/// Whether executes are checked for a paused contract by default (with
+/// `check_paused` of `build_execute_msg` overriding it); each check sends
+/// a few queries
+pub static CHECK_PAUSED: bool = false;
+
+/// Replace with the query variants of your contract reporting whether
+/// it's paused (e.g. `{"paused": {}}` returning a bool)
+pub static PAUSE_QUERIES: &[&str] = &["paused", "is_paused", "pause_info"];
+
+/// Query variants whose responses (e.g. a config) may hold a pause flag
+pub static CONFIG_QUERIES: &[&str] = &["config", "state", "status"];
+
+/// Fields of a config holding its pause flag
+pub static PAUSE_FIELDS: &[&str] = &["paused", "is_paused", "halted", "frozen"];
+
+/// Storage keys of a pause flag's `Item`
+pub static PAUSE_KEYS: &[&str] = &["paused", "pause"];
+
+/// Where a contract's pause state was found, and what it was
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseEvidence {
+    /// Query (or storage key) the state was read from
+    pub source: String,
+    pub value: Value,
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseCheck {
+    /// True if any source reports the contract as paused
+    pub paused: bool,
+    /// Sources that reported a pause state; none if the contract doesn't
+    /// implement a known pause pattern
+    pub evidence: Vec<PauseEvidence>,
+}
+
+/// Pause state of a flag: a bool, a status string (e.g. `"paused"`), or
+/// an object holding one of `PAUSE_FIELDS`
+fn pause_state(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(paused) => Some(*paused),
+        Value::String(status) => match status.to_lowercase().as_str() {
+            "paused" | "halted" | "frozen" => Some(true),
+            "active" | "running" | "unpaused" | "normal" => Some(false),
+            _ => None,
+        },
+        Value::Object(fields) => PAUSE_FIELDS
+            .iter()
+            .find_map(|field| fields.get(*field).and_then(pause_state)),
+        _ => None,
+    }
+}
+
+/// Probes a contract for common pause patterns
+#[derive(Clone, Debug)]
+pub struct PauseDetector {
+    lcd: LcdClient,
+}
+
+impl PauseDetector {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Query the pause queries, configs and pause keys of a contract;
+    /// patterns the contract doesn't implement are skipped
+    pub async fn check(&self, endpoint: &str, contract_addr: &str) -> PauseCheck {
+        let mut evidence = vec![];
+        let variants = PAUSE_QUERIES.iter().chain(CONFIG_QUERIES);
+        for variant in variants {
+            let query = json!({ *variant: {} });
+            let Ok(response) = self.lcd.smart_query(endpoint, contract_addr, &query).await else {
+                continue;
+            };
+            if let Some(paused) = pause_state(&response) {
+                evidence.push(PauseEvidence {
+                    source: query.to_string(),
+                    value: response,
+                    paused,
+                });
+            }
+        }
+        for key in PAUSE_KEYS {
+            let Ok(Some(data)) = self
+                .lcd
+                .raw_query(endpoint, contract_addr, key.as_bytes())
+                .await
+            else {
+                continue;
+            };
+            let Ok(value) = serde_json::from_slice::<Value>(&data) else {
+                continue;
+            };
+            if let Some(paused) = pause_state(&value) {
+                evidence.push(PauseEvidence {
+                    source: format!("storage key '{}'", key),
+                    value,
+                    paused,
+                });
+            }
+        }
+        PauseCheck {
+            paused: evidence.iter().any(|evidence| evidence.paused),
+            evidence,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Key wrapping a query (or execute) msg together with its permit, as
+/// expected by contracts implementing permits (e.g. `{"with_permit": ..}`)
+pub static PERMIT_WRAPPER_KEY: &str = "with_permit";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum PermitFormat {
+    /// SNIP-24 style `query_permit`, signed as an amino sign doc
+    Snip24,
+    /// ADR-036 arbitrary data signing (`sign/MsgSignData`), e.g. Keplr's
+    /// `signArbitrary`
+    Adr036,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitParams {
+    pub permit_name: String,
+    pub allowed_tokens: Vec<String>,
+    pub chain_id: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitSignRequest {
+    pub format: PermitFormat,
+    pub params: PermitParams,
+    /// Canonical (sorted, compact) JSON of the amino sign doc the wallet
+    /// must sign, e.g. with Keplr's `signAmino` or `signArbitrary`
+    pub sign_doc: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitPubKey {
+    #[serde(rename = "type")]
+    pub key_type: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitSignature {
+    pub pub_key: PermitPubKey,
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Permit {
+    pub params: PermitParams,
+    pub signature: PermitSignature,
+}
+
+/// Serialize JSON with object keys sorted at every level and no
+/// whitespace, as required for amino sign docs
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        Value::String(key.clone()),
+                        canonical_json(&map[key.as_str()])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(canonical_json).collect();
+            format!("[{}]", values.join(","))
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Build the sign doc a wallet must sign for a permit
+pub fn sign_request(
+    format: PermitFormat,
+    params: PermitParams,
+    signer: Option<&str>,
+) -> anyhow::Result<PermitSignRequest> {
+    let sign_doc = match format {
+        PermitFormat::Snip24 => json!({
+            "account_number": "0",
+            "chain_id": params.chain_id,
+            "fee": { "amount": [{ "amount": "0", "denom": "uscrt" }], "gas": "1" },
+            "memo": "",
+            "msgs": [{
+                "type": "query_permit",
+                "value": {
+                    "allowed_tokens": params.allowed_tokens,
+                    "permissions": params.permissions,
+                    "permit_name": params.permit_name,
+                },
+            }],
+            "sequence": "0",
+        }),
+        PermitFormat::Adr036 => {
+            let signer = signer.ok_or_else(|| anyhow!("ADR-036 permits require a signer"))?;
+            let data = STANDARD.encode(canonical_json(&serde_json::to_value(&params)?));
+            json!({
+                "account_number": "0",
+                "chain_id": "",
+                "fee": { "amount": [], "gas": "0" },
+                "memo": "",
+                "msgs": [{
+                    "type": "sign/MsgSignData",
+                    "value": { "data": data, "signer": signer },
+                }],
+                "sequence": "0",
+            })
+        }
+    };
+    Ok(PermitSignRequest {
+        format,
+        sign_doc: canonical_json(&sign_doc),
+        params,
+    })
+}
+
+/// Wrap a msg with a signed permit (e.g. `{"with_permit": {"permit": ..,
+/// "query": ..}}`)
+pub fn embed(
+    msg: Value,
+    permit: &Permit,
+    msg_key: &str,
+    wrapper_key: &str,
+) -> anyhow::Result<Value> {
+    STANDARD
+        .decode(&permit.signature.signature)
+        .map_err(|e| anyhow!("signature isn't valid base64: {}", e))?;
+    STANDARD
+        .decode(&permit.signature.pub_key.value)
+        .map_err(|e| anyhow!("pub_key isn't valid base64: {}", e))?;
+    Ok(json!({
+        wrapper_key: {
+            "permit": permit,
+            msg_key: msg,
+        }
+    }))
+}
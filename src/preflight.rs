@@ -0,0 +1,171 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::Coin;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::LazyLock;
+
+use crate::error::{ToolError, translate};
+use crate::rpc::LcdClient;
+use crate::tx;
+
+/// Multiplier applied to simulated gas for the suggested gas limit
+pub static GAS_ADJUSTMENT: f64 = 1.3;
+
+/// wasmd wraps contract errors as `failed to execute message; message
+/// index: 0: <contract error>: execute wasm contract failed`
+static CONTRACT_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"message index: \d+: (.*?):? execute wasm contract failed")
+        .expect("valid contract error pattern")
+});
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreflightReport {
+    pub sender: String,
+    pub contract: String,
+    /// True if the simulated transaction succeeded
+    pub will_succeed: bool,
+    pub gas_used: Option<u64>,
+    /// Simulated gas with a safety margin, for the wallet's gas limit
+    pub suggested_gas_limit: Option<u64>,
+    /// Error returned by the contract (e.g. `Unauthorized`)
+    pub contract_error: Option<String>,
+    pub error: Option<ToolError>,
+    /// Events emitted by the simulated transaction
+    pub events: Vec<Value>,
+    /// Resource holding the simulated (unsigned) `TxRaw`
+    pub tx_resource: Option<String>,
+    /// Proto encoded simulated `TxRaw`
+    #[serde(skip)]
+    pub tx_bytes: Vec<u8>,
+}
+
+/// Gas estimate of a built msg, with the fee to attach to it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GasEstimate {
+    pub sender: String,
+    pub contract: String,
+    pub will_succeed: bool,
+    pub gas_used: Option<u64>,
+    /// Simulated gas with a safety margin (`GAS_ADJUSTMENT`)
+    pub suggested_gas_limit: Option<u64>,
+    /// Gas price the fee is suggested at, in the fee denom's base unit
+    pub gas_price: String,
+    /// Fee of the suggested gas limit
+    pub suggested_fee: Option<Coin>,
+    pub contract_error: Option<String>,
+    pub error: Option<ToolError>,
+}
+
+/// Contract error of a failed wasm execution, if the error came from the
+/// contract
+pub fn contract_error(raw: &str) -> Option<String> {
+    CONTRACT_ERROR
+        .captures(raw)
+        .and_then(|captures| captures.get(1))
+        .map(|error| error.as_str().trim().to_string())
+        .filter(|error| !error.is_empty())
+}
+
+/// Runs execute msgs through the chain's tx simulation
+#[derive(Clone, Debug)]
+pub struct Preflight {
+    lcd: LcdClient,
+}
+
+impl Preflight {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Account number and sequence of an address (0 for accounts that
+    /// don't exist yet)
+    pub async fn account(&self, endpoint: &str, address: &str) -> anyhow::Result<(u64, u64)> {
+        let path = format!("/cosmos/auth/v1beta1/accounts/{}", address);
+        let body = match self.lcd.get(endpoint, &path).await {
+            Ok(body) => body,
+            Err(e) if e.to_string().contains("not found") => return Ok((0, 0)),
+            Err(e) => return Err(e),
+        };
+        let account = &body["account"];
+        // Vesting accounts nest their base account
+        let field = |key: &str| {
+            [
+                &account[key],
+                &account["base_vesting_account"]["base_account"][key],
+                &account["base_account"][key],
+            ]
+            .into_iter()
+            .find_map(|value| value.as_str().and_then(|s| s.parse().ok()))
+            .unwrap_or_default()
+        };
+        Ok((field("account_number"), field("sequence")))
+    }
+
+    /// Account sequence of the sender (0 for accounts that don't exist yet)
+    pub async fn sequence(&self, endpoint: &str, address: &str) -> anyhow::Result<u64> {
+        Ok(self.account(endpoint, address).await?.1)
+    }
+
+    /// Simulate executing `msg` on a contract as `sender`
+    pub async fn execute(
+        &self,
+        endpoint: &str,
+        sender: &str,
+        contract_addr: &str,
+        msg: &Value,
+        funds: &[Coin],
+    ) -> anyhow::Result<PreflightReport> {
+        let sequence = self.sequence(endpoint, sender).await?;
+        let any = tx::msg_execute_contract(sender, contract_addr, &serde_json::to_vec(msg)?, funds);
+        let body = tx::tx_body(vec![any], &tx::watermark(""), 0);
+        let auth_info = tx::auth_info(None, sequence, &[], 0);
+        let tx_bytes = tx::tx_raw(&body, &auth_info, &[vec![]]);
+        let simulated = self
+            .lcd
+            .post(
+                endpoint,
+                "/cosmos/tx/v1beta1/simulate",
+                &json!({ "tx_bytes": STANDARD.encode(&tx_bytes) }),
+            )
+            .await;
+        let mut report = PreflightReport {
+            sender: sender.to_string(),
+            contract: contract_addr.to_string(),
+            will_succeed: false,
+            gas_used: None,
+            suggested_gas_limit: None,
+            contract_error: None,
+            error: None,
+            events: vec![],
+            tx_resource: None,
+            tx_bytes,
+        };
+        match simulated {
+            Ok(simulated) => {
+                let gas_used = simulated["gas_info"]["gas_used"]
+                    .as_str()
+                    .and_then(|gas| gas.parse::<u64>().ok());
+                report.will_succeed = true;
+                report.gas_used = gas_used;
+                report.suggested_gas_limit =
+                    gas_used.map(|gas| (gas as f64 * GAS_ADJUSTMENT).ceil() as u64);
+                report.events = simulated["result"]["events"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default();
+            }
+            Err(e) => {
+                let raw = e.to_string();
+                // Errors reaching the endpoint aren't a verdict
+                if raw.starts_with("request to ") || raw.starts_with("invalid JSON response") {
+                    return Err(e);
+                }
+                report.contract_error = contract_error(&raw);
+                report.error = Some(translate("Simulated transaction failed", &raw));
+            }
+        }
+        Ok(report)
+    }
+}
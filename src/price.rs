@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::proxy::{HttpClient, http_client};
+
+/// Replace with the CoinGecko ids of the denoms USD values are reported
+/// for
+pub static PRICE_IDS: &[(&str, &str)] = &[("aarch", "archway"), ("aconst", "archway")];
+
+/// Replace with the price API (CoinGecko compatible) used for USD values
+pub static PRICE_API: &str = "https://api.coingecko.com/api/v3";
+
+#[cfg(feature = "prices")]
+mod api {
+    use super::*;
+    use crate::proxy::HttpRequest;
+    use anyhow::anyhow;
+    use serde_json::Value;
+
+    /// USD price of a price id on a date (`YYYY-MM-DD`)
+    pub async fn history(http: &HttpClient, id: &str, date: &str) -> anyhow::Result<Option<f64>> {
+        let [year, month, day] = date.split('-').collect::<Vec<&str>>()[..] else {
+            anyhow::bail!("invalid date '{}', expected YYYY-MM-DD", date);
+        };
+        let url = format!(
+            "{}/coins/{}/history?date={}-{}-{}&localization=false",
+            PRICE_API, id, day, month, year
+        );
+        let body: Value = http
+            .send(HttpRequest::get(&url))
+            .await?
+            .json()
+            .map_err(|e| anyhow!("invalid JSON response from {}: {}", url, e))?;
+        Ok(body["market_data"]["current_price"]["usd"].as_f64())
+    }
+}
+
+#[cfg(not(feature = "prices"))]
+mod api {
+    use super::*;
+
+    pub async fn history(
+        _http: &HttpClient,
+        _id: &str,
+        _date: &str,
+    ) -> anyhow::Result<Option<f64>> {
+        Err(anyhow::anyhow!(
+            "USD prices are disabled, build the server with the 'prices' feature"
+        ))
+    }
+}
+
+/// USD prices of native denoms, per day (cached)
+#[derive(Clone, Debug)]
+pub struct PriceProvider {
+    http: HttpClient,
+    cache: Arc<Mutex<HashMap<(String, String), f64>>>,
+}
+
+impl Default for PriceProvider {
+    fn default() -> Self {
+        Self {
+            http: http_client(None),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl PriceProvider {
+    /// USD price of one display unit of a denom (e.g. 1 ARCH) on a date
+    /// (`YYYY-MM-DD`), or `None` for denoms without a price id
+    pub async fn usd_price(&self, denom: &str, date: &str) -> anyhow::Result<Option<f64>> {
+        let Some((_, id)) = PRICE_IDS
+            .iter()
+            .find(|(price_denom, _)| *price_denom == denom)
+        else {
+            return Ok(None);
+        };
+        let key = (id.to_string(), date.to_string());
+        if let Some(price) = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&key).copied())
+        {
+            return Ok(Some(price));
+        }
+        let price = api::history(&self.http, id, date).await?;
+        if let (Some(price), Ok(mut cache)) = (price, self.cache.lock()) {
+            cache.insert(key, price);
+        }
+        Ok(price)
+    }
+}
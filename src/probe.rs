@@ -0,0 +1,218 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::sync::LazyLock;
+
+use crate::rpc::LcdClient;
+
+/// Query variant that no contract is expected to implement
+pub static PROBE_VARIANT: &str = "__mcp_probe__";
+
+/// Maximum number of queries sent while discovering the fields of one variant
+pub static MAX_FIELD_PROBES: usize = 8;
+
+static EXPECTED_VARIANTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"expected (?:one of )?((?:`[^`]+`(?:, )?)+)").expect("valid pattern")
+});
+static BACKTICKED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`([^`]+)`").expect("valid pattern"));
+static MISSING_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"missing field `([^`]+)`").expect("valid pattern"));
+static INVALID_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"invalid type: [^,]+, expected ([^:]+?)(?: at line|:|$)").expect("valid pattern")
+});
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProbedField {
+    pub name: String,
+    /// JSON schema type inferred from the contract's error messages
+    pub field_type: String,
+    /// Raw type description reported by the contract (e.g. `u32`)
+    pub type_hint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProbedVariant {
+    pub name: String,
+    pub required_fields: Vec<ProbedField>,
+    /// False if the field discovery gave up before the variant parsed
+    pub complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProbeReport {
+    pub contract_addr: String,
+    pub variants: Vec<ProbedVariant>,
+    /// Minimal JSON schema (oneOf the discovered variants)
+    pub schema: Value,
+}
+
+/// Variants listed in an `unknown variant` error, if any
+pub fn parse_expected_variants(error: &str) -> Vec<String> {
+    let Some(list) = EXPECTED_VARIANTS.captures(error).and_then(|c| c.get(1)) else {
+        return vec![];
+    };
+    BACKTICKED
+        .captures_iter(list.as_str())
+        .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
+        .collect()
+}
+
+/// Map a serde `expected ...` type description to a JSON schema type and a
+/// placeholder value that satisfies it
+fn infer_type(hint: &str) -> (&'static str, Value) {
+    let hint = hint.to_lowercase();
+    if hint.contains("bool") {
+        ("boolean", json!(false))
+    } else if hint.contains("sequence") || hint.contains("vec") {
+        ("array", json!([]))
+    } else if hint.contains("string") {
+        ("string", json!("0"))
+    } else if hint.contains("map") || hint.contains("struct") || hint.contains("enum") {
+        ("object", json!({}))
+    } else if ["u8", "u16", "u32", "u64", "i32", "i64", "integer", "f64"]
+        .iter()
+        .any(|t| hint.contains(t))
+    {
+        ("integer", json!(0))
+    } else {
+        ("string", json!(""))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Prober {
+    lcd: LcdClient,
+}
+
+impl Prober {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Send a deliberately invalid query and bootstrap a schema from the
+    /// variants (and required fields) reported in the contract's errors
+    pub async fn probe(&self, endpoint: &str, contract_addr: &str) -> anyhow::Result<ProbeReport> {
+        let error = match self
+            .lcd
+            .smart_query(endpoint, contract_addr, &json!({ PROBE_VARIANT: {} }))
+            .await
+        {
+            Ok(_) => anyhow::bail!("contract accepted the probe query, no variants were reported"),
+            Err(e) => e.to_string(),
+        };
+        let names = parse_expected_variants(&error);
+        if names.is_empty() {
+            anyhow::bail!("contract error didn't list any query variants: {}", error);
+        }
+        let mut variants = vec![];
+        for name in names {
+            variants.push(self.probe_variant(endpoint, contract_addr, name).await);
+        }
+        Ok(ProbeReport {
+            contract_addr: contract_addr.to_string(),
+            schema: to_schema(&variants),
+            variants,
+        })
+    }
+
+    /// Discover the required fields of a variant by repeatedly filling in
+    /// the fields the contract reports as missing
+    async fn probe_variant(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        name: String,
+    ) -> ProbedVariant {
+        let mut fields: Vec<ProbedField> = vec![];
+        let mut values: Map<String, Value> = Map::new();
+        for _ in 0..MAX_FIELD_PROBES {
+            let msg = json!({ name.clone(): Value::Object(values.clone()) });
+            let error = match self.lcd.smart_query(endpoint, contract_addr, &msg).await {
+                // Parsed (and possibly executed) successfully
+                Ok(_) => {
+                    return ProbedVariant {
+                        name,
+                        required_fields: fields,
+                        complete: true,
+                    };
+                }
+                Err(e) => e.to_string(),
+            };
+            if let Some(missing) = MISSING_FIELD.captures(&error).and_then(|c| c.get(1)) {
+                let field = missing.as_str().to_string();
+                values.insert(field.clone(), json!(""));
+                fields.push(ProbedField {
+                    name: field,
+                    field_type: "string".to_string(),
+                    type_hint: None,
+                });
+                continue;
+            }
+            if let Some(expected) = INVALID_TYPE.captures(&error).and_then(|c| c.get(1)) {
+                // The last filled in field has the wrong placeholder type
+                let Some(field) = fields.last_mut() else {
+                    break;
+                };
+                let hint = expected.as_str().trim().to_string();
+                let (field_type, placeholder) = infer_type(&hint);
+                if field.type_hint.as_deref() == Some(hint.as_str()) {
+                    break;
+                }
+                field.field_type = field_type.to_string();
+                field.type_hint = Some(hint);
+                values.insert(field.name.clone(), placeholder);
+                continue;
+            }
+            // Any other error means the msg parsed, but failed on execution
+            // (e.g. the placeholder address doesn't exist)
+            return ProbedVariant {
+                name,
+                required_fields: fields,
+                complete: true,
+            };
+        }
+        ProbedVariant {
+            name,
+            required_fields: fields,
+            complete: false,
+        }
+    }
+}
+
+/// Build a minimal JSON schema describing the probed variants
+pub fn to_schema(variants: &[ProbedVariant]) -> Value {
+    let one_of: Vec<Value> = variants
+        .iter()
+        .map(|variant| {
+            let properties: Map<String, Value> = variant
+                .required_fields
+                .iter()
+                .map(|field| (field.name.clone(), json!({ "type": field.field_type })))
+                .collect();
+            let required: Vec<&str> = variant
+                .required_fields
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect();
+            json!({
+                "type": "object",
+                "required": [variant.name],
+                "properties": {
+                    variant.name.clone(): {
+                        "type": "object",
+                        "required": required,
+                        "properties": properties,
+                    }
+                },
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "QueryMsg (probed)",
+        "oneOf": one_of,
+    })
+}
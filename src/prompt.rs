@@ -0,0 +1,117 @@
+use rmcp::model::{JsonObject, Prompt, PromptArgument};
+use serde_json::Value;
+
+use crate::instruction::*;
+
+/// A guided flow built on the tools, exposed as an MCP prompt; its
+/// template refers to arguments as `{name}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PromptDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Arguments of the prompt (name, description and whether it's
+    /// required), besides `contract_addr` and `network`
+    pub arguments: &'static [(&'static str, &'static str, bool)],
+    pub template: &'static str,
+}
+
+/// Arguments every prompt takes, and their value when omitted
+static TARGET_ARGUMENTS: &[(&str, &str, &str)] = &[
+    (
+        "contract_addr",
+        "Optionally the address of the contract (defaults to the session's default contract, otherwise the deployment on the network)",
+        "the default contract",
+    ),
+    (
+        "network",
+        "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the default network)",
+        "the default network",
+    ),
+];
+
+/// Replace with (or add) prompts of your contract's common workflows
+pub static PROMPTS: &[PromptDefinition] = &[
+    PromptDefinition {
+        name: "query_balance",
+        description: "Query the token balance of an address, and report it in display units",
+        arguments: &[("address", "bech32 address (or name) of the holder", true)],
+        template: QUERY_BALANCE_PROMPT,
+    },
+    PromptDefinition {
+        name: "explain_transfer",
+        description: "Build a token transfer, and explain it before it's signed",
+        arguments: &[
+            (
+                "recipient",
+                "bech32 address (or name) receiving the tokens",
+                true,
+            ),
+            (
+                "amount",
+                "amount of tokens to transfer, in the token's base unit",
+                true,
+            ),
+        ],
+        template: EXPLAIN_TRANSFER_PROMPT,
+    },
+    PromptDefinition {
+        name: "audit_contract",
+        description: "Summarize the contract's deployment, ownership and peg",
+        arguments: &[],
+        template: AUDIT_CONTRACT_PROMPT,
+    },
+];
+
+impl PromptDefinition {
+    pub fn prompt(&self) -> Prompt {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|(name, description, required)| (*name, *description, *required))
+            .chain(
+                TARGET_ARGUMENTS
+                    .iter()
+                    .map(|(name, description, _)| (*name, *description, false)),
+            )
+            .map(|(name, description, required)| PromptArgument {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                required: Some(required),
+            })
+            .collect();
+        Prompt {
+            name: self.name.to_string(),
+            description: Some(self.description.to_string()),
+            arguments: Some(arguments),
+        }
+    }
+
+    /// The template with its arguments filled in; omitted targets are
+    /// described by their default
+    pub fn render(&self, arguments: &JsonObject) -> anyhow::Result<String> {
+        let value = |name: &str| match arguments.get(name) {
+            Some(Value::String(value)) if !value.trim().is_empty() => Some(value.clone()),
+            Some(Value::String(_)) | Some(Value::Null) | None => None,
+            Some(value) => Some(value.to_string()),
+        };
+        let mut text = self.template.trim_start().to_string();
+        for (name, _, required) in self.arguments {
+            match value(name) {
+                Some(value) => text = text.replace(&format!("{{{}}}", name), &value),
+                None if *required => {
+                    anyhow::bail!("missing argument '{}' of prompt '{}'", name, self.name)
+                }
+                None => text = text.replace(&format!("{{{}}}", name), "(not given)"),
+            }
+        }
+        for (name, _, default) in TARGET_ARGUMENTS {
+            let value = value(name).unwrap_or_else(|| default.to_string());
+            text = text.replace(&format!("{{{}}}", name), &value);
+        }
+        Ok(text)
+    }
+}
+
+pub fn find(name: &str) -> Option<&'static PromptDefinition> {
+    PROMPTS.iter().find(|prompt| prompt.name == name)
+}
@@ -0,0 +1,139 @@
+/// Minimal protobuf (proto3) encoder, for the few messages the server
+/// encodes itself (e.g. Stargate query requests). Fields holding their
+/// default value are omitted, as proto3 requires for canonical encoding
+#[derive(Clone, Debug, Default)]
+pub struct ProtoEncoder {
+    buf: Vec<u8>,
+}
+
+/// Wire types
+const VARINT: u8 = 0;
+const LENGTH_DELIMITED: u8 = 2;
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+impl ProtoEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        varint(
+            &mut self.buf,
+            (u64::from(field) << 3) | u64::from(wire_type),
+        );
+    }
+
+    pub fn uint64(mut self, field: u32, value: u64) -> Self {
+        if value != 0 {
+            self.key(field, VARINT);
+            varint(&mut self.buf, value);
+        }
+        self
+    }
+
+    /// int64 values are encoded as (two's complement) varints
+    pub fn int64(self, field: u32, value: i64) -> Self {
+        self.uint64(field, value as u64)
+    }
+
+    pub fn bool(self, field: u32, value: bool) -> Self {
+        self.uint64(field, u64::from(value))
+    }
+
+    pub fn bytes(mut self, field: u32, value: &[u8]) -> Self {
+        if !value.is_empty() {
+            self.key(field, LENGTH_DELIMITED);
+            varint(&mut self.buf, value.len() as u64);
+            self.buf.extend_from_slice(value);
+        }
+        self
+    }
+
+    /// Element of a repeated bytes field, encoded even if empty (e.g. the
+    /// placeholder signature of a simulated tx)
+    pub fn bytes_element(mut self, field: u32, value: &[u8]) -> Self {
+        self.key(field, LENGTH_DELIMITED);
+        varint(&mut self.buf, value.len() as u64);
+        self.buf.extend_from_slice(value);
+        self
+    }
+
+    pub fn string(self, field: u32, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Embedded message; unlike scalars, empty messages are still encoded
+    /// when present
+    pub fn message(mut self, field: u32, value: Option<ProtoEncoder>) -> Self {
+        if let Some(value) = value {
+            self.key(field, LENGTH_DELIMITED);
+            varint(&mut self.buf, value.buf.len() as u64);
+            self.buf.extend_from_slice(&value.buf);
+        }
+        self
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::decode_hex;
+
+    fn hex(encoded: &str) -> Vec<u8> {
+        decode_hex(encoded).unwrap()
+    }
+
+    // The examples of the protobuf encoding guide
+    #[test]
+    fn encodes_varints() {
+        assert_eq!(ProtoEncoder::new().uint64(1, 150).finish(), hex("089601"));
+        assert_eq!(ProtoEncoder::new().uint64(1, 300).finish(), hex("08ac02"));
+        assert_eq!(
+            ProtoEncoder::new().int64(1, -1).finish(),
+            hex("08ffffffffffffffffff01")
+        );
+        assert_eq!(ProtoEncoder::new().bool(1, true).finish(), hex("0801"));
+    }
+
+    #[test]
+    fn encodes_length_delimited_fields() {
+        assert_eq!(
+            ProtoEncoder::new().string(2, "testing").finish(),
+            hex("120774657374696e67")
+        );
+        let embedded = ProtoEncoder::new().uint64(1, 150);
+        assert_eq!(
+            ProtoEncoder::new().message(3, Some(embedded)).finish(),
+            hex("1a03089601")
+        );
+    }
+
+    #[test]
+    fn omits_defaults() {
+        let encoded = ProtoEncoder::new()
+            .uint64(1, 0)
+            .bool(2, false)
+            .string(3, "")
+            .bytes(4, &[])
+            .message(5, None)
+            .finish();
+        assert!(encoded.is_empty());
+        // Present empty messages and repeated elements are still encoded
+        let encoded = ProtoEncoder::new()
+            .message(1, Some(ProtoEncoder::new()))
+            .bytes_element(3, &[])
+            .finish();
+        assert_eq!(encoded, hex("0a001a00"));
+    }
+}
@@ -0,0 +1,199 @@
+use rmcp::model::{CallToolResult, RawContent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::address::decode_hex;
+use crate::contract::Network;
+use crate::secrets::{LazySecret, Secrets};
+
+/// Replace with the name and version of your contract's msg schema (e.g.
+/// the version of the crate exporting `QueryMsg` and `ExecuteMsg`)
+pub static SCHEMA_VERSION: &str = "cw20-wrap 1.0.0";
+
+/// Replace with a secret reference (e.g. `env:CW_MCP_PROVENANCE_KEY`) of
+/// the hex encoded ed25519 seed (32 bytes) the server signs the content
+/// hash of built payloads with. Requires the `provenance` feature
+pub static PROVENANCE_KEY: Option<&str> = None;
+
+/// Where and when a payload was built, so downstream systems can verify
+/// its provenance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BuildMetadata {
+    pub network: Network,
+    pub chain_id: Option<String>,
+    /// Label of the contract (user label or deployment), if it has one
+    pub contract_label: Option<String>,
+    pub schema_version: String,
+    pub server_version: String,
+    pub built_at: String,
+    /// Hex encoded sha256 of the built payload (the serialized
+    /// `QueryRequest` or `CosmosMsg`; for execute msgs, the `CosmosMsg`
+    /// with its memo, timeout height, encoded msg and tx body, see
+    /// `ValidatedExecute::signed_payload`)
+    pub content_hash: String,
+    /// Base64 encoded ed25519 signature of the (32 byte) content hash by
+    /// the server key, if one is configured
+    pub signature: Option<String>,
+    /// Base64 encoded ed25519 public key of the server
+    pub public_key: Option<String>,
+}
+
+/// Hex encoded sha256 of a payload
+pub fn content_hash(payload: &str) -> String {
+    Sha256::digest(payload.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(feature = "provenance")]
+mod ed25519 {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+
+    fn signing_key(seed: &str) -> anyhow::Result<SigningKey> {
+        let seed: [u8; 32] = crate::address::decode_hex(seed.trim())
+            .and_then(|seed| seed.try_into().ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!("the provenance key must be a hex encoded 32 byte seed")
+            })?;
+        Ok(SigningKey::from_bytes(&seed))
+    }
+
+    /// Sign a message with a hex encoded seed, returning the signature and
+    /// public key (base64)
+    pub fn sign(seed: &str, message: &[u8]) -> anyhow::Result<(String, String)> {
+        let key = signing_key(seed)?;
+        let signature = key.sign(message);
+        Ok((
+            STANDARD.encode(signature.to_bytes()),
+            STANDARD.encode(key.verifying_key().to_bytes()),
+        ))
+    }
+
+    /// Verify a (base64) signature of a message by the key of a seed
+    pub fn verify(seed: &str, message: &[u8], signature: &str) -> anyhow::Result<()> {
+        let signature: [u8; 64] = STANDARD
+            .decode(signature)
+            .ok()
+            .and_then(|signature| signature.try_into().ok())
+            .ok_or_else(|| anyhow::anyhow!("invalid provenance signature"))?;
+        signing_key(seed)?
+            .verifying_key()
+            .verify(message, &Signature::from_bytes(&signature))
+            .map_err(|_| anyhow::anyhow!("the provenance signature doesn't verify"))
+    }
+}
+
+#[cfg(not(feature = "provenance"))]
+mod ed25519 {
+    static PROVENANCE_DISABLED: &str =
+        "provenance signing is disabled, build the server with the 'provenance' feature";
+
+    pub fn sign(_seed: &str, _message: &[u8]) -> anyhow::Result<(String, String)> {
+        Err(anyhow::anyhow!(PROVENANCE_DISABLED))
+    }
+
+    pub fn verify(_seed: &str, _message: &[u8], _signature: &str) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(PROVENANCE_DISABLED))
+    }
+}
+
+/// Signs the content hash of built payloads with the server's ed25519 key,
+/// so downstream approval systems can verify a payload came from this
+/// server unmodified
+#[derive(Clone, Debug)]
+pub struct ProvenanceSigner {
+    key: Option<LazySecret>,
+}
+
+impl Default for ProvenanceSigner {
+    fn default() -> Self {
+        Self {
+            key: PROVENANCE_KEY.map(|reference| LazySecret::new(reference, Secrets::default())),
+        }
+    }
+}
+
+/// Sign the `metadata` of every built payload of a JSON value
+fn sign_value(value: &mut Value, sign: &dyn Fn(&[u8]) -> Option<(String, String)>) -> bool {
+    let mut signed = false;
+    if let Some(metadata) = value.get_mut("metadata") {
+        let digest = metadata["content_hash"].as_str().and_then(decode_hex);
+        if let Some((signature, public_key)) = digest.and_then(|digest| sign(&digest)) {
+            metadata["signature"] = Value::String(signature);
+            metadata["public_key"] = Value::String(public_key);
+            signed = true;
+        }
+    }
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                signed |= sign_value(value, sign);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                if key != "metadata" {
+                    signed |= sign_value(value, sign);
+                }
+            }
+        }
+        _ => {}
+    }
+    signed
+}
+
+impl ProvenanceSigner {
+    /// Check a payload is the one its metadata was built for: its content
+    /// hash, and the server's signature of it if a key is configured
+    pub async fn verify(&self, metadata: &BuildMetadata, payload: &str) -> anyhow::Result<()> {
+        if content_hash(payload) != metadata.content_hash {
+            anyhow::bail!("the payload doesn't match its content hash, it was modified");
+        }
+        let Some(key) = &self.key else {
+            return Ok(());
+        };
+        let signature = metadata
+            .signature
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("the payload isn't signed by the server"))?;
+        let digest = decode_hex(&metadata.content_hash)
+            .ok_or_else(|| anyhow::anyhow!("invalid content hash"))?;
+        ed25519::verify(key.get().await?.expose(), &digest, signature)
+    }
+
+    /// Sign the built payloads of a tool result
+    pub async fn sign_result(&self, mut result: CallToolResult) -> CallToolResult {
+        let Some(key) = &self.key else {
+            return result;
+        };
+        let seed = match key.get().await {
+            Ok(seed) => seed,
+            Err(e) => {
+                tracing::warn!("provenance key can't be resolved: {}", e);
+                return result;
+            }
+        };
+        let sign = |digest: &[u8]| match ed25519::sign(seed.expose(), digest) {
+            Ok(signed) => Some(signed),
+            Err(e) => {
+                tracing::warn!("signing provenance failed: {}", e);
+                None
+            }
+        };
+        for content in result.content.iter_mut() {
+            if let RawContent::Text(text) = &mut content.raw {
+                let Ok(mut value) = serde_json::from_str::<Value>(&text.text) else {
+                    continue;
+                };
+                if sign_value(&mut value, &sign) {
+                    text.text = value.to_string();
+                }
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,232 @@
+use anyhow::anyhow;
+use serde_json::Value;
+
+use crate::contract::Network;
+
+/// Replace with a proxy all outbound connections (LCD, price API,
+/// webhooks and Vault requests) go through, e.g.
+/// `Some("http://proxy.corp:3128")` in corporate environments or
+/// `Some("socks5h://127.0.0.1:9050")` for Tor (`socks5h` resolves names
+/// through the proxy, so no DNS requests leak)
+pub static PROXY: Option<&str> = None;
+
+/// Replace with proxies used for the endpoints of one network instead of
+/// `PROXY`
+pub static PROXY_MAINNET: Option<&str> = None;
+pub static PROXY_TESTNET: Option<&str> = None;
+
+/// Replace with the local address outbound connections are made from,
+/// e.g. `Some("::")` to only connect over IPv6. Endpoints can be IPv6
+/// addresses (e.g. `http://[2001:db8::1]:1317`) either way
+pub static LOCAL_ADDRESS: Option<&str> = None;
+
+/// Proxy of a network's endpoints, or of other outbound connections
+pub fn proxy_for(network: Option<&Network>) -> Option<&'static str> {
+    let network_proxy = match network {
+        Some(Network::Mainnet) => PROXY_MAINNET,
+        Some(Network::Testnet) => PROXY_TESTNET,
+        None => None,
+    };
+    network_proxy.or(PROXY)
+}
+
+/// An outbound request: a GET, or a POST of a JSON body
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    pub url: String,
+    pub body: Option<Value>,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    pub fn get(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            body: None,
+            headers: vec![],
+        }
+    }
+
+    pub fn post(url: &str, body: Value) -> Self {
+        Self {
+            url: url.to_string(),
+            body: Some(body),
+            headers: vec![],
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.header("Authorization", format!("Bearer {}", token))
+    }
+}
+
+/// Status and body of a response
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.status)
+    }
+
+    pub fn json(&self) -> serde_json::Result<Value> {
+        serde_json::from_slice(&self.body)
+    }
+
+    /// The response, or an error if its status isn't a success
+    pub fn error_for_status(self, url: &str) -> anyhow::Result<Self> {
+        match self.is_success() {
+            true => Ok(self),
+            false => Err(anyhow!("{} responded with {}", url, self.status)),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+mod client {
+    use super::*;
+    use std::net::IpAddr;
+
+    /// HTTP client of outbound requests (LCD, price API, webhooks and
+    /// Vault requests)
+    #[derive(Clone, Debug, Default)]
+    pub struct HttpClient(reqwest::Client);
+
+    impl From<reqwest::Client> for HttpClient {
+        fn from(client: reqwest::Client) -> Self {
+            Self(client)
+        }
+    }
+
+    impl HttpClient {
+        /// HTTP client using the configured proxy and local address.
+        /// Invalid settings are logged and fall back to a direct
+        /// connection, rather than keeping the server from starting
+        pub fn new(network: Option<&Network>) -> Self {
+            let mut builder = reqwest::Client::builder();
+            if let Some(proxy) = proxy_for(network) {
+                match reqwest::Proxy::all(proxy) {
+                    Ok(proxy) => builder = builder.proxy(proxy),
+                    Err(e) => tracing::warn!("invalid proxy {}: {}", proxy, e),
+                }
+            }
+            if let Some(address) = LOCAL_ADDRESS {
+                match address.parse::<IpAddr>() {
+                    Ok(address) => builder = builder.local_address(address),
+                    Err(e) => tracing::warn!("invalid local address {}: {}", address, e),
+                }
+            }
+            Self(builder.build().unwrap_or_else(|e| {
+                tracing::warn!("building http client failed: {}", e);
+                reqwest::Client::default()
+            }))
+        }
+
+        /// Send a request, failing if it can't be sent (e.g. the endpoint
+        /// is unreachable); error statuses are returned as responses
+        pub async fn send(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+            let mut builder = match &request.body {
+                Some(body) => self.0.post(&request.url).json(body),
+                None => self.0.get(&request.url),
+            };
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            let failed = |e: reqwest::Error| anyhow!("request to {} failed: {}", request.url, e);
+            let response = builder.send().await.map_err(failed)?;
+            let status = response.status().as_u16();
+            let body = response.bytes().await.map_err(failed)?.to_vec();
+            Ok(HttpResponse { status, body })
+        }
+    }
+}
+
+#[cfg(not(feature = "rpc"))]
+mod client {
+    use super::*;
+
+    static RPC_DISABLED: &str =
+        "outbound requests are disabled, build the server with the 'rpc' feature";
+
+    /// Stands in for the HTTP client, failing every request
+    #[derive(Clone, Debug, Default)]
+    pub struct HttpClient;
+
+    impl HttpClient {
+        pub fn new(_network: Option<&Network>) -> Self {
+            Self
+        }
+
+        pub async fn send(&self, _request: HttpRequest) -> anyhow::Result<HttpResponse> {
+            Err(anyhow!(RPC_DISABLED))
+        }
+    }
+}
+
+pub use client::HttpClient;
+
+/// HTTP client using the configured proxy (of a network's endpoints, or
+/// of other outbound connections) and local address
+pub fn http_client(network: Option<&Network>) -> HttpClient {
+    HttpClient::new(network)
+}
+
+/// HTTP clients of the networks' endpoints (and other endpoints), each
+/// using its proxy
+#[derive(Clone, Debug)]
+pub struct HttpClients {
+    mainnet: HttpClient,
+    testnet: HttpClient,
+    other: HttpClient,
+}
+
+impl Default for HttpClients {
+    fn default() -> Self {
+        Self {
+            mainnet: http_client(Some(&Network::Mainnet)),
+            testnet: http_client(Some(&Network::Testnet)),
+            other: http_client(None),
+        }
+    }
+}
+
+/// The same client for every endpoint
+impl From<HttpClient> for HttpClients {
+    fn from(client: HttpClient) -> Self {
+        Self {
+            mainnet: client.clone(),
+            testnet: client.clone(),
+            other: client,
+        }
+    }
+}
+
+impl HttpClients {
+    /// Client of the network an endpoint (LCD or RPC) belongs to
+    pub fn for_endpoint(&self, endpoint: &str) -> &HttpClient {
+        let trimmed = endpoint.trim_end_matches('/');
+        let belongs = |network: &Network| {
+            network.rpc_endpoint() == trimmed
+                || network.lcd_endpoints().iter().any(|lcd| *lcd == trimmed)
+        };
+        if belongs(&Network::Mainnet) {
+            &self.mainnet
+        } else if belongs(&Network::Testnet) {
+            &self.testnet
+        } else {
+            &self.other
+        }
+    }
+}
@@ -1,5 +1,19 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cw20::{
+    AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse, AllowanceResponse,
+    BalanceResponse, DownloadLogoResponse, MarketingInfoResponse, MinterResponse,
+    TokenInfoResponse,
+};
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::marker::PhantomData;
+
+use crate::contract::Network;
+use crate::label::AddressLabel;
+use crate::provenance::BuildMetadata;
+use crate::resolver::NameResolution;
 
 /// (Optionally) define any custom query response types in the `AllQueryResponse` struct
 /// @see: src/main.rs (CwMcp::list_query_responses)
@@ -8,8 +22,259 @@ use serde::{Deserialize, Serialize};
 //     pub custom_response: cw20_wrap::msg::CustomResponseExample,
 // }
 
+/// A response type of the contract's queries, decoding the response of
+/// the `QueryMsg` variant it answers
+pub trait QueryResponseType: Send + Sync {
+    /// `QueryMsg` variant answered by the type
+    fn query(&self) -> &str;
+
+    fn name(&self) -> &str;
+
+    /// The response parsed as the type (and re-serialized)
+    fn decode(&self, response: &Value) -> Result<Value, String>;
+}
+
+pub struct TypedResponse<T> {
+    query: &'static str,
+    name: &'static str,
+    types: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedResponse<T> {
+    pub const fn new(query: &'static str, name: &'static str) -> Self {
+        Self {
+            query,
+            name,
+            types: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Serialize> QueryResponseType for TypedResponse<T> {
+    fn query(&self) -> &str {
+        self.query
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, response: &Value) -> Result<Value, String> {
+        let decoded: T = serde_path_to_error::deserialize(response).map_err(|e| e.to_string())?;
+        serde_json::to_value(&decoded).map_err(|e| e.to_string())
+    }
+}
+
+/// Replace with (or add) the response types of your contract's queries
+/// (e.g. the fields of `AllQueryResponse`), by the `QueryMsg` variant
+/// they answer
+pub static QUERY_RESPONSES: &[&dyn QueryResponseType] = &[
+    &TypedResponse::<BalanceResponse>::new("balance", "BalanceResponse"),
+    &TypedResponse::<TokenInfoResponse>::new("token_info", "TokenInfoResponse"),
+    &TypedResponse::<AllowanceResponse>::new("allowance", "AllowanceResponse"),
+    &TypedResponse::<AllAllowancesResponse>::new("all_allowances", "AllAllowancesResponse"),
+    &TypedResponse::<AllSpenderAllowancesResponse>::new(
+        "all_spender_allowances",
+        "AllSpenderAllowancesResponse",
+    ),
+    &TypedResponse::<AllAccountsResponse>::new("all_accounts", "AllAccountsResponse"),
+    &TypedResponse::<MinterResponse>::new("minter", "MinterResponse"),
+    &TypedResponse::<MarketingInfoResponse>::new("marketing_info", "MarketingInfoResponse"),
+    &TypedResponse::<DownloadLogoResponse>::new("download_logo", "DownloadLogoResponse"),
+];
+
+/// A smart query response decoded into one of `QUERY_RESPONSES`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DecodedQueryResponse {
+    /// Name of the response type that matched
+    pub response_type: String,
+    /// `QueryMsg` variant the response type answers
+    pub query: String,
+    /// `json`, or `base64` if the response was base64 encoded JSON
+    pub encoding: String,
+    pub response: Value,
+    /// Other response types the response also matched exactly
+    pub candidates: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// `QueryMsg` variant of a JSON stringified query msg
+fn query_variant(query_msg: &str) -> anyhow::Result<String> {
+    match serde_json::from_str(query_msg)? {
+        Value::String(name) => Ok(name),
+        Value::Object(msg) if msg.len() == 1 => Ok(msg.keys().next().cloned().unwrap_or_default()),
+        _ => anyhow::bail!("the QueryMsg isn't a single variant, e.g. {{\"balance\": {{..}}}}"),
+    }
+}
+
+/// The JSON of a response, and its encoding: JSON, base64 encoded JSON
+/// (e.g. the `data` of a raw smart query), or the LCD's `{"data": ..}`
+fn response_json(response: &str) -> anyhow::Result<(Value, &'static str)> {
+    let response = response.trim();
+    let (json, encoding) = match serde_json::from_str::<Value>(response) {
+        Ok(Value::String(encoded)) => (decode_base64(&encoded)?, "base64"),
+        Ok(json) => (json, "json"),
+        Err(_) => (decode_base64(response)?, "base64"),
+    };
+    match json {
+        Value::Object(mut response) if response.len() == 1 && response.contains_key("data") => {
+            match response.remove("data").unwrap_or_default() {
+                Value::String(encoded) => Ok((decode_base64(&encoded)?, "base64")),
+                data => Ok((data, encoding)),
+            }
+        }
+        json => Ok((json, encoding)),
+    }
+}
+
+fn decode_base64(encoded: &str) -> anyhow::Result<Value> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| anyhow::anyhow!("the response is neither JSON nor base64: {}", e))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| anyhow::anyhow!("the base64 decoded response isn't JSON: {}", e))
+}
+
+/// Whether the decoded response kept every field of the response (serde
+/// ignores unknown fields, so a response may parse as several types)
+fn exact(response: &Value, decoded: &Value) -> bool {
+    match (response.as_object(), decoded.as_object()) {
+        (Some(response), Some(decoded)) => response.keys().all(|key| decoded.contains_key(key)),
+        _ => true,
+    }
+}
+
+/// Decode a smart query response into the response type of its query (if
+/// the `QueryMsg` is given), or the response type it matches best
+pub fn decode_response(
+    response: &str,
+    query_msg: Option<&str>,
+) -> anyhow::Result<DecodedQueryResponse> {
+    let (json, encoding) = response_json(response)?;
+    let query = query_msg.map(query_variant).transpose()?;
+    let types: Vec<&dyn QueryResponseType> = QUERY_RESPONSES
+        .iter()
+        .copied()
+        .filter(|response_type| {
+            query
+                .as_ref()
+                .is_none_or(|query| response_type.query() == query.as_str())
+        })
+        .collect();
+    if types.is_empty() {
+        anyhow::bail!(
+            "no response type of the '{}' query is configured",
+            query.unwrap_or_default()
+        );
+    }
+    let mut matched = vec![];
+    let mut errors = vec![];
+    for response_type in types {
+        match response_type.decode(&json) {
+            Ok(decoded) => matched.push((response_type, exact(&json, &decoded), decoded)),
+            Err(e) => errors.push(format!("{}: {}", response_type.name(), e)),
+        }
+    }
+    // Exact matches first
+    matched.sort_by_key(|(_, exact, _)| !exact);
+    let mut matched = matched.into_iter();
+    let Some((response_type, exact, decoded)) = matched.next() else {
+        anyhow::bail!("no response type matched ({})", errors.join("; "));
+    };
+    let candidates = matched
+        .filter(|(_, exact, _)| *exact)
+        .map(|(response_type, _, _)| response_type.name().to_string())
+        .collect();
+    let mut warnings = vec![];
+    if !exact {
+        warnings.push(format!(
+            "fields of the response aren't fields of {}, and were dropped",
+            response_type.name()
+        ));
+    }
+    Ok(DecodedQueryResponse {
+        response_type: response_type.name().to_string(),
+        query: response_type.query().to_string(),
+        encoding: encoding.to_string(),
+        response: decoded,
+        candidates,
+        warnings,
+    })
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ValidatedQuery {
     pub query_msg: String,
     pub query_request: String,
+    pub address_labels: Vec<AddressLabel>,
+    pub name_resolutions: Vec<NameResolution>,
+    /// Keys of the msg that were normalized to the schema's names
+    pub warnings: Vec<String>,
+    pub metadata: BuildMetadata,
+}
+
+/// Response of a smart query executed live by the server
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryResult {
+    pub contract: String,
+    pub network: Network,
+    /// LCD endpoint the query was sent to
+    pub endpoint: String,
+    pub query_msg: String,
+    pub response: Value,
+    pub address_labels: Vec<AddressLabel>,
+    pub name_resolutions: Vec<NameResolution>,
+    /// Keys of the msg that were normalized to the schema's names
+    pub warnings: Vec<String>,
+}
+
+static UNREGISTERED_WARNING: &str = "the contract isn't a registered deployment, confirm it with your chat partner before interacting with it";
+
+/// `WasmQuery::ContractInfo` request of an address, and (if executed)
+/// the contract's info
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoQuery {
+    pub contract: String,
+    /// JSON stringified QueryRequest
+    pub query_request: String,
+    /// Whether the contract is a deployment of the server's registry
+    pub registered: bool,
+    pub address_labels: Vec<AddressLabel>,
+    pub metadata: BuildMetadata,
+    /// Whether the query was executed, filling in the contract's info
+    pub executed: bool,
+    pub code_id: Option<u64>,
+    pub creator: Option<String>,
+    pub admin: Option<String>,
+    pub label: Option<String>,
+    pub ibc_port_id: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ContractInfoQuery {
+    /// Fill in the `ContractInfo` of an executed query
+    pub fn with_info(mut self, info: &Value) -> Self {
+        let field = |key: &str| {
+            info[key]
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        self.executed = true;
+        self.code_id = field("code_id").and_then(|id| id.parse().ok());
+        self.creator = field("creator");
+        self.admin = field("admin");
+        self.label = field("label");
+        self.ibc_port_id = field("ibc_port_id");
+        if let Some(admin) = &self.admin {
+            self.warnings.push(format!(
+                "the contract has an admin ({}), who can migrate it to other code",
+                admin
+            ));
+        }
+        if !self.registered {
+            self.warnings.push(UNREGISTERED_WARNING.to_string());
+        }
+        self
+    }
 }
@@ -0,0 +1,219 @@
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::clock::{is_past, now_rfc3339, now_secs, rfc3339};
+use crate::execute::ValidatedExecute;
+
+/// Replace with the delay (in seconds) queued transactions are held for
+/// before they're released for signing, the window operators have to
+/// cancel them
+pub static EXECUTION_DELAY_SECS: u64 = 600;
+
+/// Replace with the path the execution queue is persisted to, so queued
+/// transactions outlive the session (and process) that queued them
+pub static QUEUE_PATH: &str = "execution_queue.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    /// Waiting for the delay to elapse
+    Held,
+    /// Handed out for signing and broadcasting
+    Released,
+    /// Signed and broadcast by the server (`sign_and_broadcast`)
+    Broadcast,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedExecute {
+    pub id: String,
+    pub execute: ValidatedExecute,
+    pub status: QueueStatus,
+    pub queued_at: String,
+    /// Time the transaction can be released at
+    pub releasable_at: String,
+    pub note: Option<String>,
+    pub cancel_reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+struct QueueFile {
+    entries: BTreeMap<String, QueuedExecute>,
+}
+
+/// Queue of `QUEUE_PATH`, shared by every session of the process
+static SHARED_QUEUE: OnceLock<ExecutionQueue> = OnceLock::new();
+
+/// Confirmed transactions held until a delay elapses, giving operators a
+/// window to veto agent-initiated transfers (see `src/operator.rs`).
+/// Persisted to a JSON file, so a veto doesn't depend on the session
+#[derive(Clone, Debug)]
+pub struct ExecutionQueue {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles of the file
+    lock: Arc<Mutex<()>>,
+    delay_secs: u64,
+}
+
+/// The process-wide queue of `QUEUE_PATH`
+impl Default for ExecutionQueue {
+    fn default() -> Self {
+        SHARED_QUEUE
+            .get_or_init(|| Self::new(QUEUE_PATH, EXECUTION_DELAY_SECS))
+            .clone()
+    }
+}
+
+impl ExecutionQueue {
+    /// A queue of its own file; sessions sharing a file must share the
+    /// queue, so their changes are serialized
+    pub fn new(path: impl AsRef<Path>, delay_secs: u64) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::new(Mutex::new(())),
+            delay_secs,
+        }
+    }
+
+    pub fn delay_secs(&self) -> u64 {
+        self.delay_secs
+    }
+
+    fn locked(&self) -> anyhow::Result<std::sync::MutexGuard<'_, ()>> {
+        self.lock
+            .lock()
+            .map_err(|_| anyhow!("execution queue poisoned"))
+    }
+
+    fn load(&self) -> anyhow::Result<QueueFile> {
+        if !self.path.exists() {
+            return Ok(QueueFile::default());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("invalid execution queue {}: {}", self.path.display(), e))
+    }
+
+    fn save(&self, file: &QueueFile) -> anyhow::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(file)?)?;
+        Ok(())
+    }
+
+    /// Change an entry of the file, saving it if the change succeeds
+    fn update(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut QueuedExecute) -> anyhow::Result<()>,
+    ) -> anyhow::Result<QueuedExecute> {
+        let _guard = self.locked()?;
+        let mut file = self.load()?;
+        let entry = file
+            .entries
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("no queued transaction with id '{}'", id))?;
+        change(entry)?;
+        let entry = entry.clone();
+        self.save(&file)?;
+        Ok(entry)
+    }
+
+    pub fn queue(
+        &self,
+        execute: ValidatedExecute,
+        note: Option<String>,
+    ) -> anyhow::Result<QueuedExecute> {
+        let _guard = self.locked()?;
+        let mut file = self.load()?;
+        let entries = &mut file.entries;
+        let entry = QueuedExecute {
+            id: format!("q-{}", entries.len() + 1),
+            execute,
+            status: QueueStatus::Held,
+            queued_at: now_rfc3339(),
+            releasable_at: rfc3339(now_secs() + self.delay_secs),
+            note,
+            cancel_reason: None,
+        };
+        entries.insert(entry.id.clone(), entry.clone());
+        self.save(&file)?;
+        Ok(entry)
+    }
+
+    /// Queued transactions, oldest first
+    pub fn list(&self) -> Vec<QueuedExecute> {
+        let loaded = self.locked().and_then(|_guard| self.load());
+        let mut entries: Vec<QueuedExecute> = match loaded {
+            Ok(file) => file.entries.into_values().collect(),
+            Err(e) => {
+                tracing::warn!("reading the execution queue failed: {}", e);
+                vec![]
+            }
+        };
+        entries.sort_by(|a, b| a.queued_at.cmp(&b.queued_at));
+        entries
+    }
+
+    /// Release a held transaction once its delay elapsed
+    pub fn release(&self, id: &str) -> anyhow::Result<QueuedExecute> {
+        self.update(id, |entry| match entry.status {
+            QueueStatus::Held if is_past(&entry.releasable_at) => {
+                entry.status = QueueStatus::Released;
+                Ok(())
+            }
+            QueueStatus::Held => Err(anyhow!("'{}' is held until {}", id, entry.releasable_at)),
+            QueueStatus::Released | QueueStatus::Broadcast => {
+                Err(anyhow!("'{}' was already released", id))
+            }
+            QueueStatus::Cancelled => Err(anyhow!("'{}' was cancelled", id)),
+        })
+    }
+
+    /// Claim a released transaction for broadcasting, so it's broadcast at
+    /// most once
+    pub fn claim(&self, id: &str) -> anyhow::Result<QueuedExecute> {
+        self.update(id, |entry| match entry.status {
+            QueueStatus::Released => {
+                entry.status = QueueStatus::Broadcast;
+                Ok(())
+            }
+            QueueStatus::Held => Err(anyhow!(
+                "'{}' is held until {}, release it first",
+                id,
+                entry.releasable_at
+            )),
+            QueueStatus::Broadcast => Err(anyhow!("'{}' was already broadcast", id)),
+            QueueStatus::Cancelled => Err(anyhow!("'{}' was cancelled", id)),
+        })
+    }
+
+    /// Return a claimed transaction whose broadcast failed to the released
+    /// ones
+    pub fn unclaim(&self, id: &str) -> anyhow::Result<QueuedExecute> {
+        self.update(id, |entry| {
+            if entry.status == QueueStatus::Broadcast {
+                entry.status = QueueStatus::Released;
+            }
+            Ok(())
+        })
+    }
+
+    /// Cancel a held transaction; released transactions can't be cancelled
+    pub fn cancel(&self, id: &str, reason: Option<String>) -> anyhow::Result<QueuedExecute> {
+        self.update(id, |entry| match entry.status {
+            QueueStatus::Held => {
+                entry.status = QueueStatus::Cancelled;
+                entry.cancel_reason = reason;
+                Ok(())
+            }
+            QueueStatus::Released | QueueStatus::Broadcast => {
+                Err(anyhow!("'{}' was already released", id))
+            }
+            QueueStatus::Cancelled => Err(anyhow!("'{}' was already cancelled", id)),
+        })
+    }
+}
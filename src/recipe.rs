@@ -0,0 +1,182 @@
+use rmcp::model::{JsonObject, Tool};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::workflow::{MAX_WORKFLOW_STEPS, StepAction, WorkflowDefinition, WorkflowStep};
+
+/// Replace with the path of the YAML file holding the recipes exposed as
+/// tools (missing files define no recipes)
+pub static RECIPES_PATH: &str = "recipes.yaml";
+
+/// Prefix of the tool names of recipes, e.g. `recipe__top_up`
+pub static RECIPE_TOOL_PREFIX: &str = "recipe__";
+
+/// Prefix recipe tools were first published under (`recipe_top_up`),
+/// still callable
+pub static LEGACY_RECIPE_TOOL_PREFIX: &str = "recipe_";
+
+/// Arguments of recipe tools which aren't recipe params
+static TARGET_ARGUMENTS: &[&str] = &["contract_addr", "network"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecipeParam {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Value used when the param is omitted
+    pub default: Option<Value>,
+}
+
+/// A named sequence of query and execute steps (a workflow) taking
+/// params; step msgs refer to params as `${params.<name>}`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recipe {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub params: Vec<RecipeParam>,
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct RecipeFile {
+    pub recipes: Vec<Recipe>,
+}
+
+impl Recipe {
+    pub fn tool_name(&self) -> String {
+        format!("{}{}", RECIPE_TOOL_PREFIX, self.name)
+    }
+
+    /// Tool running the recipe; its arguments are the recipe's params,
+    /// and the contract and network of the run
+    pub fn tool(&self) -> Tool {
+        let mut properties = serde_json::Map::new();
+        for param in &self.params {
+            properties.insert(
+                param.name.clone(),
+                json!({ "description": param.description.clone().unwrap_or_default() }),
+            );
+        }
+        properties.insert(
+            "contract_addr".to_string(),
+            json!({
+                "type": "string",
+                "description": "Optionally the contract of steps that don't name one (defaults to the session's default contract, otherwise the deployment on the network)",
+            }),
+        );
+        properties.insert(
+            "network".to_string(),
+            json!({
+                "type": "string",
+                "enum": ["Mainnet", "Testnet"],
+                "description": "Optionally the network (defaults to the network of contract_addr, otherwise the default network)",
+            }),
+        );
+        let required: Vec<&str> = self
+            .params
+            .iter()
+            .filter(|param| param.required && param.default.is_none())
+            .map(|param| param.name.as_str())
+            .collect();
+        let schema = json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        });
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            _ => JsonObject::default(),
+        };
+        let description = format!(
+            "{} (recipe: runs as a workflow, see 'workflow__run')",
+            self.description.trim()
+        );
+        Tool::new(self.tool_name(), description, Arc::new(schema))
+    }
+
+    /// Whether the recipe only queries (and can't build execute msgs)
+    pub fn read_only(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| matches!(step.action, StepAction::Query { .. }))
+    }
+
+    pub fn definition(&self) -> WorkflowDefinition {
+        WorkflowDefinition {
+            name: self.name.clone(),
+            steps: self.steps.clone(),
+        }
+    }
+
+    /// Params of a run, from the tool arguments and the params' defaults
+    pub fn params(&self, arguments: &JsonObject) -> anyhow::Result<Value> {
+        if let Some(unknown) = arguments.keys().find(|key| {
+            !TARGET_ARGUMENTS.contains(&key.as_str())
+                && !self.params.iter().any(|param| &param.name == *key)
+        }) {
+            anyhow::bail!("'{}' isn't a param of recipe '{}'", unknown, self.name);
+        }
+        let mut params = serde_json::Map::new();
+        for param in &self.params {
+            match arguments.get(&param.name).or(param.default.as_ref()) {
+                Some(value) => {
+                    params.insert(param.name.clone(), value.clone());
+                }
+                None if param.required => {
+                    anyhow::bail!("recipe '{}' requires the '{}' param", self.name, param.name)
+                }
+                None => {}
+            }
+        }
+        Ok(Value::Object(params))
+    }
+}
+
+/// Recipes loaded from `RECIPES_PATH` at startup
+#[derive(Clone, Debug, Default)]
+pub struct Recipes {
+    recipes: Arc<Vec<Recipe>>,
+}
+
+impl Recipes {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        if !std::path::Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let file: RecipeFile = serde_yaml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("invalid recipes file {}: {}", path, e))?;
+        for recipe in &file.recipes {
+            if recipe.steps.is_empty() || recipe.steps.len() > MAX_WORKFLOW_STEPS {
+                anyhow::bail!(
+                    "recipe '{}' must have 1 to {} steps",
+                    recipe.name,
+                    MAX_WORKFLOW_STEPS
+                );
+            }
+        }
+        Ok(Self {
+            recipes: Arc::new(file.recipes),
+        })
+    }
+
+    pub fn tools(&self) -> Vec<Tool> {
+        self.recipes.iter().map(Recipe::tool).collect()
+    }
+
+    /// Recipe of a name (rather than of its tool's name)
+    pub fn named(&self, name: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.name == name)
+    }
+
+    /// Recipe run by a tool
+    pub fn get(&self, tool_name: &str) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|recipe| recipe.tool_name() == tool_name)
+    }
+}
@@ -0,0 +1,193 @@
+use regex::Regex;
+use rmcp::model::{CallToolResult, RawContent};
+use serde_json::Value;
+
+use crate::display::PAYLOAD_KEYS;
+
+/// Replacement for redacted values
+pub static REDACTED: &str = "[REDACTED]";
+
+/// Replace with the (JSON) fields that must never be returned by tools.
+/// A rule matches any field whose path ends with it, e.g. `mnemonic`
+/// matches `signer.mnemonic`, and `*` matches any single field name
+/// (e.g. `endpoints.*.api_key`)
+pub static REDACTED_FIELDS: &[&str] = &[
+    "mnemonic",
+    "private_key",
+    "priv_key",
+    "api_key",
+    "api_token",
+    "password",
+    "secret",
+];
+
+/// Replace with patterns (regexes) that must never appear in tool output,
+/// e.g. internal endpoints (`https?://[a-z0-9.-]+\.internal\S*`)
+pub static REDACTED_PATTERNS: &[&str] = &[
+    // Credentials passed as URL query params
+    r"(?i)\b(?:api[_-]?key|access[_-]?token|token|secret)=[^&\s\x22]+",
+    // Bearer tokens
+    r"(?i)\bbearer\s+[a-z0-9._~+/=-]{16,}",
+];
+
+/// English BIP-39 wordlist (sorted), mnemonics are made of
+static BIP39_WORDS: &str = include_str!("bip39_english.txt");
+
+/// Runs of at least 12 lowercase words, which may contain a mnemonic
+static WORD_RUN: &str = r"\b(?:[a-z]{3,8} ){11,}[a-z]{3,8}\b";
+
+/// Least words of a BIP-39 mnemonic
+const MNEMONIC_MIN_WORDS: usize = 12;
+
+/// Fields of the payloads agents copy into what gets signed (or sent), on
+/// top of the `PAYLOAD_KEYS` of amount formatting: built msgs, memos and
+/// encodings. Only field rules naming them apply, never the pattern and
+/// mnemonic rules, so redaction never silently changes what gets signed
+static PAYLOAD_FIELDS: &[&str] = &[
+    "execute_msg",
+    "cosmos_msg",
+    "query_msg",
+    "query_request",
+    "instantiate_msg",
+    "migrate_msg",
+    "encoded_msg",
+    "memo",
+];
+
+/// Redaction rules applied to the output of every tool, so secrets and
+/// internal details never reach the model context. Mnemonics are runs of
+/// 12 or more words of the BIP-39 wordlist
+#[derive(Clone, Debug)]
+pub struct Redactor {
+    fields: Vec<Vec<String>>,
+    patterns: Vec<Regex>,
+    word_run: Regex,
+    words: Vec<&'static str>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(REDACTED_FIELDS, REDACTED_PATTERNS).expect("valid redaction rules")
+    }
+}
+
+impl Redactor {
+    pub fn new(fields: &[&str], patterns: &[&str]) -> anyhow::Result<Self> {
+        let fields = fields
+            .iter()
+            .map(|field| field.split('.').map(str::to_string).collect())
+            .collect();
+        let patterns = patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<Vec<Regex>, _>>()?;
+        Ok(Self {
+            fields,
+            patterns,
+            word_run: Regex::new(WORD_RUN)?,
+            words: BIP39_WORDS.lines().collect(),
+        })
+    }
+
+    fn is_payload_field(key: &str) -> bool {
+        PAYLOAD_FIELDS.contains(&key) || PAYLOAD_KEYS.contains(&key)
+    }
+
+    fn is_redacted_field(&self, path: &[String]) -> bool {
+        self.fields.iter().any(|rule| {
+            rule.len() <= path.len()
+                && rule
+                    .iter()
+                    .zip(&path[path.len() - rule.len()..])
+                    .all(|(segment, key)| segment == "*" || segment == key)
+        })
+    }
+
+    /// Replace the mnemonics of a run of words, leaving its other words
+    fn redact_run(&self, run: &str) -> String {
+        let words: Vec<&str> = run.split(' ').collect();
+        let mut redacted: Vec<&str> = vec![];
+        let mut start = 0;
+        while start < words.len() {
+            let len = words[start..]
+                .iter()
+                .take_while(|word| self.words.binary_search(word).is_ok())
+                .count();
+            if len >= MNEMONIC_MIN_WORDS {
+                redacted.push(REDACTED);
+                start += len;
+            } else {
+                redacted.extend(&words[start..start + len.max(1)]);
+                start += len.max(1);
+            }
+        }
+        redacted.join(" ")
+    }
+
+    /// Apply the pattern rules and mnemonic redaction to a string
+    pub fn redact_str(&self, text: &str) -> String {
+        let text = self
+            .word_run
+            .replace_all(text, |run: &regex::Captures| self.redact_run(&run[0]));
+        self.patterns
+            .iter()
+            .fold(text.into_owned(), |text, pattern| {
+                pattern.replace_all(&text, REDACTED).into_owned()
+            })
+    }
+
+    /// Apply the field, pattern and mnemonic rules to a JSON value, leaving
+    /// its payload fields as built
+    pub fn redact_value(&self, value: &mut Value) {
+        self.redact_at(value, &mut vec![]);
+    }
+
+    fn redact_at(&self, value: &mut Value, path: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    path.push(key.clone());
+                    if self.is_redacted_field(path) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else if !Self::is_payload_field(key) {
+                        self.redact_at(value, path);
+                    }
+                    path.pop();
+                }
+            }
+            Value::Array(values) => {
+                for value in values.iter_mut() {
+                    self.redact_at(value, path);
+                }
+            }
+            Value::String(s) => *s = self.redact_str(s),
+            _ => {}
+        }
+    }
+
+    /// Redact tool output; JSON output is redacted field by field, any
+    /// other text only with the pattern rules
+    pub fn redact_text(&self, text: &str) -> String {
+        match serde_json::from_str::<Value>(text) {
+            Ok(mut value) if value.is_object() || value.is_array() => {
+                let original = value.clone();
+                self.redact_value(&mut value);
+                if value == original {
+                    text.to_string()
+                } else {
+                    value.to_string()
+                }
+            }
+            _ => self.redact_str(text),
+        }
+    }
+
+    pub fn redact_result(&self, mut result: CallToolResult) -> CallToolResult {
+        for content in result.content.iter_mut() {
+            if let RawContent::Text(text) = &mut content.raw {
+                text.text = self.redact_text(&text.text);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::clock::now_rfc3339;
+use crate::contract::{CwContract, Network};
+use crate::label::decode_address;
+
+/// Replace with the path contracts registered at runtime are persisted to
+pub static CONTRACT_REGISTRY_PATH: &str = "contract_registry.json";
+
+/// Whether contracts can be registered at runtime; set to `false` (with
+/// `STRICT_CONTRACTS`) so agents can't add contracts to the registry
+pub static RUNTIME_REGISTRATION: bool = true;
+
+/// A contract registered at runtime (with `register_contract`), in
+/// addition to the configured deployments
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegisteredContract {
+    pub contract_address: String,
+    pub chain_id: String,
+    pub network: Network,
+    pub label: Option<String>,
+    pub registered_at: String,
+}
+
+impl From<&RegisteredContract> for CwContract {
+    fn from(registered: &RegisteredContract) -> Self {
+        CwContract::new(
+            registered.network.clone(),
+            &registered.chain_id,
+            &registered.contract_address,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+struct RegistryFile {
+    contracts: Vec<RegisteredContract>,
+}
+
+/// The configured deployments, and the contracts registered at runtime
+/// (persisted to a JSON file, so they survive restarts)
+#[derive(Clone, Debug)]
+pub struct ContractRegistry {
+    path: PathBuf,
+    configured: Vec<CwContract>,
+    registered: Arc<RwLock<Vec<RegisteredContract>>>,
+}
+
+impl ContractRegistry {
+    pub fn new(configured: Vec<CwContract>, path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            configured,
+            registered: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// The registry with the contracts registered in previous sessions
+    pub fn load(configured: Vec<CwContract>, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let registry = Self::new(configured, path);
+        if registry.path.exists() {
+            let contents = std::fs::read_to_string(&registry.path)?;
+            let file: RegistryFile = serde_json::from_str(&contents).map_err(|e| {
+                anyhow!(
+                    "invalid contract registry {}: {}",
+                    registry.path.display(),
+                    e
+                )
+            })?;
+            if let Ok(mut registered) = registry.registered.write() {
+                *registered = file.contracts;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Every contract of the registry; the configured deployments come
+    /// first, so they stay the default deployments of their networks
+    pub fn all(&self) -> Vec<CwContract> {
+        let mut contracts = self.configured.clone();
+        if let Ok(registered) = self.registered.read() {
+            contracts.extend(registered.iter().map(CwContract::from));
+        }
+        contracts
+    }
+
+    /// The contract of an address, if it's in the registry
+    pub fn find(&self, contract_addr: &str) -> Option<CwContract> {
+        self.all()
+            .into_iter()
+            .find(|contract| contract.contract_address == contract_addr)
+    }
+
+    /// The default deployment of a network (the first configured, otherwise
+    /// registered, contract on it)
+    pub fn on(&self, network: &Network) -> Option<CwContract> {
+        self.all()
+            .into_iter()
+            .find(|contract| &contract.network == network)
+    }
+
+    /// LCD endpoint of the default deployment of a network, defaulting to
+    /// the network's endpoint
+    pub fn lcd_endpoint(&self, network: &Network) -> String {
+        self.on(network)
+            .map(|contract| contract.lcd_endpoint().to_string())
+            .unwrap_or_else(|| network.lcd_endpoint().to_string())
+    }
+
+    /// Whether an address is a configured deployment (rather than
+    /// registered at runtime)
+    pub fn is_configured(&self, contract_addr: &str) -> bool {
+        self.configured
+            .iter()
+            .any(|contract| contract.contract_address == contract_addr)
+    }
+
+    pub fn registered(&self) -> Vec<RegisteredContract> {
+        self.registered
+            .read()
+            .map(|registered| registered.clone())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, registered: &[RegisteredContract]) -> anyhow::Result<()> {
+        let file = RegistryFile {
+            contracts: registered.to_vec(),
+        };
+        std::fs::write(&self.path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Register (or replace) a contract; configured deployments can't be
+    /// replaced. The chain id defaults to the network's
+    pub fn register(
+        &self,
+        contract_address: String,
+        chain_id: Option<String>,
+        network: Network,
+        label: Option<String>,
+    ) -> anyhow::Result<RegisteredContract> {
+        if !RUNTIME_REGISTRATION {
+            anyhow::bail!(
+                "runtime registration is disabled, add {} to the contracts config instead",
+                contract_address
+            );
+        }
+        decode_address(&contract_address)?;
+        if self
+            .configured
+            .iter()
+            .any(|contract| contract.contract_address == contract_address)
+        {
+            anyhow::bail!(
+                "{} is a configured deployment, and is already in the contract registry",
+                contract_address
+            );
+        }
+        let entry = RegisteredContract {
+            chain_id: chain_id.unwrap_or_else(|| network.chain_id().to_string()),
+            contract_address,
+            network,
+            label: label.filter(|label| !label.trim().is_empty()),
+            registered_at: now_rfc3339(),
+        };
+        let mut registered = self
+            .registered
+            .write()
+            .map_err(|_| anyhow!("contract registry poisoned"))?;
+        let mut updated = registered.clone();
+        updated.retain(|contract| contract.contract_address != entry.contract_address);
+        updated.push(entry.clone());
+        self.save(&updated)?;
+        *registered = updated;
+        Ok(entry)
+    }
+
+    /// Remove a contract registered at runtime; configured deployments
+    /// can't be removed
+    pub fn remove(&self, contract_address: &str) -> anyhow::Result<RegisteredContract> {
+        let mut registered = self
+            .registered
+            .write()
+            .map_err(|_| anyhow!("contract registry poisoned"))?;
+        let Some(entry) = registered
+            .iter()
+            .find(|contract| contract.contract_address == contract_address)
+            .cloned()
+        else {
+            match self
+                .configured
+                .iter()
+                .any(|contract| contract.contract_address == contract_address)
+            {
+                true => anyhow::bail!(
+                    "{} is a configured deployment, and can't be removed at runtime",
+                    contract_address
+                ),
+                false => anyhow::bail!(
+                    "{} wasn't registered with 'contract__register'",
+                    contract_address
+                ),
+            }
+        };
+        let mut updated = registered.clone();
+        updated.retain(|contract| contract.contract_address != contract_address);
+        self.save(&updated)?;
+        *registered = updated;
+        Ok(entry)
+    }
+}
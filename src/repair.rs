@@ -0,0 +1,424 @@
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Field names agents commonly use in place of a msg's field, e.g.
+/// `address` for `recipient`
+pub static FIELD_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "recipient",
+        &["address", "to", "receiver", "recipient_addr", "destination"],
+    ),
+    ("amount", &["value", "quantity", "amt", "tokens"]),
+    (
+        "spender",
+        &["address", "operator", "delegate", "spender_addr"],
+    ),
+    ("owner", &["address", "from", "owner_addr"]),
+    ("contract", &["contract_addr", "address", "to"]),
+    ("msg", &["message", "payload", "hook", "hook_msg"]),
+    ("expires", &["expiration", "expiry", "expires_at"]),
+    ("address", &["addr", "account", "wallet"]),
+];
+
+/// Repairs of an invalid msg, suggested from the schema of its type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MsgRepair {
+    /// What was repaired, e.g. `renamed field 'address' to 'recipient'`
+    pub repairs: Vec<String>,
+    /// The msg with the repairs applied; `<field>` placeholders mark
+    /// required values that couldn't be inferred
+    pub suggested_msg: Value,
+    /// Whether the suggested msg deserializes as the msg type
+    pub valid: bool,
+}
+
+/// Variant of a msg enum, from its JSON schema
+#[derive(Clone, Debug)]
+pub struct Variant {
+    pub name: String,
+    /// Unit variants are plain strings (e.g. `"token_info"`)
+    pub unit: bool,
+    pub fields: Vec<String>,
+    pub required: Vec<String>,
+    /// Schema of the variant's fields
+    pub body: Value,
+}
+
+/// Levenshtein distance of two names
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Snake case of a name, e.g. `TransferFrom` or `transfer-from` ->
+/// `transfer_from`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.trim().chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c == '-' || c == ' ' {
+            snake.push('_');
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Closest of the candidates to a name: its snake case, otherwise the
+/// candidate at the smallest edit distance if it's close enough
+pub fn nearest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let snake = snake_case(name);
+    if let Some(exact) = candidates.iter().find(|candidate| **candidate == snake) {
+        return Some(*exact);
+    }
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(&snake, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+pub use crate::entry_point::resolve;
+
+fn names(values: &Value) -> Vec<String> {
+    values
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Variants of a (cw_serde) msg enum schema: unit variants are string
+/// enums, the others are objects with a single property
+pub fn variants(root: &Value) -> Vec<Variant> {
+    let mut variants = vec![];
+    let alternatives = root["oneOf"].as_array().or(root["anyOf"].as_array());
+    for alternative in alternatives.into_iter().flatten() {
+        let alternative = resolve(alternative, root);
+        for unit in names(&alternative["enum"]) {
+            variants.push(Variant {
+                name: unit,
+                unit: true,
+                fields: vec![],
+                required: vec![],
+                body: Value::Null,
+            });
+        }
+        let Some(properties) = alternative["properties"].as_object() else {
+            continue;
+        };
+        for (name, body) in properties {
+            let body = resolve(body, root);
+            variants.push(Variant {
+                name: name.clone(),
+                unit: false,
+                fields: body["properties"]
+                    .as_object()
+                    .map(|fields| fields.keys().cloned().collect())
+                    .unwrap_or_default(),
+                required: names(&body["required"]),
+                body: body.clone(),
+            });
+        }
+    }
+    variants
+}
+
+/// Rename the fields of a variant's body to the variant's fields (by
+/// alias, snake case or edit distance), and add placeholders for missing
+/// required fields
+fn repair_fields(variant: &Variant, body: &Map<String, Value>, repairs: &mut Vec<String>) -> Value {
+    let mut repaired = Map::new();
+    let mut unknown = vec![];
+    for (field, value) in body {
+        if variant.fields.contains(field) {
+            repaired.insert(field.clone(), value.clone());
+        } else {
+            unknown.push((field, value));
+        }
+    }
+    for (field, value) in unknown {
+        let missing: Vec<&str> = variant
+            .fields
+            .iter()
+            .map(String::as_str)
+            .filter(|candidate| !repaired.contains_key(*candidate))
+            .collect();
+        let alias = FIELD_ALIASES
+            .iter()
+            .find(|(target, aliases)| missing.contains(target) && aliases.contains(&field.as_str()))
+            .map(|(target, _)| *target);
+        match alias.or_else(|| nearest(field, &missing)) {
+            Some(target) => {
+                repairs.push(format!(
+                    "renamed field '{}' of '{}' to '{}'",
+                    field, variant.name, target
+                ));
+                repaired.insert(target.to_string(), value.clone());
+            }
+            None => {
+                repairs.push(format!(
+                    "removed unknown field '{}' of '{}' (expected fields: {})",
+                    field,
+                    variant.name,
+                    variant.fields.join(", ")
+                ));
+            }
+        }
+    }
+    for required in &variant.required {
+        if !repaired.contains_key(required) {
+            repairs.push(format!(
+                "added missing required field '{}' of '{}', replace the placeholder with its value",
+                required, variant.name
+            ));
+            repaired.insert(required.clone(), Value::String(format!("<{}>", required)));
+        }
+    }
+    Value::Object(repaired)
+}
+
+/// A variant's msg with the given body
+fn variant_msg(
+    variant: &Variant,
+    body: Option<&Map<String, Value>>,
+    repairs: &mut Vec<String>,
+) -> Value {
+    if variant.unit {
+        return Value::String(variant.name.clone());
+    }
+    let body = repair_fields(variant, body.unwrap_or(&Map::new()), repairs);
+    let mut msg = Map::new();
+    msg.insert(variant.name.clone(), body);
+    Value::Object(msg)
+}
+
+/// Variant of fields given without their variant, e.g. `{"recipient":
+/// .., "amount": ..}` for `transfer`: the only variant taking them all
+fn wrapping_variant<'a>(
+    variants: &'a [Variant],
+    object: &Map<String, Value>,
+) -> Option<&'a Variant> {
+    let mut candidates = variants.iter().filter(|variant| {
+        !variant.unit && object.keys().all(|field| variant.fields.contains(field))
+    });
+    let variant = candidates.next()?;
+    match candidates.next() {
+        Some(_) => None,
+        None => Some(variant),
+    }
+}
+
+/// Best-effort repair of an invalid msg, from the JSON schema of its
+/// type: the nearest variant name, renamed (or removed) fields, missing
+/// fields, and fields given without their variant. Returns `None` if
+/// nothing could be repaired
+pub fn repair<T: DeserializeOwned>(msg: &str, schema: &Value) -> Option<MsgRepair> {
+    let msg: Value = serde_json::from_str(msg).ok()?;
+    let variants = variants(schema);
+    let names: Vec<&str> = variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    let find = |name: &str| variants.iter().find(|variant| variant.name == name);
+    let mut repairs = vec![];
+    let suggested_msg = match &msg {
+        Value::String(name) => {
+            let variant = find(name).or_else(|| nearest(name, &names).and_then(find))?;
+            if &variant.name != name {
+                repairs.push(format!("renamed variant '{}' to '{}'", name, variant.name));
+            }
+            if !variant.unit {
+                repairs.push(format!(
+                    "'{}' takes fields, sent it as an object",
+                    variant.name
+                ));
+            }
+            variant_msg(variant, None, &mut repairs)
+        }
+        Value::Object(object) => {
+            let single = object.iter().next().filter(|_| object.len() == 1);
+            let variant = single
+                .and_then(|(name, _)| find(name).or_else(|| nearest(name, &names).and_then(find)));
+            match (single, variant) {
+                (Some((name, body)), Some(variant)) => {
+                    if &variant.name != name {
+                        repairs.push(format!("renamed variant '{}' to '{}'", name, variant.name));
+                    }
+                    if variant.unit {
+                        repairs.push(format!(
+                            "'{}' takes no fields, sent it as a string",
+                            variant.name
+                        ));
+                    }
+                    variant_msg(variant, body.as_object(), &mut repairs)
+                }
+                _ => {
+                    let variant = wrapping_variant(&variants, object)?;
+                    repairs.push(format!(
+                        "wrapped the fields in the '{}' variant",
+                        variant.name
+                    ));
+                    variant_msg(variant, Some(object), &mut repairs)
+                }
+            }
+        }
+        _ => return None,
+    };
+    if repairs.is_empty() {
+        return None;
+    }
+    Some(MsgRepair {
+        repairs,
+        valid: serde_json::from_value::<T>(suggested_msg.clone()).is_ok(),
+        suggested_msg,
+    })
+}
+
+/// A name compared case and separator insensitively, e.g.
+/// `TransferFrom`, `transferFrom` and `TRANSFER_FROM` -> `transferfrom`
+fn folded(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// The only candidate a name stands for in another case (e.g. camelCase)
+fn matching<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+    let name = folded(name);
+    let mut matches = candidates.filter(|candidate| folded(candidate) == name);
+    let candidate = matches.next()?;
+    match matches.next() {
+        Some(_) => None,
+        None => Some(candidate),
+    }
+}
+
+/// Object schema of a value: the schema, or its first alternative taking
+/// properties (e.g. the `T` of an `Option<T>`)
+fn object_schema<'a>(schema: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    let schema = resolve(schema, root);
+    if schema["properties"].is_object() {
+        return Some(schema);
+    }
+    ["anyOf", "allOf", "oneOf"]
+        .iter()
+        .filter_map(|keyword| schema[*keyword].as_array())
+        .flatten()
+        .find_map(|alternative| object_schema(alternative, root))
+}
+
+/// Rename the keys of a value (and its nested objects) that stand for a
+/// property of its schema in another case
+fn normalize_value(
+    value: &mut Value,
+    schema: &Value,
+    root: &Value,
+    path: &str,
+    notes: &mut Vec<String>,
+) {
+    let resolved = resolve(schema, root);
+    if let (Value::Array(items), Some(item_schema)) = (&mut *value, resolved.get("items")) {
+        for item in items {
+            normalize_value(item, item_schema, root, path, notes);
+        }
+        return;
+    }
+    let (Value::Object(object), Some(schema)) = (value, object_schema(schema, root)) else {
+        return;
+    };
+    let Some(properties) = schema["properties"].as_object() else {
+        return;
+    };
+    let keys: Vec<String> = object.keys().cloned().collect();
+    for key in keys {
+        if properties.contains_key(&key) {
+            continue;
+        }
+        if let Some(property) = matching(&key, properties.keys()) {
+            if !object.contains_key(property) {
+                if let Some(field) = object.remove(&key) {
+                    notes.push(format!(
+                        "normalized field '{}.{}' to '{}'",
+                        path, key, property
+                    ));
+                    object.insert(property.clone(), field);
+                }
+            }
+        }
+    }
+    for (key, field) in object.iter_mut() {
+        if let Some(property) = properties.get(key) {
+            normalize_value(field, property, root, &format!("{}.{}", path, key), notes);
+        }
+    }
+}
+
+/// Normalize the variant name and field keys of a msg given in camelCase
+/// (or another case) to the snake_case names of its schema, returning a
+/// note of every normalized key
+pub fn normalize_keys(msg: &mut Value, schema: &Value) -> Vec<String> {
+    let variants = variants(schema);
+    let names: Vec<String> = variants
+        .iter()
+        .map(|variant| variant.name.clone())
+        .collect();
+    let mut notes = vec![];
+    match msg {
+        Value::String(name) if !names.contains(name) => {
+            if let Some(variant) = matching(name, names.iter()) {
+                notes.push(format!("normalized variant '{}' to '{}'", name, variant));
+                *name = variant.clone();
+            }
+        }
+        Value::Object(object) if object.len() == 1 => {
+            let Some(key) = object.keys().next().cloned() else {
+                return notes;
+            };
+            let name = match names.contains(&key) {
+                true => key,
+                false => match matching(&key, names.iter()) {
+                    Some(variant) => {
+                        notes.push(format!("normalized variant '{}' to '{}'", key, variant));
+                        if let Some(body) = object.remove(&key) {
+                            object.insert(variant.clone(), body);
+                        }
+                        variant.clone()
+                    }
+                    None => return notes,
+                },
+            };
+            if let (Some(variant), Some(body)) = (
+                variants.iter().find(|variant| variant.name == name),
+                object.get_mut(&name),
+            ) {
+                normalize_value(body, &variant.body, schema, &name, &mut notes);
+            }
+        }
+        _ => {}
+    }
+    notes
+}
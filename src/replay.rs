@@ -0,0 +1,249 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{Coin, HexBinary};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cosmos::type_url;
+use crate::rpc::{LcdClient, url_encode};
+use crate::sandbox::*;
+
+/// Page size used while downloading a contract's state
+pub static STATE_PAGE_LIMIT: u64 = 500;
+
+/// Maximum number of state entries loaded into the sandbox
+pub static MAX_SNAPSHOT_ENTRIES: usize = 50_000;
+
+/// A MsgExecuteContract extracted from a historical tx
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TxExecute {
+    pub sender: String,
+    pub contract: String,
+    pub msg: Value,
+    #[serde(default)]
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoricalTx {
+    pub tx_hash: String,
+    pub height: u64,
+    /// Result code of the tx on chain (0 is success)
+    pub code: u32,
+    pub raw_log: String,
+    pub executes: Vec<TxExecute>,
+}
+
+/// Raw contract state at a block height
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateSnapshot {
+    pub height: u64,
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Bank balances of the contract
+    pub balances: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReplayReport {
+    pub tx_hash: String,
+    pub height: u64,
+    /// Height of the state snapshot the tx was replayed on
+    pub snapshot_height: u64,
+    pub snapshot_entries: usize,
+    pub contract_addr: String,
+    pub original_success: bool,
+    pub original_log: String,
+    pub replay_success: bool,
+    /// True if the replay succeeded where the tx failed (or vice versa)
+    pub diverged: bool,
+    pub executions: Vec<ExecutionProfile>,
+}
+
+fn parse_height(value: &Value) -> u64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        _ => value.as_u64().unwrap_or_default(),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Replayer {
+    lcd: LcdClient,
+}
+
+impl Replayer {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    /// Fetch a tx by hash and extract its MsgExecuteContract messages
+    pub async fn tx(&self, endpoint: &str, tx_hash: &str) -> anyhow::Result<HistoricalTx> {
+        let path = format!("/cosmos/tx/v1beta1/txs/{}", tx_hash.to_uppercase());
+        let body = self.lcd.get(endpoint, &path).await?;
+        let response = &body["tx_response"];
+        let messages = body["tx"]["body"]["messages"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let executes = messages
+            .into_iter()
+            .filter(|msg| msg["@type"].as_str() == Some(type_url::MSG_EXECUTE_CONTRACT))
+            .map(serde_json::from_value::<TxExecute>)
+            .collect::<Result<Vec<TxExecute>, _>>()
+            .map_err(|e| anyhow!("invalid MsgExecuteContract in tx {}: {}", tx_hash, e))?;
+        Ok(HistoricalTx {
+            tx_hash: tx_hash.to_uppercase(),
+            height: parse_height(&response["height"]),
+            code: response["code"].as_u64().unwrap_or_default() as u32,
+            raw_log: response["raw_log"].as_str().unwrap_or_default().to_string(),
+            executes,
+        })
+    }
+
+    /// The msg a contract was originally instantiated with
+    pub async fn instantiate_msg(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<Value> {
+        let path = format!("/cosmwasm/wasm/v1/contract/{}/history", contract_addr);
+        let body = self.lcd.get(endpoint, &path).await?;
+        body["entries"]
+            .as_array()
+            .and_then(|entries| {
+                entries.iter().find(|entry| {
+                    entry["operation"].as_str() == Some("CONTRACT_CODE_HISTORY_OPERATION_TYPE_INIT")
+                })
+            })
+            .map(|entry| entry["msg"].clone())
+            .ok_or_else(|| anyhow!("no instantiate msg found for {}", contract_addr))
+    }
+
+    /// Download the raw state of a contract at a block height
+    pub async fn snapshot(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        height: u64,
+    ) -> anyhow::Result<StateSnapshot> {
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+        let mut next_key: Option<String> = None;
+        loop {
+            let mut path = format!(
+                "/cosmwasm/wasm/v1/contract/{}/state?pagination.limit={}",
+                contract_addr, STATE_PAGE_LIMIT
+            );
+            if let Some(key) = &next_key {
+                path.push_str(&format!("&pagination.key={}", url_encode(key)));
+            }
+            let body = self
+                .lcd
+                .get_at_height(endpoint, &path, Some(height))
+                .await?;
+            for model in body["models"].as_array().cloned().unwrap_or_default() {
+                let key = HexBinary::from_hex(model["key"].as_str().unwrap_or_default())
+                    .map_err(|e| anyhow!("invalid state key: {}", e))?;
+                let value = STANDARD
+                    .decode(model["value"].as_str().unwrap_or_default())
+                    .map_err(|e| anyhow!("invalid state value: {}", e))?;
+                entries.push((key.to_vec(), value));
+            }
+            if entries.len() > MAX_SNAPSHOT_ENTRIES {
+                anyhow::bail!(
+                    "contract state exceeds {} entries, too large to replay",
+                    MAX_SNAPSHOT_ENTRIES
+                );
+            }
+            next_key = body["pagination"]["next_key"]
+                .as_str()
+                .filter(|key| !key.is_empty())
+                .map(str::to_string);
+            if next_key.is_none() {
+                break;
+            }
+        }
+        let balances = self.balances(endpoint, contract_addr, height).await?;
+        Ok(StateSnapshot {
+            height,
+            entries,
+            balances,
+        })
+    }
+
+    /// Bank balances of an account at a block height
+    async fn balances(
+        &self,
+        endpoint: &str,
+        address: &str,
+        height: u64,
+    ) -> anyhow::Result<Vec<Coin>> {
+        let path = format!(
+            "/cosmos/bank/v1beta1/balances/{}?pagination.limit={}",
+            address, STATE_PAGE_LIMIT
+        );
+        let body = self
+            .lcd
+            .get_at_height(endpoint, &path, Some(height))
+            .await?;
+        serde_json::from_value(body["balances"].clone())
+            .map_err(|e| anyhow!("invalid balances of {}: {}", address, e))
+    }
+}
+
+/// Re-execute the messages of a historical tx (sent to `contract_addr`) on
+/// a sandbox instance restored from a state snapshot
+pub fn replay(
+    instantiate_msg: &Value,
+    tx: &HistoricalTx,
+    contract_addr: &str,
+    snapshot: &StateSnapshot,
+) -> anyhow::Result<ReplayReport> {
+    let executes: Vec<&TxExecute> = tx
+        .executes
+        .iter()
+        .filter(|execute| execute.contract == contract_addr)
+        .collect();
+    if executes.is_empty() {
+        anyhow::bail!(
+            "tx {} has no MsgExecuteContract sent to {}",
+            tx.tx_hash,
+            contract_addr
+        );
+    }
+    let mut sandbox = Sandbox::new(instantiate_msg, &[])?;
+    sandbox.restore(&snapshot.entries);
+    // Payouts (e.g. withdrawals) are made from the contract's balances
+    if !snapshot.balances.is_empty() {
+        let contract = sandbox.contract().clone();
+        sandbox.fund(&contract, snapshot.balances.clone())?;
+    }
+    sandbox.set_height(tx.height);
+    let mut executions = vec![];
+    for execute in executes {
+        if !execute.funds.is_empty() {
+            let sender = sandbox.addr(&execute.sender);
+            sandbox.fund(&sender, execute.funds.clone())?;
+        }
+        executions.push(sandbox.execute(&SandboxCase {
+            name: None,
+            execute_msg: execute.msg.clone(),
+            sender: Some(execute.sender.clone()),
+            funds: execute.funds.clone(),
+        }));
+    }
+    let original_success = tx.code == 0;
+    let replay_success = executions.iter().all(|profile| profile.success);
+    Ok(ReplayReport {
+        tx_hash: tx.tx_hash.clone(),
+        height: tx.height,
+        snapshot_height: snapshot.height,
+        snapshot_entries: snapshot.entries.len(),
+        contract_addr: contract_addr.to_string(),
+        original_success,
+        original_log: tx.raw_log.clone(),
+        replay_success,
+        diverged: original_success != replay_success,
+        executions,
+    })
+}
@@ -0,0 +1,255 @@
+use anyhow::anyhow;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use crate::contract::{BECH32_PREFIX, Network};
+use crate::label::is_address;
+use crate::registry::ContractRegistry;
+use crate::rpc::LcdClient;
+
+/// ArchID registry contracts, resolving `name.arch` domains on Archway
+pub static ARCHID_REGISTRY_MAINNET: &str =
+    "archway1275jwjpktae4y4y0cdq274a2m0jnpekhttnfuljm6n59wnpyd62qppqxq0";
+pub static ARCHID_REGISTRY_TESTNET: &str =
+    "archway1lr8rstt40s697hqpedv2nvt27f4cuccqwvly9gnvuszxmcevrlns60xw4r";
+
+/// ICNS resolver (deployed on Osmosis), resolving `name.<bech32 prefix>`
+/// (e.g. `alice.archway`, `alice.osmo`)
+pub static ICNS_RESOLVER: &str = "osmo1xk0s8xgktn9x5vwcgtjdxqzadg88fgn33p8u9cnpdxwemvxscvast52cdd";
+pub static ICNS_LCD: &str = "https://lcd.osmosis.zone";
+
+/// Stargaze Names, resolving `name.stars`
+pub static STARGAZE_NAMES: &str =
+    "stars1fx74nkqkw2748av8j7ew7r3xt9cgjqduwn8m0ur5lhe49uhlsasszc5fhr";
+pub static STARGAZE_LCD: &str = "https://rest.stargaze-apis.com";
+
+/// Bech32 prefixes that can be used as ICNS top level names
+pub static ICNS_PREFIXES: [&str; 12] = [
+    "archway", "osmo", "cosmos", "juno", "neutron", "akash", "axelar", "celestia", "inj", "kujira",
+    "stride", "terra",
+];
+
+/// Message fields (object keys) that are expected to carry an address.
+/// Only these fields (plus `*_addr` and `*_address` keys) are resolved,
+/// so unrelated values such as URLs or descriptions are left untouched
+pub static ADDRESS_FIELDS: [&str; 11] = [
+    "address",
+    "recipient",
+    "owner",
+    "spender",
+    "contract",
+    "sender",
+    "admin",
+    "minter",
+    "new_minter",
+    "new_owner",
+    "operator",
+];
+
+static NAME_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[a-z0-9_-]+(\.[a-z0-9_-]+)*\.([a-z]+)$").expect("valid name pattern")
+});
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum NameService {
+    ArchId,
+    Icns,
+    StargazeNames,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NameResolution {
+    pub name: String,
+    pub address: String,
+    pub service: NameService,
+}
+
+/// Returns the name service responsible for `value`, if `value` is a
+/// name (e.g. `alice.arch`) rather than an address
+pub fn name_service(value: &str) -> Option<NameService> {
+    if is_address(value) {
+        return None;
+    }
+    let captures = NAME_PATTERN.captures(value)?;
+    match captures.get(2)?.as_str() {
+        "arch" => Some(NameService::ArchId),
+        "stars" => Some(NameService::StargazeNames),
+        tld if ICNS_PREFIXES.contains(&tld) => Some(NameService::Icns),
+        _ => None,
+    }
+}
+
+fn is_address_field(key: &str) -> bool {
+    ADDRESS_FIELDS.contains(&key) || key.ends_with("_addr") || key.ends_with("_address")
+}
+
+#[derive(Clone, Debug)]
+pub struct Resolver {
+    lcd: LcdClient,
+    contracts: ContractRegistry,
+}
+
+impl Resolver {
+    /// Resolver querying the ArchID registry at the LCD endpoint of the
+    /// deployments
+    pub fn new(lcd: LcdClient, contracts: ContractRegistry) -> Self {
+        Self { lcd, contracts }
+    }
+
+    /// Resolve a name to an address using its name service. ArchID names
+    /// are resolved on the registry of `network`
+    pub async fn resolve(&self, name: &str, network: &Network) -> anyhow::Result<NameResolution> {
+        let service =
+            name_service(name).ok_or_else(|| anyhow!("'{}' is not a supported name", name))?;
+        let response = match service {
+            NameService::ArchId => {
+                self.lcd
+                    .smart_query(
+                        &self.contracts.lcd_endpoint(network),
+                        crate::archid::registry(network),
+                        &json!({ "resolve_record": { "name": name } }),
+                    )
+                    .await?
+            }
+            NameService::Icns => {
+                let (label, prefix) = name.rsplit_once('.').unwrap_or((name, ""));
+                self.lcd
+                    .smart_query(
+                        ICNS_LCD,
+                        ICNS_RESOLVER,
+                        &json!({ "address": { "name": label, "bech32_prefix": prefix } }),
+                    )
+                    .await?
+            }
+            NameService::StargazeNames => {
+                let label = name.strip_suffix(".stars").unwrap_or(name);
+                self.lcd
+                    .smart_query(
+                        STARGAZE_LCD,
+                        STARGAZE_NAMES,
+                        &json!({ "associated_address": { "name": label } }),
+                    )
+                    .await?
+            }
+        };
+        let address = match &response {
+            Value::String(address) => Some(address.clone()),
+            _ => response
+                .get("address")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }
+        .filter(|address| !address.is_empty())
+        .ok_or_else(|| anyhow!("'{}' is not registered", name))?;
+        Ok(NameResolution {
+            name: name.to_string(),
+            address,
+            service,
+        })
+    }
+
+    /// Resolve `value` if it's a name, otherwise return it unchanged
+    pub async fn resolve_address(
+        &self,
+        value: String,
+        network: &Network,
+        resolutions: &mut Vec<NameResolution>,
+    ) -> anyhow::Result<String> {
+        if name_service(&value).is_none() {
+            return Ok(value);
+        }
+        let resolution = self.resolve(&value, network).await?;
+        let address = resolution.address.clone();
+        resolutions.push(resolution);
+        Ok(address)
+    }
+
+    /// Resolve every name found in the address fields of a message
+    pub async fn resolve_msg(
+        &self,
+        msg: &mut Value,
+        network: &Network,
+        resolutions: &mut Vec<NameResolution>,
+    ) -> anyhow::Result<()> {
+        let mut names: Vec<String> = vec![];
+        collect_names(msg, &mut names);
+        let mut resolved: BTreeMap<String, String> = BTreeMap::new();
+        for name in names {
+            if resolved.contains_key(&name) {
+                continue;
+            }
+            let resolution = self.resolve(&name, network).await?;
+            self.check_prefix(&resolution, network)?;
+            resolved.insert(name, resolution.address.clone());
+            resolutions.push(resolution);
+        }
+        replace_names(msg, &resolved);
+        Ok(())
+    }
+
+    /// Refuse ICNS names resolving to an address of another chain (e.g.
+    /// `alice.osmo` in a msg for Archway), which can't receive the funds
+    /// or permissions of the msg
+    fn check_prefix(&self, resolution: &NameResolution, network: &Network) -> anyhow::Result<()> {
+        if resolution.service != NameService::Icns {
+            return Ok(());
+        }
+        let prefix = self
+            .contracts
+            .on(network)
+            .map(|contract| contract.bech32_prefix)
+            .unwrap_or_else(|| BECH32_PREFIX.to_string());
+        if !resolution.address.starts_with(&format!("{}1", prefix)) {
+            return Err(anyhow!(
+                "'{}' resolves to {}, which isn't a '{}' address of {:?}",
+                resolution.name,
+                resolution.address,
+                prefix,
+                network
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn collect_names(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::String(s) if is_address_field(key) && name_service(s).is_some() => {
+                        names.push(s.clone())
+                    }
+                    _ => collect_names(value, names),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_names(value, names)),
+        _ => {}
+    }
+}
+
+fn replace_names(value: &mut Value, resolved: &BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if is_address_field(key) => {
+                        if let Some(address) = resolved.get(s.as_str()) {
+                            *s = address.clone();
+                        }
+                    }
+                    _ => replace_names(value, resolved),
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| replace_names(value, resolved)),
+        _ => {}
+    }
+}
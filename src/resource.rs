@@ -0,0 +1,141 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde_json::Value;
+
+use crate::contract::Network;
+
+/// URI template of live contract state reads; `query_b64` is the URL safe
+/// base64 encoding of a JSON query msg
+pub static STATE_TEMPLATE: &str = "state://{network}/{contract}/{query_b64}";
+pub static STATE_SCHEME: &str = "state://";
+
+/// A parsed `state://` resource URI
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateUri {
+    pub network: Network,
+    pub contract: String,
+    pub query: Value,
+}
+
+impl StateUri {
+    pub fn parse(uri: &str) -> anyhow::Result<Self> {
+        let path = uri
+            .strip_prefix(STATE_SCHEME)
+            .ok_or_else(|| anyhow!("'{}' isn't a {} resource", uri, STATE_SCHEME))?;
+        let segments: Vec<&str> = path.split('/').collect();
+        let [network, contract, query_b64] = segments[..] else {
+            anyhow::bail!("'{}' doesn't match {}", uri, STATE_TEMPLATE);
+        };
+        let network = match network.to_lowercase().as_str() {
+            "mainnet" => Network::Mainnet,
+            "testnet" => Network::Testnet,
+            _ => anyhow::bail!(
+                "unknown network '{}', expected 'mainnet' or 'testnet'",
+                network
+            ),
+        };
+        let decoded = URL_SAFE_NO_PAD
+            .decode(query_b64.trim_end_matches('='))
+            .map_err(|e| anyhow!("query_b64 isn't URL safe base64: {}", e))?;
+        let query: Value = serde_json::from_slice(&decoded)
+            .map_err(|e| anyhow!("query_b64 isn't a JSON query msg: {}", e))?;
+        Ok(Self {
+            network,
+            contract: contract.to_string(),
+            query,
+        })
+    }
+
+    /// URI of a query, e.g. for subscribing to it
+    pub fn uri(network: &Network, contract: &str, query: &Value) -> String {
+        let network = match network {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+        };
+        format!(
+            "{}{}/{}/{}",
+            STATE_SCHEME,
+            network,
+            contract,
+            URL_SAFE_NO_PAD.encode(query.to_string())
+        )
+    }
+}
+
+/// URI template of the msg schemas of a deployment, e.g.
+/// `schema://archway-1/archway1.../execute`
+pub static SCHEMA_TEMPLATE: &str = "schema://{chain_id}/{contract}/{kind}";
+pub static SCHEMA_SCHEME: &str = "schema://";
+
+/// Msg schemas served as `schema://` resources
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SchemaKind {
+    Query,
+    Execute,
+    Instantiate,
+    Migrate,
+}
+
+impl SchemaKind {
+    pub fn all() -> [SchemaKind; 4] {
+        [
+            SchemaKind::Query,
+            SchemaKind::Execute,
+            SchemaKind::Instantiate,
+            SchemaKind::Migrate,
+        ]
+    }
+
+    /// Last segment of the kind's URI
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchemaKind::Query => "query",
+            SchemaKind::Execute => "execute",
+            SchemaKind::Instantiate => "instantiate",
+            SchemaKind::Migrate => "migrate",
+        }
+    }
+
+    /// Key of the rendered schema (see `src/schema.rs`)
+    pub fn schema_key(&self) -> String {
+        format!("{}:json", self.name())
+    }
+}
+
+/// A parsed `schema://` resource URI
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaUri {
+    pub chain_id: String,
+    pub contract: String,
+    pub kind: SchemaKind,
+}
+
+impl SchemaUri {
+    pub fn parse(uri: &str) -> anyhow::Result<Self> {
+        let path = uri
+            .strip_prefix(SCHEMA_SCHEME)
+            .ok_or_else(|| anyhow!("'{}' isn't a {} resource", uri, SCHEMA_SCHEME))?;
+        let segments: Vec<&str> = path.split('/').collect();
+        let [chain_id, contract, kind] = segments[..] else {
+            anyhow::bail!("'{}' doesn't match {}", uri, SCHEMA_TEMPLATE);
+        };
+        let kind = SchemaKind::all()
+            .into_iter()
+            .find(|schema_kind| schema_kind.name() == kind.to_lowercase())
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown schema '{}', expected 'query', 'execute', 'instantiate' or 'migrate'",
+                    kind
+                )
+            })?;
+        Ok(Self {
+            chain_id: chain_id.to_string(),
+            contract: contract.to_string(),
+            kind,
+        })
+    }
+
+    pub fn uri(chain_id: &str, contract: &str, kind: SchemaKind) -> String {
+        format!("{}{}/{}/{}", SCHEMA_SCHEME, chain_id, contract, kind.name())
+    }
+}
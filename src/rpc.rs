@@ -0,0 +1,339 @@
+use anyhow::anyhow;
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Instant;
+
+use crate::cache::ChainCache;
+use crate::contract::{LCD_AUTH_TOKEN, Network};
+use crate::endpoint::EndpointScores;
+use crate::proxy::{HttpClients, HttpRequest, HttpResponse};
+use crate::secrets::{LazySecret, Secrets};
+use crate::usage::UsageTracker;
+
+/// Storage key of the cw2 contract version
+pub static CW2_KEY: &[u8] = b"contract_info";
+
+/// cw2 `ContractVersion`, set by most contracts on instantiation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractVersion {
+    pub contract: String,
+    pub version: String,
+}
+
+/// Header selecting the block height LCD queries are answered at
+pub static BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// Percent-encode a value for use in a URL query string
+pub fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Error of requests no endpoint of a network was available for
+#[derive(Debug)]
+pub struct Unavailable(pub anyhow::Error);
+
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Unavailable {}
+
+/// Minimal client for the Cosmos SDK REST (LCD) gateway, used by tools
+/// that need live chain data (e.g. name resolution, smart queries)
+#[derive(Clone, Debug, Default)]
+pub struct LcdClient {
+    /// Clients of the networks' endpoints (see `src/proxy.rs`)
+    http: HttpClients,
+    /// Bearer token sent to LCD endpoints, if they require one
+    auth: Option<LazySecret>,
+    /// Health of the endpoints, shared by clones of the client
+    scores: EndpointScores,
+    /// Responses served in offline mode, shared by clones of the client
+    cache: ChainCache,
+    /// Requests of the session, shared by clones of the client
+    usage: UsageTracker,
+}
+
+impl LcdClient {
+    pub fn new() -> Self {
+        Self {
+            http: HttpClients::default(),
+            auth: LCD_AUTH_TOKEN.map(|reference| LazySecret::new(reference, Secrets::default())),
+            scores: EndpointScores::default(),
+            cache: ChainCache::default(),
+            usage: UsageTracker::default(),
+        }
+    }
+
+    /// A client sending its requests with the given http clients (e.g. of
+    /// an application embedding the server)
+    pub fn with_http(http: HttpClients) -> Self {
+        Self {
+            http,
+            ..Self::new()
+        }
+    }
+
+    pub fn scores(&self) -> &EndpointScores {
+        &self.scores
+    }
+
+    pub fn cache(&self) -> &ChainCache {
+        &self.cache
+    }
+
+    pub fn usage(&self) -> &UsageTracker {
+        &self.usage
+    }
+
+    /// Endpoints a request to `endpoint` can be sent to, healthiest first:
+    /// the LCD endpoints of its network, or only itself if it isn't one
+    fn candidates(&self, endpoint: &str) -> Vec<String> {
+        let trimmed = endpoint.trim_end_matches('/');
+        [Network::Mainnet, Network::Testnet]
+            .iter()
+            .map(Network::lcd_endpoints)
+            .find(|endpoints| endpoints.iter().any(|lcd| *lcd == trimmed))
+            .map(|endpoints| self.scores.rank(&endpoints))
+            .unwrap_or_else(|| vec![trimmed.to_string()])
+    }
+
+    /// GET a path from an LCD endpoint and return the decoded JSON body.
+    /// Error responses from the gateway (`{"code": .., "message": ..}`) are
+    /// returned as errors carrying the gateway message
+    pub async fn get(&self, endpoint: &str, path: &str) -> anyhow::Result<Value> {
+        self.get_at_height(endpoint, path, None).await
+    }
+
+    /// GET a path from an LCD endpoint, reading state at a past block
+    /// height (`x-cosmos-block-height`) if one is given. Heights pruned by
+    /// the node require an archive endpoint
+    pub async fn get_at_height(
+        &self,
+        endpoint: &str,
+        path: &str,
+        height: Option<u64>,
+    ) -> anyhow::Result<Value> {
+        let key = ChainCache::key(endpoint, path, height);
+        if self.cache.offline() {
+            return self
+                .cache
+                .get(&key)
+                .ok_or_else(|| anyhow!("offline mode, and {} isn't cached", path));
+        }
+        let sent = self
+            .send(endpoint, path, |url| {
+                let request = HttpRequest::get(url);
+                match height {
+                    Some(height) => request.header(BLOCK_HEIGHT_HEADER, height.to_string()),
+                    None => request,
+                }
+            })
+            .await;
+        match sent {
+            Ok(body) => {
+                self.cache.insert(key, &body);
+                Ok(body)
+            }
+            // Flaky networks fall back to cached (stale) data
+            Err(e) if e.is::<Unavailable>() => self.cache.get(&key).ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// POST a JSON body to an LCD endpoint (e.g. tx simulation) and return
+    /// the decoded JSON body
+    pub async fn post(&self, endpoint: &str, path: &str, body: &Value) -> anyhow::Result<Value> {
+        if self.cache.offline() {
+            return Err(anyhow!("offline mode, {} can't be sent", path));
+        }
+        self.send(endpoint, path, |url| HttpRequest::post(url, body.clone()))
+            .await
+    }
+
+    /// Send a request to the healthiest endpoint of `endpoint`'s network,
+    /// moving on to the next endpoint if it's unavailable (the request
+    /// fails, or the endpoint errors with a 5xx status). Gateway errors of
+    /// available endpoints (e.g. contract errors) are returned as is
+    async fn send(
+        &self,
+        endpoint: &str,
+        path: &str,
+        build: impl Fn(&str) -> HttpRequest,
+    ) -> anyhow::Result<Value> {
+        let mut unavailable = None;
+        for candidate in self.candidates(endpoint) {
+            let url = format!("{}{}", candidate, path);
+            let mut request = build(&url);
+            if let Some(auth) = &self.auth {
+                request = request.bearer_auth(auth.get().await?.expose());
+            }
+            let started = Instant::now();
+            let response = match self.http.for_endpoint(&candidate).send(request).await {
+                Ok(response) if !response.is_server_error() => response,
+                Ok(response) => {
+                    self.usage.record_rpc(true);
+                    let e = anyhow!("{} responded with {}", url, response.status);
+                    self.scores
+                        .record(&candidate, started.elapsed(), Some(e.to_string()));
+                    unavailable = Some(e);
+                    continue;
+                }
+                Err(e) => {
+                    self.usage.record_rpc(true);
+                    self.scores
+                        .record(&candidate, started.elapsed(), Some(e.to_string()));
+                    unavailable = Some(e);
+                    continue;
+                }
+            };
+            self.scores.record(&candidate, started.elapsed(), None);
+            self.usage.record_rpc(false);
+            return Self::decode(&url, response);
+        }
+        let unavailable =
+            unavailable.unwrap_or_else(|| anyhow!("no endpoint available for {}", endpoint));
+        Err(Unavailable(unavailable).into())
+    }
+
+    fn decode(url: &str, response: HttpResponse) -> anyhow::Result<Value> {
+        let body: Value = response
+            .json()
+            .map_err(|e| anyhow!("invalid JSON response from {}: {}", url, e))?;
+        if !response.is_success() {
+            let message = body
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| body.to_string());
+            return Err(anyhow!(message));
+        }
+        Ok(body)
+    }
+
+    /// Execute a smart query against a contract and return the `data` field
+    /// of the response
+    pub async fn smart_query(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        msg: &Value,
+    ) -> anyhow::Result<Value> {
+        self.smart_query_at(endpoint, contract_addr, msg, None)
+            .await
+    }
+
+    /// Execute a smart query against the contract's state at a past block
+    /// height (see `get_at_height`)
+    pub async fn smart_query_at(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        msg: &Value,
+        height: Option<u64>,
+    ) -> anyhow::Result<Value> {
+        let encoded = URL_SAFE.encode(serde_json::to_vec(msg)?);
+        let path = format!(
+            "/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            contract_addr, encoded
+        );
+        let mut body = self.get_at_height(endpoint, &path, height).await?;
+        Ok(body.get_mut("data").map(Value::take).unwrap_or_default())
+    }
+
+    /// `ContractInfo` of a contract (code id, creator, admin, label and
+    /// instantiation height)
+    pub async fn contract_info(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<Value> {
+        let path = format!("/cosmwasm/wasm/v1/contract/{}", contract_addr);
+        let mut body = self.get(endpoint, &path).await?;
+        Ok(body
+            .get_mut("contract_info")
+            .map(Value::take)
+            .unwrap_or_default())
+    }
+
+    /// Read a raw key of a contract's storage, returning `None` if the key
+    /// isn't set
+    pub async fn raw_query(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        key: &[u8],
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let path = format!(
+            "/cosmwasm/wasm/v1/contract/{}/raw/{}",
+            contract_addr,
+            URL_SAFE.encode(key)
+        );
+        let body = self.get(endpoint, &path).await?;
+        match body["data"].as_str() {
+            Some(data) if !data.is_empty() => {
+                Ok(Some(STANDARD.decode(data).map_err(|e| {
+                    anyhow!("invalid raw query response: {}", e)
+                })?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// cw2 `ContractVersion` of a contract, if it sets one
+    pub async fn cw2_version(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<Option<ContractVersion>> {
+        match self.raw_query(endpoint, contract_addr, CW2_KEY).await? {
+            Some(data) => Ok(serde_json::from_slice(&data).ok()),
+            None => Ok(None),
+        }
+    }
+
+    /// Bank balance of an address in a denom (the amount, in the smallest
+    /// unit of the denom)
+    pub async fn bank_balance(
+        &self,
+        endpoint: &str,
+        address: &str,
+        denom: &str,
+    ) -> anyhow::Result<String> {
+        let path = format!(
+            "/cosmos/bank/v1beta1/balances/{}/by_denom?denom={}",
+            address,
+            url_encode(denom)
+        );
+        let body = self.get(endpoint, &path).await?;
+        Ok(body["balance"]["amount"]
+            .as_str()
+            .unwrap_or("0")
+            .to_string())
+    }
+
+    /// Total supply of a denom on chain ("0" for denoms that don't exist)
+    pub async fn bank_supply(&self, endpoint: &str, denom: &str) -> anyhow::Result<String> {
+        let path = format!(
+            "/cosmos/bank/v1beta1/supply/by_denom?denom={}",
+            url_encode(denom)
+        );
+        let body = self.get(endpoint, &path).await?;
+        Ok(body["amount"]["amount"].as_str().unwrap_or("0").to_string())
+    }
+}
@@ -0,0 +1,454 @@
+use anyhow::anyhow;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{Addr, Coin, Order, Record, Storage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::example::example_msg;
+use crate::label::is_address;
+use crate::repair::variants;
+
+/// Bech32 prefix of sandbox addresses
+pub static SANDBOX_PREFIX: &str = "archway";
+
+/// Native balance every sandbox account is funded with
+pub static SANDBOX_BALANCE: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Sender of the sandbox instantiate msg (and default sender of executes)
+pub static SANDBOX_OWNER: &str = "owner";
+
+/// Cosmos SDK KV store gas costs (`storetypes.KVGasConfig`), which wasmd
+/// charges for every storage access made by a contract
+pub mod kv_gas {
+    pub const DELETE: u64 = 1_000;
+    pub const READ_FLAT: u64 = 1_000;
+    pub const READ_PER_BYTE: u64 = 3;
+    pub const WRITE_FLAT: u64 = 2_000;
+    pub const WRITE_PER_BYTE: u64 = 30;
+    pub const ITER_NEXT_FLAT: u64 = 30;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct StorageMeter {
+    pub reads: u64,
+    pub writes: u64,
+    pub deletes: u64,
+    pub iterations: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Estimated SDK gas charged for storage access; wasm compute isn't
+    /// metered by the sandbox
+    pub storage_gas: u64,
+}
+
+impl StorageMeter {
+    fn read(&mut self, key: &[u8], value: Option<&Vec<u8>>) {
+        let bytes = (key.len() + value.map(Vec::len).unwrap_or_default()) as u64;
+        self.reads += 1;
+        self.bytes_read += bytes;
+        self.storage_gas += kv_gas::READ_FLAT + kv_gas::READ_PER_BYTE * bytes;
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) {
+        let bytes = (key.len() + value.len()) as u64;
+        self.writes += 1;
+        self.bytes_written += bytes;
+        self.storage_gas += kv_gas::WRITE_FLAT + kv_gas::WRITE_PER_BYTE * bytes;
+    }
+
+    fn delete(&mut self) {
+        self.deletes += 1;
+        self.storage_gas += kv_gas::DELETE;
+    }
+
+    fn iterate(&mut self, key: &[u8], value: &[u8]) {
+        let bytes = (key.len() + value.len()) as u64;
+        self.iterations += 1;
+        self.bytes_read += bytes;
+        self.storage_gas += kv_gas::ITER_NEXT_FLAT + kv_gas::READ_PER_BYTE * bytes;
+    }
+}
+
+/// Storage counting every access made through it, so the sandbox can
+/// estimate the storage gas of each execution
+pub struct MeteredStorage {
+    inner: MockStorage,
+    meter: Rc<RefCell<StorageMeter>>,
+}
+
+impl Storage for MeteredStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        self.meter.borrow_mut().read(key, value.as_ref());
+        value
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let meter = self.meter.clone();
+        Box::new(
+            self.inner
+                .range(start, end, order)
+                .inspect(move |(key, value)| meter.borrow_mut().iterate(key, value)),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.meter.borrow_mut().write(key, value);
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.meter.borrow_mut().delete();
+        self.inner.remove(key);
+    }
+}
+
+/// A single execute msg to be run in the sandbox
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SandboxCase {
+    /// Optional name for the case, defaults to the ExecuteMsg variant
+    pub name: Option<String>,
+    pub execute_msg: Value,
+    /// Sender; a bech32 address or a name (e.g. 'alice') that is turned
+    /// into a sandbox address. Defaults to the instantiating 'owner'
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub funds: Vec<Coin>,
+}
+
+impl SandboxCase {
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.execute_msg
+                .as_object()
+                .and_then(|msg| msg.keys().next().cloned())
+                .unwrap_or_else(|| "unknown".to_string())
+        })
+    }
+
+    pub fn sender(&self) -> &str {
+        self.sender.as_deref().unwrap_or(SANDBOX_OWNER)
+    }
+}
+
+/// Cases profiled when none are given: an example msg (with its required
+/// fields) of every variant of an ExecuteMsg schema, sent by the owner
+pub fn default_cases(execute_schema: &Value) -> Vec<SandboxCase> {
+    variants(execute_schema)
+        .iter()
+        .map(|variant| SandboxCase {
+            name: Some(variant.name.clone()),
+            execute_msg: example_msg(variant, execute_schema),
+            sender: None,
+            funds: vec![],
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutionProfile {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub storage: StorageMeter,
+    pub wall_time_micros: u128,
+    /// Storage gas relative to the most expensive profiled case (percent)
+    pub relative_gas: u64,
+}
+
+#[cfg(feature = "sandbox")]
+mod chain {
+    /// Replace the below imports with the contract you want the sandbox
+    /// to run (the same contract imported by `src/server.rs`)
+    use cw20_wrap::contract::{execute, instantiate, query};
+    use cw20_wrap::msg::{ExecuteMsg, InstantiateMsg};
+
+    use super::*;
+    use cosmwasm_std::Uint128;
+    use cw_multi_test::{App, AppBuilder, BankKeeper, ContractWrapper, Executor};
+    use std::collections::BTreeSet;
+    use std::time::Instant;
+
+    use crate::contract::NATIVE_DENOM_MAINNET;
+
+    pub type SandboxApp = App<BankKeeper, MockApi, MeteredStorage>;
+
+    /// In-process chain (cw-multi-test) running one instance of the contract
+    pub struct Sandbox {
+        app: SandboxApp,
+        contract: Addr,
+        meter: Rc<RefCell<StorageMeter>>,
+    }
+
+    impl Sandbox {
+        /// Instantiate the contract in a fresh sandbox, funding every sender
+        /// (and the owner) with native tokens
+        pub fn new(instantiate_msg: &Value, senders: &[&str]) -> anyhow::Result<Self> {
+            let meter = Rc::new(RefCell::new(StorageMeter::default()));
+            let storage = MeteredStorage {
+                inner: MockStorage::new(),
+                meter: meter.clone(),
+            };
+            let api = MockApi::default().with_prefix(SANDBOX_PREFIX);
+            let mut accounts: BTreeSet<Addr> = BTreeSet::new();
+            accounts.insert(sandbox_addr(&api, SANDBOX_OWNER));
+            for sender in senders {
+                accounts.insert(sandbox_addr(&api, sender));
+            }
+            let mut app = AppBuilder::new().with_api(api).with_storage(storage).build(
+                |router, _api, storage| {
+                    for account in &accounts {
+                        router
+                            .bank
+                            .init_balance(
+                                storage,
+                                account,
+                                vec![Coin {
+                                    denom: NATIVE_DENOM_MAINNET.to_string(),
+                                    amount: Uint128::new(SANDBOX_BALANCE),
+                                }],
+                            )
+                            .expect("sandbox accounts can be funded");
+                    }
+                },
+            );
+            let code_id =
+                app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+            let msg: InstantiateMsg = serde_json::from_value(instantiate_msg.clone())
+                .map_err(|e| anyhow!("invalid InstantiateMsg: {}", e))?;
+            let owner = sandbox_addr(app.api(), SANDBOX_OWNER);
+            let contract = app
+                .instantiate_contract(
+                    code_id,
+                    owner.clone(),
+                    &msg,
+                    &[],
+                    "sandbox",
+                    Some(owner.to_string()),
+                )
+                .map_err(|e| anyhow!("sandbox instantiation failed: {}", e))?;
+            Ok(Self {
+                app,
+                contract,
+                meter,
+            })
+        }
+
+        pub fn contract(&self) -> &Addr {
+            &self.contract
+        }
+
+        pub fn addr(&self, sender: &str) -> Addr {
+            sandbox_addr(self.app.api(), sender)
+        }
+
+        /// Replace the contract's storage with a snapshot of raw key/value
+        /// pairs (e.g. the contract's state on chain)
+        pub fn restore(&mut self, snapshot: &[(Vec<u8>, Vec<u8>)]) {
+            let mut storage = self.app.contract_storage_mut(&self.contract);
+            let keys: Vec<Vec<u8>> = storage
+                .range(None, None, Order::Ascending)
+                .map(|(key, _)| key)
+                .collect();
+            for key in keys {
+                storage.remove(&key);
+            }
+            for (key, value) in snapshot {
+                storage.set(key, value);
+            }
+        }
+
+        /// Set the native (or any bank) balance of an account
+        pub fn fund(&mut self, account: &Addr, coins: Vec<Coin>) -> anyhow::Result<()> {
+            self.app
+                .init_modules(|router, _api, storage| {
+                    router.bank.init_balance(storage, account, coins)
+                })
+                .map_err(|e| anyhow!("funding {} failed: {}", account, e))
+        }
+
+        /// Move the sandbox chain to a block height
+        pub fn set_height(&mut self, height: u64) {
+            self.app.update_block(|block| block.height = height);
+        }
+
+        /// Run an execute msg against the sandbox contract, metering its
+        /// storage access
+        pub fn execute(&mut self, case: &SandboxCase) -> ExecutionProfile {
+            let name = case.name();
+            let sender = self.addr(case.sender());
+            let msg: ExecuteMsg = match serde_json::from_value(case.execute_msg.clone()) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    return ExecutionProfile {
+                        name,
+                        success: false,
+                        error: Some(format!("invalid ExecuteMsg: {}", e)),
+                        storage: StorageMeter::default(),
+                        wall_time_micros: 0,
+                        relative_gas: 0,
+                    };
+                }
+            };
+            *self.meter.borrow_mut() = StorageMeter::default();
+            let started = Instant::now();
+            let result =
+                self.app
+                    .execute_contract(sender, self.contract.clone(), &msg, &case.funds);
+            let wall_time_micros = started.elapsed().as_micros();
+            ExecutionProfile {
+                name,
+                success: result.is_ok(),
+                error: result.err().map(|e| format!("{:#}", e)),
+                storage: self.meter.borrow().clone(),
+                wall_time_micros,
+                relative_gas: 0,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "sandbox"))]
+mod chain {
+    use super::*;
+
+    static SANDBOX_DISABLED: &str =
+        "the sandbox is disabled, build the server with the 'sandbox' feature";
+
+    /// Stands in for the sandbox, which can't be instantiated without the
+    /// 'sandbox' feature (the type has no values)
+    pub enum Sandbox {}
+
+    impl Sandbox {
+        pub fn new(_instantiate_msg: &Value, _senders: &[&str]) -> anyhow::Result<Self> {
+            Err(anyhow!(SANDBOX_DISABLED))
+        }
+
+        pub fn contract(&self) -> &Addr {
+            match *self {}
+        }
+
+        pub fn addr(&self, _sender: &str) -> Addr {
+            match *self {}
+        }
+
+        pub fn restore(&mut self, _snapshot: &[(Vec<u8>, Vec<u8>)]) {
+            match *self {}
+        }
+
+        pub fn fund(&mut self, _account: &Addr, _coins: Vec<Coin>) -> anyhow::Result<()> {
+            Err(anyhow!(SANDBOX_DISABLED))
+        }
+
+        pub fn set_height(&mut self, _height: u64) {
+            match *self {}
+        }
+
+        pub fn execute(&mut self, _case: &SandboxCase) -> ExecutionProfile {
+            match *self {}
+        }
+    }
+}
+
+pub use chain::*;
+
+/// Sandbox address of a sender; bech32 addresses are used as is, anything
+/// else (e.g. 'alice') is turned into a deterministic sandbox address
+pub fn sandbox_addr(api: &MockApi, sender: &str) -> Addr {
+    if is_address(sender) {
+        Addr::unchecked(sender)
+    } else {
+        api.addr_make(sender)
+    }
+}
+
+/// Sandbox address of a sender, as msgs refer to it (the same in every
+/// sandbox)
+pub fn sandbox_address(sender: &str) -> String {
+    sandbox_addr(&MockApi::default().with_prefix(SANDBOX_PREFIX), sender).to_string()
+}
+
+/// Run every case in a fresh sandbox and rank them by storage gas
+pub fn profile_gas(
+    instantiate_msg: &Value,
+    cases: &[SandboxCase],
+) -> anyhow::Result<Vec<ExecutionProfile>> {
+    let senders: Vec<&str> = cases.iter().map(SandboxCase::sender).collect();
+    let mut sandbox = Sandbox::new(instantiate_msg, &senders)?;
+    let mut profiles: Vec<ExecutionProfile> =
+        cases.iter().map(|case| sandbox.execute(case)).collect();
+    let max_gas = profiles
+        .iter()
+        .map(|profile| profile.storage.storage_gas)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    for profile in profiles.iter_mut() {
+        profile.relative_gas = profile.storage.storage_gas * 100 / max_gas;
+    }
+    profiles.sort_by(|a, b| b.storage.storage_gas.cmp(&a.storage.storage_gas));
+    Ok(profiles)
+}
+
+enum SandboxCommand {
+    Execute(SandboxCase, oneshot::Sender<ExecutionProfile>),
+}
+
+/// Handle to a sandbox running on its own thread. The sandbox (like any
+/// cw-multi-test `App`) can't be shared across threads, so concurrent
+/// callers send it commands which are executed one at a time
+#[derive(Clone)]
+pub struct SandboxHandle {
+    commands: mpsc::UnboundedSender<SandboxCommand>,
+}
+
+impl SandboxHandle {
+    /// Start a sandbox thread; returns once the contract is instantiated
+    pub async fn spawn(instantiate_msg: Value, senders: Vec<String>) -> anyhow::Result<Self> {
+        let (commands, mut receiver) = mpsc::unbounded_channel::<SandboxCommand>();
+        let (ready, started) = oneshot::channel::<anyhow::Result<()>>();
+        std::thread::spawn(move || {
+            let senders: Vec<&str> = senders.iter().map(String::as_str).collect();
+            let mut sandbox = match Sandbox::new(&instantiate_msg, &senders) {
+                Ok(sandbox) => {
+                    let _ = ready.send(Ok(()));
+                    sandbox
+                }
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            while let Some(command) = receiver.blocking_recv() {
+                match command {
+                    SandboxCommand::Execute(case, reply) => {
+                        let _ = reply.send(sandbox.execute(&case));
+                    }
+                }
+            }
+        });
+        started
+            .await
+            .map_err(|_| anyhow!("sandbox thread exited before instantiation"))??;
+        Ok(Self { commands })
+    }
+
+    pub async fn execute(&self, case: SandboxCase) -> anyhow::Result<ExecutionProfile> {
+        let (reply, profile) = oneshot::channel();
+        self.commands
+            .send(SandboxCommand::Execute(case, reply))
+            .map_err(|_| anyhow!("sandbox thread has exited"))?;
+        profile
+            .await
+            .map_err(|_| anyhow!("sandbox thread has exited"))
+    }
+}
@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+/// Rendered schemas, shared by every session of the server process (the
+/// sse and streamable http transports create a server per session)
+static RENDERED: LazyLock<RwLock<HashMap<String, String>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SchemaCacheStats {
+    /// Cached outputs, keyed by entry point type and format
+    pub entries: Vec<String>,
+    pub hits: u64,
+    pub misses: u64,
+    pub bytes: usize,
+}
+
+/// Rendered output of a schema tool (e.g. `query:json`), rendering it on
+/// first use
+pub fn rendered(key: &str, render: impl FnOnce() -> String) -> String {
+    let cached = RENDERED
+        .read()
+        .ok()
+        .and_then(|rendered| rendered.get(key).cloned());
+    if let Some(cached) = cached {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return cached;
+    }
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    let output = render();
+    if let Ok(mut rendered) = RENDERED.write() {
+        rendered.insert(key.to_string(), output.clone());
+    }
+    output
+}
+
+pub fn stats() -> SchemaCacheStats {
+    let (mut entries, bytes) = RENDERED
+        .read()
+        .map(|rendered| {
+            (
+                rendered.keys().cloned().collect::<Vec<String>>(),
+                rendered.values().map(String::len).sum(),
+            )
+        })
+        .unwrap_or_default();
+    entries.sort();
+    SchemaCacheStats {
+        entries,
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        bytes,
+    }
+}
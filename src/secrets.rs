@@ -0,0 +1,302 @@
+use anyhow::anyhow;
+use serde_json::Value;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use zeroize::Zeroize;
+
+use crate::proxy::{HttpClient, HttpRequest, http_client};
+
+pub type SecretFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<Secret>> + Send + 'a>>;
+
+/// A secret value (mnemonic, API token, KMS credential, ..). Its `Debug`
+/// and `Display` output never include the value
+#[derive(Clone, PartialEq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Constant time comparison of a given token with the expected one, so
+/// the token can't be guessed from timings
+pub fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Source of secrets, selected by the scheme of a secret reference (e.g.
+/// `env:` in `env:LCD_AUTH_TOKEN`)
+pub trait SecretsProvider: Send + Sync {
+    fn scheme(&self) -> &'static str;
+
+    /// Fetch a secret; `key` is the reference without its scheme
+    fn fetch<'a>(&'a self, key: &'a str) -> SecretFuture<'a>;
+}
+
+/// Split `path#field` references into the path and an optional field
+fn split_field(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('#') {
+        Some((path, field)) => (path, Some(field)),
+        None => (key, None),
+    }
+}
+
+/// Pick a field from a JSON document of secrets
+fn json_field(document: &Value, field: &str, source: &str) -> anyhow::Result<Secret> {
+    document
+        .get(field)
+        .and_then(Value::as_str)
+        .map(Secret::new)
+        .ok_or_else(|| anyhow!("secret field '{}' not found in {}", field, source))
+}
+
+/// `env:NAME`, reads an environment variable
+#[derive(Clone, Debug, Default)]
+pub struct EnvSecrets;
+
+impl SecretsProvider for EnvSecrets {
+    fn scheme(&self) -> &'static str {
+        "env"
+    }
+
+    fn fetch<'a>(&'a self, key: &'a str) -> SecretFuture<'a> {
+        Box::pin(async move {
+            std::env::var(key)
+                .map(Secret::new)
+                .map_err(|_| anyhow!("environment variable {} isn't set", key))
+        })
+    }
+}
+
+/// `file:/path/to/secret` (the trimmed file contents) or
+/// `file:/path/to/secrets.json#field`. Files readable by the group or
+/// others are rejected
+#[derive(Clone, Debug, Default)]
+pub struct FileSecrets;
+
+impl FileSecrets {
+    #[cfg(unix)]
+    fn check_permissions(metadata: &std::fs::Metadata, path: &str) -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = metadata.permissions().mode();
+        if mode & 0o077 != 0 {
+            anyhow::bail!(
+                "secret file {} has permissions {:o}, it must only be accessible by its owner (e.g. chmod 600)",
+                path,
+                mode & 0o777
+            );
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn check_permissions(_metadata: &std::fs::Metadata, _path: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl SecretsProvider for FileSecrets {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+
+    fn fetch<'a>(&'a self, key: &'a str) -> SecretFuture<'a> {
+        Box::pin(async move {
+            let (path, field) = split_field(key);
+            let metadata = tokio::fs::metadata(path)
+                .await
+                .map_err(|e| anyhow!("secret file {} can't be read: {}", path, e))?;
+            Self::check_permissions(&metadata, path)?;
+            let contents = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| anyhow!("secret file {} can't be read: {}", path, e))?;
+            match field {
+                Some(field) => json_field(&serde_json::from_str(&contents)?, field, path),
+                None => Ok(Secret::new(contents.trim())),
+            }
+        })
+    }
+}
+
+/// `vault:secret/data/mcp#field`, reads a (KV v2) secret from HashiCorp
+/// Vault at `VAULT_ADDR`, authenticating with `VAULT_TOKEN`
+#[derive(Clone, Debug)]
+pub struct VaultSecrets {
+    http: HttpClient,
+}
+
+impl Default for VaultSecrets {
+    fn default() -> Self {
+        Self {
+            http: http_client(None),
+        }
+    }
+}
+
+impl SecretsProvider for VaultSecrets {
+    fn scheme(&self) -> &'static str {
+        "vault"
+    }
+
+    fn fetch<'a>(&'a self, key: &'a str) -> SecretFuture<'a> {
+        Box::pin(async move {
+            let (path, field) = split_field(key);
+            let field = field.ok_or_else(|| anyhow!("vault secrets require a '#field'"))?;
+            let addr = std::env::var("VAULT_ADDR")
+                .map_err(|_| anyhow!("environment variable VAULT_ADDR isn't set"))?;
+            let token = std::env::var("VAULT_TOKEN")
+                .map_err(|_| anyhow!("environment variable VAULT_TOKEN isn't set"))?;
+            let url = format!("{}/v1/{}", addr.trim_end_matches('/'), path);
+            let response = self
+                .http
+                .send(HttpRequest::get(&url).header("X-Vault-Token", token))
+                .await?;
+            if !response.is_success() {
+                anyhow::bail!("vault returned {} for {}", response.status, path);
+            }
+            let body: Value = response.json()?;
+            // KV v2 nests the secret in `data.data`, KV v1 in `data`
+            let data = match &body["data"]["data"] {
+                Value::Object(_) => &body["data"]["data"],
+                _ => &body["data"],
+            };
+            json_field(data, field, path)
+        })
+    }
+}
+
+/// `sops:/path/to/secrets.enc.json#field`, decrypts a SOPS encrypted file
+/// with the `sops` CLI
+#[derive(Clone, Debug, Default)]
+pub struct SopsSecrets;
+
+impl SecretsProvider for SopsSecrets {
+    fn scheme(&self) -> &'static str {
+        "sops"
+    }
+
+    fn fetch<'a>(&'a self, key: &'a str) -> SecretFuture<'a> {
+        Box::pin(async move {
+            let (path, field) = split_field(key);
+            let field = field.ok_or_else(|| anyhow!("sops secrets require a '#field'"))?;
+            let output = tokio::process::Command::new("sops")
+                .args(["--decrypt", "--output-type", "json", path])
+                .output()
+                .await
+                .map_err(|e| anyhow!("sops couldn't be run: {}", e))?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "sops couldn't decrypt {}: {}",
+                    path,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            json_field(&serde_json::from_slice(&output.stdout)?, field, path)
+        })
+    }
+}
+
+/// Registry of secrets providers, resolving secret references like
+/// `env:NAME`, `file:/path`, `vault:path#field` or `sops:/path#field`
+#[derive(Clone)]
+pub struct Secrets {
+    providers: Vec<Arc<dyn SecretsProvider>>,
+}
+
+impl fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schemes: Vec<&str> = self.providers.iter().map(|p| p.scheme()).collect();
+        f.debug_struct("Secrets")
+            .field("schemes", &schemes)
+            .finish()
+    }
+}
+
+impl Default for Secrets {
+    fn default() -> Self {
+        Self {
+            providers: vec![
+                Arc::new(EnvSecrets),
+                Arc::new(FileSecrets),
+                Arc::new(VaultSecrets::default()),
+                Arc::new(SopsSecrets),
+            ],
+        }
+    }
+}
+
+impl Secrets {
+    /// Add (or replace) the provider of a scheme, e.g. for a KMS
+    pub fn with_provider(mut self, provider: Arc<dyn SecretsProvider>) -> Self {
+        self.providers.retain(|p| p.scheme() != provider.scheme());
+        self.providers.push(provider);
+        self
+    }
+
+    pub async fn resolve(&self, reference: &str) -> anyhow::Result<Secret> {
+        let (scheme, key) = reference
+            .split_once(':')
+            .ok_or_else(|| anyhow!("secret references must look like 'scheme:key'"))?;
+        let provider = self
+            .providers
+            .iter()
+            .find(|provider| provider.scheme() == scheme)
+            .ok_or_else(|| anyhow!("no secrets provider for '{}:' references", scheme))?;
+        provider.fetch(key).await
+    }
+}
+
+/// A secret reference resolved on first use, and cached afterwards
+#[derive(Clone, Debug)]
+pub struct LazySecret {
+    reference: String,
+    secrets: Secrets,
+    resolved: Arc<OnceCell<Secret>>,
+}
+
+impl LazySecret {
+    pub fn new(reference: &str, secrets: Secrets) -> Self {
+        Self {
+            reference: reference.to_string(),
+            secrets,
+            resolved: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub async fn get(&self) -> anyhow::Result<&Secret> {
+        self.resolved
+            .get_or_try_init(|| self.secrets.resolve(&self.reference))
+            .await
+    }
+}
@@ -0,0 +1,127 @@
+use cosmwasm_std::Uint128;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+/// Replace with the fields of your contract's msgs holding amounts (in
+/// the smallest unit of a denom or token)
+pub static AMOUNT_FIELDS: &[&str] = &["amount"];
+
+/// Replace with the ExecuteMsg variants your contract rejects zero
+/// amounts of (cw20 moves, burns and mints)
+pub static NON_ZERO_VARIANTS: &[&str] = &[
+    "transfer",
+    "send",
+    "transfer_from",
+    "send_from",
+    "burn",
+    "burn_from",
+    "mint",
+];
+
+/// Fields naming the recipient of an entry of a batch (a list of
+/// transfers, balances or grants)
+pub static RECIPIENT_FIELDS: &[&str] = &["recipient", "address", "contract"];
+
+/// An amount, in the smallest unit of its denom: negative amounts and
+/// amounts exceeding u128 are refused with their own error
+pub fn parse_amount(path: &str, amount: &str) -> anyhow::Result<Uint128> {
+    let amount = amount.trim();
+    if amount.starts_with('-') {
+        anyhow::bail!(
+            "negative amount '{}' at '{}', amounts must be positive",
+            amount,
+            path
+        );
+    }
+    if !amount.is_empty() && amount.chars().all(|c| c.is_ascii_digit()) {
+        return Uint128::from_str(amount).map_err(|_| {
+            anyhow::anyhow!(
+                "amount '{}' at '{}' exceeds the maximum amount ({})",
+                amount,
+                path,
+                Uint128::MAX
+            )
+        });
+    }
+    anyhow::bail!(
+        "invalid amount '{}' at '{}', expected an integer amount in the smallest unit of the denom",
+        amount,
+        path
+    )
+}
+
+fn check_value(value: &Value, path: &str, non_zero: bool, errors: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, field) in object {
+                let field_path = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", path, key),
+                };
+                if AMOUNT_FIELDS.contains(&key.as_str()) {
+                    let amount = match field {
+                        // Other strings (e.g. decimals) are left to the schema
+                        Value::String(amount)
+                            if amount.trim().starts_with('-')
+                                || amount.trim().chars().all(|c| c.is_ascii_digit()) =>
+                        {
+                            Some(parse_amount(&field_path, amount))
+                        }
+                        Value::Number(number) if number.as_f64().unwrap_or_default() < 0.0 => {
+                            Some(parse_amount(&field_path, &number.to_string()))
+                        }
+                        _ => None,
+                    };
+                    match amount {
+                        Some(Err(e)) => errors.push(e.to_string()),
+                        Some(Ok(amount)) if amount.is_zero() && non_zero => errors.push(format!(
+                            "invalid zero amount at '{}', the contract rejects it",
+                            field_path
+                        )),
+                        _ => {}
+                    }
+                }
+                check_value(field, &field_path, false, errors);
+            }
+        }
+        Value::Array(items) => {
+            let mut recipients = BTreeSet::new();
+            for (i, item) in items.iter().enumerate() {
+                let recipient = RECIPIENT_FIELDS
+                    .iter()
+                    .find_map(|field| item.get(field)?.as_str());
+                if let Some(recipient) = recipient {
+                    if !recipients.insert(recipient) {
+                        errors.push(format!(
+                            "duplicate recipient '{}' at '{}[{}]', merge its entries into one",
+                            recipient, path, i
+                        ));
+                    }
+                }
+                check_value(item, &format!("{}[{}]", path, i), false, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Semantic checks of a msg beyond its schema: negative amounts, amounts
+/// exceeding u128, zero amounts of variants rejecting them, and duplicate
+/// recipients of batches
+pub fn check_msg(msg: &Value) -> anyhow::Result<()> {
+    let mut errors = vec![];
+    match msg.as_object().filter(|object| object.len() == 1) {
+        Some(object) => {
+            for (variant, body) in object {
+                let non_zero = NON_ZERO_VARIANTS.contains(&variant.as_str());
+                check_value(body, variant, non_zero, &mut errors);
+            }
+        }
+        None => check_value(msg, "", false, &mut errors),
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(anyhow::anyhow!(errors.join("; "))),
+    }
+}
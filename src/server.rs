@@ -1,64 +1,1229 @@
-/// Replace the below import with the contract you want the MCP server
-/// to support
+/// Types of the default contract (the first of `ADAPTERS` in
+/// `src/adapter.rs`), used by the tools that aren't dispatched by adapter
 use cw20_wrap::msg::{ExecuteMsg, QueryMsg};
 
-use cosmwasm_std::{Coin, CosmosMsg, QueryRequest, Uint128, WasmMsg, WasmQuery, to_json_binary};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{
+    Binary, Coin, CosmosMsg, QueryRequest, Uint128, WasmMsg, WasmQuery, to_json_binary,
+};
 use rmcp::{
-    Error, ServerHandler, model::CallToolResult, model::Content, model::Implementation,
-    model::ProtocolVersion, model::ServerCapabilities, model::ServerInfo, tool,
+    Error, Peer, RoleServer, ServerHandler, handler::server::tool::ToolCallContext,
+    model::AnnotateAble, model::CallToolRequestParam, model::CallToolResult, model::Content,
+    model::GetPromptRequestParam, model::GetPromptResult, model::Implementation, model::JsonObject,
+    model::ListPromptsResult, model::ListResourceTemplatesResult, model::ListResourcesResult,
+    model::ListToolsResult, model::PaginatedRequestParam, model::PromptMessage,
+    model::PromptMessageRole, model::ProtocolVersion, model::RawContent, model::RawResource,
+    model::RawResourceTemplate, model::ReadResourceRequestParam, model::ReadResourceResult,
+    model::Resource, model::ResourceContents, model::ServerCapabilities, model::ServerInfo,
+    service::RequestContext, tool,
 };
 use schemars::{JsonSchema, schema_for};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use crate::activity::*;
+use crate::adapter::*;
+use crate::address::*;
+use crate::alert::*;
+use crate::archid::*;
+use crate::authz::*;
+use crate::blob::*;
+use crate::breaker::*;
+use crate::broadcast::*;
+use crate::builder::CwMcpBuilder;
+use crate::cache::*;
+use crate::chunk::*;
+use crate::clock::{now_rfc3339, parse_rfc3339};
+use crate::compare::*;
+use crate::config::*;
 use crate::contract::*;
+use crate::cosmos::*;
+use crate::denom::*;
+use crate::display::*;
+use crate::encoding::*;
+use crate::endpoint::*;
+use crate::error::*;
+use crate::events::*;
+use crate::example::*;
 use crate::execute::*;
+use crate::fees::*;
+use crate::fixture::*;
+use crate::funds::*;
+use crate::gov::*;
+use crate::graph::*;
+use crate::group::*;
+use crate::history::*;
 use crate::instruction::*;
+use crate::keystore::*;
+use crate::label::*;
+use crate::loadtest::*;
+use crate::mempool::*;
+use crate::mint::*;
+use crate::naming::*;
+use crate::pause::*;
+use crate::permit::*;
+use crate::preflight::*;
+use crate::price::*;
+use crate::probe::*;
+use crate::prompt;
+use crate::provenance::*;
+use crate::proxy::{PROXY, PROXY_MAINNET, PROXY_TESTNET};
 use crate::query::*;
+use crate::queue::*;
+use crate::recipe::*;
+use crate::redact::*;
+use crate::registry::*;
+use crate::repair::*;
+use crate::replay::*;
+use crate::resolver::*;
+use crate::resource::*;
+use crate::rpc::*;
+use crate::sandbox::*;
+use crate::schema;
+use crate::secrets::*;
+use crate::semantic::*;
+use crate::session::*;
+use crate::spend::*;
+use crate::stargate::*;
+use crate::storage::*;
+use crate::supply::*;
+use crate::tags::*;
+use crate::tx::{MEMO_WATERMARK, built_memo, check_memo, watermark};
+use crate::usage::*;
+use crate::validate::*;
+use crate::watch::*;
+use crate::workflow::*;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub enum ServerTransport {
-    Stdio,
-    Sse,
-    StreamableHttp,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Clone, Debug)]
 pub struct CwMcp {
-    contracts: [CwContract; 2],
+    contracts: ContractRegistry,
+    lcd: LcdClient,
+    labels: Arc<RwLock<LabelStore>>,
+    resolver: Resolver,
+    archid: ArchId,
+    prober: Prober,
+    replayer: Replayer,
+    events: EventScanner,
+    redactor: Redactor,
+    secrets: Secrets,
+    keystore: Keystore,
+    signer: SignerSession,
+    authz: Authz,
+    groups: Groups,
+    gov: Gov,
+    graph: GraphExplorer,
+    minting: MintChecker,
+    supply: SupplyAuditor,
+    history: SupplyHistory,
+    preflight: Preflight,
+    amounts: AmountFormatter,
+    defaults: Arc<RwLock<SessionDefaults>>,
+    watcher: Watcher,
+    peer: Arc<RwLock<Option<Peer<RoleServer>>>>,
+    alerts: Alerts,
+    breaker: CircuitBreaker,
+    activity: ActivityExporter,
+    tags: TagStore,
+    workflows: Workflows,
+    queue: ExecutionQueue,
+    spend: SpendLedger,
+    chunks: ResultChunks,
+    blobs: BlobStore,
+    provenance: ProvenanceSigner,
+    broadcaster: Broadcaster,
+    recipes: Recipes,
+    denoms: DenomVerifier,
+    fees: FeeHistory,
+    mempool: MempoolMonitor,
+    pause: PauseDetector,
 }
 
 #[tool(tool_box)]
 impl CwMcp {
+    /// A server instance with the binary's defaults (see `CwMcpBuilder`)
     pub fn new() -> Self {
-        Self {
-            contracts: [
-                CwContract {
-                    network: Network::Mainnet,
-                    chain_id: "archway-1".to_string(),
-                    contract_address: CONTRACT_MAINNET.to_string(),
-                },
-                CwContract {
-                    network: Network::Testnet,
-                    chain_id: "constantine-3".to_string(),
-                    contract_address: CONTRACT_TESTNET.to_string(),
-                },
-            ],
+        CwMcpBuilder::new().build()
+    }
+
+    pub(crate) fn from_parts(
+        contracts: ContractRegistry,
+        lcd: LcdClient,
+        recipes: Recipes,
+        network: Option<Network>,
+    ) -> Self {
+        let mut labels = LabelStore::new(&contracts.all());
+        for registered in contracts.registered() {
+            if let Some(label) = registered.label {
+                labels.insert(registered.contract_address, label);
+            }
+        }
+        let peer = Arc::new(RwLock::new(None));
+        let breaker = CircuitBreaker::default();
+        // Clients share their endpoint scores
+        let mcp = Self {
+            contracts: contracts.clone(),
+            labels: Arc::new(RwLock::new(labels)),
+            lcd: lcd.clone(),
+            resolver: Resolver::new(lcd.clone(), contracts.clone()),
+            archid: ArchId::new(lcd.clone(), contracts.clone()),
+            prober: Prober::new(lcd.clone()),
+            replayer: Replayer::new(lcd.clone()),
+            events: EventScanner::new(lcd.clone()),
+            redactor: Redactor::default(),
+            secrets: Secrets::default(),
+            keystore: Keystore::default(),
+            signer: SignerSession::default(),
+            authz: Authz::new(lcd.clone()),
+            groups: Groups::new(lcd.clone()),
+            gov: Gov::new(lcd.clone()),
+            graph: GraphExplorer::new(lcd.clone()),
+            minting: MintChecker::new(lcd.clone()),
+            supply: SupplyAuditor::new(lcd.clone()),
+            history: SupplyHistory::new(lcd.clone()),
+            preflight: Preflight::new(lcd.clone()),
+            amounts: AmountFormatter::default(),
+            defaults: Arc::new(RwLock::new(SessionDefaults {
+                contract: None,
+                network,
+            })),
+            watcher: Watcher::new(lcd.clone()),
+            alerts: Alerts::new(lcd.clone(), contracts, peer.clone(), breaker.clone()),
+            peer,
+            breaker,
+            activity: ActivityExporter::new(lcd.clone(), PriceProvider::default()),
+            tags: TagStore::default(),
+            workflows: Workflows::default(),
+            queue: ExecutionQueue::default(),
+            spend: SpendLedger::default(),
+            chunks: ResultChunks::default(),
+            blobs: BlobStore::default(),
+            provenance: ProvenanceSigner::default(),
+            broadcaster: Broadcaster::new(lcd.clone()),
+            recipes,
+            denoms: DenomVerifier::new(lcd.clone()),
+            fees: FeeHistory::new(lcd.clone()),
+            mempool: MempoolMonitor::new(lcd.clone()),
+            pause: PauseDetector::new(lcd.clone()),
+        };
+        #[cfg(feature = "graphql")]
+        crate::graphql::track_session(mcp.lcd.cache(), &mcp.alerts, &mcp.watcher);
+        mcp
+    }
+
+    /// Network of a registered deployment, defaulting to the session's
+    /// default network (otherwise mainnet) for addresses that aren't
+    /// registered
+    fn network_for(&self, contract_addr: &str) -> Network {
+        self.contracts
+            .find(contract_addr)
+            .map(|contract| contract.network.clone())
+            .unwrap_or_else(|| self.session_defaults().network.unwrap_or(Network::Mainnet))
+    }
+
+    fn session_defaults(&self) -> SessionDefaults {
+        self.defaults
+            .read()
+            .map(|defaults| defaults.clone())
+            .unwrap_or_default()
+    }
+
+    /// Deployment of the contract on a network
+    fn deployment(&self, network: &Network) -> String {
+        self.contracts
+            .on(network)
+            .map(|contract| contract.contract_address.clone())
+            .unwrap_or_else(|| CONTRACT_MAINNET.to_string())
+    }
+
+    /// LCD endpoint of the deployment on a network, defaulting to the
+    /// network's endpoint
+    fn lcd_endpoint(&self, network: &Network) -> String {
+        self.contracts.lcd_endpoint(network)
+    }
+
+    /// RPC endpoint of the deployment on a network, defaulting to the
+    /// network's endpoint
+    fn rpc_endpoint(&self, network: &Network) -> String {
+        self.contracts
+            .on(network)
+            .and_then(|contract| contract.rpc_endpoints.first().cloned())
+            .unwrap_or_else(|| network.rpc_endpoint().to_string())
+    }
+
+    /// Refuse funds of denoms that don't exist on the network, returning
+    /// warnings of the denoms that couldn't be verified
+    async fn verify_funds(
+        &self,
+        network: &Network,
+        funds: &[Coin],
+    ) -> Result<Vec<String>, CallToolResult> {
+        if funds.is_empty() {
+            return Ok(vec![]);
+        }
+        self.denoms
+            .verify(&self.lcd_endpoint(network), network, funds)
+            .await
+            .map_err(|e| tool_error("Error verifying funds", e))
+    }
+
+    /// Provenance metadata of a payload built for a contract
+    fn build_metadata(&self, contract_addr: &str, payload: &str) -> BuildMetadata {
+        let contract_label = self.labels.read().ok().and_then(|labels| {
+            labels
+                .get(contract_addr)
+                .map(|contract_label| contract_label.label.clone())
+        });
+        self.network_metadata(self.network_for(contract_addr), contract_label, payload)
+    }
+
+    /// Provenance metadata of a payload built for a network (e.g. a new
+    /// instance of a code id)
+    fn network_metadata(
+        &self,
+        network: Network,
+        contract_label: Option<String>,
+        payload: &str,
+    ) -> BuildMetadata {
+        let chain_id = self
+            .contracts
+            .on(&network)
+            .map(|contract| contract.chain_id.clone());
+        BuildMetadata {
+            network,
+            chain_id,
+            contract_label,
+            schema_version: SCHEMA_VERSION.to_string(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            built_at: now_rfc3339(),
+            content_hash: content_hash(payload),
+            signature: None,
+            public_key: None,
+        }
+    }
+
+    /// Deserialize a JSON stringified msg, normalizing variant and field
+    /// keys given in another case (e.g. camelCase) to the snake_case names
+    /// of the schema of its type (rendered as `schema_key`); the returned
+    /// warnings note the normalized keys. Msgs failing the semantic checks
+    /// (e.g. negative amounts) are refused, and invalid msgs are returned
+    /// with their field level errors and suggested repairs
+    pub fn parse_msg<T: DeserializeOwned + JsonSchema>(
+        message: &str,
+        msg: &str,
+        schema_key: &str,
+    ) -> Result<(T, Vec<String>), CallToolResult> {
+        let json = serde_json::from_str::<Value>(msg).ok();
+        if let Some(json) = &json {
+            check_msg(json).map_err(|e| tool_error(message, e))?;
+        }
+        let e = match serde_json::from_str(msg) {
+            Ok(deserialized) => return Ok((deserialized, vec![])),
+            Err(e) => e,
+        };
+        let rendered = schema::rendered(schema_key, || {
+            let schema = schema_for!(T);
+            serde_json::to_string(&schema).unwrap_or("".to_string())
+        });
+        let schema: Value = serde_json::from_str(&rendered).unwrap_or_default();
+        if let Some(mut normalized) = json.clone() {
+            let notes = normalize_keys(&mut normalized, &schema);
+            if !notes.is_empty() {
+                // Checked again, variants given in another case skipped the
+                // checks of their snake_case names
+                check_msg(&normalized).map_err(|e| tool_error(message, e))?;
+                if let Ok(deserialized) = serde_json::from_value(normalized) {
+                    let warnings = notes
+                        .into_iter()
+                        .map(|note| format!("{}, use the schema's snake_case names", note))
+                        .collect();
+                    return Ok((deserialized, warnings));
+                }
+            }
+        }
+        // The root path (`.`) doesn't point at a field
+        let deserializer = &mut serde_json::Deserializer::from_str(msg);
+        let field_path = serde_path_to_error::deserialize::<_, T>(deserializer)
+            .err()
+            .map(|e| e.path().to_string())
+            .filter(|path| path != ".");
+        let field_errors = json
+            .as_ref()
+            .map(|json| validate(json, &schema))
+            .unwrap_or_default();
+        Err(schema_error(
+            message,
+            e,
+            field_path,
+            field_errors,
+            repair::<T>(msg, &schema),
+        ))
+    }
+
+    /// Wrap a query msg as a QueryRequest and serialize it as a ValidatedQuery
+    fn wrap_query(
+        &self,
+        contract_addr: String,
+        query_msg: String,
+        msg: &Value,
+        name_resolutions: Vec<NameResolution>,
+        warnings: Vec<String>,
+    ) -> CallToolResult {
+        let query_req: QueryRequest<QueryMsg> = QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract_addr.clone(),
+            msg: to_json_binary(msg).unwrap_or_default(),
+        });
+        let serialized_query_req = serde_json::to_string(&query_req);
+        if serialized_query_req.is_err() {
+            return CallToolResult::error(vec![Content::text(
+                "Error wrapping QueryMsg as QueryRequest",
+            )]);
+        }
+        let query_request = serialized_query_req.unwrap_or_default();
+        let valid_query = ValidatedQuery {
+            address_labels: self.annotate_msg(msg, &query_request),
+            metadata: self.build_metadata(&contract_addr, &query_request),
+            name_resolutions,
+            warnings,
+            query_msg,
+            query_request,
+        };
+        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        CallToolResult::success(vec![Content::text(serialized)])
+    }
+
+    /// Wrap an execute msg as a CosmosMsg, refusing msgs that would exceed
+    /// the daily spend caps
+    fn validated_execute(
+        &self,
+        contract_addr: String,
+        execute_msg: String,
+        msg: &Value,
+        funds: Vec<Coin>,
+        name_resolutions: Vec<NameResolution>,
+    ) -> Result<ValidatedExecute, CallToolResult> {
+        let funds = normalized_funds(funds).map_err(|e| tool_error("Error building funds", e))?;
+        if let Err(e) = self.spend.check(&spend_of(&contract_addr, msg, &funds)) {
+            return Err(tool_error("Error building transaction", e));
+        }
+        let cosmos_msg = execute_cosmos_msg(&contract_addr, msg, funds).map_err(|_| {
+            CallToolResult::error(vec![Content::text(
+                "Error wrapping ExecuteMsg as CosmosMsg",
+            )])
+        })?;
+        let mut execute = ValidatedExecute {
+            address_labels: self.annotate_msg(msg, &cosmos_msg),
+            metadata: self.build_metadata(&contract_addr, &cosmos_msg),
+            name_resolutions,
+            execute_msg,
+            cosmos_msg,
+            mint_check: None,
+            pause_check: None,
+            warnings: vec![],
+            memo: built_memo(),
+            timeout_height: None,
+            encoded_msg: None,
+            tx_body: None,
+        };
+        execute.metadata.content_hash = content_hash(&execute.signed_payload());
+        Ok(execute)
+    }
+
+    /// Signer info and fee of an unsigned `TxRaw` of an execute msg: the
+    /// sender's sequence (queried if not given), and the gas limit
+    /// (simulated if not given) at `GAS_PRICE`, returned with the warnings
+    /// of what's missing
+    #[allow(clippy::too_many_arguments)]
+    async fn unsigned_auth(
+        &self,
+        network: &Network,
+        sender: &str,
+        public_key: Option<&str>,
+        sequence: Option<u64>,
+        gas_limit: Option<u64>,
+        contract_addr: &str,
+        msg: &Value,
+        funds: &[Coin],
+    ) -> Result<(TxAuth, Vec<String>), CallToolResult> {
+        let endpoint = self.lcd_endpoint(network);
+        let mut warnings = vec![];
+        let public_key = match public_key {
+            Some(public_key) => match decode_pubkey(public_key) {
+                Ok((public_key, _)) => Some(public_key),
+                Err(e) => return Err(tool_error("Error decoding public key", e)),
+            },
+            None => {
+                warnings.push(
+                    "the TxRaw has no public key, the signer must set it in the signer info"
+                        .to_string(),
+                );
+                None
+            }
+        };
+        // A wrong sequence makes the signed tx fail, so it's never guessed
+        let sequence = match sequence {
+            Some(sequence) => sequence,
+            None => match self.preflight.account(&endpoint, sender).await {
+                Ok((_, sequence)) => sequence,
+                Err(e) => {
+                    return Err(tool_error(
+                        "Error querying the sender's sequence, pass the 'sequence'",
+                        e,
+                    ));
+                }
+            },
+        };
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => {
+                let message = "Error simulating gas, pass the 'gas_limit'";
+                let report = self
+                    .preflight
+                    .execute(&endpoint, sender, contract_addr, msg, funds)
+                    .await
+                    .map_err(|e| tool_error(message, e))?;
+                match (report.suggested_gas_limit, report.error) {
+                    (Some(gas_limit), None) => gas_limit,
+                    (_, Some(error)) => return Err(tool_error(message, error.raw)),
+                    (None, None) => {
+                        return Err(tool_error(
+                            message,
+                            "the simulation didn't report the gas used",
+                        ));
+                    }
+                }
+            }
+        };
+        let fee_denom = self
+            .contracts
+            .on(network)
+            .map(|contract| contract.fee_denom.clone())
+            .unwrap_or_else(|| network.native_denom().to_string());
+        let auth = TxAuth {
+            public_key,
+            sequence,
+            fee: vec![fee(gas_limit, &fee_denom)],
+            gas_limit,
+        };
+        Ok((auth, warnings))
+    }
+
+    /// Wrap an execute msg as a CosmosMsg and serialize it as a ValidatedExecute
+    fn wrap_execute(
+        &self,
+        contract_addr: String,
+        execute_msg: String,
+        msg: &Value,
+        funds: Vec<Coin>,
+        name_resolutions: Vec<NameResolution>,
+    ) -> CallToolResult {
+        match self.validated_execute(contract_addr, execute_msg, msg, funds, name_resolutions) {
+            Ok(valid_execute) => {
+                let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+                CallToolResult::success(vec![Content::text(serialized)])
+            }
+            Err(result) => result,
+        }
+    }
+
+    /// Adapter of the msg types of a contract (see `adapter_of`)
+    fn contract_adapter(&self, contract_addr: &str) -> &'static dyn ContractAdapter {
+        let registry_label = self
+            .contracts
+            .registered()
+            .into_iter()
+            .find(|entry| entry.contract_address == contract_addr)
+            .and_then(|entry| entry.label);
+        adapter_of(contract_addr, registry_label.as_deref())
+    }
+
+    /// Adapter of a `contract_label` if one is given, otherwise of the
+    /// contract
+    fn adapter_for(
+        &self,
+        contract_label: Option<&str>,
+        contract_addr: &str,
+    ) -> Result<&'static dyn ContractAdapter, CallToolResult> {
+        match contract_label {
+            Some(_) => adapter(contract_label)
+                .map_err(|e| tool_error("Error selecting contract adapter", e)),
+            None => Ok(self.contract_adapter(contract_addr)),
+        }
+    }
+
+    /// Contract and network of a tool call; omitted contracts default to
+    /// the session's default contract (if it's on the network), otherwise
+    /// to the deployment on the (default) network. Contracts that don't
+    /// belong to the selected network are refused
+    fn target(
+        &self,
+        contract_addr: Option<String>,
+        network: Option<Network>,
+    ) -> Result<(String, Network), CallToolResult> {
+        let defaults = self.session_defaults();
+        if let Some(contract_addr) = contract_addr {
+            let selected = network.or(defaults.network);
+            let network = self.guard_network(&contract_addr, selected)?;
+            return Ok((contract_addr, network));
+        }
+        let network = network.or(defaults.network).unwrap_or(Network::Mainnet);
+        match defaults.contract {
+            Some(contract_addr) if self.network_for(&contract_addr) == network => {
+                Ok((contract_addr, network))
+            }
+            _ => Ok((self.deployment(&network), network)),
+        }
+    }
+
+    /// Check a contract belongs to the selected network: registered
+    /// deployments must be on it, other addresses must carry its bech32
+    /// prefix (and are refused in strict mode). Returns the network of
+    /// the contract
+    fn check_network(
+        &self,
+        contract_addr: &str,
+        selected: Option<Network>,
+    ) -> anyhow::Result<Network> {
+        let contracts = self.contracts.all();
+        let chain = |network: &Network| {
+            contracts
+                .iter()
+                .find(|contract| &contract.network == network)
+        };
+        let registered = contracts
+            .iter()
+            .find(|contract| contract.contract_address == contract_addr);
+        match (registered, selected) {
+            (Some(deployment), Some(selected)) if deployment.network != selected => {
+                anyhow::bail!(
+                    "wrong network, {} is the {:?} deployment (chain id {}) and can't be used with {:?} (chain id {})",
+                    contract_addr,
+                    deployment.network,
+                    deployment.chain_id,
+                    selected,
+                    chain(&selected)
+                        .map(|contract| contract.chain_id.as_str())
+                        .unwrap_or_default()
+                )
+            }
+            (Some(deployment), _) => Ok(deployment.network.clone()),
+            (None, _) if STRICT_CONTRACTS => anyhow::bail!(
+                "{} is not in the contract registry, and only registered contracts are accepted in strict mode",
+                contract_addr
+            ),
+            (None, selected) => {
+                let network = selected.unwrap_or(Network::Mainnet);
+                // Names (e.g. `name.arch`) are resolved and checked later
+                if name_service(contract_addr).is_some() {
+                    return Ok(network);
+                }
+                let prefix = decode_address(contract_addr)?;
+                let (chain_id, expected) = match chain(&network) {
+                    Some(contract) => (contract.chain_id.clone(), contract.bech32_prefix.clone()),
+                    None => (network.chain_id().to_string(), BECH32_PREFIX.to_string()),
+                };
+                if prefix != expected {
+                    anyhow::bail!(
+                        "wrong network, {} has the bech32 prefix '{}', but {:?} (chain id {}) addresses start with '{}1'",
+                        contract_addr,
+                        prefix,
+                        network,
+                        chain_id,
+                        expected
+                    );
+                }
+                Ok(network)
+            }
+        }
+    }
+
+    fn guard_network(
+        &self,
+        contract_addr: &str,
+        selected: Option<Network>,
+    ) -> Result<Network, CallToolResult> {
+        self.check_network(contract_addr, selected)
+            .map_err(|e| tool_error("Error selecting contract", e))
+    }
+
+    /// Build a cw20_wrap execute msg for a (non zero) amount
+    fn wrap_msg(
+        &self,
+        contract_addr: String,
+        msg: Value,
+        amount: &str,
+        funds: Vec<Coin>,
+    ) -> CallToolResult {
+        if Uint128::from_str(amount)
+            .map(|amount| amount.is_zero())
+            .unwrap_or(true)
+        {
+            return CallToolResult::error(vec![Content::text(format!(
+                "Invalid amount '{}', expected a positive integer in the smallest unit of the denom",
+                amount
+            ))]);
+        }
+        if let Err(result) = Self::parse_msg::<ExecuteMsg>(
+            "Error building ExecuteMsg",
+            &msg.to_string(),
+            "execute:json",
+        ) {
+            return result;
+        }
+        let execute_msg = msg.to_string();
+        self.wrap_execute(contract_addr, execute_msg, &msg, funds, vec![])
+    }
+
+    /// Build the execute msg of a workflow step (or of its compensation)
+    async fn build_workflow_msg(
+        &self,
+        run: &WorkflowRun,
+        step: &str,
+        workflow_msg: &WorkflowMsg,
+    ) -> anyhow::Result<PendingStep> {
+        let contract = workflow_msg
+            .contract
+            .clone()
+            .unwrap_or_else(|| run.contract.clone());
+        let network = self.check_network(&contract, Some(run.network.clone()))?;
+        // Substituted msgs are checked like those of execute__build_msg
+        let (mut msg, mut warnings) = self
+            .contract_adapter(&contract)
+            .parse(
+                SchemaKind::Execute,
+                "Error building ExecuteMsg",
+                &run.substitute(&workflow_msg.msg)?.to_string(),
+            )
+            .map_err(Self::result_error)?;
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        let contract = self
+            .resolve_names(contract, &network, &mut msg, &mut name_resolutions)
+            .await?;
+        // Resolved names must also be addresses of the network
+        self.check_network(&contract, Some(network.clone()))?;
+        warnings.extend(
+            self.verify_funds(&network, &workflow_msg.funds)
+                .await
+                .map_err(Self::result_error)?,
+        );
+        self.spend
+            .check(&spend_of(&contract, &msg, &workflow_msg.funds))?;
+        let mut execute = self
+            .validated_execute(
+                contract,
+                msg.to_string(),
+                &msg,
+                workflow_msg.funds.clone(),
+                name_resolutions,
+            )
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "execute msg of step '{}' can't be wrapped as a CosmosMsg",
+                    step
+                )
+            })?;
+        execute.warnings = warnings;
+        Ok(PendingStep {
+            step: step.to_string(),
+            execute,
+        })
+    }
+
+    /// Record a successful broadcast: its amounts count towards the daily
+    /// spend caps
+    fn record_spend(&self, execute: &ValidatedExecute, tx_hash: Option<String>) {
+        self.breaker.record_broadcast(true);
+        let recorded = spend_of_cosmos_msg(&execute.cosmos_msg)
+            .and_then(|spend| self.spend.record(spend, tx_hash));
+        if let Err(e) = recorded {
+            tracing::warn!("recording spend failed: {}", e);
+        }
+    }
+
+    /// Start a workflow run, and drive it until it needs a broadcast (or
+    /// ends)
+    async fn start_workflow(
+        &self,
+        definition: WorkflowDefinition,
+        contract_addr: Option<String>,
+        network: Option<Network>,
+        vars: BTreeMap<String, Value>,
+    ) -> CallToolResult {
+        let (contract, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return result,
+        };
+        let mut run = WorkflowRun {
+            id: self.workflows.next_id(),
+            definition,
+            contract,
+            network,
+            status: WorkflowStatus::Running,
+            position: 0,
+            log: vec![],
+            pending: None,
+            compensations: vec![],
+            vars,
+        };
+        self.drive_workflow(&mut run).await;
+        self.workflows.save(&run);
+        let serialized: String = serde_json::to_string(&run).unwrap_or_default();
+        CallToolResult::success(vec![Content::text(serialized)])
+    }
+
+    /// Run a recipe called as a tool; its params are saved as the
+    /// workflow's `params` value
+    async fn run_recipe(&self, recipe: &Recipe, arguments: JsonObject) -> CallToolResult {
+        let params = match recipe.params(&arguments) {
+            Ok(params) => params,
+            Err(e) => return tool_error("Error running recipe", e),
+        };
+        let contract_addr = arguments
+            .get("contract_addr")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let network = match arguments.get("network") {
+            Some(network) => match serde_json::from_value::<Network>(network.clone()) {
+                Ok(network) => Some(network),
+                Err(e) => return tool_error("Error running recipe", e),
+            },
+            None => None,
+        };
+        let vars = BTreeMap::from([("params".to_string(), params)]);
+        self.start_workflow(recipe.definition(), contract_addr, network, vars)
+            .await
+    }
+
+    /// Run a read-only recipe for an inbound hook (see `src/hook.rs`),
+    /// returning its (redacted) workflow run
+    pub async fn run_hook(&self, name: &str, arguments: JsonObject) -> anyhow::Result<Value> {
+        let recipe = self
+            .recipes
+            .named(name)
+            .ok_or_else(|| anyhow::anyhow!("no recipe '{}'", name))?;
+        if !recipe.read_only() {
+            anyhow::bail!(
+                "recipe '{}' has execute steps, hooks only run read-only recipes",
+                name
+            );
+        }
+        let result = self.run_recipe(recipe, arguments).await;
+        Self::result_value(self.redactor.redact_result(result))
+    }
+
+    /// Build an execute msg as `build_execute_msg` does, for applications
+    /// building msgs without an MCP client
+    pub async fn build_execute(&self, request: ExecuteRequest) -> anyhow::Result<ValidatedExecute> {
+        let result = self
+            .build_execute_msg(
+                request.contract_addr,
+                request.execute_msg,
+                request.payment,
+                request.payment_denom,
+                request.funds,
+                request.format,
+                request.sender,
+                request.check_paused,
+                request.contract_label,
+                request.memo,
+                request.timeout_height,
+                request.gas_limit,
+                request.sequence,
+                request.public_key,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        Ok(serde_json::from_value(Self::result_value(result)?)?)
+    }
+
+    /// JSON of a tool's result, or its error text
+    fn result_value(result: CallToolResult) -> anyhow::Result<Value> {
+        let text = result
+            .content
+            .iter()
+            .find_map(|content| match &content.raw {
+                RawContent::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        if result.is_error == Some(true) {
+            anyhow::bail!(text);
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Error text of a failed tool's result
+    fn result_error(result: CallToolResult) -> anyhow::Error {
+        Self::result_value(result)
+            .err()
+            .unwrap_or_else(|| anyhow::anyhow!("unexpected result"))
+    }
+
+    /// Stop a workflow at a failed step; completed steps with a
+    /// compensation must then be compensated
+    fn fail_workflow(run: &mut WorkflowRun, step: &str, error: String) {
+        run.log.push(StepLog {
+            step: step.to_string(),
+            outcome: "failed".to_string(),
+            detail: Some(error),
+        });
+        run.pending = None;
+        run.status = if run.compensations.is_empty() {
+            WorkflowStatus::Failed
+        } else {
+            WorkflowStatus::Compensating
+        };
+    }
+
+    /// Evaluate the predicates of a workflow step with live queries,
+    /// returning the first that doesn't hold
+    async fn check_predicates(
+        &self,
+        run: &WorkflowRun,
+        predicates: &[Predicate],
+    ) -> anyhow::Result<Option<String>> {
+        let endpoint = &self.lcd_endpoint(&run.network);
+        for predicate in predicates {
+            let (Predicate::Query {
+                contract,
+                msg,
+                field,
+                ..
+            }
+            | Predicate::Expiration {
+                contract,
+                msg,
+                field,
+                ..
+            }) = predicate;
+            let contract = contract.clone().unwrap_or_else(|| run.contract.clone());
+            self.check_network(&contract, Some(run.network.clone()))?;
+            let response = self
+                .lcd
+                .smart_query(endpoint, &contract, &run.substitute(msg)?)
+                .await?;
+            let value = WorkflowRun::field(&response, field)
+                .ok_or_else(|| anyhow::anyhow!("'{}' isn't a field of the response", field))?;
+            let holds = match predicate {
+                Predicate::Query {
+                    op,
+                    value: expected,
+                    ..
+                } => compare(value, op, &run.substitute(expected)?),
+                Predicate::Expiration { expired, .. } => {
+                    let block = self
+                        .lcd
+                        .get(endpoint, "/cosmos/base/tendermint/v1beta1/blocks/latest")
+                        .await?;
+                    let header = &block["block"]["header"];
+                    let height = header["height"]
+                        .as_str()
+                        .and_then(|height| height.parse().ok())
+                        .unwrap_or_default();
+                    let time = header["time"]
+                        .as_str()
+                        .and_then(parse_rfc3339)
+                        .unwrap_or_default();
+                    let is = is_expired(value, height, time).ok_or_else(|| {
+                        anyhow::anyhow!("'{}' isn't an expiration ({})", field, value)
+                    })?;
+                    is == *expired
+                }
+            };
+            if !holds {
+                return Ok(Some(format!(
+                    "predicate on '{}' doesn't hold (current value {})",
+                    field, value
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Run the steps of a workflow until an execute msg has to be
+    /// broadcast, a step fails, or the workflow completes
+    async fn drive_workflow(&self, run: &mut WorkflowRun) {
+        while run.status == WorkflowStatus::Running {
+            let Some(step) = run.definition.steps.get(run.position).cloned() else {
+                run.status = WorkflowStatus::Completed;
+                break;
+            };
+            if let Some(condition) = &step.condition {
+                // The compared value can be a saved value too, e.g. a recipe param
+                let expected = run
+                    .substitute(&condition.value)
+                    .unwrap_or_else(|_| condition.value.clone());
+                let holds = run
+                    .var(&condition.var)
+                    .is_some_and(|value| compare(value, &condition.op, &expected));
+                if !holds {
+                    run.log.push(StepLog {
+                        step: step.name.clone(),
+                        outcome: "skipped".to_string(),
+                        detail: Some(format!("condition on '{}' doesn't hold", condition.var)),
+                    });
+                    run.position += 1;
+                    continue;
+                }
+            }
+            match &step.action {
+                StepAction::Query {
+                    contract,
+                    msg,
+                    save_as,
+                } => {
+                    let contract = contract.clone().unwrap_or_else(|| run.contract.clone());
+                    let queried = match (
+                        self.check_network(&contract, Some(run.network.clone())),
+                        run.substitute(msg),
+                    ) {
+                        (Ok(_), Ok(msg)) => {
+                            self.lcd
+                                .smart_query(&self.lcd_endpoint(&run.network), &contract, &msg)
+                                .await
+                        }
+                        (Err(e), _) | (_, Err(e)) => Err(e),
+                    };
+                    match queried {
+                        Ok(response) => {
+                            run.vars.insert(save_as.clone(), response);
+                            run.log.push(StepLog {
+                                step: step.name.clone(),
+                                outcome: "queried".to_string(),
+                                detail: None,
+                            });
+                            run.position += 1;
+                        }
+                        Err(e) => Self::fail_workflow(run, &step.name, e.to_string()),
+                    }
+                }
+                StepAction::Execute(workflow_msg) => {
+                    if let Some(refusal) = self.breaker.refusal("build_execute_msg") {
+                        Self::fail_workflow(run, &step.name, refusal);
+                        break;
+                    }
+                    match self.check_predicates(run, &step.predicates).await {
+                        Ok(None) => {}
+                        Ok(Some(failed)) => {
+                            Self::fail_workflow(run, &step.name, failed);
+                            break;
+                        }
+                        Err(e) => {
+                            Self::fail_workflow(
+                                run,
+                                &step.name,
+                                format!("predicates can't be checked: {}", e),
+                            );
+                            break;
+                        }
+                    }
+                    match self.build_workflow_msg(run, &step.name, workflow_msg).await {
+                        Ok(pending) => {
+                            run.pending = Some(pending);
+                            run.status = WorkflowStatus::AwaitingBroadcast;
+                        }
+                        Err(e) => Self::fail_workflow(run, &step.name, e.to_string()),
+                    }
+                }
+            }
         }
     }
 
+    /// Serialize a proto JSON Cosmos SDK msg of a network as a
+    /// ValidatedSdkMsg
+    fn wrap_sdk_msg(&self, network: &Network, msg: Value, warnings: Vec<String>) -> CallToolResult {
+        let sdk_msg = msg.to_string();
+        let valid_msg = ValidatedSdkMsg {
+            type_url: msg["@type"].as_str().unwrap_or_default().to_string(),
+            address_labels: self.annotate(&sdk_msg),
+            metadata: self.network_metadata(network.clone(), None, &sdk_msg),
+            sdk_msg,
+            warnings,
+            memo: built_memo(),
+        };
+        let serialized: String = serde_json::to_string(&valid_msg).unwrap_or_default();
+        CallToolResult::success(vec![Content::text(serialized)])
+    }
+
+    /// Wrap an ArchID registry msg, attaching the registration cost of
+    /// `years` (for registrations and renewals)
+    async fn wrap_archid_execute(
+        &self,
+        network: &Network,
+        msg: &RegistryExecuteMsg,
+        years: Option<u64>,
+    ) -> CallToolResult {
+        let funds: Vec<Coin> = match years {
+            Some(years) => match self.archid.registration_cost(network, years).await {
+                Ok(cost) => vec![cost],
+                Err(e) => {
+                    return tool_error("Error querying ArchID registration cost", e);
+                }
+            },
+            None => vec![],
+        };
+        let msg_json: Value = serde_json::to_value(msg).unwrap_or_default();
+        self.wrap_execute(
+            registry(network).to_string(),
+            msg_json.to_string(),
+            &msg_json,
+            funds,
+            vec![],
+        )
+    }
+
+    /// Resolve names (e.g. `alice.arch`) used as the contract address, or
+    /// in any address field of the message, on `network`, returning the
+    /// contract address
+    async fn resolve_names(
+        &self,
+        contract_addr: String,
+        network: &Network,
+        msg: &mut Value,
+        resolutions: &mut Vec<NameResolution>,
+    ) -> anyhow::Result<String> {
+        let contract_addr = self
+            .resolver
+            .resolve_address(contract_addr, network, resolutions)
+            .await?;
+        self.resolver.resolve_msg(msg, network, resolutions).await?;
+        Ok(contract_addr)
+    }
+
+    /// Labels of any known addresses appearing in `text`
+    fn annotate(&self, text: &str) -> Vec<AddressLabel> {
+        self.labels
+            .read()
+            .map(|labels| labels.annotate(text))
+            .unwrap_or_default()
+    }
+
+    /// Labels of the addresses of a wrapped msg: those of the plain msg
+    /// (base64 encoded in the wrapper, e.g. recipients), and of the wrapper
+    fn annotate_msg(&self, msg: &Value, wrapped: &str) -> Vec<AddressLabel> {
+        self.annotate(&format!("{} {}", msg, wrapped))
+    }
+
     /// List deployed contracts, networks, chain ids
     #[tool(description = LIST_CONTRACTS_DESCR)]
     async fn list_contract_deployments(&self) -> Result<CallToolResult, Error> {
-        let serialized: String = serde_json::to_string(&self.contracts).unwrap_or("".to_string());
+        let mut deployments: Vec<Deployment> = vec![];
+        let registered = self.contracts.registered();
+        for contract in self.contracts.all() {
+            let info = self
+                .lcd
+                .contract_info(contract.lcd_endpoint(), &contract.contract_address)
+                .await
+                .ok();
+            let mut deployment = Deployment::new(contract, info.as_ref());
+            if !self
+                .contracts
+                .is_configured(&deployment.contract.contract_address)
+            {
+                if let Some(entry) = registered
+                    .iter()
+                    .find(|entry| entry.contract_address == deployment.contract.contract_address)
+                {
+                    deployment =
+                        deployment.registered(entry.label.clone(), entry.registered_at.clone());
+                }
+            }
+            let adapter = self.contract_adapter(&deployment.contract.contract_address);
+            deployment.adapter = Some(adapter.label().to_string());
+            deployments.push(deployment);
+        }
+        let serialized: String = serde_json::to_string(&deployments).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Register a contract at runtime
+    #[tool(description = REGISTER_CONTRACT_DESCR)]
+    async fn register_contract(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "bech32 address of the contract (e.g. archway1...)")]
+        contract_address: String,
+        #[tool(param)]
+        #[schemars(description = "network of the contract, 'Mainnet' or 'Testnet'")]
+        network: Network,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the chain id of the contract (defaults to the network's, e.g. archway-1)"
+        )]
+        chain_id: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally a human readable label for the contract (e.g. 'staking pool')"
+        )]
+        label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let entry = match self
+            .contracts
+            .register(contract_address, chain_id, network, label)
+        {
+            Ok(entry) => entry,
+            Err(e) => return Ok(tool_error("Error registering contract", e)),
+        };
+        if let (Some(label), Ok(mut labels)) = (&entry.label, self.labels.write()) {
+            labels.insert(entry.contract_address.clone(), label.clone());
+        }
+        if MONITOR_OWNERSHIP {
+            self.alerts
+                .monitor_ownership(&[CwContract::from(&entry)])
+                .await;
+        }
+        let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Remove a contract registered at runtime
+    #[tool(description = REMOVE_CONTRACT_DESCR)]
+    async fn remove_contract(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "bech32 address of the contract registered with 'contract__register'"
+        )]
+        contract_address: String,
+    ) -> Result<CallToolResult, Error> {
+        let entry = match self.contracts.remove(&contract_address) {
+            Ok(entry) => entry,
+            Err(e) => return Ok(tool_error("Error removing contract", e)),
+        };
+        if let (Some(_), Ok(mut labels)) = (&entry.label, self.labels.write()) {
+            labels.remove(&entry.contract_address);
+        }
+        let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// List the adapters of the contracts the server supports
+    #[tool(description = LIST_CONTRACT_ADAPTERS_DESCR)]
+    async fn list_contract_adapters(&self) -> Result<CallToolResult, Error> {
+        let adapters: Vec<AdapterInfo> = adapters().into_iter().map(AdapterInfo::of).collect();
+        let serialized: String = serde_json::to_string(&adapters).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
     /// List query entry points
     #[tool(description = LIST_QUERY_ENTRY_POINTS_DESCR)]
-    async fn list_query_entry_points(&self) -> Result<CallToolResult, Error> {
-        let schema = schema_for!(QueryMsg);
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+    async fn list_query_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the default adapter"
+        )]
+        contract_label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let adapter = match adapter(contract_label.as_deref()) {
+            Ok(adapter) => adapter,
+            Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+        };
+        let serialized: String = adapter.schema(SchemaKind::Query);
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
@@ -82,112 +1247,4065 @@ impl CwMcp {
         &self,
         #[tool(param)]
         #[schemars(
-            description = "address of the deployed contract (e.g. mainnet or testnet address)"
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the default network)"
         )]
-        contract_addr: String,
+        contract_addr: Option<String>,
         #[tool(param)]
         #[schemars(
             description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
         )]
         query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the contract's adapter"
+        )]
+        contract_label: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let deserialized: QueryMsg = serde_json::from_str(query_msg.as_str()).unwrap();
-        let query_req: QueryRequest<QueryMsg> = QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr,
-            msg: to_json_binary(&deserialized).unwrap_or_default(),
-        });
-        let serialized_query_req = serde_json::to_string(&query_req);
-        if serialized_query_req.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping QueryMsg as QueryRequest",
-            )]));
+        let (contract_addr, network) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let adapter = match self.adapter_for(contract_label.as_deref(), &contract_addr) {
+            Ok(adapter) => adapter,
+            Err(result) => return Ok(result),
+        };
+        let (mut msg_json, warnings) =
+            match adapter.parse(SchemaKind::Query, "Error building QueryMsg", &query_msg) {
+                Ok(parsed) => parsed,
+                Err(result) => return Ok(result),
+            };
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        let resolved = self
+            .resolve_names(
+                contract_addr,
+                &network,
+                &mut msg_json,
+                &mut name_resolutions,
+            )
+            .await;
+        let contract_addr = match resolved {
+            Ok(contract_addr) => contract_addr,
+            Err(e) => {
+                return Ok(tool_error("Error resolving name", e));
+            }
+        };
+        // Resolved names must also be addresses of the network
+        if let Err(result) = self.guard_network(&contract_addr, Some(network)) {
+            return Ok(result);
         }
-        let valid_query = ValidatedQuery {
+        Ok(self.wrap_query(
+            contract_addr,
             query_msg,
-            query_request: serialized_query_req.unwrap_or_default(),
-        };
-        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
-        Ok(CallToolResult::success(vec![Content::text(serialized)]))
-    }
-
-    /// List execute entry points
-    #[tool(description = LIST_TX_ENTRY_POINTS_DESCR)]
-    async fn list_tx_entry_points(&self) -> Result<CallToolResult, Error> {
-        let schema = schema_for!(ExecuteMsg);
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
-        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            &msg_json,
+            name_resolutions,
+            warnings,
+        ))
     }
 
-    /// Build a transaction that can be signed and broadcast by an RPC connected wallet
-    #[tool(description = BUILD_EXECUTE_MSG_DESCR)]
-    async fn build_execute_msg(
+    /// Build (and optionally execute) a raw storage query
+    #[tool(description = BUILD_RAW_QUERY_DESCR)]
+    async fn build_raw_query(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "address of the deployed contract (e.g. mainnet or testnet address)"
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the network)"
         )]
-        contract_addr: String,
+        contract_addr: Option<String>,
         #[tool(param)]
         #[schemars(
-            description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+            description = "Optionally the storage key, hex (e.g. '0x636f6e666967') or base64 encoded (required unless 'namespace' is set)"
         )]
-        execute_msg: String,
+        key: Option<String>,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment funds to be sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+            description = "Optionally the cw-storage-plus namespace of an Item or Map (e.g. 'config' or 'balance'), to build the key from"
         )]
-        payment: Option<String>,
+        namespace: Option<String>,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment denom for funds being sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+            description = "Optionally the keys of a Map entry in the namespace (e.g. an address), in order for composite keys"
         )]
-        payment_denom: Option<String>,
+        map_keys: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally send the query to the network's LCD endpoint and return the stored value (defaults to false)"
+        )]
+        execute: Option<bool>,
     ) -> Result<CallToolResult, Error> {
-        let funds: Vec<Coin> = if payment.is_some() && payment_denom.is_some() {
-            let funds = Coin {
-                denom: payment_denom.unwrap_or_default(),
-                amount: Uint128::from_str(payment.unwrap_or_default().as_str()).unwrap_or_default(),
-            };
-            vec![funds]
-        } else {
-            vec![]
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
         };
-        let deserialized: ExecuteMsg = serde_json::from_str(execute_msg.as_str()).unwrap();
-        let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-            contract_addr,
-            msg: to_json_binary(&deserialized).unwrap_or_default(),
-            funds,
-        }
-        .into();
-        let serialized_cosmos_msg = serde_json::to_string(&cosmos_msg);
-        if serialized_cosmos_msg.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping ExecuteMsg as CosmosMsg",
-            )]));
-        }
-        let valid_execute = ValidatedExecute {
-            execute_msg,
-            cosmos_msg: serialized_cosmos_msg.unwrap_or_default(),
+        let key = match (key, namespace) {
+            (Some(key), None) => decode_storage_key(&key),
+            (None, Some(namespace)) => storage_key(&namespace, &map_keys.unwrap_or_default()),
+            _ => Err(anyhow::anyhow!(
+                "invalid storage key, set either the 'key' or the 'namespace' (and 'map_keys')"
+            )),
         };
-        let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+        let key = match key {
+            Ok(key) => key,
+            Err(e) => return Ok(tool_error("Error building raw query", e)),
+        };
+        let query_req: QueryRequest<QueryMsg> = QueryRequest::Wasm(WasmQuery::Raw {
+            contract_addr: contract_addr.clone(),
+            key: Binary::from(key.clone()),
+        });
+        let query_request = serde_json::to_string(&query_req).unwrap_or_default();
+        let mut raw_query = RawQuery {
+            contract: contract_addr.clone(),
+            key_hex: key_hex(&key),
+            key_base64: STANDARD.encode(&key),
+            address_labels: self.annotate(&query_request),
+            metadata: self.build_metadata(&contract_addr, &query_request),
+            query_request,
+            executed: false,
+            value: None,
+            value_base64: None,
+        };
+        if execute.unwrap_or(false) {
+            let endpoint = self.lcd_endpoint(&network);
+            match self.lcd.raw_query(&endpoint, &contract_addr, &key).await {
+                Ok(data) => raw_query = raw_query.with_value(data),
+                Err(e) => return Ok(tool_error("Error executing raw query", e)),
+            }
+        }
+        let serialized: String = serde_json::to_string(&raw_query).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
-}
-
-impl Default for CwMcp {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-#[tool(tool_box)]
-impl ServerHandler for CwMcp {
+    /// Build (and optionally execute) a ContractInfo query of an address
+    #[tool(description = QUERY_CONTRACT_INFO_DESCR)]
+    async fn query_contract_info(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of a contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally send the query to the network's LCD endpoint and return the contract's info (defaults to true)"
+        )]
+        execute: Option<bool>,
+    ) -> Result<CallToolResult, Error> {
+        // Any contract can be inspected (even in strict mode), so agents can
+        // check what they're about to interact with
+        let (contract_addr, network) = match contract_addr {
+            Some(contract_addr) => {
+                if let Err(e) = decode_address(&contract_addr) {
+                    return Ok(tool_error("Error querying contract info", e));
+                }
+                let network = network.unwrap_or_else(|| self.network_for(&contract_addr));
+                (contract_addr, network)
+            }
+            None => match self.target(None, network) {
+                Ok(target) => target,
+                Err(result) => return Ok(result),
+            },
+        };
+        let query_req: QueryRequest<QueryMsg> = QueryRequest::Wasm(WasmQuery::ContractInfo {
+            contract_addr: contract_addr.clone(),
+        });
+        let query_request = serde_json::to_string(&query_req).unwrap_or_default();
+        let mut contract_info = ContractInfoQuery {
+            contract: contract_addr.clone(),
+            registered: self.contracts.find(&contract_addr).is_some(),
+            address_labels: self.annotate(&query_request),
+            metadata: self.build_metadata(&contract_addr, &query_request),
+            query_request,
+            executed: false,
+            code_id: None,
+            creator: None,
+            admin: None,
+            label: None,
+            ibc_port_id: None,
+            warnings: vec![],
+        };
+        if execute.unwrap_or(true) {
+            let endpoint = self.lcd_endpoint(&network);
+            match self.lcd.contract_info(&endpoint, &contract_addr).await {
+                Ok(info) => contract_info = contract_info.with_info(&info),
+                Err(e) => return Ok(tool_error("Error querying contract info", e)),
+            }
+        }
+        let serialized: String = serde_json::to_string(&contract_info).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Send a smart query to the contract and return its response
+    #[tool(description = EXECUTE_QUERY_DESCR)]
+    async fn execute_query(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant to send to the contract")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let (mut msg_json, warnings) = match self.contract_adapter(&contract_addr).parse(
+            SchemaKind::Query,
+            "Error building QueryMsg",
+            &query_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        let resolved = self
+            .resolve_names(
+                contract_addr,
+                &network,
+                &mut msg_json,
+                &mut name_resolutions,
+            )
+            .await;
+        let contract_addr = match resolved {
+            Ok(contract_addr) => contract_addr,
+            Err(e) => {
+                return Ok(tool_error("Error resolving name", e));
+            }
+        };
+        // Resolved names must also be addresses of the network
+        if let Err(result) = self.guard_network(&contract_addr, Some(network)) {
+            return Ok(result);
+        }
+        let endpoint = self.lcd_endpoint(&network);
+        match self
+            .lcd
+            .smart_query(&endpoint, &contract_addr, &msg_json)
+            .await
+        {
+            Ok(response) => {
+                let result = QueryResult {
+                    address_labels: self.annotate(&response.to_string()),
+                    contract: contract_addr,
+                    network,
+                    endpoint,
+                    query_msg: msg_json.to_string(),
+                    response,
+                    name_resolutions,
+                    warnings,
+                };
+                let serialized: String = serde_json::to_string(&result).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error executing query", e)),
+        }
+    }
+
+    /// Decode a smart query response into the contract's response types
+    #[tool(description = DECODE_QUERY_RESPONSE_DESCR)]
+    async fn decode_query_response(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The query's response: JSON, base64 encoded JSON, or the LCD's {\"data\": ..}"
+        )]
+        response: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the JSON stringified QueryMsg variant the response answers (otherwise every response type is tried)"
+        )]
+        query_msg: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        match decode_response(&response, query_msg.as_deref()) {
+            Ok(decoded) => {
+                let serialized: String = serde_json::to_string(&decoded).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error decoding query response", e)),
+        }
+    }
+
+    /// List execute entry points
+    #[tool(description = LIST_TX_ENTRY_POINTS_DESCR)]
+    async fn list_tx_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the default adapter"
+        )]
+        contract_label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let adapter = match adapter(contract_label.as_deref()) {
+            Ok(adapter) => adapter,
+            Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+        };
+        let serialized: String = adapter.schema(SchemaKind::Execute);
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Generate an example msg of an entry point
+    #[tool(description = GENERATE_EXAMPLE_MSG_DESCR)]
+    async fn generate_example_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the entry point (e.g. 'transfer' or 'balance')")]
+        entry_point: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the msg type of the entry point ('Execute' or 'Query', defaults to whichever has an entry point of that name, execute first)"
+        )]
+        msg_type: Option<MsgType>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the default adapter"
+        )]
+        contract_label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let adapter = match adapter(contract_label.as_deref()) {
+            Ok(adapter) => adapter,
+            Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+        };
+        let execute: Value =
+            serde_json::from_str(&adapter.schema(SchemaKind::Execute)).unwrap_or_default();
+        let query: Value =
+            serde_json::from_str(&adapter.schema(SchemaKind::Query)).unwrap_or_default();
+        let schemas = match msg_type {
+            Some(MsgType::Execute) => vec![(MsgType::Execute, &execute)],
+            Some(MsgType::Query) => vec![(MsgType::Query, &query)],
+            None => vec![(MsgType::Execute, &execute), (MsgType::Query, &query)],
+        };
+        let mut entry_points: Vec<String> = vec![];
+        for (msg_type, schema) in schemas {
+            match generate(msg_type, schema, &entry_point) {
+                Ok(mut example) => {
+                    let kind = match msg_type {
+                        MsgType::Execute => SchemaKind::Execute,
+                        MsgType::Query => SchemaKind::Query,
+                    };
+                    example.valid = adapter
+                        .parse(kind, "Error parsing example", &example.example.to_string())
+                        .is_ok();
+                    let serialized: String = serde_json::to_string(&example).unwrap_or_default();
+                    return Ok(CallToolResult::success(vec![Content::text(serialized)]));
+                }
+                Err(names) => entry_points.extend(names),
+            }
+        }
+        let candidates: Vec<&str> = entry_points.iter().map(String::as_str).collect();
+        let suggestion = match nearest(&entry_point, &candidates) {
+            Some(nearest) => format!(", did you mean '{}'?", nearest),
+            None => format!(", expected one of: {}", entry_points.join(", ")),
+        };
+        Ok(tool_error(
+            "Error generating example msg",
+            format!("unknown entry point '{}'{}", entry_point, suggestion),
+        ))
+    }
+
+    /// List the instantiate msg schema
+    #[tool(description = LIST_INSTANTIATE_SCHEMA_DESCR)]
+    async fn list_instantiate_schema(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the default adapter"
+        )]
+        contract_label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let adapter = match adapter(contract_label.as_deref()) {
+            Ok(adapter) => adapter,
+            Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+        };
+        let serialized: String = adapter.schema(SchemaKind::Instantiate);
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Build a transaction instantiating a new instance of the contract
+    #[tool(description = BUILD_INSTANTIATE_MSG_DESCR)]
+    async fn build_instantiate_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "InstantiateMsg and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+        )]
+        instantiate_msg: String,
+        #[tool(param)]
+        #[schemars(description = "human readable label of the new instance (e.g. 'wrapped arch')")]
+        label: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the admin who can migrate the instance (without one, it can never be migrated)"
+        )]
+        admin: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the code id to instantiate (defaults to the code id of the deployment on the network)"
+        )]
+        code_id: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the default network)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment funds to be sent to the new instance"
+        )]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment denom for funds being sent in the transaction"
+        )]
+        payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the native funds, as a JSON array of coins (e.g. '[{\"denom\": \"aarch\", \"amount\": \"1000\"}]')"
+        )]
+        funds: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the default adapter"
+        )]
+        contract_label: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let adapter = match adapter(contract_label.as_deref()) {
+            Ok(adapter) => adapter,
+            Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+        };
+        // The code id defaults to a deployment of the same adapter
+        let deployment = self.contracts.all().into_iter().find(|contract| {
+            contract.network == network
+                && self.contract_adapter(&contract.contract_address).label() == adapter.label()
+        });
+        let code_id = match code_id.or(deployment.as_ref().and_then(|contract| contract.code_id)) {
+            Some(code_id) => code_id,
+            None => {
+                let info = match &deployment {
+                    Some(contract) => self
+                        .lcd
+                        .contract_info(contract.lcd_endpoint(), &contract.contract_address)
+                        .await
+                        .ok(),
+                    None => None,
+                };
+                match info
+                    .as_ref()
+                    .and_then(|info| info["code_id"].as_str())
+                    .and_then(|code_id| code_id.parse().ok())
+                {
+                    Some(code_id) => code_id,
+                    None => {
+                        return Ok(tool_error(
+                            "Error building instantiate msg",
+                            format!("no code id is configured for {:?}, set 'code_id'", network),
+                        ));
+                    }
+                }
+            }
+        };
+        if label.trim().is_empty() {
+            return Ok(tool_error(
+                "Error building instantiate msg",
+                "invalid label, instances must have a (non empty) label",
+            ));
+        }
+        let funds: Vec<Coin> = match native_funds(payment, payment_denom, funds) {
+            Ok(funds) => funds,
+            Err(e) => return Ok(tool_error("Error building funds", e)),
+        };
+        let (mut msg_json, mut warnings) = match adapter.parse(
+            SchemaKind::Instantiate,
+            "Error building InstantiateMsg",
+            &instantiate_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        if let Err(e) = self
+            .resolver
+            .resolve_msg(&mut msg_json, &network, &mut name_resolutions)
+            .await
+        {
+            return Ok(tool_error("Error resolving name", e));
+        }
+        let admin = match admin {
+            Some(admin) => match self
+                .resolver
+                .resolve_address(admin, &network, &mut name_resolutions)
+                .await
+                .and_then(|admin| decode_address(&admin).map(|_| admin))
+            {
+                Ok(admin) => Some(admin),
+                Err(e) => return Ok(tool_error("Error building instantiate msg", e)),
+            },
+            None => {
+                warnings.push("the instance has no admin, so it can never be migrated".to_string());
+                None
+            }
+        };
+        match self.verify_funds(&network, &funds).await {
+            Ok(denom_warnings) => warnings.extend(denom_warnings),
+            Err(result) => return Ok(result),
+        }
+        if let Err(e) = self.spend.check(&spend_of("", &Value::Null, &funds)) {
+            return Ok(tool_error("Error building transaction", e));
+        }
+        let cosmos_msg: CosmosMsg = WasmMsg::Instantiate {
+            admin: admin.clone(),
+            code_id,
+            msg: to_json_binary(&msg_json).unwrap_or_default(),
+            funds,
+            label: label.clone(),
+        }
+        .into();
+        let cosmos_msg = match serde_json::to_string(&cosmos_msg) {
+            Ok(cosmos_msg) => cosmos_msg,
+            Err(e) => return Ok(tool_error("Error wrapping InstantiateMsg as CosmosMsg", e)),
+        };
+        let valid_instantiate = ValidatedInstantiate {
+            code_id,
+            address_labels: self.annotate_msg(&msg_json, &cosmos_msg),
+            metadata: self.network_metadata(network, Some(label.clone()), &cosmos_msg),
+            label,
+            admin,
+            instantiate_msg,
+            cosmos_msg,
+            name_resolutions,
+            warnings,
+            memo: built_memo(),
+        };
+        let serialized: String = serde_json::to_string(&valid_instantiate).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Build a transaction migrating the contract to a new code id
+    #[tool(description = BUILD_MIGRATE_MSG_DESCR)]
+    async fn build_migrate_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the contract to migrate (defaults to the session's default contract, otherwise the deployment on the default network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "code id the contract is migrated to")]
+        new_code_id: u64,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified MigrateMsg of the new code (e.g. '{}')")]
+        migrate_msg: String,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let (msg_json, mut warnings) = match self.contract_adapter(&contract_addr).parse(
+            SchemaKind::Migrate,
+            "Error building MigrateMsg",
+            &migrate_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        let info = match self
+            .lcd
+            .contract_info(&self.lcd_endpoint(&network), &contract_addr)
+            .await
+        {
+            Ok(info) => info,
+            Err(e) => return Ok(tool_error("Error querying contract info", e)),
+        };
+        let admin = info["admin"]
+            .as_str()
+            .filter(|admin| !admin.is_empty())
+            .map(str::to_string);
+        let Some(admin) = admin else {
+            return Ok(tool_error(
+                "Error building migrate msg",
+                format!("{} has no admin, so it can't be migrated", contract_addr),
+            ));
+        };
+        let current_code_id: Option<u64> = info["code_id"]
+            .as_str()
+            .and_then(|code_id| code_id.parse().ok());
+        if current_code_id == Some(new_code_id) {
+            warnings.push(format!(
+                "the contract already runs code id {}, the migration only runs its migrate entry point",
+                new_code_id
+            ));
+        }
+        warnings.push(format!(
+            "only the admin ({}) can sign this migration",
+            admin
+        ));
+        let cosmos_msg: CosmosMsg = WasmMsg::Migrate {
+            contract_addr: contract_addr.clone(),
+            new_code_id,
+            msg: to_json_binary(&msg_json).unwrap_or_default(),
+        }
+        .into();
+        let cosmos_msg = match serde_json::to_string(&cosmos_msg) {
+            Ok(cosmos_msg) => cosmos_msg,
+            Err(e) => return Ok(tool_error("Error wrapping MigrateMsg as CosmosMsg", e)),
+        };
+        let valid_migrate = ValidatedMigrate {
+            address_labels: self.annotate_msg(&msg_json, &cosmos_msg),
+            metadata: self.build_metadata(&contract_addr, &cosmos_msg),
+            contract: contract_addr,
+            current_code_id,
+            new_code_id,
+            admin: Some(admin),
+            migrate_msg,
+            cosmos_msg,
+            warnings,
+            memo: built_memo(),
+        };
+        let serialized: String = serde_json::to_string(&valid_migrate).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Build a transaction that can be signed and broadcast by an RPC connected wallet
+    #[tool(description = BUILD_EXECUTE_MSG_DESCR)]
+    async fn build_execute_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the default network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+        )]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment funds to be sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+        )]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment denom for funds being sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+        )]
+        payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the native funds to send as a JSON stringified array of coins, e.g. '[{\"denom\": \"ibc/...\", \"amount\": \"1000\"}, {\"denom\": \"aarch\", \"amount\": \"5000\"}]' (for transactions requiring several native denoms; sent in addition to 'payment')"
+        )]
+        funds: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the format of the built msg for the signing stack: 'json' (the default, a CosmosMsg), 'proto' (a base64 encoded protobuf Any, requires sender), 'cbor' (base64 encoded CBOR of the CosmosMsg) or 'tx_raw' (a base64 encoded unsigned TxRaw, requires sender)"
+        )]
+        format: Option<MsgFormat>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address signing the transaction (required for 'proto')"
+        )]
+        sender: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally whether to query the contract for common pause patterns first, warning if it appears paused (defaults to the server's setting)"
+        )]
+        check_paused: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the label of the contract's adapter (e.g. 'cw20-wrap', see 'adapter' of contract__list_deployments), defaults to the contract's adapter"
+        )]
+        contract_label: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the memo of the transaction (the server's watermark, if one is configured, is appended)"
+        )]
+        memo: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the block height past which the transaction can no longer be included (omit, or 0, for none)"
+        )]
+        timeout_height: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the gas limit of the 'tx_raw' format (defaults to the simulated gas used, with a safety margin)"
+        )]
+        gas_limit: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the sender's account sequence of the 'tx_raw' format (defaults to the queried sequence)"
+        )]
+        sequence: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the sender's public key (hex, base64 or the key JSON of its account), set in the signer info of the 'tx_raw' format"
+        )]
+        public_key: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let timeout_height = timeout_height.filter(|height| *height != 0);
+        // Whether the signer has to set more than the watermark
+        let tx_fields = memo.is_some() || timeout_height.is_some();
+        let memo = watermark(memo.as_deref().unwrap_or_default().trim());
+        if let Err(e) = check_memo(&memo) {
+            return Ok(tool_error("Error building transaction", e));
+        }
+        let (contract_addr, network) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let funds: Vec<Coin> = match native_funds(payment, payment_denom, funds) {
+            Ok(funds) => funds,
+            Err(e) => return Ok(tool_error("Error building funds", e)),
+        };
+        let adapter = match self.adapter_for(contract_label.as_deref(), &contract_addr) {
+            Ok(adapter) => adapter,
+            Err(result) => return Ok(result),
+        };
+        let (mut msg_json, warnings) = match adapter.parse(
+            SchemaKind::Execute,
+            "Error building ExecuteMsg",
+            &execute_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        let resolved = self
+            .resolve_names(
+                contract_addr,
+                &network,
+                &mut msg_json,
+                &mut name_resolutions,
+            )
+            .await;
+        let contract_addr = match resolved {
+            Ok(contract_addr) => contract_addr,
+            Err(e) => {
+                return Ok(tool_error("Error resolving name", e));
+            }
+        };
+        // Resolved names must also be addresses of the network
+        if let Err(result) = self.guard_network(&contract_addr, Some(network)) {
+            return Ok(result);
+        }
+        let denom_warnings = match self
+            .verify_funds(&self.network_for(&contract_addr), &funds)
+            .await
+        {
+            Ok(denom_warnings) => denom_warnings,
+            Err(result) => return Ok(result),
+        };
+        // A cap that can't be checked (e.g. offline) only warns
+        let mut mint_warnings = vec![];
+        let mint_check = match mint_amount(&msg_json) {
+            Some(amount) => {
+                let endpoint = self.lcd_endpoint(&self.network_for(&contract_addr));
+                match self.minting.check(&endpoint, &contract_addr, amount).await {
+                    Ok(check) if !check.within_cap => {
+                        return Ok(tool_error(
+                            "Error building mint msg",
+                            format!(
+                                "minting {} exceeds the cap of {}, only {} remaining mintable",
+                                amount,
+                                check.cap.unwrap_or_default(),
+                                check.remaining_mintable.unwrap_or_default()
+                            ),
+                        ));
+                    }
+                    Ok(check) => Some(check),
+                    Err(e) => {
+                        mint_warnings.push(format!(
+                            "the minter and supply cap couldn't be checked, the mint may exceed the cap or be signed by a non-minter: {}",
+                            e
+                        ));
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        let mut valid_execute = match self.validated_execute(
+            contract_addr.clone(),
+            execute_msg,
+            &msg_json,
+            funds.clone(),
+            name_resolutions,
+        ) {
+            Ok(valid_execute) => valid_execute,
+            Err(result) => return Ok(result),
+        };
+        valid_execute.warnings.extend(warnings);
+        valid_execute.warnings.extend(denom_warnings);
+        valid_execute.warnings.extend(mint_warnings);
+        if let Some(timeout_height) = timeout_height {
+            match self.events.latest_height(&self.lcd_endpoint(&network)).await {
+                Ok(latest) if timeout_height <= latest => {
+                    return Ok(tool_error(
+                        "Error building transaction",
+                        format!(
+                            "the timeout height {} has passed, the latest block is {}",
+                            timeout_height, latest
+                        ),
+                    ));
+                }
+                Ok(_) => {}
+                Err(_) => valid_execute.warnings.push(format!(
+                    "the latest block height couldn't be queried, check the timeout height {} hasn't passed",
+                    timeout_height
+                )),
+            }
+        }
+        let format = format.unwrap_or_default();
+        let auth = match (format, sender.as_deref()) {
+            (MsgFormat::TxRaw, Some(sender)) => {
+                let auth = self
+                    .unsigned_auth(
+                        &network,
+                        sender,
+                        public_key.as_deref(),
+                        sequence,
+                        gas_limit,
+                        &contract_addr,
+                        &msg_json,
+                        &funds,
+                    )
+                    .await;
+                match auth {
+                    Ok((auth, auth_warnings)) => {
+                        valid_execute.warnings.extend(auth_warnings);
+                        Some(auth)
+                    }
+                    Err(result) => return Ok(result),
+                }
+            }
+            _ => None,
+        };
+        let execute = ExecuteContract {
+            sender: sender.as_deref(),
+            contract: &contract_addr,
+            msg: &msg_json,
+            funds: &funds,
+            cosmos_msg: &valid_execute.cosmos_msg,
+            memo: &memo,
+            timeout_height: timeout_height.unwrap_or_default(),
+            auth: auth.as_ref(),
+        };
+        let encoded_msg = match format {
+            MsgFormat::Json => None,
+            format => match format.encode(&execute) {
+                Ok(encoded) => Some(encoded),
+                Err(e) => return Ok(tool_error("Error encoding msg", e)),
+            },
+        };
+        let tx_body = match &sender {
+            Some(_) => match TxBody::new(&execute) {
+                Ok(tx_body) => Some(tx_body),
+                Err(e) => return Ok(tool_error("Error building tx body", e)),
+            },
+            None => None,
+        };
+        valid_execute.encoded_msg = encoded_msg;
+        valid_execute.tx_body = tx_body;
+        valid_execute.memo = Some(memo.clone()).filter(|memo| !memo.is_empty());
+        valid_execute.timeout_height = timeout_height;
+        if sender.is_none() && tx_fields {
+            valid_execute.warnings.push(
+                "the memo and timeout height must be set by the signer, pass the 'sender' to build the tx body".to_string(),
+            );
+        }
+        if let Some(check) = &mint_check {
+            valid_execute.warnings.push(format!(
+                "only the minter ({}) can sign this mint",
+                check.minter
+            ));
+        }
+        valid_execute.mint_check = mint_check;
+        if check_paused.unwrap_or(CHECK_PAUSED) {
+            let pause_check = self
+                .pause
+                .check(&self.lcd_endpoint(&network), &contract_addr)
+                .await;
+            if pause_check.paused {
+                valid_execute.warnings.push(format!(
+                    "{} appears paused, the transaction will likely fail until it's unpaused",
+                    contract_addr
+                ));
+            }
+            valid_execute.pause_check = Some(pause_check);
+        }
+        // Hashed again now the memo, timeout height and encodings are set
+        valid_execute.metadata.content_hash = content_hash(&valid_execute.signed_payload());
+        let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Add (or replace) a label for an address
+    #[tool(description = SET_ADDRESS_LABEL_DESCR)]
+    async fn set_address_label(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "bech32 address to be labeled (e.g. archway1...)")]
+        address: String,
+        #[tool(param)]
+        #[schemars(description = "human readable label for the address (e.g. 'treasury')")]
+        label: String,
+    ) -> Result<CallToolResult, Error> {
+        if !is_address(&address) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error labeling address, '{}' is not a bech32 address",
+                address
+            ))]));
+        }
+        let Ok(mut labels) = self.labels.write() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error accessing address labels",
+            )]));
+        };
+        let entry = labels.insert(address, label);
+        let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Remove a user supplied address label
+    #[tool(description = REMOVE_ADDRESS_LABEL_DESCR)]
+    async fn remove_address_label(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "bech32 address whose label should be removed")]
+        address: String,
+    ) -> Result<CallToolResult, Error> {
+        let Ok(mut labels) = self.labels.write() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error accessing address labels",
+            )]));
+        };
+        match labels.remove(&address) {
+            Some(entry) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            None => Ok(CallToolResult::error(vec![Content::text(format!(
+                "No user supplied label found for address '{}'",
+                address
+            ))])),
+        }
+    }
+
+    /// List all address labels
+    #[tool(description = LIST_ADDRESS_LABELS_DESCR)]
+    async fn list_address_labels(&self) -> Result<CallToolResult, Error> {
+        let entries = self
+            .labels
+            .read()
+            .map(|labels| labels.list())
+            .unwrap_or_default();
+        let serialized: String = serde_json::to_string(&entries).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Look up the label of an address, or the addresses matching a label
+    #[tool(description = LOOKUP_ADDRESS_LABEL_DESCR)]
+    async fn lookup_address_label(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "either a bech32 address (to find its label) or a label (to find its addresses)"
+        )]
+        search: String,
+    ) -> Result<CallToolResult, Error> {
+        let Ok(labels) = self.labels.read() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error accessing address labels",
+            )]));
+        };
+        let entries: Vec<AddressLabel> = if is_address(&search) {
+            labels.get(&search).cloned().into_iter().collect()
+        } else {
+            labels.find_by_label(&search)
+        };
+        let serialized: String = serde_json::to_string(&entries).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Re-encode an address with another bech32 prefix
+    #[tool(description = CONVERT_ADDRESS_PREFIX_DESCR)]
+    async fn convert_address_prefix(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "bech32 address to be converted (e.g. cosmos1...)")]
+        address: String,
+        #[tool(param)]
+        #[schemars(description = "bech32 prefix of the converted address (e.g. 'archway')")]
+        prefix: String,
+    ) -> Result<CallToolResult, Error> {
+        match convert_prefix(&address, &prefix) {
+            Ok(converted) => {
+                let serialized: String = serde_json::to_string(&converted).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error converting address", e)),
+        }
+    }
+
+    /// Derive the account address of a secp256k1 public key
+    #[tool(description = DERIVE_PUBKEY_ADDRESS_DESCR)]
+    async fn derive_pubkey_address(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "secp256k1 public key, base64 or hex encoded (or the JSON public key of an account)"
+        )]
+        public_key: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the bech32 prefix of the address (defaults to the contract's, e.g. 'archway')"
+        )]
+        prefix: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let prefix = prefix.unwrap_or_else(|| BECH32_PREFIX.to_string());
+        match derive_address(&public_key, &prefix) {
+            Ok(derived) => {
+                let serialized: String = serde_json::to_string(&derived).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error deriving address", e)),
+        }
+    }
+
+    /// Resolve a name (e.g. `alice.arch`) to an address
+    #[tool(description = RESOLVE_NAME_DESCR)]
+    async fn resolve_name(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "name to be resolved (e.g. 'alice.arch' for ArchID, 'alice.archway' for ICNS, or 'alice.stars' for Stargaze Names)"
+        )]
+        name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally resolve ArchID names on 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        match self.resolver.resolve(&name, &network).await {
+            Ok(resolution) => {
+                let serialized: String = serde_json::to_string(&resolution).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error resolving name", e)),
+        }
+    }
+
+    /// List the ArchID domains of an address
+    #[tool(description = LIST_ARCHID_DOMAINS_DESCR)]
+    async fn list_archid_domains(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "bech32 address whose ArchID domains should be listed")]
+        address: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally use 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        match self.archid.domains_of(&address, &network).await {
+            Ok(domains) => {
+                let serialized: String = serde_json::to_string(&domains).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error querying ArchID domains", e)),
+        }
+    }
+
+    /// Build a tx registering an ArchID domain
+    #[tool(description = BUILD_ARCHID_REGISTER_MSG_DESCR)]
+    async fn build_archid_register_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "domain to be registered (e.g. 'alice' or 'alice.arch')")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally the number of years to register for (defaults to 1)")]
+        years: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally use 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let msg = RegistryExecuteMsg::Register {
+            name: bare_name(&name).to_string(),
+        };
+        Ok(self
+            .wrap_archid_execute(&network, &msg, Some(years.unwrap_or(1)))
+            .await)
+    }
+
+    /// Build a tx renewing an ArchID domain
+    #[tool(description = BUILD_ARCHID_RENEW_MSG_DESCR)]
+    async fn build_archid_renew_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "domain to be renewed (e.g. 'alice' or 'alice.arch')")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally the number of years to renew for (defaults to 1)")]
+        years: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally use 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let msg = RegistryExecuteMsg::RenewRegistration {
+            name: bare_name(&name).to_string(),
+        };
+        Ok(self
+            .wrap_archid_execute(&network, &msg, Some(years.unwrap_or(1)))
+            .await)
+    }
+
+    /// Build a tx changing the address an ArchID domain resolves to
+    #[tool(description = BUILD_ARCHID_UPDATE_RESOLVER_MSG_DESCR)]
+    async fn build_archid_update_resolver_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "domain to be updated (e.g. 'alice' or 'alice.arch')")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "bech32 address the domain should resolve to")]
+        new_resolver: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally use 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let msg = RegistryExecuteMsg::UpdateResolver {
+            name: bare_name(&name).to_string(),
+            new_resolver,
+        };
+        Ok(self.wrap_archid_execute(&network, &msg, None).await)
+    }
+
+    /// Build a tx updating the metadata of an ArchID domain
+    #[tool(description = BUILD_ARCHID_UPDATE_DATA_MSG_DESCR)]
+    async fn build_archid_update_data_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "domain to be updated (e.g. 'alice' or 'alice.arch')")]
+        name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified metadata update (e.g. '{\"description\": \"...\", \"image\": \"...\", \"accounts\": [], \"websites\": []}')"
+        )]
+        metadata_update: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally use 'Testnet' instead of 'Mainnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let Ok(metadata_update) = serde_json::from_str::<Value>(&metadata_update) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error parsing 'metadata_update', expected a JSON object",
+            )]));
+        };
+        let msg = RegistryExecuteMsg::UpdateUserDomainData {
+            name: bare_name(&name).to_string(),
+            metadata_update,
+        };
+        Ok(self.wrap_archid_execute(&network, &msg, None).await)
+    }
+
+    /// Bootstrap a query schema for a contract without a published schema
+    #[tool(description = PROBE_CONTRACT_DESCR)]
+    async fn probe_contract(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the contract to be probed (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        match self
+            .prober
+            .probe(&self.lcd_endpoint(&network), &contract_addr)
+            .await
+        {
+            Ok(report) => {
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error probing contract", e)),
+        }
+    }
+
+    /// Profile the storage gas of execute msgs in a sandbox
+    #[tool(description = PROFILE_GAS_DESCR)]
+    async fn profile_gas(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified InstantiateMsg used to instantiate the contract in the sandbox"
+        )]
+        instantiate_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally a JSON stringified array of cases to be profiled, e.g. '[{\"name\": \"transfer\", \"execute_msg\": {...}, \"sender\": \"alice\", \"funds\": []}]'; 'name', 'sender' and 'funds' are optional. Defaults to an example msg of every entry point"
+        )]
+        cases: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let Ok(instantiate_msg) = serde_json::from_str::<Value>(&instantiate_msg) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error parsing 'instantiate_msg', expected a JSON object",
+            )]));
+        };
+        let cases: Vec<SandboxCase> = match cases {
+            Some(cases) => match serde_json::from_str(&cases) {
+                Ok(cases) => cases,
+                Err(e) => return Ok(tool_error("Error parsing 'cases'", e)),
+            },
+            // The sandbox runs the contract of the default adapter
+            None => match adapter(None) {
+                Ok(adapter) => default_cases(
+                    &serde_json::from_str(&adapter.schema(SchemaKind::Execute)).unwrap_or_default(),
+                ),
+                Err(e) => return Ok(tool_error("Error selecting contract adapter", e)),
+            },
+        };
+        let profiled = tokio::task::spawn_blocking(move || {
+            crate::sandbox::profile_gas(&instantiate_msg, &cases)
+        })
+        .await;
+        match profiled {
+            Ok(Ok(profiles)) => {
+                let serialized: String = serde_json::to_string(&profiles).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(Err(e)) => Ok(tool_error("Error profiling gas", e)),
+            Err(e) => Ok(tool_error("Error profiling gas", e)),
+        }
+    }
+
+    /// Turn execute msgs into a cw-multi-test regression test
+    #[tool(description = GENERATE_TEST_FIXTURE_DESCR)]
+    async fn generate_test_fixture(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified InstantiateMsg used to instantiate the contract in the test"
+        )]
+        instantiate_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified array of cases run by the test, in order and in the same format as 'gas__profile'"
+        )]
+        cases: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally the name of the test (defaults to 'agent_scenario')")]
+        test_name: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let Ok(instantiate_msg) = serde_json::from_str::<Value>(&instantiate_msg) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error parsing 'instantiate_msg', expected a JSON object",
+            )]));
+        };
+        let cases: Vec<SandboxCase> = match serde_json::from_str(&cases) {
+            Ok(cases) => cases,
+            Err(e) => return Ok(tool_error("Error parsing 'cases'", e)),
+        };
+        let test_name = test_name.unwrap_or_else(|| "agent_scenario".to_string());
+        let generated = tokio::task::spawn_blocking(move || {
+            let outcomes = record_outcomes(&instantiate_msg, &cases)?;
+            generate(&test_name, &instantiate_msg, &cases, outcomes)
+        })
+        .await;
+        match generated {
+            Ok(Ok(fixture)) => {
+                let serialized: String = serde_json::to_string(&fixture).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(Err(e)) => Ok(tool_error("Error generating test fixture", e)),
+            Err(e) => Ok(tool_error("Error generating test fixture", e)),
+        }
+    }
+
+    /// Stress test the contract with randomized executes in a sandbox
+    #[tool(description = LOAD_TEST_DESCR)]
+    async fn load_test(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified InstantiateMsg used to instantiate the contract in the sandbox"
+        )]
+        instantiate_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified array of cases to pick from at random, in the same format as 'gas__profile'. String values '$random_amount' and '$random_sender' are replaced with a random amount and sender in every execution"
+        )]
+        cases: String,
+        #[tool(param)]
+        #[schemars(description = "number of executes to run (max 10000)")]
+        executions: usize,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the number of executes in flight at once (defaults to 16, max 256)"
+        )]
+        concurrency: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the senders to pick from at random (e.g. ['alice', 'bob']), used for cases without a sender and for '$random_sender'"
+        )]
+        senders: Option<Vec<String>>,
+    ) -> Result<CallToolResult, Error> {
+        let Ok(instantiate_msg) = serde_json::from_str::<Value>(&instantiate_msg) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error parsing 'instantiate_msg', expected a JSON object",
+            )]));
+        };
+        let cases: Vec<SandboxCase> = match serde_json::from_str(&cases) {
+            Ok(cases) => cases,
+            Err(e) => return Ok(tool_error("Error parsing 'cases'", e)),
+        };
+        let senders = senders.unwrap_or_else(|| vec![SANDBOX_OWNER.to_string()]);
+        let report = crate::loadtest::run(
+            instantiate_msg,
+            cases,
+            executions,
+            concurrency.unwrap_or(16),
+            senders,
+        )
+        .await;
+        match report {
+            Ok(report) => {
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error running load test", e)),
+        }
+    }
+
+    /// Build a permit sign doc for wallet signing
+    #[tool(description = BUILD_PERMIT_DESCR)]
+    async fn build_permit(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "name of the permit (e.g. 'agent-balance-permit')")]
+        permit_name: String,
+        #[tool(param)]
+        #[schemars(description = "contract addresses the permit can be used with")]
+        allowed_contracts: Vec<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "permissions granted by the permit (e.g. ['balance', 'history'])"
+        )]
+        permissions: Vec<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the permit format, 'Snip24' or 'Adr036' (defaults to Snip24)"
+        )]
+        format: Option<PermitFormat>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the chain id the permit is valid for (defaults to the chain id of the first allowed contract, if registered, otherwise mainnet)"
+        )]
+        chain_id: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "address of the signer (required for the Adr036 format)")]
+        signer: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let chain_id = chain_id.unwrap_or_else(|| {
+            let network = allowed_contracts
+                .first()
+                .map(|contract_addr| self.network_for(contract_addr))
+                .unwrap_or(Network::Mainnet);
+            self.contracts
+                .on(&network)
+                .map(|contract| contract.chain_id.clone())
+                .unwrap_or_default()
+        });
+        let params = PermitParams {
+            permit_name,
+            allowed_tokens: allowed_contracts,
+            chain_id,
+            permissions,
+        };
+        match sign_request(
+            format.unwrap_or(PermitFormat::Snip24),
+            params,
+            signer.as_deref(),
+        ) {
+            Ok(request) => {
+                let serialized: String = serde_json::to_string(&request).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error building permit: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Embed a signed permit into a query or execute msg
+    #[tool(description = EMBED_PERMIT_SIGNATURE_DESCR)]
+    async fn embed_permit_signature(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "address of the contract the msg is sent to")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified permit 'params', exactly as returned by 'permit__build'"
+        )]
+        permit_params: String,
+        #[tool(param)]
+        #[schemars(description = "base64 encoded secp256k1 public key of the signer")]
+        pub_key: String,
+        #[tool(param)]
+        #[schemars(description = "base64 encoded signature returned by the wallet")]
+        signature: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified query (or execute) msg to be sent with the permit"
+        )]
+        msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally embed the permit in an execute msg instead of a query msg (defaults to false)"
+        )]
+        execute: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the key wrapping the msg and permit (defaults to 'with_permit')"
+        )]
+        wrapper_key: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        if let Err(result) = self.guard_network(&contract_addr, self.session_defaults().network) {
+            return Ok(result);
+        }
+        let params: PermitParams = match serde_json::from_str(&permit_params) {
+            Ok(params) => params,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Error parsing 'permit_params': {}",
+                    e
+                ))]));
+            }
+        };
+        let Ok(msg_json) = serde_json::from_str::<Value>(&msg) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error parsing 'msg', expected a JSON object",
+            )]));
+        };
+        let permit = Permit {
+            params,
+            signature: PermitSignature {
+                pub_key: PermitPubKey {
+                    key_type: "tendermint/PubKeySecp256k1".to_string(),
+                    value: pub_key,
+                },
+                signature,
+            },
+        };
+        let execute = execute.unwrap_or(false);
+        let msg_key = if execute { "msg" } else { "query" };
+        let wrapper_key = wrapper_key.unwrap_or(PERMIT_WRAPPER_KEY.to_string());
+        let wrapped = match embed(msg_json, &permit, msg_key, &wrapper_key) {
+            Ok(wrapped) => wrapped,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Error embedding permit: {}",
+                    e
+                ))]));
+            }
+        };
+        if execute {
+            Ok(self.wrap_execute(contract_addr, wrapped.to_string(), &wrapped, vec![], vec![]))
+        } else {
+            Ok(self.wrap_query(contract_addr, wrapped.to_string(), &wrapped, vec![], vec![]))
+        }
+    }
+
+    /// Replay a historical tx on a sandbox restored from chain state
+    #[tool(description = REPLAY_TX_DESCR)]
+    async fn replay_tx(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "hash of the tx to be replayed")]
+        tx_hash: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the tx, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract whose executes are replayed (defaults to the contract of the tx's first MsgExecuteContract)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally a JSON stringified InstantiateMsg used to create the sandbox contract before its state is restored (defaults to the contract's instantiate msg on chain)"
+        )]
+        instantiate_msg: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let endpoint = &self.lcd_endpoint(&network);
+        let tx = match self.replayer.tx(endpoint, &tx_hash).await {
+            Ok(tx) => tx,
+            Err(e) => return Ok(tool_error("Error fetching tx", e)),
+        };
+        let Some(contract_addr) =
+            contract_addr.or_else(|| tx.executes.first().map(|execute| execute.contract.clone()))
+        else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error replaying tx, it doesn't contain a MsgExecuteContract",
+            )]));
+        };
+        let instantiate_msg = match instantiate_msg {
+            Some(msg) => match serde_json::from_str::<Value>(&msg) {
+                Ok(msg) => msg,
+                Err(_) => {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "Error parsing 'instantiate_msg', expected a JSON object",
+                    )]));
+                }
+            },
+            None => match self
+                .replayer
+                .instantiate_msg(endpoint, &contract_addr)
+                .await
+            {
+                Ok(msg) => msg,
+                Err(e) => return Ok(tool_error("Error fetching instantiate msg", e)),
+            },
+        };
+        let snapshot = match self
+            .replayer
+            .snapshot(endpoint, &contract_addr, tx.height.saturating_sub(1))
+            .await
+        {
+            Ok(snapshot) => snapshot,
+            Err(e) => return Ok(tool_error("Error loading contract state", e)),
+        };
+        let replayed = tokio::task::spawn_blocking(move || {
+            crate::replay::replay(&instantiate_msg, &tx, &contract_addr, &snapshot)
+        })
+        .await;
+        match replayed {
+            Ok(Ok(report)) => {
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(Err(e)) => Ok(tool_error("Error replaying tx", e)),
+            Err(e) => Ok(tool_error("Error replaying tx", e)),
+        }
+    }
+
+    /// Scan the events emitted by a contract over a block range
+    #[tool(description = SCAN_EVENTS_DESCR)]
+    async fn scan_events(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract whose events are scanned (defaults to the session's default contract, otherwise the deployment on the network; ignored when resuming from a cursor)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the first block height of the range (required unless resuming from a cursor)"
+        )]
+        from_height: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the last block height of the range (defaults to the latest block)"
+        )]
+        to_height: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally only return events of this type, e.g. 'wasm' or a custom 'wasm-{type}' event"
+        )]
+        event_type: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the 'next_cursor' returned by a previous scan, to continue where it stopped"
+        )]
+        cursor: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let cursor = match cursor {
+            Some(cursor) => match ScanCursor::decode(&cursor) {
+                Ok(cursor) => cursor,
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Error parsing 'cursor': {}",
+                        e
+                    ))]));
+                }
+            },
+            None => {
+                let Some(from_height) = from_height else {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "Error scanning events, 'from_height' is required without a 'cursor'",
+                    )]));
+                };
+                let (contract_addr, network) = match self.target(contract_addr, network.clone()) {
+                    Ok(target) => target,
+                    Err(result) => return Ok(result),
+                };
+                let to_height = match to_height {
+                    Some(height) => height,
+                    None => match self
+                        .events
+                        .latest_height(&self.lcd_endpoint(&network))
+                        .await
+                    {
+                        Ok(height) => height,
+                        Err(e) => return Ok(tool_error("Error fetching latest height", e)),
+                    },
+                };
+                ScanCursor {
+                    contract_addr,
+                    from_height,
+                    to_height,
+                    page: 1,
+                }
+            }
+        };
+        let network = network.unwrap_or_else(|| self.network_for(&cursor.contract_addr));
+        match self
+            .events
+            .scan(&self.lcd_endpoint(&network), cursor, event_type.as_deref())
+            .await
+        {
+            Ok(scan) => {
+                let serialized: String = serde_json::to_string(&scan).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error scanning events", e)),
+        }
+    }
+
+    /// Encrypt a key into the keystore
+    #[tool(description = IMPORT_SIGNER_KEY_DESCR)]
+    async fn import_signer_key(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "name the key is stored (and unlocked) under")]
+        name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "secret reference of the key, e.g. 'env:SIGNER_MNEMONIC' or 'file:/run/secrets/signer' (never the key itself)"
+        )]
+        key_ref: String,
+        #[tool(param)]
+        #[schemars(description = "passphrase the key is encrypted with")]
+        passphrase: String,
+    ) -> Result<CallToolResult, Error> {
+        let key = match self.secrets.resolve(&key_ref).await {
+            Ok(key) => key,
+            Err(e) => return Ok(tool_error("Error loading key", e)),
+        };
+        // Encrypting (scrypt) and writing the keystore block
+        let (keystore, signer) = (self.keystore.clone(), self.signer.clone());
+        let imported = tokio::task::spawn_blocking(move || {
+            keystore
+                .import(&name, &key, &passphrase)
+                .map(|_| signer.status(&keystore))
+        })
+        .await;
+        match imported {
+            Ok(Ok(status)) => {
+                let serialized: String = serde_json::to_string(&status).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(Err(e)) => Ok(tool_error("Error importing key", e)),
+            Err(e) => Ok(tool_error("Error importing key", e)),
+        }
+    }
+
+    /// Decrypt a keystore key for signing, until it auto-locks
+    #[tool(description = UNLOCK_SIGNER_DESCR)]
+    async fn unlock_signer(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "name of the key to be unlocked")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "passphrase the key is encrypted with")]
+        passphrase: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the number of seconds until the signer locks again (defaults to 300, max 3600)"
+        )]
+        timeout_secs: Option<u64>,
+    ) -> Result<CallToolResult, Error> {
+        let timeout = timeout_secs
+            .unwrap_or(SIGNER_AUTO_LOCK_SECS)
+            .clamp(1, MAX_SIGNER_AUTO_LOCK_SECS);
+        // Reading the keystore and decrypting (scrypt) block
+        let (keystore, signer) = (self.keystore.clone(), self.signer.clone());
+        let unlocked = tokio::task::spawn_blocking(move || {
+            signer
+                .unlock(&keystore, &name, &passphrase, Duration::from_secs(timeout))
+                .map(|_| signer.status(&keystore))
+        })
+        .await;
+        match unlocked {
+            Ok(Ok(status)) => {
+                let serialized: String = serde_json::to_string(&status).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(Err(e)) => Ok(tool_error("Error unlocking signer", e)),
+            Err(e) => Ok(tool_error("Error unlocking signer", e)),
+        }
+    }
+
+    /// Lock the unlocked signer
+    #[tool(description = LOCK_SIGNER_DESCR)]
+    async fn lock_signer(&self) -> Result<CallToolResult, Error> {
+        self.signer.lock();
+        let status = self.signer.status(&self.keystore);
+        let serialized: String = serde_json::to_string(&status).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// List the authz grants given (or received) by an address
+    #[tool(description = LIST_AUTHZ_GRANTS_DESCR)]
+    async fn list_authz_grants(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "address whose grants are listed")]
+        address: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally list grants received by the address as 'Grantee', instead of grants given as 'Granter' (defaults to Granter)"
+        )]
+        role: Option<GrantRole>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let role = role.unwrap_or(GrantRole::Granter);
+        match self
+            .authz
+            .grants_of(&self.lcd_endpoint(&network), &address, &role)
+            .await
+        {
+            Ok(grants) => {
+                let serialized: String = serde_json::to_string(&grants).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error listing authz grants", e)),
+        }
+    }
+
+    /// Build an authz MsgRevoke
+    #[tool(description = BUILD_AUTHZ_REVOKE_MSG_DESCR)]
+    async fn build_authz_revoke_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "address that gave the grant (and signs the revoke)")]
+        granter: String,
+        #[tool(param)]
+        #[schemars(description = "address the grant was given to")]
+        grantee: String,
+        #[tool(param)]
+        #[schemars(
+            description = "message type of the grant to be revoked (e.g. '/cosmwasm.wasm.v1.MsgExecuteContract')"
+        )]
+        msg_type_url: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let mut warnings = vec![];
+        match self
+            .authz
+            .grants_between(&self.lcd_endpoint(&network), &granter, &grantee)
+            .await
+        {
+            Ok(grants) => {
+                if !grants.iter().any(|grant| grant.covers(&msg_type_url, None)) {
+                    warnings.push(format!(
+                        "{} has no grant to {} for {}, so there's nothing to revoke",
+                        granter, grantee, msg_type_url
+                    ));
+                }
+            }
+            Err(e) => warnings.push(format!("grants couldn't be checked: {}", e)),
+        }
+        let msg = msg_revoke(&granter, &grantee, &msg_type_url);
+        Ok(self.wrap_sdk_msg(&network, msg, warnings))
+    }
+
+    /// Build an authz MsgExec executing the contract on behalf of a granter
+    #[tool(description = BUILD_AUTHZ_EXEC_MSG_DESCR)]
+    async fn build_authz_exec_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the default network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "address that gave the grant, and executes the contract")]
+        granter: String,
+        #[tool(param)]
+        #[schemars(description = "address that was given the grant, and signs the MsgExec")]
+        grantee: String,
+        #[tool(param)]
+        #[schemars(description = "ExecuteMsg variant and its values, as for 'execute__build_msg'")]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally include native funds sent with the execute")]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally include the denom of the native funds")]
+        payment_denom: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, _) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let funds: Vec<Coin> = match native_funds(payment, payment_denom, None) {
+            Ok(funds) => funds,
+            Err(e) => return Ok(tool_error("Error building funds", e)),
+        };
+        let (msg_json, notes) = match self.contract_adapter(&contract_addr).parse(
+            SchemaKind::Execute,
+            "Error parsing 'execute_msg'",
+            &execute_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        if let Err(e) = self
+            .spend
+            .check(&spend_of(&contract_addr, &msg_json, &funds))
+        {
+            return Ok(tool_error("Error building transaction", e));
+        }
+        let network = self.network_for(&contract_addr);
+        let denom_warnings = match self.verify_funds(&network, &funds).await {
+            Ok(denom_warnings) => denom_warnings,
+            Err(result) => return Ok(result),
+        };
+        let mut warnings = self
+            .authz
+            .exec_warnings(
+                &self.lcd_endpoint(&network),
+                &granter,
+                &grantee,
+                type_url::MSG_EXECUTE_CONTRACT,
+                Some(&contract_addr),
+            )
+            .await;
+        warnings.extend(notes);
+        warnings.extend(denom_warnings);
+        let execute = msg_execute_contract(&granter, &contract_addr, &msg_json, &funds);
+        Ok(self.wrap_sdk_msg(&network, msg_exec(&grantee, vec![execute]), warnings))
+    }
+
+    /// Build an x/group proposal executing the contract as a group policy
+    #[tool(description = BUILD_GROUP_PROPOSAL_MSG_DESCR)]
+    async fn build_group_proposal_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the default network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "address of the group policy (account) that executes the contract"
+        )]
+        group_policy_address: String,
+        #[tool(param)]
+        #[schemars(description = "address of the group member submitting the proposal")]
+        proposer: String,
+        #[tool(param)]
+        #[schemars(description = "ExecuteMsg variant and its values, as for 'execute__build_msg'")]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(description = "title of the proposal")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally a summary of the proposal")]
+        summary: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally include native funds sent with the execute")]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally include the denom of the native funds")]
+        payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally try to execute the proposal as soon as it passes (defaults to false)"
+        )]
+        try_exec: Option<bool>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, _) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let funds: Vec<Coin> = match native_funds(payment, payment_denom, None) {
+            Ok(funds) => funds,
+            Err(e) => return Ok(tool_error("Error building funds", e)),
+        };
+        let (msg_json, notes) = match self.contract_adapter(&contract_addr).parse(
+            SchemaKind::Execute,
+            "Error parsing 'execute_msg'",
+            &execute_msg,
+        ) {
+            Ok(parsed) => parsed,
+            Err(result) => return Ok(result),
+        };
+        if let Err(e) = self
+            .spend
+            .check(&spend_of(&contract_addr, &msg_json, &funds))
+        {
+            return Ok(tool_error("Error building transaction", e));
+        }
+        let network = self.network_for(&contract_addr);
+        let denom_warnings = match self.verify_funds(&network, &funds).await {
+            Ok(denom_warnings) => denom_warnings,
+            Err(result) => return Ok(result),
+        };
+        let proposers = vec![proposer];
+        let mut warnings = self
+            .groups
+            .proposal_warnings(
+                &self.lcd_endpoint(&network),
+                &group_policy_address,
+                &proposers,
+            )
+            .await;
+        warnings.extend(notes);
+        warnings.extend(denom_warnings);
+        let execute =
+            msg_execute_contract(&group_policy_address, &contract_addr, &msg_json, &funds);
+        let msg = msg_group_submit_proposal(
+            &group_policy_address,
+            &proposers,
+            vec![execute],
+            &title,
+            &summary.unwrap_or_default(),
+            try_exec.unwrap_or(false),
+        );
+        Ok(self.wrap_sdk_msg(&network, msg, warnings))
+    }
+
+    /// Build an x/group vote
+    #[tool(description = BUILD_GROUP_VOTE_MSG_DESCR)]
+    async fn build_group_vote_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the group proposal")]
+        proposal_id: u64,
+        #[tool(param)]
+        #[schemars(description = "address of the group member voting")]
+        voter: String,
+        #[tool(param)]
+        #[schemars(description = "vote, one of 'Yes', 'Abstain', 'No' or 'NoWithVeto'")]
+        option: VoteOption,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally try to execute the proposal if this vote makes it pass (defaults to false)"
+        )]
+        try_exec: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let warnings = self
+            .groups
+            .vote_warnings(&self.lcd_endpoint(&network), proposal_id, &voter)
+            .await;
+        let msg = msg_group_vote(proposal_id, &voter, &option, try_exec.unwrap_or(false));
+        Ok(self.wrap_sdk_msg(&network, msg, warnings))
+    }
+
+    /// Build an x/group exec of an accepted proposal
+    #[tool(description = BUILD_GROUP_EXEC_MSG_DESCR)]
+    async fn build_group_exec_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the accepted group proposal")]
+        proposal_id: u64,
+        #[tool(param)]
+        #[schemars(description = "address signing the exec (any address can execute)")]
+        executor: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let warnings = self
+            .groups
+            .exec_warnings(&self.lcd_endpoint(&network), proposal_id)
+            .await;
+        Ok(self.wrap_sdk_msg(&network, msg_group_exec(proposal_id, &executor), warnings))
+    }
+
+    /// List governance proposals
+    #[tool(description = LIST_GOV_PROPOSALS_DESCR)]
+    async fn list_gov_proposals(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the status of the proposals, one of 'DepositPeriod', 'VotingPeriod', 'Passed', 'Rejected' or 'Failed' (defaults to VotingPeriod)"
+        )]
+        status: Option<ProposalStatus>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let status = status.unwrap_or(ProposalStatus::VotingPeriod);
+        let contracts: Vec<String> = self
+            .contracts
+            .all()
+            .iter()
+            .map(|contract| contract.contract_address.clone())
+            .collect();
+        match self
+            .gov
+            .proposals(&self.lcd_endpoint(&network), &status, &contracts)
+            .await
+        {
+            Ok(proposals) => {
+                let serialized: String = serde_json::to_string(&proposals).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error listing governance proposals", e)),
+        }
+    }
+
+    /// Fetch the tally of a governance proposal
+    #[tool(description = GET_GOV_TALLY_DESCR)]
+    async fn get_gov_tally(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the governance proposal")]
+        proposal_id: u64,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        match self
+            .gov
+            .tally(&self.lcd_endpoint(&network), proposal_id)
+            .await
+        {
+            Ok(tally) => {
+                let serialized: String = serde_json::to_string(&tally).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error fetching tally", e)),
+        }
+    }
+
+    /// Build a governance MsgVote
+    #[tool(description = BUILD_GOV_VOTE_MSG_DESCR)]
+    async fn build_gov_vote_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the governance proposal")]
+        proposal_id: u64,
+        #[tool(param)]
+        #[schemars(description = "address of the voter (signs the vote)")]
+        voter: String,
+        #[tool(param)]
+        #[schemars(description = "vote, one of 'Yes', 'Abstain', 'No' or 'NoWithVeto'")]
+        option: VoteOption,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let warnings = self
+            .gov
+            .status_warnings(
+                &self.lcd_endpoint(&network),
+                proposal_id,
+                &[ProposalStatus::VotingPeriod],
+            )
+            .await;
+        Ok(self.wrap_sdk_msg(
+            &network,
+            msg_gov_vote(proposal_id, &voter, &option),
+            warnings,
+        ))
+    }
+
+    /// Build a governance MsgDeposit
+    #[tool(description = BUILD_GOV_DEPOSIT_MSG_DESCR)]
+    async fn build_gov_deposit_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the governance proposal")]
+        proposal_id: u64,
+        #[tool(param)]
+        #[schemars(description = "address of the depositor (signs the deposit)")]
+        depositor: String,
+        #[tool(param)]
+        #[schemars(description = "amount deposited, in the smallest unit of the denom")]
+        amount: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the session's default network, otherwise Mainnet); deposits are made in its native denom"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        let amount = match Uint128::from_str(&amount) {
+            Ok(amount) if !amount.is_zero() => amount,
+            _ => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Invalid amount '{}', expected a positive integer in the smallest unit of the denom",
+                    amount
+                ))]));
+            }
+        };
+        let deposit = vec![Coin {
+            denom: network.native_denom().to_string(),
+            amount,
+        }];
+        if let Err(e) = self.spend.check(&spend_of("", &Value::Null, &deposit)) {
+            return Ok(tool_error("Error building transaction", e));
+        }
+        let warnings = self
+            .gov
+            .status_warnings(
+                &self.lcd_endpoint(&network),
+                proposal_id,
+                &[ProposalStatus::DepositPeriod, ProposalStatus::VotingPeriod],
+            )
+            .await;
+        Ok(self.wrap_sdk_msg(
+            &network,
+            msg_gov_deposit(proposal_id, &depositor, &deposit),
+            warnings,
+        ))
+    }
+
+    /// Build a Stargate query for Archway specific modules
+    #[tool(description = BUILD_STARGATE_QUERY_DESCR)]
+    async fn build_stargate_query(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Archway query and its values, e.g. {\"rewards_contract_metadata\": {\"contract_address\": \"archway1...\"}}"
+        )]
+        query: ArchwayQuery,
+    ) -> Result<CallToolResult, Error> {
+        match crate::stargate::build(&query) {
+            Ok(query) => {
+                let serialized: String = serde_json::to_string(&query).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error building Stargate query: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Discover the contracts the contract depends on
+    #[tool(description = DISCOVER_DEPENDENCIES_DESCR)]
+    async fn discover_dependencies(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract to start from (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the number of hops to follow from the contract (defaults to 1, max 3)"
+        )]
+        depth: Option<u8>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        match self
+            .graph
+            .discover(
+                &self.lcd_endpoint(&network),
+                &contract_addr,
+                depth.unwrap_or(1),
+            )
+            .await
+        {
+            Ok(graph) => {
+                let serialized: String = serde_json::to_string(&graph).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error discovering dependencies", e)),
+        }
+    }
+
+    #[tool(description = SUPPLY_REPORT_DESCR)]
+    async fn supply_report(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the token contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        match self
+            .supply
+            .report(&self.lcd_endpoint(&network), &contract_addr)
+            .await
+        {
+            Ok(report) => {
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error building supply report", e)),
+        }
+    }
+
+    #[tool(description = SUPPLY_HISTORY_DESCR)]
+    async fn supply_history(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the token contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the height of the first sample (defaults to DEFAULT_SAMPLES intervals before 'to_height')"
+        )]
+        from_height: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the height of the last sample (defaults to the latest block)"
+        )]
+        to_height: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the blocks between samples (defaults to DEFAULT_SAMPLE_INTERVAL)"
+        )]
+        interval: Option<u64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally whether holders are counted at each sample (defaults to true), counting sends a query per page of holders"
+        )]
+        include_holders: Option<bool>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let endpoint = &self.lcd_endpoint(&network);
+        let to_height = match to_height {
+            Some(height) => height,
+            None => match self.events.latest_height(endpoint).await {
+                Ok(height) => height,
+                Err(e) => return Ok(tool_error("Error querying latest height", e)),
+            },
+        };
+        let interval = interval.unwrap_or(DEFAULT_SAMPLE_INTERVAL);
+        let from_height = from_height.unwrap_or_else(|| {
+            to_height
+                .saturating_sub(interval.saturating_mul(DEFAULT_SAMPLES - 1))
+                .max(1)
+        });
+        match self
+            .history
+            .series(
+                endpoint,
+                &contract_addr,
+                from_height,
+                to_height,
+                interval,
+                include_holders.unwrap_or(true),
+            )
+            .await
+        {
+            Ok(series) => {
+                let serialized: String = serde_json::to_string(&series).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error sampling supply history", e)),
+        }
+    }
+
+    #[tool(description = WRAP_DESCR)]
+    async fn wrap(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "amount of native tokens to wrap, in the smallest unit of the denom"
+        )]
+        amount: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract to deposit to (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let funds = vec![Coin {
+            denom: network.wrapped_denom().to_string(),
+            amount: Uint128::from_str(&amount).unwrap_or_default(),
+        }];
+        Ok(self.wrap_msg(contract_addr, json!({ "deposit": {} }), &amount, funds))
+    }
+
+    #[tool(description = UNWRAP_DESCR)]
+    async fn unwrap(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "amount of cw20 tokens to unwrap, in the smallest unit of the denom"
+        )]
+        amount: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract to withdraw from (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, _) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let msg = json!({ "withdraw": { "amount": amount } });
+        Ok(self.wrap_msg(contract_addr, msg, &amount, vec![]))
+    }
+
+    #[tool(description = VERIFY_PEG_DESCR)]
+    async fn verify_peg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the wrapped token contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise Mainnet)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        match self
+            .supply
+            .peg(
+                &self.lcd_endpoint(&network),
+                &contract_addr,
+                network.wrapped_denom(),
+            )
+            .await
+        {
+            Ok(report) => {
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error verifying peg", e)),
+        }
+    }
+
+    #[tool(description = PREFLIGHT_EXECUTE_DESCR)]
+    async fn preflight_execute(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "address that will sign the transaction")]
+        sender: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the deployed contract (defaults to the session's default contract, otherwise the deployment on the default network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "ExecuteMsg variant and its values, as built by execute__build_msg"
+        )]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment funds to be sent in the transaction"
+        )]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment denom for funds being sent in the transaction"
+        )]
+        payment_denom: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, None) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let funds: Vec<Coin> = match native_funds(payment, payment_denom, None) {
+            Ok(funds) => funds,
+            Err(e) => return Ok(tool_error("Error building funds", e)),
+        };
+        let mut msg_json = match self.contract_adapter(&contract_addr).parse(
+            SchemaKind::Execute,
+            "Error parsing ExecuteMsg",
+            &execute_msg,
+        ) {
+            Ok((msg_json, _)) => msg_json,
+            Err(result) => return Ok(result),
+        };
+        let mut name_resolutions: Vec<NameResolution> = vec![];
+        let contract_addr = match self
+            .resolve_names(
+                contract_addr,
+                &network,
+                &mut msg_json,
+                &mut name_resolutions,
+            )
+            .await
+        {
+            Ok(contract_addr) => contract_addr,
+            Err(e) => return Ok(tool_error("Error resolving name", e)),
+        };
+        if let Err(result) = self.verify_funds(&network, &funds).await {
+            return Ok(result);
+        }
+        match self
+            .preflight
+            .execute(
+                &self.lcd_endpoint(&network),
+                &sender,
+                &contract_addr,
+                &msg_json,
+                &funds,
+            )
+            .await
+        {
+            Ok(mut report) => {
+                report.tx_resource =
+                    self.blobs
+                        .put("simulated_tx.bin", MIME_PROTOBUF, report.tx_bytes.clone());
+                let serialized: String = serde_json::to_string(&report).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error simulating transaction", e)),
+        }
+    }
+
+    #[tool(description = SIMULATE_EXECUTE_DESCR)]
+    async fn simulate_execute(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "address that will sign the transaction")]
+        sender: String,
+        #[tool(param)]
+        #[schemars(
+            description = "the built transaction: the result of an execute tool (e.g. execute__build_msg), or its JSON stringified 'cosmos_msg'"
+        )]
+        cosmos_msg: String,
+    ) -> Result<CallToolResult, Error> {
+        let cosmos_msg = match serde_json::from_str::<ValidatedExecute>(&cosmos_msg) {
+            Ok(execute) => execute.cosmos_msg,
+            Err(_) => cosmos_msg,
+        };
+        let (contract_addr, msg, funds) = match wasm_execute(&cosmos_msg) {
+            Ok(execute) => execute,
+            Err(e) => return Ok(tool_error("Error parsing CosmosMsg", e)),
+        };
+        let network = match self.check_network(&contract_addr, self.session_defaults().network) {
+            Ok(network) => network,
+            Err(e) => return Ok(tool_error("Error checking network", e)),
+        };
+        let msg_json = match serde_json::from_slice::<Value>(&msg) {
+            Ok(msg_json) => msg_json,
+            Err(e) => return Ok(tool_error("Error parsing CosmosMsg", e)),
+        };
+        let fee_denom = self
+            .contracts
+            .on(&network)
+            .map(|contract| contract.fee_denom.clone())
+            .unwrap_or_else(|| network.native_denom().to_string());
+        match self
+            .preflight
+            .execute(
+                &self.lcd_endpoint(&network),
+                &sender,
+                &contract_addr,
+                &msg_json,
+                &funds,
+            )
+            .await
+        {
+            Ok(report) => {
+                let estimate = GasEstimate {
+                    sender: report.sender,
+                    contract: report.contract,
+                    will_succeed: report.will_succeed,
+                    gas_used: report.gas_used,
+                    suggested_gas_limit: report.suggested_gas_limit,
+                    gas_price: GAS_PRICE.to_string(),
+                    suggested_fee: report
+                        .suggested_gas_limit
+                        .map(|gas_limit| fee(gas_limit, &fee_denom)),
+                    contract_error: report.contract_error,
+                    error: report.error,
+                };
+                let serialized: String = serde_json::to_string(&estimate).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error simulating transaction", e)),
+        }
+    }
+
+    /// Run the same query or simulation against the mainnet and testnet
+    /// deployments, and diff their results
+    #[tool(description = COMPARE_DEPLOYMENTS_DESCR)]
+    async fn compare_deployments(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally a JSON stringified QueryMsg variant to query both deployments with"
+        )]
+        query_msg: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally a JSON stringified ExecuteMsg variant to simulate on both deployments"
+        )]
+        execute_msg: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "address simulating the execute_msg (required with execute_msg)")]
+        sender: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address simulating the execute_msg on testnet (defaults to sender)"
+        )]
+        testnet_sender: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally native funds sent with the execute_msg, in the base unit of each network's native denom"
+        )]
+        payment: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let contracts = self.contracts.all();
+        let deployment = |network: Network| {
+            contracts
+                .iter()
+                .find(|contract| contract.network == network)
+                .map(|contract| contract.contract_address.clone())
+                .ok_or(format!("no {:?} deployment is registered", network))
+        };
+        let (mainnet_contract, testnet_contract) = match deployment(Network::Mainnet)
+            .and_then(|mainnet| deployment(Network::Testnet).map(|testnet| (mainnet, testnet)))
+        {
+            Ok(deployments) => deployments,
+            Err(e) => return Ok(tool_error("Error comparing deployments", e)),
+        };
+        let error_value = |e: anyhow::Error| json!({ "error": e.to_string() });
+        let comparison = match (query_msg, execute_msg) {
+            (Some(query_msg), None) => {
+                let (deserialized, warnings): (QueryMsg, Vec<String>) =
+                    match Self::parse_msg("Error building QueryMsg", &query_msg, "query:json") {
+                        Ok(parsed) => parsed,
+                        Err(result) => return Ok(result),
+                    };
+                let msg_json: Value = serde_json::to_value(&deserialized).unwrap_or_default();
+                let (mainnet, testnet) = tokio::join!(
+                    self.lcd.smart_query(
+                        &self.lcd_endpoint(&Network::Mainnet),
+                        &mainnet_contract,
+                        &msg_json
+                    ),
+                    self.lcd.smart_query(
+                        &self.lcd_endpoint(&Network::Testnet),
+                        &testnet_contract,
+                        &msg_json
+                    ),
+                );
+                DeploymentComparison::new(
+                    ComparisonKind::Query,
+                    msg_json.to_string(),
+                    (mainnet_contract, mainnet.unwrap_or_else(error_value)),
+                    (testnet_contract, testnet.unwrap_or_else(error_value)),
+                    warnings,
+                )
+            }
+            (None, Some(execute_msg)) => {
+                let Some(sender) = sender else {
+                    return Ok(tool_error(
+                        "Error comparing deployments",
+                        "missing sender, simulations need the address signing the execute_msg",
+                    ));
+                };
+                let testnet_sender = testnet_sender.unwrap_or_else(|| sender.clone());
+                let (deserialized, warnings): (ExecuteMsg, Vec<String>) = match Self::parse_msg(
+                    "Error building ExecuteMsg",
+                    &execute_msg,
+                    "execute:json",
+                ) {
+                    Ok(parsed) => parsed,
+                    Err(result) => return Ok(result),
+                };
+                let msg_json: Value = serde_json::to_value(&deserialized).unwrap_or_default();
+                let funds = |network: Network| {
+                    native_funds(
+                        payment.clone(),
+                        payment.as_ref().map(|_| network.native_denom().to_string()),
+                        None,
+                    )
+                };
+                let (mainnet_funds, testnet_funds) = match funds(Network::Mainnet)
+                    .and_then(|mainnet| funds(Network::Testnet).map(|testnet| (mainnet, testnet)))
+                {
+                    Ok(funds) => funds,
+                    Err(e) => return Ok(tool_error("Error building funds", e)),
+                };
+                let (mainnet, testnet) = tokio::join!(
+                    self.preflight.execute(
+                        &self.lcd_endpoint(&Network::Mainnet),
+                        &sender,
+                        &mainnet_contract,
+                        &msg_json,
+                        &mainnet_funds,
+                    ),
+                    self.preflight.execute(
+                        &self.lcd_endpoint(&Network::Testnet),
+                        &testnet_sender,
+                        &testnet_contract,
+                        &msg_json,
+                        &testnet_funds,
+                    ),
+                );
+                let gas_used = |report: &anyhow::Result<PreflightReport>| {
+                    report.as_ref().ok().and_then(|report| report.gas_used)
+                };
+                let (mainnet_gas_used, testnet_gas_used) = (gas_used(&mainnet), gas_used(&testnet));
+                let mut comparison = DeploymentComparison::new(
+                    ComparisonKind::Simulation,
+                    msg_json.to_string(),
+                    (
+                        mainnet_contract,
+                        mainnet.map_or_else(error_value, |report| simulation_outcome(&report)),
+                    ),
+                    (
+                        testnet_contract,
+                        testnet.map_or_else(error_value, |report| simulation_outcome(&report)),
+                    ),
+                    warnings,
+                );
+                comparison.mainnet_gas_used = mainnet_gas_used;
+                comparison.testnet_gas_used = testnet_gas_used;
+                comparison
+            }
+            _ => {
+                return Ok(tool_error(
+                    "Error comparing deployments",
+                    "set either query_msg or execute_msg",
+                ));
+            }
+        };
+        let serialized: String = serde_json::to_string(&comparison).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    /// Report the congestion of the network's mempool
+    #[tool(description = GET_MEMPOOL_STATUS_DESCR)]
+    async fn get_mempool_status(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the default network)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let network = network
+            .or(self.session_defaults().network)
+            .unwrap_or(Network::Mainnet);
+        match self.mempool.status(&self.rpc_endpoint(&network)).await {
+            Ok(status) => {
+                let serialized: String = serde_json::to_string(&status).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error reading mempool", e)),
+        }
+    }
+
+    /// Suggest a fee from the fees of recent executions
+    #[tool(description = SUGGEST_FEE_DESCR)]
+    async fn suggest_fee(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the address of the contract whose recent executions are sampled (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the gas limit of the tx (e.g. the 'suggested_gas_limit' of execute__simulate), to get the fee to attach to it"
+        )]
+        gas_limit: Option<u64>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let fee_denom = self
+            .contracts
+            .on(&network)
+            .map(|contract| contract.fee_denom.clone())
+            .unwrap_or_else(|| network.native_denom().to_string());
+        // Fees are suggested from recent fees alone if the mempool can't
+        // be read
+        let mempool = self.mempool.status(&self.rpc_endpoint(&network)).await.ok();
+        match self
+            .fees
+            .suggest(
+                &self.lcd_endpoint(&network),
+                &contract_addr,
+                &fee_denom,
+                gas_limit,
+                mempool,
+            )
+            .await
+        {
+            Ok(suggestion) => {
+                let serialized: String = serde_json::to_string(&suggestion).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error sampling recent fees", e)),
+        }
+    }
+
+    #[tool(description = SET_AMOUNT_FORMAT_DESCR)]
+    async fn set_amount_format(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "'Base' (chain units, e.g. aarch), 'Display' (e.g. ARCH) or 'Both'"
+        )]
+        format: AmountFormat,
+    ) -> Result<CallToolResult, Error> {
+        self.amounts.set_format(format);
+        let serialized: String = serde_json::to_string(&format).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = SET_DEFAULT_CONTRACT_DESCR)]
+    async fn set_default_contract(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "address of the contract used when tools omit contract_addr (omit to clear the default contract)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network of the contract, 'Mainnet' or 'Testnet' (defaults to the network of a registered deployment, otherwise the default network)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        if let Some(contract_addr) = &contract_addr {
+            if !is_address(contract_addr) {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Error setting default contract, '{}' is not a bech32 address",
+                    contract_addr
+                ))]));
+            }
+        }
+        let network = match &contract_addr {
+            // Unlike other tools, the session's default network isn't
+            // enforced, since the default contract replaces it
+            Some(contract_addr) => match self.guard_network(contract_addr, network) {
+                Ok(network) => Some(network),
+                Err(result) => return Ok(result),
+            },
+            None => network.or(self.session_defaults().network),
+        };
+        let Ok(mut defaults) = self.defaults.write() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error accessing session defaults",
+            )]));
+        };
+        *defaults = SessionDefaults {
+            contract: contract_addr,
+            network,
+        };
+        let serialized: String = serde_json::to_string(&*defaults).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = SET_DEFAULT_NETWORK_DESCR)]
+    async fn set_default_network(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "network used when tools omit it, 'Mainnet' or 'Testnet'")]
+        network: Network,
+    ) -> Result<CallToolResult, Error> {
+        let contract = self
+            .session_defaults()
+            .contract
+            .filter(|contract_addr| self.network_for(contract_addr) == network);
+        let Ok(mut defaults) = self.defaults.write() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Error accessing session defaults",
+            )]));
+        };
+        // A default contract on another network is cleared, rather than
+        // used with the wrong chain
+        *defaults = SessionDefaults {
+            contract,
+            network: Some(network),
+        };
+        let serialized: String = serde_json::to_string(&*defaults).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = ADD_ALERT_RULE_DESCR)]
+    async fn add_alert_rule(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the rule (replaces any rule with the same id)")]
+        id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "condition raising the alert, e.g. {\"peg_below\": {\"ratio\": \"1\"}} or {\"balance_below\": {\"address\": \"archway1...\", \"threshold\": \"1000000\"}}"
+        )]
+        condition: AlertCondition,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract the condition is evaluated on (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+        #[tool(param)]
+        #[schemars(description = "Optionally a URL alerts are POSTed to (as JSON)")]
+        webhook: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally switch the server into read-only mode when the alert is raised (defaults to true for 'peg_below', 'admin_changed' and 'owner_changed')"
+        )]
+        trips_breaker: Option<bool>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        let rule = AlertRule {
+            id,
+            network,
+            contract,
+            trips_breaker: trips_breaker.unwrap_or_else(|| condition.is_anomaly()),
+            condition,
+            webhook,
+        };
+        if let Err(e) = self.alerts.evaluate(&rule).await {
+            return Ok(tool_error("Error evaluating alert rule", e));
+        }
+        self.alerts.add(rule.clone()).await;
+        let serialized: String = serde_json::to_string(&rule).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = REMOVE_ALERT_RULE_DESCR)]
+    async fn remove_alert_rule(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the rule to be removed")]
+        id: String,
+    ) -> Result<CallToolResult, Error> {
+        match self.alerts.remove(&id) {
+            Some(rule) => {
+                let serialized: String = serde_json::to_string(&rule).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            None => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error removing alert rule, no rule with id '{}'",
+                id
+            ))])),
+        }
+    }
+
+    #[tool(description = LIST_ALERTS_DESCR)]
+    async fn list_alerts(&self) -> Result<CallToolResult, Error> {
+        let serialized: String = serde_json::to_string(&self.alerts.status()).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GET_CIRCUIT_BREAKER_DESCR)]
+    async fn get_circuit_breaker(&self) -> Result<CallToolResult, Error> {
+        let serialized: String = serde_json::to_string(&self.breaker.status()).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = RESET_CIRCUIT_BREAKER_DESCR)]
+    async fn reset_circuit_breaker(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The operator token, given by the operator asking for the reset")]
+        operator_token: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let Some(reference) = OPERATOR_TOKEN else {
+            return Ok(tool_error(
+                "Error resetting circuit breaker",
+                "unauthorized, the server has no operator token (OPERATOR_TOKEN), an operator must restart it",
+            ));
+        };
+        let expected = match self.secrets.resolve(reference).await {
+            Ok(expected) => expected,
+            Err(e) => return Ok(tool_error("Error resolving operator token", e)),
+        };
+        if !operator_token
+            .as_deref()
+            .is_some_and(|given| token_matches(given, expected.expose()))
+        {
+            return Ok(tool_error(
+                "Error resetting circuit breaker",
+                "unauthorized, a valid operator token is required",
+            ));
+        }
+        let serialized: String = serde_json::to_string(&self.breaker.reset()).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = EXPORT_ACTIVITY_DESCR)]
+    async fn export_activity(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Optionally only export transactions sent by this address")]
+        sender: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally the first day exported (YYYY-MM-DD, UTC)")]
+        from_date: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally the last day exported (YYYY-MM-DD, UTC)")]
+        to_date: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally 'csv' or 'json' (defaults to csv)")]
+        format: Option<ExportFormat>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        let (contract_addr, network) = match self.target(contract_addr, network) {
+            Ok(target) => target,
+            Err(result) => return Ok(result),
+        };
+        for date in [&from_date, &to_date].into_iter().flatten() {
+            let valid = date.len() == 10
+                && date.bytes().enumerate().all(|(i, b)| {
+                    if i == 4 || i == 7 {
+                        b == b'-'
+                    } else {
+                        b.is_ascii_digit()
+                    }
+                });
+            if !valid {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Error exporting activity, invalid date '{}' (expected YYYY-MM-DD)",
+                    date
+                ))]));
+            }
+        }
+        match self
+            .activity
+            .export(
+                &self.lcd_endpoint(&network),
+                &contract_addr,
+                sender.as_deref(),
+                from_date.as_deref(),
+                to_date.as_deref(),
+                network.wrapped_denom(),
+                format.unwrap_or_default(),
+            )
+            .await
+        {
+            Ok(mut export) => {
+                let (name, mime_type) = match export.format {
+                    ExportFormat::Csv => ("activity.csv", MIME_CSV),
+                    ExportFormat::Json => ("activity.json", MIME_JSON),
+                };
+                export.resource = self
+                    .blobs
+                    .put(name, mime_type, export.data.clone().into_bytes());
+                let serialized: String = serde_json::to_string(&export).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error exporting activity", e)),
+        }
+    }
+
+    #[tool(description = TAG_ACTIVITY_DESCR)]
+    async fn tag_activity(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "tx hash of a broadcast transaction, or a built message (e.g. the 'cosmos_msg' returned by execute__build_msg)"
+        )]
+        tx: String,
+        #[tool(param)]
+        #[schemars(description = "free-form tags, e.g. ['payroll', 'q1']")]
+        tags: Vec<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the cost basis of the tokens moved by the transaction"
+        )]
+        cost_basis: Option<CostBasis>,
+        #[tool(param)]
+        #[schemars(description = "Optionally a note")]
+        note: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        match self
+            .tags
+            .tag(activity_reference(&tx), tags, cost_basis, note)
+        {
+            Ok(entry) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error tagging activity", e)),
+        }
+    }
+
+    #[tool(description = UNTAG_ACTIVITY_DESCR)]
+    async fn untag_activity(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "tx hash, built message or reference ('msg:...') of the tagged transaction"
+        )]
+        tx: String,
+    ) -> Result<CallToolResult, Error> {
+        let reference = if tx.starts_with("msg:") {
+            tx.clone()
+        } else {
+            activity_reference(&tx)
+        };
+        match self.tags.untag(&reference) {
+            Ok(Some(entry)) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Ok(None) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error untagging activity, '{}' isn't tagged",
+                reference
+            ))])),
+            Err(e) => Ok(tool_error("Error untagging activity", e)),
+        }
+    }
+
+    #[tool(description = LIST_TAGGED_ACTIVITY_DESCR)]
+    async fn list_tagged_activity(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Optionally only list activity with this tag")]
+        tag: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally only list activity tagged on or after this day (YYYY-MM-DD)"
+        )]
+        from_date: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally only list activity tagged on or before this day (YYYY-MM-DD)"
+        )]
+        to_date: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        match self
+            .tags
+            .list(tag.as_deref(), from_date.as_deref(), to_date.as_deref())
+        {
+            Ok(activity) => {
+                let serialized: String = serde_json::to_string(&activity).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error listing tagged activity", e)),
+        }
+    }
+
+    #[tool(description = RUN_WORKFLOW_DESCR)]
+    async fn run_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "workflow definition: a name and its steps")]
+        definition: WorkflowDefinition,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the contract of steps that don't name one (defaults to the session's default contract, otherwise the deployment on the network)"
+        )]
+        contract_addr: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network, 'Mainnet' or 'Testnet' (defaults to the network of contract_addr, otherwise the default network)"
+        )]
+        network: Option<Network>,
+    ) -> Result<CallToolResult, Error> {
+        if definition.steps.is_empty() || definition.steps.len() > MAX_WORKFLOW_STEPS {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error running workflow, workflows must have 1 to {} steps",
+                MAX_WORKFLOW_STEPS
+            ))]));
+        }
+        Ok(self
+            .start_workflow(definition, contract_addr, network, Default::default())
+            .await)
+    }
+
+    #[tool(description = ADVANCE_WORKFLOW_DESCR)]
+    async fn advance_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the workflow run")]
+        id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "whether the pending msg (or the compensations) were broadcast successfully"
+        )]
+        succeeded: bool,
+        #[tool(param)]
+        #[schemars(description = "Optionally the tx hash of the broadcast")]
+        tx_hash: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally the error of a failed broadcast")]
+        error: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let Some(mut run) = self.workflows.get(&id) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error advancing workflow, no workflow run with id '{}'",
+                id
+            ))]));
+        };
+        match (&run.status, run.pending.clone()) {
+            (WorkflowStatus::AwaitingBroadcast, Some(pending)) if succeeded => {
+                self.record_spend(&pending.execute, tx_hash.clone());
+                run.log.push(StepLog {
+                    step: pending.step.clone(),
+                    outcome: "broadcast".to_string(),
+                    detail: tx_hash,
+                });
+                let compensation = run
+                    .definition
+                    .steps
+                    .get(run.position)
+                    .and_then(|step| step.compensation.clone());
+                if let Some(compensation) = compensation {
+                    match self
+                        .build_workflow_msg(&run, &pending.step, &compensation)
+                        .await
+                    {
+                        Ok(compensation) => run.compensations.insert(0, compensation),
+                        Err(e) => run.log.push(StepLog {
+                            step: pending.step.clone(),
+                            outcome: "failed".to_string(),
+                            detail: Some(format!("compensation can't be built: {}", e)),
+                        }),
+                    }
+                }
+                run.pending = None;
+                run.position += 1;
+                run.status = WorkflowStatus::Running;
+                self.drive_workflow(&mut run).await;
+            }
+            (WorkflowStatus::AwaitingBroadcast, Some(pending)) => {
+                self.breaker.record_broadcast(false);
+                let error = error.unwrap_or_else(|| "broadcast failed".to_string());
+                Self::fail_workflow(&mut run, &pending.step, error);
+            }
+            (WorkflowStatus::Compensating, _) => {
+                run.log.push(StepLog {
+                    step: "compensation".to_string(),
+                    outcome: if succeeded { "broadcast" } else { "failed" }.to_string(),
+                    detail: tx_hash.or(error),
+                });
+                run.status = WorkflowStatus::Failed;
+            }
+            (status, _) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Error advancing workflow, workflow '{}' is {:?}",
+                    id, status
+                ))]));
+            }
+        }
+        self.workflows.save(&run);
+        let serialized: String = serde_json::to_string(&run).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GET_WORKFLOW_DESCR)]
+    async fn get_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the workflow run")]
+        id: String,
+    ) -> Result<CallToolResult, Error> {
+        match self.workflows.get(&id) {
+            Some(run) => {
+                let serialized: String = serde_json::to_string(&run).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            None => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error getting workflow, no workflow run with id '{}'",
+                id
+            ))])),
+        }
+    }
+
+    #[tool(description = QUEUE_EXECUTE_DESCR)]
+    async fn queue_execute(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "the confirmed transaction: the result of an execute tool, e.g. execute__build_msg"
+        )]
+        execute: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally a note for operators reviewing the queue")]
+        note: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let execute: ValidatedExecute = match serde_json::from_str(&execute) {
+            Ok(execute) => execute,
+            Err(e) => return Ok(tool_error("Error queueing transaction", e)),
+        };
+        if let Err(e) = self
+            .provenance
+            .verify(&execute.metadata, &execute.signed_payload())
+            .await
+        {
+            return Ok(tool_error("Error verifying transaction", e));
+        }
+        match self.queue.queue(execute, note) {
+            Ok(entry) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error queueing transaction", e)),
+        }
+    }
+
+    #[tool(description = LIST_EXECUTION_QUEUE_DESCR)]
+    async fn list_execution_queue(&self) -> Result<CallToolResult, Error> {
+        let serialized: String = serde_json::to_string(&json!({
+            "delay_secs": self.queue.delay_secs(),
+            "queue": self.queue.list(),
+        }))
+        .unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = RELEASE_QUEUED_EXECUTE_DESCR)]
+    async fn release_queued_execute(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the queued transaction")]
+        id: String,
+    ) -> Result<CallToolResult, Error> {
+        let held = self.queue.list().into_iter().find(|entry| entry.id == id);
+        if let Some(entry) = held {
+            let checked = spend_of_cosmos_msg(&entry.execute.cosmos_msg)
+                .and_then(|spend| self.spend.check(&spend));
+            if let Err(e) = checked {
+                return Ok(tool_error("Error releasing transaction", e));
+            }
+        }
+        match self.queue.release(&id) {
+            Ok(entry) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error releasing transaction", e)),
+        }
+    }
+
+    #[tool(description = CANCEL_QUEUED_EXECUTE_DESCR)]
+    async fn cancel_queued_execute(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "id of the queued transaction")]
+        id: String,
+        #[tool(param)]
+        #[schemars(description = "Optionally the reason the transaction was vetoed")]
+        reason: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        match self.queue.cancel(&id, reason) {
+            Ok(entry) => {
+                let serialized: String = serde_json::to_string(&entry).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error cancelling transaction", e)),
+        }
+    }
+
+    #[tool(description = REPORT_BROADCAST_DESCR)]
+    async fn report_broadcast(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "the broadcast transaction: the result of an execute tool, e.g. execute__build_msg"
+        )]
+        execute: String,
+        #[tool(param)]
+        #[schemars(description = "whether the transaction was broadcast successfully")]
+        succeeded: bool,
+        #[tool(param)]
+        #[schemars(description = "Optionally the tx hash of the broadcast")]
+        tx_hash: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let execute: ValidatedExecute = match serde_json::from_str(&execute) {
+            Ok(execute) => execute,
+            Err(e) => return Ok(tool_error("Error reporting broadcast", e)),
+        };
+        if succeeded {
+            self.record_spend(&execute, tx_hash);
+        } else {
+            self.breaker.record_broadcast(false);
+        }
+        self.get_spend_status().await
+    }
+
+    #[tool(description = SIGN_AND_BROADCAST_DESCR)]
+    async fn sign_and_broadcast(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "the transaction to broadcast: the result of an execute tool, e.g. execute__build_msg (optional when a queue_id is given)"
+        )]
+        execute: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "the id of a released transaction of the execution queue (required when an execution delay is configured)"
+        )]
+        queue_id: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the gas limit (defaults to the simulated gas used, with a safety margin)"
+        )]
+        gas_limit: Option<u64>,
+    ) -> Result<CallToolResult, Error> {
+        let given: Option<ValidatedExecute> = match execute.as_deref().map(serde_json::from_str) {
+            None => None,
+            Some(Ok(execute)) => Some(execute),
+            Some(Err(e)) => return Ok(tool_error("Error parsing transaction", e)),
+        };
+        let Some(queue_id) = queue_id else {
+            if self.queue.delay_secs() > 0 {
+                return Ok(tool_error(
+                    "Error signing transaction",
+                    format!(
+                        "transactions are held for {}s before broadcasting, queue it \
+                         (queue__add_execute) and pass the queue_id once it's released",
+                        self.queue.delay_secs()
+                    ),
+                ));
+            }
+            let Some(execute) = given else {
+                return Ok(tool_error(
+                    "Error parsing transaction",
+                    "either an execute or a queue_id is required",
+                ));
+            };
+            return Ok(self.broadcast_execute(execute, gas_limit).await);
+        };
+        // Claimed before broadcasting so a released entry is broadcast once
+        let entry = match self.queue.claim(&queue_id) {
+            Ok(entry) => entry,
+            Err(e) => return Ok(tool_error("Error signing transaction", e)),
+        };
+        if given.is_some_and(|given| given.cosmos_msg != entry.execute.cosmos_msg) {
+            let _ = self.queue.unclaim(&queue_id);
+            return Ok(tool_error(
+                "Error signing transaction",
+                format!("the transaction isn't the one queued as '{}'", queue_id),
+            ));
+        }
+        let result = self.broadcast_execute(entry.execute, gas_limit).await;
+        if result.is_error == Some(true) {
+            if let Err(e) = self.queue.unclaim(&queue_id) {
+                tracing::warn!("returning '{}' to the queue failed: {}", queue_id, e);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Sign and broadcast a transaction built by this server, after
+    /// checking it wasn't modified since
+    async fn broadcast_execute(
+        &self,
+        execute: ValidatedExecute,
+        gas_limit: Option<u64>,
+    ) -> CallToolResult {
+        if let Err(e) = self
+            .provenance
+            .verify(&execute.metadata, &execute.signed_payload())
+            .await
+        {
+            return tool_error("Error verifying transaction", e);
+        }
+        let contract_addr = match wasm_execute(&execute.cosmos_msg) {
+            Ok((contract_addr, _, _)) => contract_addr,
+            Err(e) => return tool_error("Error parsing transaction", e),
+        };
+        let network = match self.check_network(&contract_addr, None) {
+            Ok(network) => network,
+            Err(e) => return tool_error("Error checking network", e),
+        };
+        let (signer, key) = match self.signer.key() {
+            Ok(unlocked) => unlocked,
+            Err(e) => return tool_error("Error signing transaction", e),
+        };
+        let contracts = self.contracts.all();
+        let Some(deployment) = contracts
+            .iter()
+            .find(|contract| contract.network == network)
+        else {
+            return tool_error(
+                "Error signing transaction",
+                format!("no deployment is configured for {:?}", network),
+            );
+        };
+        let request = BroadcastRequest {
+            endpoint: deployment.lcd_endpoint().to_string(),
+            chain_id: deployment.chain_id.clone(),
+            bech32_prefix: deployment.bech32_prefix.clone(),
+            fee_denom: deployment.fee_denom.clone(),
+            cosmos_msg: execute.cosmos_msg.clone(),
+            memo: execute.memo.clone().or_else(built_memo).unwrap_or_default(),
+            timeout_height: execute.timeout_height.unwrap_or_default(),
+            gas_limit,
+        };
+        // Reserved until the broadcast's outcome is known
+        let reserved =
+            spend_of_cosmos_msg(&execute.cosmos_msg).and_then(|spend| self.spend.reserve(spend));
+        let reservation = match reserved {
+            Ok(reservation) => reservation,
+            Err(e) => return tool_error("Error broadcasting transaction", e),
+        };
+        match self
+            .broadcaster
+            .sign_and_broadcast(&signer, &key, request)
+            .await
+        {
+            Ok(result) if result.code != 0 => {
+                self.spend.release(reservation);
+                self.breaker.record_broadcast(false);
+                tool_error("Transaction failed", &result.raw_log)
+            }
+            Ok(result) => {
+                self.breaker.record_broadcast(true);
+                if let Err(e) = self
+                    .spend
+                    .confirm(reservation, Some(result.tx_hash.clone()))
+                {
+                    tracing::warn!("recording spend failed: {}", e);
+                }
+                let serialized: String = serde_json::to_string(&result).unwrap_or_default();
+                CallToolResult::success(vec![Content::text(serialized)])
+            }
+            Err(e) => {
+                self.spend.release(reservation);
+                tool_error("Error broadcasting transaction", e)
+            }
+        }
+    }
+
+    #[tool(description = GET_SERVER_CONFIG_DESCR)]
+    async fn get_server_config(&self) -> Result<CallToolResult, Error> {
+        let breaker = self.breaker.status();
+        let config = ServerConfig {
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: enabled_features(),
+            transport: TransportConfig::current(),
+            networks: self
+                .contracts
+                .all()
+                .iter()
+                .map(|contract| NetworkConfig {
+                    network: contract.network.clone(),
+                    chain_id: contract.chain_id.clone(),
+                    contract_address: contract.contract_address.clone(),
+                    lcd_endpoints: contract.lcd_endpoints.clone(),
+                    rpc_endpoints: contract.rpc_endpoints.clone(),
+                })
+                .collect(),
+            policy: PolicySummary {
+                strict_contracts: STRICT_CONTRACTS,
+                breaker: breaker.clone(),
+                daily_spend_caps: DAILY_SPEND_CAPS
+                    .iter()
+                    .map(|(denom, cap)| SpendCap {
+                        denom: denom.to_string(),
+                        cap: cap.to_string(),
+                    })
+                    .collect(),
+                execution_delay_secs: self.queue.delay_secs(),
+                offline: self.lcd.cache().offline(),
+                memo_watermark: MEMO_WATERMARK.map(str::to_string),
+                proxied: PROXY.is_some() || PROXY_MAINNET.is_some() || PROXY_TESTNET.is_some(),
+                redacted_fields: REDACTED_FIELDS.len(),
+                redacted_patterns: REDACTED_PATTERNS.len(),
+                max_result_bytes: MAX_RESULT_BYTES,
+            },
+            signer: SignerAvailability::new(
+                self.signer.status(&self.keystore),
+                &breaker,
+                PROVENANCE_KEY.is_some(),
+            ),
+            recipes: self
+                .recipes
+                .tools()
+                .into_iter()
+                .map(|tool| tool.name.to_string())
+                .collect(),
+        };
+        let serialized: String = serde_json::to_string(&config).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GET_USAGE_STATS_DESCR)]
+    async fn get_usage_stats(&self) -> Result<CallToolResult, Error> {
+        match self.lcd.usage().stats() {
+            Some(stats) => {
+                let serialized: String = serde_json::to_string(&stats).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            None => Ok(tool_error(
+                "Error reading usage stats",
+                "the usage stats are unavailable",
+            )),
+        }
+    }
+
+    #[tool(description = GET_SPEND_STATUS_DESCR)]
+    async fn get_spend_status(&self) -> Result<CallToolResult, Error> {
+        match self.spend.status() {
+            Ok(status) => {
+                let serialized: String = serde_json::to_string(&status).unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(serialized)]))
+            }
+            Err(e) => Ok(tool_error("Error getting spend status", e)),
+        }
+    }
+
+    #[tool(description = GET_ENDPOINT_STATS_DESCR)]
+    async fn get_endpoint_stats(&self) -> Result<CallToolResult, Error> {
+        let stats: Vec<Value> = self
+            .lcd
+            .scores()
+            .stats()
+            .into_iter()
+            .map(|stat| {
+                let score = stat.score();
+                let mut stat = serde_json::to_value(stat).unwrap_or_default();
+                stat["score"] = json!(score);
+                stat
+            })
+            .collect();
+        let serialized: String = serde_json::to_string(&stats).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = SET_OFFLINE_MODE_DESCR)]
+    async fn set_offline_mode(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "true to serve live tools from cached chain data only, false to query the network again"
+        )]
+        offline: bool,
+    ) -> Result<CallToolResult, Error> {
+        let cache = self.lcd.cache();
+        if offline && !cache.offline() {
+            if let Err(e) = cache.save() {
+                return Ok(tool_error("Error snapshotting chain data", e));
+            }
+        }
+        cache.set_offline(offline);
+        let serialized: String = serde_json::to_string(&cache.status()).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GET_SCHEMA_CACHE_STATS_DESCR)]
+    async fn get_schema_cache_stats(&self) -> Result<CallToolResult, Error> {
+        let serialized: String = serde_json::to_string(&schema::stats()).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GET_RESULT_CHUNK_DESCR)]
+    async fn get_result_chunk(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "result_id of the chunked result")]
+        result_id: String,
+        #[tool(param)]
+        #[schemars(description = "index of the chunk, starting at 0")]
+        chunk: usize,
+    ) -> Result<CallToolResult, Error> {
+        match self.chunks.chunk(&result_id, chunk) {
+            Ok(content) => Ok(CallToolResult::success(content)),
+            Err(e) => Ok(tool_error("Error getting result chunk", e)),
+        }
+    }
+}
+
+impl Default for CwMcp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerHandler for CwMcp {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_logging()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(SERVER_INFO_DESCR.to_string()),
         }
     }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, Error> {
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools: Self::tool_box()
+                .list()
+                .into_iter()
+                .filter(|tool| tool_available(&tool.name))
+                .map(published)
+                .chain(self.recipes.tools())
+                .collect(),
+        })
+    }
+
+    /// Every tool call is dispatched here, so output rules (e.g. redaction
+    /// and amount formatting) apply to all tools
+    async fn call_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, Error> {
+        // Published names and their aliases are dispatched to the same tool
+        request.name = dispatch_name(&request.name).into();
+        if let Some(refusal) = self.breaker.refusal(&request.name) {
+            return Ok(tool_error("Error building transaction", refusal));
+        }
+        let name = request.name.clone();
+        let (result, stale) = match self.recipes.get(&name) {
+            Some(recipe) => {
+                let arguments = request.arguments.unwrap_or_default();
+                let (result, stale) = track_stale(self.run_recipe(recipe, arguments)).await;
+                (Ok(result), stale)
+            }
+            None => {
+                let context = ToolCallContext::new(self, request, context);
+                track_stale(Self::tool_box().call(context)).await
+            }
+        };
+        let mut result = result?;
+        if let Some(cached_at) = stale {
+            let notice = json!({
+                "stale": true,
+                "cached_at": cached_at,
+                "notice": "served from cached chain data, which may be outdated",
+            });
+            result.content.push(Content::text(notice.to_string()));
+        }
+        let result = self.amounts.format_result(result);
+        let result = self.redactor.redact_result(result);
+        let result = self.provenance.sign_result(result).await;
+        let result = if name == "get_result_chunk" {
+            result
+        } else {
+            self.chunks.chunk_result(result)
+        };
+        self.lcd.usage().record_tool(&name, &result);
+        Ok(result)
+    }
+
+    fn get_peer(&self) -> Option<Peer<RoleServer>> {
+        self.peer.read().ok().and_then(|peer| peer.clone())
+    }
+
+    /// The peer is kept to deliver notifications outside of requests
+    /// (e.g. alerts); the ownership (and large transfers) of the
+    /// registered contracts are monitored from when it connects
+    fn set_peer(&mut self, peer: Peer<RoleServer>) {
+        if let Ok(mut current) = self.peer.write() {
+            *current = Some(peer);
+        }
+        let alerts = self.alerts.clone();
+        let contracts = self.contracts.all();
+        tokio::spawn(async move {
+            if MONITOR_OWNERSHIP {
+                alerts.monitor_ownership(&contracts).await;
+            }
+            if let Some(threshold) = WHALE_TRANSFER_THRESHOLD {
+                alerts
+                    .monitor_transfers(&contracts, Uint128::from(threshold))
+                    .await;
+            }
+        });
+    }
+
+    /// Guided flows of common workflows (see `src/prompt.rs`)
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, Error> {
+        Ok(ListPromptsResult {
+            next_cursor: None,
+            prompts: prompt::PROMPTS
+                .iter()
+                .map(prompt::PromptDefinition::prompt)
+                .collect(),
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, Error> {
+        let definition = prompt::find(&request.name)
+            .ok_or_else(|| Error::invalid_params(format!("no prompt '{}'", request.name), None))?;
+        let text = definition
+            .render(&request.arguments.unwrap_or_default())
+            .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+        Ok(GetPromptResult {
+            description: Some(definition.description.to_string()),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        Ok(ListResourceTemplatesResult {
+            next_cursor: None,
+            resource_templates: vec![
+                RawResourceTemplate {
+                    uri_template: STATE_TEMPLATE.to_string(),
+                    name: "contract_state".to_string(),
+                    description: Some(STATE_RESOURCE_DESCR.to_string()),
+                    mime_type: Some("application/json".to_string()),
+                }
+                .no_annotation(),
+                RawResourceTemplate {
+                    uri_template: SCHEMA_TEMPLATE.to_string(),
+                    name: "msg_schema".to_string(),
+                    description: Some(SCHEMA_RESOURCE_DESCR.to_string()),
+                    mime_type: Some(MIME_JSON.to_string()),
+                }
+                .no_annotation(),
+                RawResourceTemplate {
+                    uri_template: WASM_TEMPLATE.to_string(),
+                    name: "wasm_code".to_string(),
+                    description: Some(WASM_RESOURCE_DESCR.to_string()),
+                    mime_type: Some(MIME_WASM.to_string()),
+                }
+                .no_annotation(),
+            ],
+        })
+    }
+
+    /// Msg schemas of every deployment, and blobs produced by tools (e.g.
+    /// CSV exports, proto encoded txs)
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, Error> {
+        let mut resources: Vec<Resource> = vec![];
+        for contract in self.contracts.all() {
+            for kind in SchemaKind::all() {
+                resources.push(
+                    RawResource {
+                        uri: SchemaUri::uri(&contract.chain_id, &contract.contract_address, kind),
+                        name: format!("{} {} schema", contract.contract_address, kind.name()),
+                        description: Some(SCHEMA_RESOURCE_DESCR.to_string()),
+                        mime_type: Some(MIME_JSON.to_string()),
+                        size: None,
+                    }
+                    .no_annotation(),
+                );
+            }
+        }
+        resources.extend(self.blobs.list().into_iter().map(|blob| {
+            RawResource {
+                uri: blob.uri,
+                name: blob.name,
+                description: Some(BLOB_RESOURCE_DESCR.to_string()),
+                mime_type: Some(blob.mime_type),
+                size: u32::try_from(blob.bytes.len()).ok(),
+            }
+            .no_annotation()
+        }));
+        Ok(ListResourcesResult {
+            next_cursor: None,
+            resources,
+        })
+    }
+
+    /// Reads of `state://` resources execute their query live; `blob://`
+    /// and `wasm://` resources are served as blobs, and `schema://`
+    /// resources as the rendered JSON schema
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, Error> {
+        if request.uri.starts_with(SCHEMA_SCHEME) {
+            let schema_uri = SchemaUri::parse(&request.uri)
+                .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+            let registered = self.contracts.all().iter().any(|contract| {
+                contract.chain_id == schema_uri.chain_id
+                    && contract.contract_address == schema_uri.contract
+            });
+            if !registered {
+                return Err(Error::invalid_params(
+                    format!(
+                        "{} isn't a registered deployment on {}",
+                        schema_uri.contract, schema_uri.chain_id
+                    ),
+                    None,
+                ));
+            }
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::TextResourceContents {
+                    uri: request.uri,
+                    mime_type: Some(MIME_JSON.to_string()),
+                    text: self
+                        .contract_adapter(&schema_uri.contract)
+                        .schema(schema_uri.kind),
+                }],
+            });
+        }
+        if request.uri.starts_with(BLOB_SCHEME) {
+            let blob = self
+                .blobs
+                .get(&request.uri)
+                .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::BlobResourceContents {
+                    uri: request.uri,
+                    mime_type: Some(blob.mime_type),
+                    blob: STANDARD.encode(&blob.bytes),
+                }],
+            });
+        }
+        if request.uri.starts_with(WASM_SCHEME) {
+            let (network, code_id) = parse_wasm_uri(&request.uri)
+                .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+            let path = format!("/cosmwasm/wasm/v1/code/{}", code_id);
+            let body = self
+                .lcd
+                .get(&self.lcd_endpoint(&network), &path)
+                .await
+                .map_err(|e| {
+                    let error = translate("Error downloading wasm code", &e.to_string());
+                    Error::internal_error(error.message.clone(), serde_json::to_value(&error).ok())
+                })?;
+            // The LCD returns the byte code base64 encoded already
+            let blob = body["data"].as_str().unwrap_or_default().to_string();
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::BlobResourceContents {
+                    uri: request.uri,
+                    mime_type: Some(MIME_WASM.to_string()),
+                    blob,
+                }],
+            });
+        }
+        let state = StateUri::parse(&request.uri)
+            .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+        self.check_network(&state.contract, Some(state.network.clone()))
+            .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+        let data = self
+            .lcd
+            .smart_query(
+                &self.lcd_endpoint(&state.network),
+                &state.contract,
+                &state.query,
+            )
+            .await
+            .map_err(|e| {
+                let error = translate("Error reading contract state", &e.to_string());
+                Error::internal_error(error.message.clone(), serde_json::to_value(&error).ok())
+            })?;
+        let text = self.redactor.redact_text(&data.to_string());
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(text, request.uri)],
+        })
+    }
+
+    /// Subscribed `state://` resources are polled, and an update is
+    /// notified whenever their query result changes
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), Error> {
+        let state = StateUri::parse(&request.uri)
+            .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+        self.check_network(&state.contract, Some(state.network.clone()))
+            .map_err(|e| Error::invalid_params(e.to_string(), None))?;
+        let endpoint = self.lcd_endpoint(&state.network);
+        self.watcher
+            .subscribe(request.uri, state, endpoint, context.peer)
+            .map_err(|e| Error::invalid_params(e.to_string(), None))
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), Error> {
+        self.watcher.unsubscribe(&request.uri);
+        Ok(())
+    }
 }
@@ -0,0 +1,13 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::Network;
+
+/// Contract and network used by tools when a call omits them, set for the
+/// session with `set_default_contract` and `set_default_network`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct SessionDefaults {
+    pub contract: Option<String>,
+    /// Network of the default contract, or the default network
+    pub network: Option<Network>,
+}
@@ -0,0 +1,435 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::clock::{now_secs, rfc3339};
+
+/// Replace with the path broadcast spend is persisted to
+pub static SPEND_PATH: &str = "spend_ledger.json";
+
+/// Replace with the caps on the amounts broadcast per denom (in the
+/// smallest unit) within any 24h window, e.g. `("aarch",
+/// "1000000000000000000000")`. The contract's cw20 token is capped as
+/// `cw20:<contract>`; denoms without a cap aren't limited
+pub static DAILY_SPEND_CAPS: &[(&str, &str)] = &[];
+
+/// Length of the rolling window caps apply to
+pub static SPEND_WINDOW_SECS: u64 = 86_400;
+
+/// cw20 ExecuteMsg variants moving tokens out of the sender's balance (or
+/// an allowance)
+pub static CW20_SPEND_VARIANTS: &[&str] = &["transfer", "send", "transfer_from", "send_from"];
+
+/// Amount of a denom moved by a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Spend {
+    pub denom: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendEntry {
+    pub denom: String,
+    pub amount: Uint128,
+    pub tx_hash: Option<String>,
+    pub broadcast_at: String,
+    pub broadcast_at_secs: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomSpend {
+    pub denom: String,
+    /// Amount broadcast within the last 24h
+    pub spent: Uint128,
+    /// Amount of broadcasts in progress
+    pub reserved: Uint128,
+    pub cap: Option<Uint128>,
+    pub remaining: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+struct SpendFile {
+    entries: Vec<SpendEntry>,
+}
+
+/// Amounts moved by an execute msg: its native funds, and the amount of
+/// cw20 transfers and sends (as `cw20:<contract>`)
+pub fn spend_of(contract_addr: &str, msg: &Value, funds: &[Coin]) -> Vec<Spend> {
+    let mut spend: Vec<Spend> = funds
+        .iter()
+        .map(|coin| Spend {
+            denom: coin.denom.clone(),
+            amount: coin.amount,
+        })
+        .collect();
+    let cw20_amount = CW20_SPEND_VARIANTS
+        .iter()
+        .find_map(|variant| msg.get(variant)?["amount"].as_str())
+        .and_then(|amount| Uint128::from_str(amount).ok());
+    if let Some(amount) = cw20_amount {
+        spend.push(Spend {
+            denom: format!("cw20:{}", contract_addr),
+            amount,
+        });
+    }
+    spend
+}
+
+/// Amounts moved by a serialized wasm execute `CosmosMsg`
+pub fn spend_of_cosmos_msg(cosmos_msg: &str) -> anyhow::Result<Vec<Spend>> {
+    let cosmos_msg: Value = serde_json::from_str(cosmos_msg)?;
+    let execute = &cosmos_msg["wasm"]["execute"];
+    let contract_addr = execute["contract_addr"]
+        .as_str()
+        .ok_or_else(|| anyhow!("not a wasm execute msg"))?;
+    let msg: Value = match execute["msg"].as_str() {
+        Some(msg) => serde_json::from_slice(&STANDARD.decode(msg)?)?,
+        None => Value::Null,
+    };
+    let funds: Vec<Coin> = serde_json::from_value(execute["funds"].clone()).unwrap_or_default();
+    Ok(spend_of(contract_addr, &msg, &funds))
+}
+
+/// Cap of a denom
+pub fn daily_cap(denom: &str) -> Option<Uint128> {
+    cap_of(DAILY_SPEND_CAPS, denom)
+}
+
+fn cap_of(caps: &[(&str, &str)], denom: &str) -> Option<Uint128> {
+    caps.iter()
+        .find(|(capped, _)| *capped == denom)
+        .and_then(|(_, cap)| Uint128::from_str(cap).ok())
+}
+
+/// Amounts reserved by a broadcast in progress, until it's confirmed (or
+/// released)
+#[derive(Debug, PartialEq)]
+pub struct SpendReservation(u64);
+
+#[derive(Debug, Default)]
+struct Reservations {
+    reserved: BTreeMap<u64, Vec<Spend>>,
+    next: u64,
+}
+
+/// Ledger of `SPEND_PATH`, shared by every session of the process
+static SHARED_LEDGER: OnceLock<SpendLedger> = OnceLock::new();
+
+/// Cumulative amounts broadcast per denom, persisted to a JSON file so caps
+/// are enforced across sessions
+#[derive(Clone, Debug)]
+pub struct SpendLedger {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles of the file, and holds the
+    /// amounts of broadcasts in progress
+    lock: Arc<Mutex<Reservations>>,
+    caps: &'static [(&'static str, &'static str)],
+}
+
+/// The process-wide ledger of `SPEND_PATH`
+impl Default for SpendLedger {
+    fn default() -> Self {
+        SHARED_LEDGER.get_or_init(|| Self::new(SPEND_PATH)).clone()
+    }
+}
+
+impl SpendLedger {
+    /// A ledger of its own file; sessions sharing a file must share the
+    /// ledger, so their spend is serialized
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self::with_caps(path, DAILY_SPEND_CAPS)
+    }
+
+    /// A ledger of its own file, enforcing other caps than
+    /// `DAILY_SPEND_CAPS`
+    pub fn with_caps(
+        path: impl AsRef<Path>,
+        caps: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::new(Mutex::new(Reservations::default())),
+            caps,
+        }
+    }
+
+    fn locked(&self) -> anyhow::Result<std::sync::MutexGuard<'_, Reservations>> {
+        self.lock
+            .lock()
+            .map_err(|_| anyhow!("spend ledger poisoned"))
+    }
+
+    /// Entries of the current window
+    fn load(&self) -> anyhow::Result<SpendFile> {
+        if !self.path.exists() {
+            return Ok(SpendFile::default());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        let mut file: SpendFile = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("invalid spend ledger {}: {}", self.path.display(), e))?;
+        let window_start = now_secs().saturating_sub(SPEND_WINDOW_SECS);
+        file.entries
+            .retain(|entry| entry.broadcast_at_secs > window_start);
+        Ok(file)
+    }
+
+    fn save(&self, file: &SpendFile) -> anyhow::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(file)?)?;
+        Ok(())
+    }
+
+    fn spent(file: &SpendFile, denom: &str) -> Uint128 {
+        file.entries
+            .iter()
+            .filter(|entry| entry.denom == denom)
+            .fold(Uint128::zero(), |spent, entry| {
+                spent.saturating_add(entry.amount)
+            })
+    }
+
+    fn reserved(reservations: &Reservations, denom: &str) -> Uint128 {
+        reservations
+            .reserved
+            .values()
+            .flatten()
+            .filter(|spend| spend.denom == denom)
+            .fold(Uint128::zero(), |reserved, spend| {
+                reserved.saturating_add(spend.amount)
+            })
+    }
+
+    fn check_within(
+        &self,
+        file: &SpendFile,
+        reservations: &Reservations,
+        spend: &[Spend],
+    ) -> anyhow::Result<()> {
+        for Spend { denom, amount } in spend {
+            let Some(cap) = cap_of(self.caps, denom) else {
+                continue;
+            };
+            let spent =
+                Self::spent(file, denom).saturating_add(Self::reserved(reservations, denom));
+            if spent.saturating_add(*amount) > cap {
+                return Err(anyhow!(
+                    "spending {} {} exceeds the cap of {} per 24h, {} already spent (or being broadcast)",
+                    amount,
+                    denom,
+                    cap,
+                    spent
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check broadcasting amounts keeps every denom within its cap
+    pub fn check(&self, spend: &[Spend]) -> anyhow::Result<()> {
+        let reservations = self.locked()?;
+        self.check_within(&self.load()?, &reservations, spend)
+    }
+
+    /// Check amounts are within the caps, and reserve them until the
+    /// broadcast is confirmed (or released), so concurrent broadcasts
+    /// can't exceed the caps together
+    pub fn reserve(&self, spend: Vec<Spend>) -> anyhow::Result<SpendReservation> {
+        let mut reservations = self.locked()?;
+        self.check_within(&self.load()?, &reservations, &spend)?;
+        let id = reservations.next;
+        reservations.next += 1;
+        reservations.reserved.insert(id, spend);
+        Ok(SpendReservation(id))
+    }
+
+    /// Record the reserved amounts of a successful broadcast
+    pub fn confirm(
+        &self,
+        reservation: SpendReservation,
+        tx_hash: Option<String>,
+    ) -> anyhow::Result<()> {
+        let mut reservations = self.locked()?;
+        let spend = reservations
+            .reserved
+            .remove(&reservation.0)
+            .unwrap_or_default();
+        self.append(spend, tx_hash)
+    }
+
+    /// Release the reserved amounts of a failed broadcast
+    pub fn release(&self, reservation: SpendReservation) {
+        if let Ok(mut reservations) = self.locked() {
+            reservations.reserved.remove(&reservation.0);
+        }
+    }
+
+    /// Record the amounts of a successful broadcast
+    pub fn record(&self, spend: Vec<Spend>, tx_hash: Option<String>) -> anyhow::Result<()> {
+        let _reservations = self.locked()?;
+        self.append(spend, tx_hash)
+    }
+
+    /// Append entries to the file, under the lock
+    fn append(&self, spend: Vec<Spend>, tx_hash: Option<String>) -> anyhow::Result<()> {
+        let mut file = self.load()?;
+        let now = now_secs();
+        file.entries
+            .extend(spend.into_iter().map(|Spend { denom, amount }| SpendEntry {
+                denom,
+                amount,
+                tx_hash: tx_hash.clone(),
+                broadcast_at: rfc3339(now),
+                broadcast_at_secs: now,
+            }));
+        self.save(&file)
+    }
+
+    /// Amounts spent within the last 24h, of capped denoms and denoms spent
+    pub fn status(&self) -> anyhow::Result<Vec<DenomSpend>> {
+        let reservations = self.locked()?;
+        let file = self.load()?;
+        let mut denoms: BTreeMap<String, Option<Uint128>> = self
+            .caps
+            .iter()
+            .map(|(denom, _)| (denom.to_string(), cap_of(self.caps, denom)))
+            .collect();
+        for entry in &file.entries {
+            denoms
+                .entry(entry.denom.clone())
+                .or_insert_with(|| cap_of(self.caps, &entry.denom));
+        }
+        Ok(denoms
+            .into_iter()
+            .map(|(denom, cap)| {
+                let spent = Self::spent(&file, &denom);
+                let reserved = Self::reserved(&reservations, &denom);
+                DenomSpend {
+                    remaining: cap.map(|cap| cap.saturating_sub(spent).saturating_sub(reserved)),
+                    denom,
+                    spent,
+                    reserved,
+                    cap,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    static CAPS: &[(&str, &str)] = &[("uarch", "1000"), ("cw20:archway1token", "50")];
+
+    /// A ledger of a new file of the temp dir
+    fn ledger(name: &str) -> SpendLedger {
+        let path =
+            std::env::temp_dir().join(format!("spend_ledger_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        SpendLedger::with_caps(path, CAPS)
+    }
+
+    fn uarch(amount: u128) -> Vec<Spend> {
+        vec![Spend {
+            denom: "uarch".to_string(),
+            amount: Uint128::new(amount),
+        }]
+    }
+
+    fn denom_spend(ledger: &SpendLedger, denom: &str) -> DenomSpend {
+        ledger
+            .status()
+            .unwrap()
+            .into_iter()
+            .find(|spend| spend.denom == denom)
+            .unwrap()
+    }
+
+    #[test]
+    fn spend_of_funds_and_cw20_transfers() {
+        let msg = json!({ "transfer": { "recipient": "archway1...", "amount": "25" } });
+        let funds = vec![Coin::new(7u128, "uarch")];
+        assert_eq!(
+            spend_of("archway1token", &msg, &funds),
+            vec![
+                Spend {
+                    denom: "uarch".to_string(),
+                    amount: Uint128::new(7),
+                },
+                Spend {
+                    denom: "cw20:archway1token".to_string(),
+                    amount: Uint128::new(25),
+                },
+            ]
+        );
+        // Other variants don't move the sender's tokens
+        let msg = json!({ "burn": { "amount": "25" } });
+        assert!(spend_of("archway1token", &msg, &[]).is_empty());
+    }
+
+    #[test]
+    fn spend_of_wrapped_msgs() {
+        let msg = STANDARD.encode(br#"{"send":{"contract":"archway1...","amount":"40","msg":""}}"#);
+        let cosmos_msg = json!({
+            "wasm": { "execute": {
+                "contract_addr": "archway1token",
+                "msg": msg,
+                "funds": [{ "denom": "uarch", "amount": "3" }],
+            } }
+        });
+        let spend = spend_of_cosmos_msg(&cosmos_msg.to_string()).unwrap();
+        assert_eq!(spend.len(), 2);
+        assert_eq!(spend[1].amount, Uint128::new(40));
+        assert!(spend_of_cosmos_msg(r#"{"bank": {}}"#).is_err());
+    }
+
+    #[test]
+    fn caps_spend_within_the_window() {
+        let ledger = ledger("caps");
+        assert!(ledger.check(&uarch(1000)).is_ok());
+        assert!(ledger.check(&uarch(1001)).is_err());
+        ledger.record(uarch(600), None).unwrap();
+        assert!(ledger.check(&uarch(400)).is_ok());
+        assert!(ledger.check(&uarch(401)).is_err());
+        // Uncapped denoms aren't limited
+        let uncapped = vec![Spend {
+            denom: "uosmo".to_string(),
+            amount: Uint128::MAX,
+        }];
+        assert!(ledger.check(&uncapped).is_ok());
+        let status = denom_spend(&ledger, "uarch");
+        assert_eq!(status.spent, Uint128::new(600));
+        assert_eq!(status.cap, Some(Uint128::new(1000)));
+        assert_eq!(status.remaining, Some(Uint128::new(400)));
+    }
+
+    #[test]
+    fn reservations_count_until_released_or_confirmed() {
+        let ledger = ledger("reservations");
+        let first = ledger.reserve(uarch(700)).unwrap();
+        // Concurrent broadcasts can't exceed the cap together
+        assert!(ledger.reserve(uarch(301)).is_err());
+        let second = ledger.reserve(uarch(300)).unwrap();
+        assert_ne!(first, second);
+        let status = denom_spend(&ledger, "uarch");
+        assert_eq!(status.reserved, Uint128::new(1000));
+        assert_eq!(status.remaining, Some(Uint128::zero()));
+        ledger.release(second);
+        assert!(ledger.check(&uarch(300)).is_ok());
+        ledger.confirm(first, Some("ABCD".to_string())).unwrap();
+        let status = denom_spend(&ledger, "uarch");
+        assert_eq!(status.spent, Uint128::new(700));
+        assert_eq!(status.reserved, Uint128::zero());
+        assert_eq!(status.remaining, Some(Uint128::new(300)));
+        // Confirmed spend is persisted, for every ledger of the file
+        let reopened = SpendLedger::with_caps(&ledger.path, CAPS);
+        assert!(reopened.check(&uarch(301)).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmwasm_std::{Binary, Empty, QueryRequest};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::proto::ProtoEncoder;
+
+/// `cosmos.base.query.v1beta1.PageRequest`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PageRequest {
+    /// Base64 encoded `next_key` of the previous page
+    pub key: Option<String>,
+    pub limit: Option<u64>,
+    pub reverse: Option<bool>,
+}
+
+impl PageRequest {
+    fn encode(&self) -> anyhow::Result<ProtoEncoder> {
+        let key = match &self.key {
+            Some(key) => STANDARD
+                .decode(key)
+                .map_err(|e| anyhow!("invalid pagination key: {}", e))?,
+            None => vec![],
+        };
+        Ok(ProtoEncoder::new()
+            .bytes(1, &key)
+            .uint64(3, self.limit.unwrap_or_default())
+            .bool(5, self.reverse.unwrap_or_default()))
+    }
+}
+
+/// Archway specific gRPC queries (x/rewards, x/callback) that can't be
+/// expressed as a `WasmQuery`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchwayQuery {
+    /// Rewards and owner addresses registered for a contract
+    RewardsContractMetadata {
+        contract_address: String,
+    },
+    /// Flat fee charged for executing a contract
+    RewardsFlatFee {
+        contract_address: String,
+    },
+    /// Rewards records (not yet withdrawn) of a rewards address
+    RewardsRecords {
+        rewards_address: String,
+        pagination: Option<PageRequest>,
+    },
+    /// Total rewards (not yet withdrawn) of a rewards address
+    RewardsOutstanding {
+        rewards_address: String,
+    },
+    /// Estimated fees of a tx with a gas limit, optionally executing a
+    /// contract (including its flat fee)
+    RewardsEstimateTxFees {
+        gas_limit: u64,
+        contract_address: Option<String>,
+    },
+    RewardsParams {},
+    /// Callbacks registered for a block height
+    Callbacks {
+        block_height: i64,
+    },
+    /// Fees required to register a callback at a block height
+    CallbackEstimateFees {
+        block_height: i64,
+    },
+    CallbackParams {},
+    /// Any other gRPC query; `data` is the base64 encoded request proto
+    Custom {
+        path: String,
+        data: Option<String>,
+    },
+}
+
+impl ArchwayQuery {
+    /// Fully qualified gRPC method of the query
+    pub fn path(&self) -> String {
+        let path = match self {
+            ArchwayQuery::RewardsContractMetadata { .. } => {
+                "/archway.rewards.v1.Query/ContractMetadata"
+            }
+            ArchwayQuery::RewardsFlatFee { .. } => "/archway.rewards.v1.Query/FlatFee",
+            ArchwayQuery::RewardsRecords { .. } => "/archway.rewards.v1.Query/RewardsRecords",
+            ArchwayQuery::RewardsOutstanding { .. } => {
+                "/archway.rewards.v1.Query/OutstandingRewards"
+            }
+            ArchwayQuery::RewardsEstimateTxFees { .. } => {
+                "/archway.rewards.v1.Query/EstimateTxFees"
+            }
+            ArchwayQuery::RewardsParams {} => "/archway.rewards.v1.Query/Params",
+            ArchwayQuery::Callbacks { .. } => "/archway.callback.v1.Query/Callbacks",
+            ArchwayQuery::CallbackEstimateFees { .. } => {
+                "/archway.callback.v1.Query/EstimateCallbackFees"
+            }
+            ArchwayQuery::CallbackParams {} => "/archway.callback.v1.Query/Params",
+            ArchwayQuery::Custom { path, .. } => return path.clone(),
+        };
+        path.to_string()
+    }
+
+    /// Proto encoded request of the query
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        let encoded = match self {
+            ArchwayQuery::RewardsContractMetadata { contract_address }
+            | ArchwayQuery::RewardsFlatFee { contract_address } => {
+                ProtoEncoder::new().string(1, contract_address)
+            }
+            ArchwayQuery::RewardsRecords {
+                rewards_address,
+                pagination,
+            } => ProtoEncoder::new()
+                .string(1, rewards_address)
+                .message(2, pagination.as_ref().map(PageRequest::encode).transpose()?),
+            ArchwayQuery::RewardsOutstanding { rewards_address } => {
+                ProtoEncoder::new().string(1, rewards_address)
+            }
+            ArchwayQuery::RewardsEstimateTxFees {
+                gas_limit,
+                contract_address,
+            } => ProtoEncoder::new()
+                .uint64(1, *gas_limit)
+                .string(2, contract_address.as_deref().unwrap_or_default()),
+            ArchwayQuery::Callbacks { block_height }
+            | ArchwayQuery::CallbackEstimateFees { block_height } => {
+                ProtoEncoder::new().int64(1, *block_height)
+            }
+            ArchwayQuery::RewardsParams {} | ArchwayQuery::CallbackParams {} => ProtoEncoder::new(),
+            ArchwayQuery::Custom { data, .. } => {
+                return match data {
+                    Some(data) => STANDARD
+                        .decode(data)
+                        .map_err(|e| anyhow!("invalid base64 'data': {}", e)),
+                    None => Ok(vec![]),
+                };
+            }
+        };
+        Ok(encoded.finish())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatedStargateQuery {
+    pub path: String,
+    /// Base64 encoded request proto
+    pub data: String,
+    /// `QueryRequest::Stargate` (JSON), as sent by contracts or wallets
+    pub query_request: String,
+}
+
+pub fn build(query: &ArchwayQuery) -> anyhow::Result<ValidatedStargateQuery> {
+    let path = query.path();
+    let data = query.encode()?;
+    #[allow(deprecated)]
+    let query_request: QueryRequest<Empty> = QueryRequest::Stargate {
+        path: path.clone(),
+        data: Binary::from(data.clone()),
+    };
+    Ok(ValidatedStargateQuery {
+        path,
+        data: STANDARD.encode(&data),
+        query_request: serde_json::to_string(&query_request)?,
+    })
+}
@@ -0,0 +1,73 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::address::decode_hex;
+use crate::label::AddressLabel;
+use crate::provenance::BuildMetadata;
+
+/// A storage key given as hex (optionally `0x` prefixed) or base64
+pub fn decode_storage_key(key: &str) -> anyhow::Result<Vec<u8>> {
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("the storage key is empty");
+    }
+    decode_hex(key.trim_start_matches("0x"))
+        .or_else(|| STANDARD.decode(key).ok())
+        .ok_or_else(|| anyhow::anyhow!("invalid storage key '{}', expected hex or base64", key))
+}
+
+/// Storage key of a cw-storage-plus `Item` (a namespace without keys),
+/// or of an entry of a `Map`: every part but the last is prefixed with
+/// its length (as a big endian u16). Keys are the bytes of their string
+/// (e.g. an address); integer keys are big endian bytes, pass them to
+/// `decode_storage_key` as hex instead
+pub fn storage_key(namespace: &str, keys: &[String]) -> anyhow::Result<Vec<u8>> {
+    let mut parts: Vec<&[u8]> = vec![namespace.as_bytes()];
+    parts.extend(keys.iter().map(|key| key.as_bytes()));
+    let mut key = vec![];
+    for (i, part) in parts.iter().enumerate() {
+        if i < parts.len() - 1 {
+            let length = u16::try_from(part.len())
+                .map_err(|_| anyhow::anyhow!("storage key parts must be under 64 KiB"))?;
+            key.extend_from_slice(&length.to_be_bytes());
+        }
+        key.extend_from_slice(part);
+    }
+    Ok(key)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawQuery {
+    pub contract: String,
+    pub key_hex: String,
+    pub key_base64: String,
+    /// `WasmQuery::Raw` request of the key, as a JSON stringified
+    /// QueryRequest
+    pub query_request: String,
+    pub address_labels: Vec<AddressLabel>,
+    pub metadata: BuildMetadata,
+    /// Whether the query was executed, filling in its value
+    pub executed: bool,
+    /// Stored value (its JSON, if it's JSON), or null if the key isn't set
+    pub value: Option<Value>,
+    pub value_base64: Option<String>,
+}
+
+impl RawQuery {
+    /// Fill in the value of an executed query
+    pub fn with_value(mut self, data: Option<Vec<u8>>) -> Self {
+        self.executed = true;
+        if let Some(data) = data {
+            self.value = serde_json::from_slice(&data).ok();
+            self.value_base64 = Some(STANDARD.encode(&data));
+        }
+        self
+    }
+}
+
+/// Hex of a storage key
+pub fn key_hex(key: &[u8]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,224 @@
+use anyhow::anyhow;
+use cosmwasm_std::{Decimal, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+use crate::rpc::LcdClient;
+
+/// Page size of `all_accounts` queries (the cw20-base maximum)
+pub static HOLDERS_PAGE_LIMIT: u32 = 30;
+/// Replace with the maximum number of holders enumerated for a report
+pub static MAX_HOLDERS: usize = 1_000;
+pub static TOP_HOLDERS: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HolderBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplyReport {
+    pub contract: String,
+    pub total_supply: Uint128,
+    pub holders: usize,
+    /// Sum of the balances of the enumerated holders
+    pub holder_balances: Uint128,
+    /// Absolute difference between the total supply and the holder balances
+    pub difference: Uint128,
+    /// True if the total supply matches the holder balances
+    pub reconciled: bool,
+    /// False if not all holders were enumerated (see `MAX_HOLDERS`), in
+    /// which case the holder balances are a lower bound
+    pub complete: bool,
+    /// Largest holders, by balance
+    pub top_holders: Vec<HolderBalance>,
+    pub discrepancies: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PegReport {
+    pub contract: String,
+    pub denom: String,
+    /// Native balance held by the contract
+    pub collateral: Uint128,
+    pub total_supply: Uint128,
+    /// True if every wrapped token is backed by the native denom
+    pub fully_collateralized: bool,
+    /// Native balance the contract is missing to back the supply
+    pub shortfall: Uint128,
+    /// Native balance held in excess of the supply (e.g. sent to the
+    /// contract without depositing)
+    pub surplus: Uint128,
+    /// Collateral per wrapped token, e.g. `1.0` for a fully backed wrap
+    pub collateral_ratio: Option<String>,
+}
+
+fn parse_uint(value: &Value) -> Option<Uint128> {
+    value
+        .as_str()
+        .and_then(|value| Uint128::from_str(value).ok())
+}
+
+#[derive(Clone, Debug)]
+pub struct SupplyAuditor {
+    lcd: LcdClient,
+}
+
+impl SupplyAuditor {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self { lcd }
+    }
+
+    pub async fn total_supply(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<Uint128> {
+        let token_info = self
+            .lcd
+            .smart_query(endpoint, contract_addr, &json!({ "token_info": {} }))
+            .await?;
+        parse_uint(&token_info["total_supply"])
+            .ok_or_else(|| anyhow!("token_info of {} has no total_supply", contract_addr))
+    }
+
+    /// Enumerate the token's holders with (cw20 enumerable) `all_accounts`
+    /// queries, and query their balances. Returns false along with the
+    /// holders if there are more than `MAX_HOLDERS`
+    pub async fn holders(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<(Vec<HolderBalance>, bool)> {
+        let mut holders: Vec<HolderBalance> = vec![];
+        let mut start_after: Option<String> = None;
+        loop {
+            let page = self
+                .lcd
+                .smart_query(
+                    endpoint,
+                    contract_addr,
+                    &json!({ "all_accounts": {
+                        "start_after": start_after,
+                        "limit": HOLDERS_PAGE_LIMIT,
+                    }}),
+                )
+                .await
+                .map_err(|e| anyhow!("holders of {} can't be enumerated ({})", contract_addr, e))?;
+            let accounts: Vec<String> = page["accounts"]
+                .as_array()
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .filter_map(|account| account.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            for address in accounts.iter() {
+                if holders.len() >= MAX_HOLDERS {
+                    return Ok((holders, false));
+                }
+                let balance = self
+                    .lcd
+                    .smart_query(
+                        endpoint,
+                        contract_addr,
+                        &json!({ "balance": { "address": address } }),
+                    )
+                    .await?;
+                holders.push(HolderBalance {
+                    address: address.clone(),
+                    balance: parse_uint(&balance["balance"]).unwrap_or_default(),
+                });
+            }
+            if accounts.len() < HOLDERS_PAGE_LIMIT as usize {
+                return Ok((holders, true));
+            }
+            start_after = accounts.last().cloned();
+        }
+    }
+
+    /// Reconcile the token's total supply against the balances of its
+    /// holders
+    pub async fn report(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+    ) -> anyhow::Result<SupplyReport> {
+        let total_supply = self.total_supply(endpoint, contract_addr).await?;
+        let (mut holders, complete) = self.holders(endpoint, contract_addr).await?;
+        let holder_balances = holders.iter().fold(Uint128::zero(), |sum, holder| {
+            sum.saturating_add(holder.balance)
+        });
+        let difference = total_supply.abs_diff(holder_balances);
+        let mut discrepancies = vec![];
+        if holder_balances > total_supply {
+            discrepancies.push(format!(
+                "holder balances exceed the total supply by {}, tokens were credited without being minted",
+                difference
+            ));
+        } else if complete && holder_balances < total_supply {
+            discrepancies.push(format!(
+                "the total supply exceeds the holder balances by {}, tokens were burned (e.g. with 'burn_from') or debited without reducing the supply",
+                difference
+            ));
+        }
+        if !complete {
+            discrepancies.push(format!(
+                "only the first {} holders were enumerated, the supply can't be fully reconciled",
+                MAX_HOLDERS
+            ));
+        }
+        let holders_count = holders.len();
+        holders.sort_by(|a, b| b.balance.cmp(&a.balance));
+        holders.truncate(TOP_HOLDERS);
+        Ok(SupplyReport {
+            contract: contract_addr.to_string(),
+            total_supply,
+            holders: holders_count,
+            holder_balances,
+            difference,
+            reconciled: complete && difference.is_zero(),
+            complete,
+            top_holders: holders,
+            discrepancies,
+        })
+    }
+
+    /// Compare the native balance of the contract against the wrapped
+    /// token's total supply
+    pub async fn peg(
+        &self,
+        endpoint: &str,
+        contract_addr: &str,
+        denom: &str,
+    ) -> anyhow::Result<PegReport> {
+        let total_supply = self.total_supply(endpoint, contract_addr).await?;
+        let balance = self
+            .lcd
+            .bank_balance(endpoint, contract_addr, denom)
+            .await?;
+        let collateral = Uint128::from_str(&balance)
+            .map_err(|e| anyhow!("invalid {} balance '{}': {}", denom, balance, e))?;
+        let collateral_ratio = if total_supply.is_zero() {
+            None
+        } else {
+            Decimal::checked_from_ratio(collateral, total_supply)
+                .ok()
+                .map(|ratio| ratio.to_string())
+        };
+        Ok(PegReport {
+            contract: contract_addr.to_string(),
+            denom: denom.to_string(),
+            fully_collateralized: collateral >= total_supply,
+            shortfall: total_supply.saturating_sub(collateral),
+            surplus: collateral.saturating_sub(total_supply),
+            collateral,
+            total_supply,
+            collateral_ratio,
+        })
+    }
+}
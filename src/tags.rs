@@ -0,0 +1,165 @@
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::clock::now_rfc3339;
+
+/// Replace with the path tagged activity is persisted to
+pub static TAGS_PATH: &str = "tagged_activity.json";
+
+/// Cost basis of the tokens moved by a transaction, for tax-lot style
+/// accounting
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CostBasis {
+    /// Total cost, e.g. `1500.00`
+    pub amount: String,
+    /// Currency of the cost, e.g. `USD`
+    pub currency: String,
+    /// Quantity of tokens acquired or disposed of, in the smallest unit
+    pub quantity: Option<String>,
+    /// Lot the transaction belongs to, e.g. `2025-Q1-treasury`
+    pub lot: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaggedActivity {
+    /// Tx hash of a broadcast transaction, or `msg:<sha256>` of a built
+    /// (not yet broadcast) message
+    pub reference: String,
+    pub tags: Vec<String>,
+    pub cost_basis: Option<CostBasis>,
+    pub note: Option<String>,
+    pub tagged_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+struct TagsFile {
+    activity: BTreeMap<String, TaggedActivity>,
+}
+
+/// Reference of a tx hash, or of a built message (its sha256)
+pub fn activity_reference(tx_hash_or_msg: &str) -> String {
+    let trimmed = tx_hash_or_msg.trim();
+    let is_tx_hash = trimmed.len() == 64 && trimmed.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_tx_hash {
+        trimmed.to_uppercase()
+    } else {
+        let digest = Sha256::digest(trimmed.as_bytes());
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("msg:{}", hex)
+    }
+}
+
+/// Tags and cost-basis metadata of transactions, persisted to a JSON file
+#[derive(Clone, Debug)]
+pub struct TagStore {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles of the file
+    lock: Arc<Mutex<()>>,
+}
+
+impl Default for TagStore {
+    fn default() -> Self {
+        Self::new(TAGS_PATH)
+    }
+}
+
+impl TagStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn load(&self) -> anyhow::Result<TagsFile> {
+        if !self.path.exists() {
+            return Ok(TagsFile::default());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("invalid tags file {}: {}", self.path.display(), e))
+    }
+
+    fn save(&self, file: &TagsFile) -> anyhow::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(file)?)?;
+        Ok(())
+    }
+
+    /// Tag a transaction; tags are added to existing ones, the cost basis
+    /// and note replace existing ones if given
+    pub fn tag(
+        &self,
+        reference: String,
+        tags: Vec<String>,
+        cost_basis: Option<CostBasis>,
+        note: Option<String>,
+    ) -> anyhow::Result<TaggedActivity> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow!("tag store poisoned"))?;
+        let mut file = self.load()?;
+        let entry = file
+            .activity
+            .entry(reference.clone())
+            .or_insert_with(|| TaggedActivity {
+                reference,
+                tags: vec![],
+                cost_basis: None,
+                note: None,
+                tagged_at: now_rfc3339(),
+            });
+        for tag in tags {
+            if !entry.tags.contains(&tag) {
+                entry.tags.push(tag);
+            }
+        }
+        if cost_basis.is_some() {
+            entry.cost_basis = cost_basis;
+        }
+        if note.is_some() {
+            entry.note = note;
+        }
+        let entry = entry.clone();
+        self.save(&file)?;
+        Ok(entry)
+    }
+
+    pub fn untag(&self, reference: &str) -> anyhow::Result<Option<TaggedActivity>> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow!("tag store poisoned"))?;
+        let mut file = self.load()?;
+        let removed = file.activity.remove(reference);
+        self.save(&file)?;
+        Ok(removed)
+    }
+
+    /// Tagged activity, optionally only with a tag, or tagged between two
+    /// dates (`YYYY-MM-DD`, inclusive); newest first
+    pub fn list(
+        &self,
+        tag: Option<&str>,
+        from_date: Option<&str>,
+        to_date: Option<&str>,
+    ) -> anyhow::Result<Vec<TaggedActivity>> {
+        let mut activity: Vec<TaggedActivity> = self
+            .load()?
+            .activity
+            .into_values()
+            .filter(|entry| tag.is_none_or(|tag| entry.tags.iter().any(|t| t == tag)))
+            .filter(|entry| {
+                let date = entry.tagged_at.get(..10).unwrap_or_default();
+                from_date.is_none_or(|from| date >= from) && to_date.is_none_or(|to| date <= to)
+            })
+            .collect();
+        activity.sort_by(|a, b| b.tagged_at.cmp(&a.tagged_at));
+        Ok(activity)
+    }
+}
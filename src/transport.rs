@@ -0,0 +1,287 @@
+use rmcp::{ServiceExt, transport::stdio};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::builder::CwMcpBuilder;
+use crate::cli::cli;
+use crate::idl::load_schemas;
+
+/// Address the sse and streamable http servers listen on; the `--bind`
+/// argument (or the `CW_MCP_BIND_ADDRESS` environment variable) overrides it
+pub static BIND_ADDRESS: &str = "127.0.0.1:8000";
+
+/// Paths of the sse server: clients open the event stream at `SSE_PATH`,
+/// and post their messages to `SSE_POST_PATH` (with their session id).
+/// The `MCP_SSE_PATH` and `MCP_SSE_POST_PATH` environment variables
+/// override them
+pub static SSE_PATH: &str = "/";
+pub static SSE_POST_PATH: &str = "/";
+
+/// Path of the streamable http endpoint (overridden by `MCP_HTTP_PATH`)
+pub static STREAMABLE_HTTP_PATH: &str = "/";
+
+/// Interval of the keep-alive comments sent on idle event streams, so
+/// proxies don't close them
+pub static SSE_KEEP_ALIVE_SECS: Option<u64> = Some(15);
+
+/// Time open connections get to finish after a shutdown signal, before
+/// the server exits anyway
+pub static SHUTDOWN_GRACE_SECS: u64 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum ServerTransport {
+    Stdio,
+    Sse,
+    StreamableHttp,
+}
+
+impl FromStr for ServerTransport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stdio" => Ok(ServerTransport::Stdio),
+            "sse" => Ok(ServerTransport::Sse),
+            "http" | "streamable-http" | "http-streamable" => Ok(ServerTransport::StreamableHttp),
+            _ => anyhow::bail!(
+                "unknown transport '{}', expected 'stdio', 'sse' or 'streamable-http'",
+                s
+            ),
+        }
+    }
+}
+
+/// Transport the server was started with
+static ACTIVE: OnceLock<ServerTransport> = OnceLock::new();
+
+pub fn active_transport() -> Option<ServerTransport> {
+    ACTIVE.get().copied()
+}
+
+/// A setting, overridden by an environment variable if it's set
+pub fn setting(env: &str, default: &str) -> String {
+    std::env::var(env)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Address the sse and streamable http servers listen on: the `--bind`
+/// argument (or `CW_MCP_BIND_ADDRESS`), otherwise `BIND_ADDRESS`
+pub fn bind_address() -> anyhow::Result<SocketAddr> {
+    match cli().bind {
+        Some(bind) => Ok(bind),
+        None => Ok(BIND_ADDRESS.parse()?),
+    }
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use super::*;
+    use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+    use rmcp::transport::streamable_http_server::axum::{
+        StreamableHttpServer, StreamableHttpServerConfig,
+    };
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    /// Resolves on ctrl-c, or on SIGTERM (e.g. from a container runtime)
+    async fn shutdown_signal() {
+        let ctrl_c = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                tracing::error!("ctrl-c can't be listened for: {}", e);
+                std::future::pending::<()>().await;
+            }
+        };
+        #[cfg(unix)]
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut terminate) => {
+                    terminate.recv().await;
+                }
+                Err(_) => std::future::pending::<()>().await,
+            }
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+        tokio::select! {
+            _ = ctrl_c => {}
+            _ = terminate => {}
+        }
+    }
+
+    /// Serve an axum router until a shutdown signal, then close the sessions
+    /// and give open connections `SHUTDOWN_GRACE_SECS` to finish
+    async fn serve_router(
+        name: &'static str,
+        bind: SocketAddr,
+        router: axum::Router,
+        sessions: CancellationToken,
+    ) -> anyhow::Result<()> {
+        let listener = tokio::net::TcpListener::bind(bind).await?;
+        let shutdown = sessions.child_token();
+        let server = axum::serve(listener, router).with_graceful_shutdown(async move {
+            shutdown.cancelled().await;
+            tracing::info!("{} server shutting down", name);
+        });
+        let handle = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::error!(error = %e, "{} server shutdown with error", name);
+            }
+        });
+        shutdown_signal().await;
+        // Closing the sessions ends their event streams, so the connections
+        // can drain
+        sessions.cancel();
+        if tokio::time::timeout(Duration::from_secs(SHUTDOWN_GRACE_SECS), handle)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "{} server connections didn't close within {}s",
+                name,
+                SHUTDOWN_GRACE_SECS
+            );
+        }
+        Ok(())
+    }
+
+    /// Endpoints served next to the MCP endpoint: inbound hooks (see
+    /// `src/hook.rs`), the operators' execution queue (`src/operator.rs`)
+    /// and, with the `graphql` feature, the GraphQL endpoint
+    fn side_router(builder: &CwMcpBuilder) -> Option<axum::Router> {
+        let router = match (crate::hook::router(builder), crate::operator::router()) {
+            (Some(hooks), Some(operator)) => Some(hooks.merge(operator)),
+            (hooks, operator) => hooks.or(operator),
+        };
+        #[cfg(feature = "graphql")]
+        let router = match (router, crate::graphql::router()) {
+            (Some(router), Some(graphql)) => Some(router.merge(graphql)),
+            (router, graphql) => router.or(graphql),
+        };
+        router
+    }
+
+    /// Serve the side endpoints on their own, for transports that don't
+    /// serve a router themselves (stdio)
+    async fn serve_side(bind: SocketAddr, router: axum::Router) {
+        let listener = match tokio::net::TcpListener::bind(bind).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!("side endpoints can't listen on {}: {}", bind, e);
+                return;
+            }
+        };
+        tracing::info!("side endpoints listening on {}", bind);
+        if let Err(e) = axum::serve(listener, router).await {
+            tracing::error!(error = %e, "side endpoints shutdown with error");
+        }
+    }
+
+    /// Serve the side endpoints of the stdio transport on the bind address,
+    /// which is free since stdio doesn't listen
+    pub fn spawn_side(builder: &CwMcpBuilder) -> anyhow::Result<()> {
+        if let Some(router) = side_router(builder) {
+            tokio::spawn(serve_side(bind_address()?, router));
+        }
+        Ok(())
+    }
+
+    /// Serve the sse transport, each session (event stream) getting a server
+    /// instance of the builder's
+    pub async fn serve_sse(builder: CwMcpBuilder) -> anyhow::Result<()> {
+        let config = SseServerConfig {
+            bind: bind_address()?,
+            sse_path: setting("MCP_SSE_PATH", SSE_PATH),
+            post_path: setting("MCP_SSE_POST_PATH", SSE_POST_PATH),
+            ct: CancellationToken::new(),
+            sse_keep_alive: SSE_KEEP_ALIVE_SECS.map(Duration::from_secs),
+        };
+        let (sse_server, router) = SseServer::new(config);
+        let bind = sse_server.config.bind;
+        tracing::info!(
+            "sse server listening on {} (events at {}, messages to {})",
+            bind,
+            sse_server.config.sse_path,
+            sse_server.config.post_path
+        );
+        let router = match side_router(&builder) {
+            Some(side) => router.merge(side),
+            None => router,
+        };
+        let sessions = sse_server.with_service(move || builder.build());
+        serve_router("sse", bind, router, sessions).await
+    }
+
+    /// Serve the streamable http transport
+    pub async fn serve_streamable_http(builder: CwMcpBuilder) -> anyhow::Result<()> {
+        let config = StreamableHttpServerConfig {
+            bind: bind_address()?,
+            path: setting("MCP_HTTP_PATH", STREAMABLE_HTTP_PATH),
+            ct: CancellationToken::new(),
+            sse_keep_alive: SSE_KEEP_ALIVE_SECS.map(Duration::from_secs),
+        };
+        let (http_server, router) = StreamableHttpServer::new(config);
+        let bind = http_server.config.bind;
+        tracing::info!(
+            "streamable http server listening on {} (at {})",
+            bind,
+            http_server.config.path
+        );
+        let router = match side_router(&builder) {
+            Some(side) => router.merge(side),
+            None => router,
+        };
+        // Every session (`Mcp-Session-Id`) gets its own server
+        // instance, and is closed when the token is cancelled
+        let sessions = http_server.with_service(move || builder.build());
+        serve_router("streamable http", bind, router, sessions).await
+    }
+}
+
+#[cfg(not(feature = "http"))]
+mod http {
+    use super::*;
+
+    static HTTP_DISABLED: &str = "the sse and streamable http transports are disabled, build the server with the 'http' feature";
+
+    pub fn spawn_side(_builder: &CwMcpBuilder) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    pub async fn serve_sse(_builder: CwMcpBuilder) -> anyhow::Result<()> {
+        anyhow::bail!(HTTP_DISABLED)
+    }
+
+    pub async fn serve_streamable_http(_builder: CwMcpBuilder) -> anyhow::Result<()> {
+        anyhow::bail!(HTTP_DISABLED)
+    }
+}
+
+/// Serve the MCP server over a transport, until a shutdown signal (or, for
+/// stdio, until the client disconnects)
+pub async fn serve(transport: ServerTransport) -> anyhow::Result<()> {
+    serve_with(CwMcpBuilder::new(), transport).await
+}
+
+/// Serve the server instances of a builder over a transport
+pub async fn serve_with(builder: CwMcpBuilder, transport: ServerTransport) -> anyhow::Result<()> {
+    let _ = ACTIVE.set(transport);
+    load_schemas(&builder.startup_options().schemas).await?;
+    builder.check_contracts()?;
+    match transport {
+        ServerTransport::Stdio => {
+            http::spawn_side(&builder)?;
+            let mcp_server = builder.build().serve(stdio()).await.inspect_err(|e| {
+                tracing::error!("serving error: {:?}", e);
+            })?;
+            mcp_server.waiting().await?;
+        }
+        ServerTransport::Sse => http::serve_sse(builder).await?,
+        ServerTransport::StreamableHttp => http::serve_streamable_http(builder).await?,
+    }
+    Ok(())
+}
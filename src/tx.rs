@@ -0,0 +1,251 @@
+use cosmwasm_std::Coin;
+
+use crate::cosmos::type_url;
+use crate::proto::ProtoEncoder;
+
+/// Replace with a watermark appended to the memo of every transaction
+/// built by this server (e.g. `Some("via cw-mcp")`), so on-chain activity
+/// initiated by agents can be told apart during audits
+pub static MEMO_WATERMARK: Option<&str> = None;
+
+/// Append the watermark (if one is configured) to a memo
+pub fn watermark(memo: &str) -> String {
+    match MEMO_WATERMARK {
+        Some(watermark) if memo.is_empty() => watermark.to_string(),
+        Some(watermark) if !memo.ends_with(watermark) => format!("{} {}", memo, watermark),
+        _ => memo.to_string(),
+    }
+}
+
+/// Memo of built transactions, if one has to be set
+pub fn built_memo() -> Option<String> {
+    Some(watermark("")).filter(|memo| !memo.is_empty())
+}
+
+/// Replace with the `max_memo_characters` of your chain's auth params
+pub static MAX_MEMO_CHARACTERS: usize = 256;
+
+/// A memo the chain accepts (the chain counts its bytes)
+pub fn check_memo(memo: &str) -> anyhow::Result<()> {
+    if memo.len() > MAX_MEMO_CHARACTERS {
+        anyhow::bail!(
+            "the memo is {} characters long, the chain accepts at most {}",
+            memo.len(),
+            MAX_MEMO_CHARACTERS
+        );
+    }
+    Ok(())
+}
+
+/// `SIGN_MODE_DIRECT`
+pub static SIGN_MODE_DIRECT: u64 = 1;
+
+/// `google.protobuf.Any`
+pub fn any(type_url: &str, value: Vec<u8>) -> ProtoEncoder {
+    ProtoEncoder::new().string(1, type_url).bytes(2, &value)
+}
+
+/// `cosmos.base.v1beta1.Coin`
+pub fn coin(coin: &Coin) -> ProtoEncoder {
+    ProtoEncoder::new()
+        .string(1, &coin.denom)
+        .string(2, &coin.amount.to_string())
+}
+
+/// `cosmwasm.wasm.v1.MsgExecuteContract`, as an `Any`
+pub fn msg_execute_contract(
+    sender: &str,
+    contract: &str,
+    msg: &[u8],
+    funds: &[Coin],
+) -> ProtoEncoder {
+    let encoded = funds.iter().fold(
+        ProtoEncoder::new()
+            .string(1, sender)
+            .string(2, contract)
+            .bytes(3, msg),
+        |encoded, funds| encoded.message(5, Some(coin(funds))),
+    );
+    any(type_url::MSG_EXECUTE_CONTRACT, encoded.finish())
+}
+
+/// `cosmos.tx.v1beta1.TxBody`; a zero timeout height sets none
+pub fn tx_body(msgs: Vec<ProtoEncoder>, memo: &str, timeout_height: u64) -> Vec<u8> {
+    msgs.into_iter()
+        .fold(ProtoEncoder::new(), |body, msg| body.message(1, Some(msg)))
+        .string(2, memo)
+        .uint64(3, timeout_height)
+        .finish()
+}
+
+/// `cosmos.crypto.secp256k1.PubKey` of a compressed public key, as an `Any`
+pub fn secp256k1_pub_key(public_key: &[u8]) -> ProtoEncoder {
+    any(
+        type_url::PUB_KEY_SECP256K1,
+        ProtoEncoder::new().bytes(1, public_key).finish(),
+    )
+}
+
+/// `cosmos.tx.v1beta1.AuthInfo` of a single (direct mode) signer; without
+/// a public key, which only simulations accept, it's omitted
+pub fn auth_info(
+    public_key: Option<&[u8]>,
+    sequence: u64,
+    fee: &[Coin],
+    gas_limit: u64,
+) -> Vec<u8> {
+    let mode_info =
+        ProtoEncoder::new().message(1, Some(ProtoEncoder::new().uint64(1, SIGN_MODE_DIRECT)));
+    let signer_info = ProtoEncoder::new()
+        .message(1, public_key.map(secp256k1_pub_key))
+        .message(2, Some(mode_info))
+        .uint64(3, sequence);
+    let fee = fee
+        .iter()
+        .fold(ProtoEncoder::new(), |encoded, fee| {
+            encoded.message(1, Some(coin(fee)))
+        })
+        .uint64(2, gas_limit);
+    ProtoEncoder::new()
+        .message(1, Some(signer_info))
+        .message(2, Some(fee))
+        .finish()
+}
+
+/// `cosmos.tx.v1beta1.SignDoc`, the bytes signed in direct mode
+pub fn sign_doc(body: &[u8], auth_info: &[u8], chain_id: &str, account_number: u64) -> Vec<u8> {
+    ProtoEncoder::new()
+        .bytes(1, body)
+        .bytes(2, auth_info)
+        .string(3, chain_id)
+        .uint64(4, account_number)
+        .finish()
+}
+
+/// `cosmos.tx.v1beta1.TxRaw`
+pub fn tx_raw(body: &[u8], auth_info: &[u8], signatures: &[Vec<u8>]) -> Vec<u8> {
+    signatures
+        .iter()
+        .fold(
+            ProtoEncoder::new().bytes(1, body).bytes(2, auth_info),
+            |encoded, signature| encoded.bytes_element(3, signature),
+        )
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::decode_hex;
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    fn hex(encoded: &str) -> Vec<u8> {
+        decode_hex(encoded).unwrap()
+    }
+
+    /// Public key of the cosmjs test faucet
+    fn faucet_pubkey() -> Vec<u8> {
+        STANDARD
+            .decode("A08EGB7ro1ORuFhjOnZcSgwYlpe0DSFjVNUIkNNQxwKQ")
+            .unwrap()
+    }
+
+    fn fee() -> Vec<Coin> {
+        vec![Coin::new(2000u128, "ucosm")]
+    }
+
+    const BODY: &str = concat!(
+        "0ac1010a242f636f736d7761736d2e7761736d2e76312e4d736745786563757465436f",
+        "6e74726163741298010a2d636f736d6f7331706b707472653766646b6c366766727a6c",
+        "65736a6a766878686c63337234676d6d6b387273361241636f736d6f733134686a3274",
+        "6176713866706573647778786375343472747933686839307668756a7276636d73746c",
+        "347a723374786d667677397334686d616c721a157b227265736574223a7b22636f756e",
+        "74223a357d7d2a0d0a0575636f736d12043130303012046d656d6f18b960",
+    );
+
+    /// The faucet's signer info at sequence 7, and a fee of 2000ucosm for
+    /// 200000 gas
+    const AUTH_INFO: &str = concat!(
+        "0a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b",
+        "657912230a21034f04181eeba35391b858633a765c4a0c189697b40d216354d50890d3",
+        "50c7029012040a020801180712130a0d0a0575636f736d12043230303010c09a0c",
+    );
+
+    fn body() -> Vec<u8> {
+        let execute = msg_execute_contract(
+            "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "cosmos14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9s4hmalr",
+            br#"{"reset":{"count":5}}"#,
+            &[Coin::new(1000u128, "ucosm")],
+        );
+        tx_body(vec![execute], "memo", 12345)
+    }
+
+    #[test]
+    fn encodes_tx_bodies() {
+        assert_eq!(body(), hex(BODY));
+        // Without a memo or timeout height, only the msgs are encoded
+        let execute = msg_execute_contract("a", "b", b"{}", &[]);
+        let any = hex(concat!(
+            "0a242f636f736d7761736d2e7761736d2e76312e4d736745786563757465436f6e7472",
+            "616374120a0a0161120162",
+            "1a027b7d",
+        ));
+        let mut expected = vec![0x0a, any.len() as u8];
+        expected.extend(any);
+        assert_eq!(tx_body(vec![execute], "", 0), expected);
+    }
+
+    #[test]
+    fn encodes_auth_infos() {
+        assert_eq!(
+            auth_info(Some(&faucet_pubkey()), 7, &fee(), 200_000),
+            hex(AUTH_INFO)
+        );
+        // Sequence 0 is omitted, as is the public key of simulations
+        assert_eq!(
+            auth_info(Some(&faucet_pubkey()), 0, &fee(), 200_000),
+            hex(concat!(
+                "0a4e0a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075",
+                "624b657912230a21034f04181eeba35391b858633a765c4a0c189697b40d216354",
+                "d50890d350c7029012040a02080112130a0d0a0575636f736d1204323030301",
+                "0c09a0c",
+            ))
+        );
+        assert_eq!(
+            auth_info(None, 0, &fee(), 200_000),
+            hex("0a0612040a02080112130a0d0a0575636f736d12043230303010c09a0c")
+        );
+    }
+
+    #[test]
+    fn encodes_sign_docs_and_tx_raws() {
+        let (body_bytes, auth_info_bytes) = (hex(BODY), hex(AUTH_INFO));
+        let mut expected = hex("0acd01");
+        expected.extend(&body_bytes);
+        expected.extend(hex("1267"));
+        expected.extend(&auth_info_bytes);
+        let mut sign_doc_bytes = expected.clone();
+        sign_doc_bytes.extend(hex("1a0774657374696e672001"));
+        assert_eq!(
+            sign_doc(&body_bytes, &auth_info_bytes, "testing", 1),
+            sign_doc_bytes
+        );
+        // Signatures are repeated, a placeholder (empty) one still encoded
+        let mut signed = expected.clone();
+        signed.extend(hex("1a40"));
+        signed.extend([0; 64]);
+        assert_eq!(
+            tx_raw(&body_bytes, &auth_info_bytes, &[vec![0; 64]]),
+            signed
+        );
+        expected.extend(hex("1a00"));
+        assert_eq!(tx_raw(&body_bytes, &auth_info_bytes, &[vec![]]), expected);
+    }
+
+    #[test]
+    fn checks_memo_lengths() {
+        assert!(check_memo(&"a".repeat(MAX_MEMO_CHARACTERS)).is_ok());
+        assert!(check_memo(&"a".repeat(MAX_MEMO_CHARACTERS + 1)).is_err());
+    }
+}
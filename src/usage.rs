@@ -0,0 +1,132 @@
+use rmcp::model::{CallToolResult, RawContent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use crate::clock::now_rfc3339;
+
+/// Replace with the average size (in bytes) of a token of the client's
+/// model, tool results are estimated to cost `bytes / BYTES_PER_TOKEN`
+/// tokens of context
+pub static BYTES_PER_TOKEN: usize = 4;
+
+/// Estimated tokens of a text
+pub fn estimate_tokens(bytes: usize) -> u64 {
+    bytes.div_ceil(BYTES_PER_TOKEN) as u64
+}
+
+/// Bytes of the text of a tool result, as returned to the client
+pub fn result_bytes(result: &CallToolResult) -> usize {
+    result
+        .content
+        .iter()
+        .map(|content| match &content.raw {
+            RawContent::Text(text) => text.text.len(),
+            _ => serde_json::to_string(content)
+                .map(|content| content.len())
+                .unwrap_or_default(),
+        })
+        .sum()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ToolUsage {
+    pub tool: String,
+    pub calls: u64,
+    /// Calls returning an error result
+    pub errors: u64,
+    pub bytes_returned: u64,
+    pub estimated_tokens: u64,
+    /// Largest result of the tool, in bytes
+    pub largest_result_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UsageStats {
+    pub session_started: String,
+    pub tool_calls: u64,
+    pub tool_errors: u64,
+    /// Requests sent to LCD endpoints (retries on other endpoints included)
+    pub rpc_calls: u64,
+    pub rpc_failures: u64,
+    pub bytes_returned: u64,
+    pub estimated_tokens: u64,
+    /// Tools by estimated tokens, highest first
+    pub tools: Vec<ToolUsage>,
+}
+
+#[derive(Clone, Debug)]
+struct Usage {
+    session_started: String,
+    rpc_calls: u64,
+    rpc_failures: u64,
+    tools: BTreeMap<String, ToolUsage>,
+}
+
+/// Tool calls, RPC calls and result sizes of a session; clones (e.g. of
+/// the `LcdClient`) share the counts
+#[derive(Clone, Debug)]
+pub struct UsageTracker {
+    usage: Arc<RwLock<Usage>>,
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self {
+            usage: Arc::new(RwLock::new(Usage {
+                session_started: now_rfc3339(),
+                rpc_calls: 0,
+                rpc_failures: 0,
+                tools: BTreeMap::new(),
+            })),
+        }
+    }
+}
+
+impl UsageTracker {
+    pub fn record_tool(&self, tool: &str, result: &CallToolResult) {
+        let bytes = result_bytes(result) as u64;
+        if let Ok(mut usage) = self.usage.write() {
+            let tool_usage = usage
+                .tools
+                .entry(tool.to_string())
+                .or_insert_with(|| ToolUsage {
+                    tool: tool.to_string(),
+                    ..ToolUsage::default()
+                });
+            tool_usage.calls += 1;
+            if result.is_error == Some(true) {
+                tool_usage.errors += 1;
+            }
+            tool_usage.bytes_returned += bytes;
+            tool_usage.estimated_tokens += estimate_tokens(bytes as usize);
+            tool_usage.largest_result_bytes = tool_usage.largest_result_bytes.max(bytes);
+        }
+    }
+
+    pub fn record_rpc(&self, failed: bool) {
+        if let Ok(mut usage) = self.usage.write() {
+            usage.rpc_calls += 1;
+            if failed {
+                usage.rpc_failures += 1;
+            }
+        }
+    }
+
+    pub fn stats(&self) -> Option<UsageStats> {
+        let usage = self.usage.read().ok()?;
+        let mut tools: Vec<ToolUsage> = usage.tools.values().cloned().collect();
+        tools.sort_by(|a, b| b.estimated_tokens.cmp(&a.estimated_tokens));
+        Some(UsageStats {
+            session_started: usage.session_started.clone(),
+            tool_calls: tools.iter().map(|tool| tool.calls).sum(),
+            tool_errors: tools.iter().map(|tool| tool.errors).sum(),
+            rpc_calls: usage.rpc_calls,
+            rpc_failures: usage.rpc_failures,
+            bytes_returned: tools.iter().map(|tool| tool.bytes_returned).sum(),
+            estimated_tokens: tools.iter().map(|tool| tool.estimated_tokens).sum(),
+            tools,
+        })
+    }
+}
@@ -0,0 +1,258 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::repair::{normalize_keys, resolve};
+use crate::semantic::check_msg;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldErrorKind {
+    UnknownVariant,
+    MissingField,
+    UnknownField,
+    WrongType,
+    InvalidValue,
+}
+
+/// A field of a msg that doesn't match the schema of its type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FieldError {
+    /// Path of the field, e.g. `transfer.amount` (empty for the msg)
+    pub path: String,
+    pub kind: FieldErrorKind,
+    pub message: String,
+}
+
+fn field_error(path: &str, kind: FieldErrorKind, message: String) -> FieldError {
+    FieldError {
+        path: path.to_string(),
+        kind,
+        message,
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", path, key),
+    }
+}
+
+/// JSON schema type of a value
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = type_of(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// Names of the variants of a msg enum's alternatives (unit variants, and
+/// the single property of the others)
+fn variant_names(alternatives: &[Value], root: &Value) -> Vec<String> {
+    let mut names = vec![];
+    for alternative in alternatives {
+        let alternative = resolve(alternative, root);
+        if let Some(units) = alternative["enum"].as_array() {
+            names.extend(units.iter().filter_map(Value::as_str).map(str::to_string));
+        }
+        if let Some(properties) = alternative["properties"].as_object() {
+            names.extend(properties.keys().cloned());
+        }
+    }
+    names
+}
+
+/// Errors of the alternative a value matches best (`oneOf`/`anyOf`),
+/// or an unknown variant error for msg enums none of whose variants the
+/// value names
+fn validate_alternatives(
+    value: &Value,
+    alternatives: &[Value],
+    root: &Value,
+    path: &str,
+) -> Vec<FieldError> {
+    let mut best: Option<Vec<FieldError>> = None;
+    for alternative in alternatives {
+        let errors = validate_node(value, alternative, root, path);
+        if errors.is_empty() {
+            return errors;
+        }
+        if best.as_ref().is_none_or(|best| errors.len() < best.len()) {
+            best = Some(errors);
+        }
+    }
+    let names = variant_names(alternatives, root);
+    let named = match value {
+        Value::String(name) => Some(name),
+        Value::Object(object) if object.len() == 1 => object.keys().next(),
+        _ => None,
+    };
+    if let Some(name) = named.filter(|name| !names.is_empty() && !names.contains(name)) {
+        return vec![field_error(
+            path,
+            FieldErrorKind::UnknownVariant,
+            format!(
+                "unknown variant '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            ),
+        )];
+    }
+    best.unwrap_or_default()
+}
+
+fn validate_node(value: &Value, schema: &Value, root: &Value, path: &str) -> Vec<FieldError> {
+    let schema = resolve(schema, root);
+    let mut errors = vec![];
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(alternatives) = schema[keyword].as_array() {
+            errors.extend(validate_alternatives(value, alternatives, root, path));
+        }
+    }
+    if let Some(all) = schema["allOf"].as_array() {
+        for part in all {
+            errors.extend(validate_node(value, part, root, path));
+        }
+    }
+    if !errors.is_empty() {
+        return errors;
+    }
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(expected) => vec![expected.as_str()],
+        Value::Array(expected) => expected.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|expected| has_type(value, expected)) {
+        return vec![field_error(
+            path,
+            FieldErrorKind::WrongType,
+            format!("expected {}, found {}", types.join(" or "), type_of(value)),
+        )];
+    }
+    if let Some(allowed) = schema["enum"].as_array() {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            return vec![field_error(
+                path,
+                FieldErrorKind::InvalidValue,
+                format!("{} isn't one of: {}", value, allowed.join(", ")),
+            )];
+        }
+    }
+    if let (Some(minimum), Some(number)) = (schema["minimum"].as_f64(), value.as_f64()) {
+        if number < minimum {
+            errors.push(field_error(
+                path,
+                FieldErrorKind::InvalidValue,
+                format!("{} is below the minimum of {}", number, minimum),
+            ));
+        }
+    }
+    match value {
+        Value::Object(object) => {
+            let properties = schema["properties"].as_object();
+            if let Some(required) = schema["required"].as_array() {
+                for field in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(field) {
+                        errors.push(field_error(
+                            &join(path, field),
+                            FieldErrorKind::MissingField,
+                            format!("missing required field '{}'", field),
+                        ));
+                    }
+                }
+            }
+            for (key, field) in object {
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(property) => {
+                        errors.extend(validate_node(field, property, root, &join(path, key)))
+                    }
+                    None if schema["additionalProperties"] == Value::Bool(false) => {
+                        let expected: Vec<&str> = properties
+                            .map(|properties| properties.keys().map(String::as_str).collect())
+                            .unwrap_or_default();
+                        errors.push(field_error(
+                            &join(path, key),
+                            FieldErrorKind::UnknownField,
+                            format!(
+                                "unknown field '{}', expected one of: {}",
+                                key,
+                                expected.join(", ")
+                            ),
+                        ));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => match &schema["items"] {
+            // Tuples list the schema of each item
+            Value::Array(tuple) => {
+                for (i, (item, item_schema)) in items.iter().zip(tuple).enumerate() {
+                    let item_path = format!("{}[{}]", path, i);
+                    errors.extend(validate_node(item, item_schema, root, &item_path));
+                }
+            }
+            Value::Object(_) => {
+                for (i, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, i);
+                    errors.extend(validate_node(item, &schema["items"], root, &item_path));
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    errors
+}
+
+/// Field level errors of a msg against the (schemars) JSON schema of its
+/// type: unknown variants, missing and unknown fields, wrong types and
+/// invalid values
+pub fn validate(msg: &Value, schema: &Value) -> Vec<FieldError> {
+    validate_node(msg, schema, schema, "")
+}
+
+/// Why a JSON stringified msg isn't a msg of a schema
+#[derive(Debug)]
+pub enum MsgError {
+    /// Not a JSON msg, or refused by the semantic checks
+    Invalid(anyhow::Error),
+    /// Fields of the msg don't match the schema
+    Fields(Vec<FieldError>),
+}
+
+/// Check a JSON stringified msg against the schema of its type (when
+/// only the schema is known), normalizing its keys to the schema's names
+/// (e.g. `transferFrom`) if that makes it valid; returns the msg with the
+/// warnings of its normalization
+pub fn check_schema_msg(msg: &str, schema: &Value) -> Result<(Value, Vec<String>), MsgError> {
+    let json: Value = serde_json::from_str(msg).map_err(|e| MsgError::Invalid(e.into()))?;
+    check_msg(&json).map_err(MsgError::Invalid)?;
+    let field_errors = validate(&json, schema);
+    if field_errors.is_empty() {
+        return Ok((json, vec![]));
+    }
+    let mut normalized = json.clone();
+    let notes = normalize_keys(&mut normalized, schema);
+    if !notes.is_empty() && validate(&normalized, schema).is_empty() {
+        check_msg(&normalized).map_err(MsgError::Invalid)?;
+        let warnings = notes
+            .into_iter()
+            .map(|note| format!("{}, use the schema's snake_case names", note))
+            .collect();
+        return Ok((normalized, warnings));
+    }
+    Err(MsgError::Fields(field_errors))
+}
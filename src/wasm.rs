@@ -0,0 +1,146 @@
+//! JavaScript bindings of the msg building core, so web dApps build msgs
+//! with the same logic as the server (`scripts/build-wasm.sh`).
+//! Values are passed as JSON strings, and errors thrown as `Error`s
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::address::convert_prefix;
+use crate::encoding::{ExecuteContract, MsgFormat, execute_cosmos_msg};
+use crate::example::{MsgType, generate};
+use crate::funds::native_funds;
+use crate::stargate::{self, ArchwayQuery};
+use crate::validate::{FieldError, MsgError, check_schema_msg};
+
+/// A msg checked against the schema of its type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckedMsg {
+    pub valid: bool,
+    /// The msg, with its keys normalized to the schema's names
+    pub msg: Option<Value>,
+    pub warnings: Vec<String>,
+    /// Why the msg isn't JSON, or was refused by the semantic checks
+    pub error: Option<String>,
+    pub field_errors: Vec<FieldError>,
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(name: &str, json: &str) -> Result<T, JsError> {
+    serde_json::from_str(json).map_err(|e| JsError::new(&format!("invalid {}: {}", name, e)))
+}
+
+/// Check a JSON stringified msg against a msg schema (e.g. of the
+/// `cosmwasm schema` output, or `list_query_entry_points`), returning a
+/// `CheckedMsg`
+#[wasm_bindgen(js_name = checkMsg)]
+pub fn check_msg(schema: &str, msg: &str) -> Result<String, JsError> {
+    let schema: Value = parse_json("schema", schema)?;
+    let checked = match check_schema_msg(msg, &schema) {
+        Ok((msg, warnings)) => CheckedMsg {
+            valid: true,
+            msg: Some(msg),
+            warnings,
+            error: None,
+            field_errors: vec![],
+        },
+        Err(MsgError::Invalid(e)) => CheckedMsg {
+            valid: false,
+            msg: None,
+            warnings: vec![],
+            error: Some(e.to_string()),
+            field_errors: vec![],
+        },
+        Err(MsgError::Fields(field_errors)) => CheckedMsg {
+            valid: false,
+            msg: None,
+            warnings: vec![],
+            error: None,
+            field_errors,
+        },
+    };
+    Ok(to_json(&checked))
+}
+
+/// Example msg of an entry point of a msg schema (`msg_type` is `Query`
+/// or `Execute`), as `generate_example_msg` returns it
+#[wasm_bindgen(js_name = exampleMsg)]
+pub fn example_msg(msg_type: &str, schema: &str, entry_point: &str) -> Result<String, JsError> {
+    let msg_type: MsgType = serde_json::from_value(Value::String(msg_type.to_string()))
+        .map_err(|_| JsError::new("the msg type must be 'Query' or 'Execute'"))?;
+    let schema: Value = parse_json("schema", schema)?;
+    match generate(msg_type, &schema, entry_point) {
+        Ok(example) => Ok(to_json(&example)),
+        Err(entry_points) => Err(JsError::new(&format!(
+            "no entry point '{}', the entry points are: {}",
+            entry_point,
+            entry_points.join(", ")
+        ))),
+    }
+}
+
+/// JSON stringified `CosmosMsg` of an execute msg to a contract, with
+/// its `funds` (a JSON array of coins) normalized as the chain requires
+#[wasm_bindgen(js_name = buildExecuteMsg)]
+pub fn build_execute_msg(
+    contract: &str,
+    msg: &str,
+    funds: Option<String>,
+) -> Result<String, JsError> {
+    let msg: Value = parse_json("execute msg", msg)?;
+    let funds = native_funds(None, None, funds).map_err(|e| JsError::new(&e.to_string()))?;
+    execute_cosmos_msg(contract, &msg, funds).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// An execute msg encoded in a format (`json`, `proto` or `cbor`) of the
+/// downstream signing stack, as an `EncodedMsg`; `proto` requires the
+/// sender, and `tx_raw` (the sender's sequence and fee) isn't supported
+#[wasm_bindgen(js_name = encodeExecuteMsg)]
+pub fn encode_execute_msg(
+    format: &str,
+    sender: Option<String>,
+    contract: &str,
+    msg: &str,
+    funds: Option<String>,
+) -> Result<String, JsError> {
+    let format: MsgFormat = serde_json::from_value(Value::String(format.to_string()))
+        .map_err(|_| JsError::new("the format must be 'json', 'proto' or 'cbor'"))?;
+    let msg: Value = parse_json("execute msg", msg)?;
+    let funds = native_funds(None, None, funds).map_err(|e| JsError::new(&e.to_string()))?;
+    let cosmos_msg = execute_cosmos_msg(contract, &msg, funds.clone())
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    let encoded = format
+        .encode(&ExecuteContract {
+            sender: sender.as_deref(),
+            contract,
+            msg: &msg,
+            funds: &funds,
+            cosmos_msg: &cosmos_msg,
+            memo: "",
+            timeout_height: 0,
+            auth: None,
+        })
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(to_json(&encoded))
+}
+
+/// Stargate query of an Archway module (a JSON `ArchwayQuery`), as
+/// `build_stargate_query` returns it
+#[wasm_bindgen(js_name = buildStargateQuery)]
+pub fn build_stargate_query(query: &str) -> Result<String, JsError> {
+    let query: ArchwayQuery = parse_json("stargate query", query)?;
+    let built = stargate::build(&query).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(to_json(&built))
+}
+
+/// Re-encode a bech32 address with another prefix, as a
+/// `ConvertedAddress`
+#[wasm_bindgen(js_name = convertAddressPrefix)]
+pub fn convert_address_prefix(address: &str, prefix: &str) -> Result<String, JsError> {
+    let converted = convert_prefix(address, prefix).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(to_json(&converted))
+}
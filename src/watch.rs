@@ -0,0 +1,137 @@
+use rmcp::{Peer, RoleServer, model::ResourceUpdatedNotificationParam};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::resource::StateUri;
+use crate::rpc::LcdClient;
+
+/// Replace with the interval watched queries are polled at
+pub static WATCH_INTERVAL_SECS: u64 = 15;
+
+/// Replace with the maximum number of resources a session can watch
+pub static MAX_WATCHES: usize = 32;
+
+/// Watch tasks of a session, aborted when the session's server is dropped
+#[derive(Debug, Default)]
+struct Watches(Mutex<HashMap<String, JoinHandle<()>>>);
+
+impl Drop for Watches {
+    fn drop(&mut self) {
+        if let Ok(watches) = self.0.get_mut() {
+            for task in watches.values() {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// Polls the queries of subscribed `state://` resources, and notifies the
+/// subscriber when their result changes
+#[derive(Clone, Debug, Default)]
+pub struct Watcher {
+    lcd: LcdClient,
+    watches: Arc<Watches>,
+}
+
+impl Watcher {
+    pub fn new(lcd: LcdClient) -> Self {
+        Self {
+            lcd,
+            watches: Arc::default(),
+        }
+    }
+
+    /// Watch the query of a resource at an LCD endpoint (of the resource's
+    /// deployment), replacing any watch of the same URI. The watch ends
+    /// when it's unsubscribed, the peer goes away or the session ends; a
+    /// session watches at most `MAX_WATCHES` resources
+    pub fn subscribe(
+        &self,
+        uri: String,
+        state: StateUri,
+        endpoint: String,
+        peer: Peer<RoleServer>,
+    ) -> anyhow::Result<()> {
+        let mut watches = self
+            .watches
+            .0
+            .lock()
+            .map_err(|_| anyhow::anyhow!("watches poisoned"))?;
+        watches.retain(|_, task| !task.is_finished());
+        if !watches.contains_key(&uri) && watches.len() >= MAX_WATCHES {
+            anyhow::bail!(
+                "the session already watches {} resources, unsubscribe from one first",
+                MAX_WATCHES
+            );
+        }
+        let lcd = self.lcd.clone();
+        let watched = uri.clone();
+        let task = tokio::spawn(async move {
+            let mut last: Option<Value> = None;
+            let mut interval = tokio::time::interval(Duration::from_secs(WATCH_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if peer.is_transport_closed() {
+                    break;
+                }
+                // Failed polls (e.g. an unavailable endpoint) are retried
+                let Ok(result) = lcd
+                    .smart_query(&endpoint, &state.contract, &state.query)
+                    .await
+                else {
+                    continue;
+                };
+                let changed = last.as_ref().is_some_and(|last| last != &result);
+                last = Some(result);
+                if changed
+                    && peer
+                        .notify_resource_updated(ResourceUpdatedNotificationParam {
+                            uri: watched.clone(),
+                        })
+                        .await
+                        .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        if let Some(previous) = watches.insert(uri, task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    pub fn unsubscribe(&self, uri: &str) -> bool {
+        match self
+            .watches
+            .0
+            .lock()
+            .ok()
+            .and_then(|mut watches| watches.remove(uri))
+        {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// URIs of the watched resources
+    pub fn watched(&self) -> Vec<String> {
+        self.watches
+            .0
+            .lock()
+            .map(|watches| {
+                watches
+                    .iter()
+                    .filter(|(_, task)| !task.is_finished())
+                    .map(|(uri, _)| uri.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,252 @@
+use cosmwasm_std::Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use crate::contract::Network;
+use crate::execute::ValidatedExecute;
+
+/// Maximum number of steps of a workflow
+pub static MAX_WORKFLOW_STEPS: usize = 20;
+
+/// An execute msg, of a step or of its compensation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WorkflowMsg {
+    /// Contract executed (defaults to the workflow's contract)
+    pub contract: Option<String>,
+    /// Execute msg; string values like `${balance.balance}` are replaced
+    /// by the value saved by a previous query step
+    pub msg: Value,
+    #[serde(default)]
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    /// Run a smart query, saving its response under `save_as`
+    Query {
+        contract: Option<String>,
+        msg: Value,
+        save_as: String,
+    },
+    /// Build an execute msg, and wait for it to be broadcast
+    Execute(WorkflowMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Condition on a value saved by a query step, e.g. `balance.balance`
+/// `gte` `1000`; steps whose condition doesn't hold are skipped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StepCondition {
+    pub var: String,
+    pub op: Comparison,
+    pub value: Value,
+}
+
+/// Condition checked with a live query right before an execute step's msg
+/// is handed out for signing; the workflow fails (and is compensated) if
+/// it doesn't hold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Predicate {
+    /// A (dotted) field of a query response compares to a value, e.g.
+    /// `balance` of `{"balance": {"address": ..}}` `gte` `1000`
+    Query {
+        contract: Option<String>,
+        msg: Value,
+        field: String,
+        op: Comparison,
+        value: Value,
+    },
+    /// A cw-utils `Expiration` field of a query response (e.g. `expires`
+    /// of an `allowance` query) has expired, or hasn't if `expired` is
+    /// false
+    Expiration {
+        contract: Option<String>,
+        msg: Value,
+        field: String,
+        expired: bool,
+    },
+}
+
+/// Whether a cw-utils `Expiration` (`{"at_height": ..}`, `{"at_time":
+/// ..}` in nanoseconds, or `{"never": {}}`) has expired at a block
+pub fn is_expired(expiration: &Value, height: u64, time_secs: u64) -> Option<bool> {
+    if let Some(at_height) = expiration.get("at_height") {
+        return as_number(at_height).map(|at_height| u128::from(height) >= at_height);
+    }
+    if let Some(at_time) = expiration.get("at_time") {
+        let time_nanos = u128::from(time_secs) * 1_000_000_000;
+        return as_number(at_time).map(|at_time| time_nanos >= at_time);
+    }
+    expiration.get("never").map(|_| false)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WorkflowStep {
+    pub name: String,
+    pub action: StepAction,
+    pub condition: Option<StepCondition>,
+    /// Live checks of an execute step, see `Predicate`
+    #[serde(default)]
+    pub predicates: Vec<Predicate>,
+    /// Execute msg undoing the step, broadcast if a later step fails
+    pub compensation: Option<WorkflowMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WorkflowDefinition {
+    pub name: String,
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStatus {
+    Running,
+    /// A built execute msg is waiting to be broadcast (see `pending`)
+    AwaitingBroadcast,
+    Completed,
+    /// A step failed, and the compensations of the completed steps must
+    /// be broadcast (see `compensations`)
+    Compensating,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StepLog {
+    pub step: String,
+    /// `queried`, `skipped`, `broadcast` (with the tx hash) or `failed`
+    pub outcome: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingStep {
+    pub step: String,
+    pub execute: ValidatedExecute,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub definition: WorkflowDefinition,
+    pub contract: String,
+    pub network: Network,
+    pub status: WorkflowStatus,
+    /// Index of the next step
+    pub position: usize,
+    pub log: Vec<StepLog>,
+    pub pending: Option<PendingStep>,
+    /// Compensations of the completed steps, most recent first
+    pub compensations: Vec<PendingStep>,
+    pub vars: BTreeMap<String, Value>,
+}
+
+impl WorkflowRun {
+    /// Value of a (dotted) path into the saved query responses
+    pub fn var(&self, path: &str) -> Option<&Value> {
+        match path.split_once('.') {
+            Some((name, path)) => Self::field(self.vars.get(name)?, path),
+            None => self.vars.get(path),
+        }
+    }
+
+    /// Value of a (dotted) path into a JSON value
+    pub fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+        path.split('.').try_fold(value, |value, key| match value {
+            Value::Array(values) => key.parse::<usize>().ok().and_then(|i| values.get(i)),
+            _ => value.get(key),
+        })
+    }
+
+    /// Replace `${path}` strings of a msg with saved values
+    pub fn substitute(&self, msg: &Value) -> anyhow::Result<Value> {
+        Ok(match msg {
+            Value::String(s) if s.starts_with("${") && s.ends_with('}') => {
+                let path = &s[2..s.len() - 1];
+                self.var(path)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("'{}' isn't a saved value", path))?
+            }
+            Value::Object(map) => {
+                let mut substituted = serde_json::Map::new();
+                for (key, value) in map {
+                    substituted.insert(key.clone(), self.substitute(value)?);
+                }
+                Value::Object(substituted)
+            }
+            Value::Array(values) => Value::Array(
+                values
+                    .iter()
+                    .map(|value| self.substitute(value))
+                    .collect::<anyhow::Result<Vec<Value>>>()?,
+            ),
+            value => value.clone(),
+        })
+    }
+}
+
+/// Numeric value of a JSON number or numeric string (e.g. a `Uint128`)
+fn as_number(value: &Value) -> Option<u128> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
+/// Compare two values, numerically if both are numeric
+pub fn compare(actual: &Value, op: &Comparison, expected: &Value) -> bool {
+    match (as_number(actual), as_number(expected)) {
+        (Some(actual), Some(expected)) => match op {
+            Comparison::Eq => actual == expected,
+            Comparison::Ne => actual != expected,
+            Comparison::Gt => actual > expected,
+            Comparison::Gte => actual >= expected,
+            Comparison::Lt => actual < expected,
+            Comparison::Lte => actual <= expected,
+        },
+        _ => match op {
+            Comparison::Eq => actual == expected,
+            Comparison::Ne => actual != expected,
+            _ => false,
+        },
+    }
+}
+
+/// Workflow runs of the session
+#[derive(Clone, Debug, Default)]
+pub struct Workflows {
+    runs: Arc<RwLock<BTreeMap<String, WorkflowRun>>>,
+}
+
+impl Workflows {
+    pub fn next_id(&self) -> String {
+        let count = self.runs.read().map(|runs| runs.len()).unwrap_or_default();
+        format!("wf-{}", count + 1)
+    }
+
+    pub fn get(&self, id: &str) -> Option<WorkflowRun> {
+        self.runs.read().ok()?.get(id).cloned()
+    }
+
+    pub fn save(&self, run: &WorkflowRun) {
+        if let Ok(mut runs) = self.runs.write() {
+            runs.insert(run.id.clone(), run.clone());
+        }
+    }
+}